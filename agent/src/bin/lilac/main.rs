@@ -16,6 +16,18 @@ pub async fn main() -> Result<(), CliError> {
             let config = config::load_user_config()?;
             handlers::submit_job(config, args).await?;
         }
+        Commands::Explain(args) => {
+            let config = config::load_user_config()?;
+            handlers::explain_job(config, args).await?;
+        }
+        Commands::Logs(args) => {
+            let config = config::load_user_config()?;
+            handlers::print_job_logs(config, args).await?;
+        }
+        Commands::Artifacts(args) => {
+            let config = config::load_user_config()?;
+            handlers::job_artifacts(config, args).await?;
+        }
         Commands::Configure => {
             let config = config::load_user_config()?;
             handlers::configure_user(config).await?;
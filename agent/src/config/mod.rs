@@ -1,5 +1,6 @@
 use crate::errors::ConfigError;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::env;
 use std::fs;
 use std::path::PathBuf;
@@ -19,6 +20,32 @@ pub struct AgentConfig {
     pub cluster_api_key: String,
     pub node_id: Uuid,
     pub private_registry: Option<PrivateRegistryConfig>,
+    /// The address other nodes use to reach this one for multi-node jobs.
+    #[serde(default)]
+    pub advertise_address: Option<String>,
+    /// Labels describing this node, e.g. its network or storage, which jobs
+    /// can select nodes by.
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+    /// Where the outputs of jobs are kept until they are uploaded. Defaults
+    /// to `~/.lilac/outputs`.
+    #[serde(default)]
+    pub outputs_dir: Option<PathBuf>,
+    /// Directories on this node jobs may mount, along with everything below
+    /// them.
+    #[serde(default)]
+    pub allowed_host_paths: Vec<PathBuf>,
+    /// Docker volumes jobs may mount.
+    #[serde(default)]
+    pub allowed_volumes: Vec<String>,
+    /// Where datasets are cached between jobs. Defaults to
+    /// `~/.lilac/datasets`.
+    #[serde(default)]
+    pub dataset_cache_dir: Option<PathBuf>,
+    /// How large the dataset cache may grow before the least recently used
+    /// datasets are evicted, in GB. Unlimited if not set.
+    #[serde(default)]
+    pub dataset_cache_max_gb: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -60,7 +87,7 @@ pub fn load_user_config() -> Result<UserConfig, ConfigError> {
     }
 
     let content = fs::read_to_string(&config_path).map_err(|_| ConfigError::ReadFile)?;
-    Ok(toml::from_str(&content).map_err(|_| ConfigError::Parse)?)
+    toml::from_str(&content).map_err(|_| ConfigError::Parse)
 }
 
 pub fn load_agent_config() -> Result<AgentConfig, ConfigError> {
@@ -93,6 +120,23 @@ pub fn load_agent_config() -> Result<AgentConfig, ConfigError> {
             } else {
                 None
             },
+            advertise_address: env::var("LILAC_ADVERTISE_ADDRESS").ok(),
+            labels: match env::var("LILAC_NODE_LABELS") {
+                Ok(labels) => parse_labels(&labels)?,
+                Err(_) => BTreeMap::new(),
+            },
+            outputs_dir: env::var("LILAC_OUTPUTS_DIR").ok().map(PathBuf::from),
+            allowed_host_paths: env::var("LILAC_ALLOWED_HOST_PATHS")
+                .map(|paths| parse_list(&paths).map(PathBuf::from).collect())
+                .unwrap_or_default(),
+            allowed_volumes: env::var("LILAC_ALLOWED_VOLUMES")
+                .map(|volumes| parse_list(&volumes).map(str::to_string).collect())
+                .unwrap_or_default(),
+            dataset_cache_dir: env::var("LILAC_DATASET_CACHE_DIR").ok().map(PathBuf::from),
+            dataset_cache_max_gb: env::var("LILAC_DATASET_CACHE_MAX_GB")
+                .ok()
+                .map(|max_gb| max_gb.parse().map_err(|_| ConfigError::Parse))
+                .transpose()?,
         };
         // Write to file if env vars are used, to persist the config
         let toml_string = toml::to_string(&config).map_err(|_| ConfigError::WriteFile)?;
@@ -109,6 +153,13 @@ pub fn load_agent_config() -> Result<AgentConfig, ConfigError> {
             cluster_api_key: "".to_string(),
             node_id: Uuid::new_v4(),
             private_registry: None,
+            advertise_address: None,
+            labels: BTreeMap::new(),
+            outputs_dir: None,
+            allowed_host_paths: Vec::new(),
+            allowed_volumes: Vec::new(),
+            dataset_cache_dir: None,
+            dataset_cache_max_gb: None,
         };
         let toml_string = toml::to_string(&config).map_err(|_| ConfigError::WriteFile)?;
         fs::create_dir_all(config_path.parent().unwrap())
@@ -119,11 +170,40 @@ pub fn load_agent_config() -> Result<AgentConfig, ConfigError> {
     }
 
     let content = fs::read_to_string(&config_path).map_err(|_| ConfigError::ReadFile)?;
-    Ok(toml::from_str(&content).map_err(|_| ConfigError::Parse)?)
+    toml::from_str(&content).map_err(|_| ConfigError::Parse)
 }
 
 pub fn get_config_path(file_name: &str) -> Result<PathBuf, ConfigError> {
     let home_dir = dirs::home_dir().ok_or(ConfigError::HomeDirNotFound)?;
     let config_dir = home_dir.join(".lilac");
     Ok(config_dir.join(file_name))
-}
\ No newline at end of file
+}
+
+/// Parses labels given as comma separated `key=value` pairs.
+pub fn parse_labels(labels: &str) -> Result<BTreeMap<String, String>, ConfigError> {
+    labels
+        .split(',')
+        .map(str::trim)
+        .filter(|label| !label.is_empty())
+        .map(|label| match label.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => {
+                Ok((key.trim().to_string(), value.trim().to_string()))
+            }
+            _ => Err(ConfigError::InvalidLabel(label.to_string())),
+        })
+        .collect()
+}
+
+/// Splits a comma separated list, skipping empty entries.
+fn parse_list(list: &str) -> impl Iterator<Item = &str> {
+    list.split(',').map(str::trim).filter(|item| !item.is_empty())
+}
+
+/// Formats labels the way [parse_labels] reads them.
+pub fn format_labels(labels: &BTreeMap<String, String>) -> String {
+    labels
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect::<Vec<_>>()
+        .join(",")
+}
@@ -0,0 +1,318 @@
+use std::collections::HashMap;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+/// Datasets are renamed with this prefix before they are deleted, so that no
+/// job picks them up halfway through.
+const EVICTING_PREFIX: &str = ".evicting-";
+
+/// Datasets kept on this node between jobs, each in a directory of its own.
+/// Once the cache grows beyond its size limit, the datasets used least
+/// recently are evicted, skipping the ones running jobs still use.
+pub struct DatasetCache {
+    dir: PathBuf,
+    max_bytes: Option<u64>,
+    /// How many running jobs use each dataset.
+    in_use: Mutex<HashMap<String, usize>>,
+}
+
+/// A dataset in use by a job. Released once dropped.
+pub struct CacheLease {
+    cache: Arc<DatasetCache>,
+    name: String,
+    /// The dataset's directory on the host.
+    pub path: PathBuf,
+}
+
+impl DatasetCache {
+    pub fn new(dir: PathBuf, max_gb: Option<u64>) -> Self {
+        let cache = Self {
+            dir,
+            max_bytes: max_gb.map(|max_gb| max_gb.saturating_mul(1_000_000_000)),
+            in_use: Mutex::new(HashMap::new()),
+        };
+        if let Err(e) = cache.remove_leftovers() {
+            eprintln!("[CACHE] Error removing unfinished evictions: {}", e);
+        }
+        cache
+    }
+
+    /// Removes what is left of the datasets the agent was evicting when it
+    /// last stopped.
+    fn remove_leftovers(&self) -> io::Result<()> {
+        if !self.dir.exists() {
+            return Ok(());
+        }
+        for entry in std::fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            if entry
+                .file_name()
+                .to_string_lossy()
+                .starts_with(EVICTING_PREFIX)
+            {
+                remove(&entry.path())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Marks a dataset as in use and returns its directory, which is created
+    /// for the first job using it. Other datasets are evicted beforehand if
+    /// the cache has grown too large.
+    pub async fn acquire(self: &Arc<Self>, name: &str) -> io::Result<CacheLease> {
+        // The name is joined onto the cache directory, so it may only name a
+        // directory right inside it.
+        let mut components = Path::new(name).components();
+        let single = matches!(
+            (components.next(), components.next()),
+            (Some(Component::Normal(_)), None)
+        );
+        if !single || name.starts_with(EVICTING_PREFIX) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid dataset name {:?}", name),
+            ));
+        }
+
+        *self
+            .in_use
+            .lock()
+            .unwrap()
+            .entry(name.to_string())
+            .or_default() += 1;
+        let lease = CacheLease {
+            cache: self.clone(),
+            name: name.to_string(),
+            path: self.dir.join(name),
+        };
+
+        let cache = self.clone();
+        let path = lease.path.clone();
+        tokio::task::spawn_blocking(move || {
+            cache.evict()?;
+            std::fs::create_dir_all(&path)?;
+            // The job may run as any user.
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o777))?;
+            }
+            touch(&path)
+        })
+        .await
+        .map_err(io::Error::other)??;
+        Ok(lease)
+    }
+
+    /// Evicts the least recently used datasets no job is using until the
+    /// cache fits within its size limit.
+    fn evict(&self) -> io::Result<()> {
+        let Some(max_bytes) = self.max_bytes else {
+            return Ok(());
+        };
+        std::fs::create_dir_all(&self.dir)?;
+
+        let mut datasets = Vec::new();
+        let mut total_bytes = 0;
+        for entry in std::fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            // Left over from an eviction the agent didn't get to finish.
+            if name.starts_with(EVICTING_PREFIX) {
+                remove(&entry.path())?;
+                continue;
+            }
+            let size_bytes = disk_usage(&entry.path())?;
+            let last_used = entry.metadata()?.modified()?;
+            total_bytes += size_bytes;
+            datasets.push((name, size_bytes, last_used));
+        }
+        datasets.sort_by_key(|(_, _, last_used)| *last_used);
+
+        for (name, size_bytes, _) in datasets {
+            if total_bytes <= max_bytes {
+                break;
+            }
+            let evicting = self.dir.join(format!("{}{}", EVICTING_PREFIX, name));
+            {
+                let in_use = self.in_use.lock().unwrap();
+                if in_use.contains_key(&name) {
+                    continue;
+                }
+                std::fs::rename(self.dir.join(&name), &evicting)?;
+            }
+            remove(&evicting)?;
+            total_bytes -= size_bytes;
+            println!("[CACHE] Evicted dataset {} ({} bytes)", name, size_bytes);
+        }
+        if total_bytes > max_bytes {
+            eprintln!(
+                "[CACHE] Datasets in use take up {} bytes, more than the limit of {}.",
+                total_bytes, max_bytes
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Drop for CacheLease {
+    fn drop(&mut self) {
+        let mut in_use = self.cache.in_use.lock().unwrap();
+        if let Some(count) = in_use.get_mut(&self.name) {
+            *count -= 1;
+            if *count == 0 {
+                in_use.remove(&self.name);
+            }
+        }
+        // Datasets are evicted by when they were last used, which includes
+        // the whole time a job was using them.
+        let _ = touch(&self.path);
+    }
+}
+
+/// Sets the modification time of a dataset's directory to now, which marks
+/// when it was last used.
+fn touch(path: &Path) -> io::Result<()> {
+    std::fs::File::open(path)?.set_modified(SystemTime::now())
+}
+
+/// The bytes taken up by the files below a path. Symbolic links aren't
+/// followed.
+fn disk_usage(path: &Path) -> io::Result<u64> {
+    let mut size_bytes = 0;
+    let mut paths = vec![path.to_path_buf()];
+    while let Some(path) = paths.pop() {
+        let metadata = std::fs::symlink_metadata(&path)?;
+        if metadata.is_dir() {
+            for entry in std::fs::read_dir(&path)? {
+                paths.push(entry?.path());
+            }
+        } else {
+            size_bytes += metadata.len();
+        }
+    }
+    Ok(size_bytes)
+}
+
+fn remove(path: &Path) -> io::Result<()> {
+    if std::fs::symlink_metadata(path)?.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    /// A cache limited to `max_bytes`, rather than whole gigabytes.
+    fn cache(dir: &Path, max_bytes: u64) -> Arc<DatasetCache> {
+        Arc::new(DatasetCache {
+            max_bytes: Some(max_bytes),
+            ..DatasetCache::new(dir.to_path_buf(), None)
+        })
+    }
+
+    /// Creates a dataset holding `size_bytes`, last used `age_secs` after the
+    /// epoch.
+    fn dataset(dir: &Path, name: &str, size_bytes: usize, age_secs: u64) {
+        let path = dir.join(name);
+        std::fs::create_dir_all(&path).unwrap();
+        std::fs::write(path.join("data"), vec![0; size_bytes]).unwrap();
+        std::fs::File::open(&path)
+            .unwrap()
+            .set_modified(UNIX_EPOCH + Duration::from_secs(age_secs))
+            .unwrap();
+    }
+
+    fn in_use(cache: &DatasetCache, name: &str) -> Option<usize> {
+        cache.in_use.lock().unwrap().get(name).copied()
+    }
+
+    #[tokio::test]
+    async fn test_leases_count_the_jobs_using_a_dataset() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Arc::new(DatasetCache::new(dir.path().to_path_buf(), None));
+
+        let first = cache.acquire("imagenet").await.unwrap();
+        let second = cache.acquire("imagenet").await.unwrap();
+        assert_eq!(first.path, dir.path().join("imagenet"));
+        assert!(first.path.is_dir());
+        assert_eq!(in_use(&cache, "imagenet"), Some(2));
+
+        drop(first);
+        assert_eq!(in_use(&cache, "imagenet"), Some(1));
+        drop(second);
+        assert_eq!(in_use(&cache, "imagenet"), None);
+        // The dataset is kept for the jobs after them.
+        assert!(dir.path().join("imagenet").is_dir());
+    }
+
+    #[tokio::test]
+    async fn test_eviction_skips_datasets_in_use() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = cache(dir.path(), 10);
+        dataset(dir.path(), "oldest", 8, 1);
+        dataset(dir.path(), "older", 8, 2);
+        dataset(dir.path(), "newer", 8, 3);
+
+        // The least recently used dataset is the one being acquired, so the
+        // ones after it are evicted in its place.
+        let _oldest = cache.acquire("oldest").await.unwrap();
+        assert!(dir.path().join("oldest").is_dir());
+        assert!(!dir.path().join("older").exists());
+        assert!(!dir.path().join("newer").exists());
+
+        // Datasets in use stay, even when they alone are over the limit.
+        dataset(dir.path(), "large", 16, 0);
+        let _large = cache.acquire("large").await.unwrap();
+        let _fresh = cache.acquire("fresh").await.unwrap();
+        assert!(dir.path().join("oldest").is_dir());
+        assert!(dir.path().join("large").is_dir());
+        assert!(dir.path().join("fresh").is_dir());
+    }
+
+    #[tokio::test]
+    async fn test_acquire_rejects_names_outside_the_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Arc::new(DatasetCache::new(dir.path().join("datasets"), None));
+
+        for name in [
+            "",
+            ".",
+            "..",
+            "../escape",
+            "a/b",
+            "/etc",
+            ".evicting-imagenet",
+        ] {
+            let Err(e) = cache.acquire(name).await else {
+                panic!("{:?} was accepted", name);
+            };
+            assert_eq!(e.kind(), io::ErrorKind::InvalidInput);
+            assert_eq!(in_use(&cache, name), None);
+        }
+        assert!(!dir.path().join("escape").exists());
+        assert!(!dir.path().join("datasets").exists());
+    }
+
+    #[test]
+    fn test_new_removes_unfinished_evictions() {
+        let dir = tempfile::tempdir().unwrap();
+        dataset(dir.path(), ".evicting-imagenet", 8, 1);
+        std::fs::write(dir.path().join(".evicting-file"), "data").unwrap();
+        dataset(dir.path(), "cifar", 8, 1);
+
+        DatasetCache::new(dir.path().to_path_buf(), None);
+        assert!(!dir.path().join(".evicting-imagenet").exists());
+        assert!(!dir.path().join(".evicting-file").exists());
+        assert!(dir.path().join("cifar").is_dir());
+
+        // A cache directory that doesn't exist yet is fine too.
+        DatasetCache::new(dir.path().join("missing"), None);
+    }
+}
@@ -1,49 +1,163 @@
 use crate::{
     domain::agent::{
-        models::{HeartbeatRequest, JobInfo, JobStatus},
+        models::{
+            HeartbeatRequest, JobDetails, JobError, JobErrorKind, JobInfo, JobStatus, MetricSample,
+        },
+        outputs::OutputUploader,
         ports::{ControlPlaneApi, JobExecutor, SystemMonitor},
     },
+    errors::JobExecutorError,
 };
+use chrono::Utc;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tokio::{
-    sync::Notify,
+    sync::{
+        mpsc::{self, UnboundedReceiver},
+        Notify,
+    },
     task::JoinHandle,
     time::{self, MissedTickBehavior},
 };
 use uuid::Uuid;
 
+/// A job the agent has started, together with the GPUs it was given.
+struct AgentJob {
+    info: JobInfo,
+    handle: JoinHandle<()>,
+    gpu_ids: Vec<String>,
+}
+
+type Jobs = Arc<Mutex<HashMap<Uuid, AgentJob>>>;
+
+/// Job output is sent to the control plane once this much has been collected,
+/// or once it has waited for `LOG_FLUSH_INTERVAL`.
+const LOG_BATCH_BYTES: usize = 64 * 1024;
+const LOG_FLUSH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Sends a job's output to the control plane in batches, until the job has
+/// stopped writing any.
+async fn forward_logs<C: ControlPlaneApi>(
+    control_plane: Arc<C>,
+    job_id: Uuid,
+    mut output: UnboundedReceiver<String>,
+) {
+    let mut batch = String::new();
+    let mut flush = time::interval(LOG_FLUSH_INTERVAL);
+    flush.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        let (send, finished) = tokio::select! {
+            chunk = output.recv() => match chunk {
+                Some(chunk) => {
+                    batch.push_str(&chunk);
+                    (batch.len() >= LOG_BATCH_BYTES, false)
+                }
+                None => (true, true),
+            },
+            _ = flush.tick() => (true, false),
+        };
+
+        if send && !batch.is_empty() {
+            let logs = std::mem::take(&mut batch);
+            if let Err(e) = control_plane.post_logs(job_id, logs).await {
+                eprintln!("[JOB {}] Error sending logs: {}", job_id, e);
+            }
+        }
+        if finished {
+            break;
+        }
+    }
+}
+
+/// How often the resource usage of running jobs is sampled.
+const METRICS_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Samples the resource usage of a running job and sends it to the control
+/// plane, for as long as it is polled.
+async fn sample_metrics<C: ControlPlaneApi, S: SystemMonitor, J: JobExecutor>(
+    control_plane: Arc<C>,
+    system_monitor: Arc<S>,
+    executor: Arc<J>,
+    node_id: Uuid,
+    job_id: Uuid,
+    gpu_ids: Vec<String>,
+) {
+    let mut interval = time::interval(METRICS_INTERVAL);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        interval.tick().await;
+        // There's no container to sample while the image is being pulled.
+        let Ok(usage) = executor.get_usage(&job_id.to_string()).await else {
+            continue;
+        };
+        let gpus = system_monitor.get_gpu_usage(&gpu_ids).await.unwrap_or_else(|e| {
+            eprintln!("[JOB {}] Error reading GPU usage: {}", job_id, e);
+            Vec::new()
+        });
+        let sample = MetricSample {
+            node_id,
+            sampled_at: Utc::now(),
+            cpu_millicores: usage.cpu_millicores,
+            memory_mb: usage.memory_mb,
+            gpus,
+        };
+        if let Err(e) = control_plane.post_metrics(job_id, vec![sample]).await {
+            eprintln!("[JOB {}] Error sending metrics: {}", job_id, e);
+        }
+    }
+}
+
 pub struct Daemon<C, S, J>
 where
     C: ControlPlaneApi + Clone + 'static,
-    S: SystemMonitor,
+    S: SystemMonitor + 'static,
     J: JobExecutor + Clone + 'static,
 {
     control_plane: Arc<C>,
     system_monitor: Arc<S>,
     job_executor: Arc<J>,
     heartbeat_interval: Duration,
-    current_job: Arc<Mutex<Option<JobInfo>>>,
-    job_handle: Arc<Mutex<Option<(Uuid, JoinHandle<()>)>>>,
+    jobs: Jobs,
+    /// Indices of the GPUs that are not in use by any job.
+    free_gpus: Arc<Mutex<Vec<String>>>,
     node_id: Uuid,
+    advertise_address: Option<String>,
+    labels: BTreeMap<String, String>,
+    /// Where the outputs of jobs are kept until they are uploaded.
+    outputs_dir: PathBuf,
     heartbeat_now: Arc<Notify>,
 }
 
 impl<C, S, J> Daemon<C, S, J>
 where
     C: ControlPlaneApi + Clone + 'static,
-    S: SystemMonitor,
+    S: SystemMonitor + 'static,
     J: JobExecutor + Clone + 'static,
 {
-    pub fn new(control_plane: C, system_monitor: S, job_executor: J, node_id: Uuid) -> Self {
+    pub fn new(
+        control_plane: C,
+        system_monitor: S,
+        job_executor: J,
+        node_id: Uuid,
+        advertise_address: Option<String>,
+        labels: BTreeMap<String, String>,
+        outputs_dir: PathBuf,
+    ) -> Self {
         Self {
             control_plane: Arc::new(control_plane),
             system_monitor: Arc::new(system_monitor),
             job_executor: Arc::new(job_executor),
             heartbeat_interval: Duration::from_secs(30),
-            current_job: Arc::new(Mutex::new(None)),
-            job_handle: Arc::new(Mutex::new(None)),
+            jobs: Arc::new(Mutex::new(HashMap::new())),
+            free_gpus: Arc::new(Mutex::new(Vec::new())),
             node_id,
+            advertise_address,
+            labels,
+            outputs_dir,
             heartbeat_now: Arc::new(Notify::new()),
         }
     }
@@ -58,6 +172,9 @@ where
             .map_err(|e| anyhow::Error::new(e).context("Failed to get node resources"))?;
         println!("[DAEMON] Discovered resources: {:?}", resources);
 
+        let gpu_count: i32 = resources.gpus.iter().map(|gpu| gpu.count).sum();
+        *self.free_gpus.lock().unwrap() = (0..gpu_count).map(|i| i.to_string()).collect();
+
         let mut interval = time::interval(self.heartbeat_interval);
         interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
 
@@ -71,12 +188,20 @@ where
                 }
             }
 
-            let current_job_info = self.current_job.lock().unwrap().clone();
+            let jobs = self
+                .jobs
+                .lock()
+                .unwrap()
+                .values()
+                .map(|job| job.info.clone())
+                .collect();
             let request = HeartbeatRequest {
                 memory_info: resources.memory_mb,
                 cpu_info: resources.cpu.clone(),
                 gpu_info: resources.gpus.first().cloned(),
-                job_info: current_job_info,
+                jobs,
+                address: self.advertise_address.clone(),
+                labels: self.labels.clone(),
             };
 
             let response = self
@@ -85,88 +210,183 @@ where
                 .await;
 
             match response {
-                Ok(response) => {
-                    let mut current_job_guard = self.current_job.lock().unwrap();
-                    let assigned_job_id = response.assigned_job.as_ref().map(|j| j.id);
-                    let current_job_id = current_job_guard.as_ref().map(|j| j.current_job_id);
-
-                    if current_job_id != assigned_job_id {
-                        println!(
-                            "[DAEMON] Reconciliation needed. Current: {:?}, Assigned: {:?}",
-                            current_job_id, assigned_job_id
-                        );
-
-                        if let Some((job_id, handle)) = self.job_handle.lock().unwrap().take() {
-                            println!("[DAEMON] Aborting previous job {}.", job_id);
-                            handle.abort();
-                            let job_executor = self.job_executor.clone();
-                            tokio::spawn(async move {
-                                if let Err(e) = job_executor.stop_job(&job_id.to_string()).await {
-                                    eprintln!(
-                                        "[DAEMON] Error stopping job container for job {}: {}",
-                                        job_id, e
-                                    );
-                                }
-                            });
-                        }
+                Ok(response) => self.reconcile(response.assigned_jobs),
+                Err(e) => {
+                    eprintln!("[DAEMON] Error sending heartbeat: {}. Will retry.", e);
+                }
+            }
+        }
+    }
+
+    /// Brings the jobs running on this node in line with the jobs the control
+    /// plane has assigned to it. Jobs that are no longer assigned are stopped,
+    /// and newly assigned jobs are started. Finished jobs are kept around so
+    /// their final status is reported until the control plane unassigns them.
+    fn reconcile(&self, assigned_jobs: Vec<JobDetails>) {
+        let mut jobs = self.jobs.lock().unwrap();
+        let assigned_ids: HashSet<Uuid> = assigned_jobs.iter().map(|job| job.id).collect();
+
+        let unassigned: Vec<Uuid> = jobs
+            .keys()
+            .filter(|job_id| !assigned_ids.contains(job_id))
+            .copied()
+            .collect();
+        for job_id in unassigned {
+            let Some(job) = jobs.remove(&job_id) else {
+                continue;
+            };
+            println!("[DAEMON] Job {} is no longer assigned, stopping it.", job_id);
+            job.handle.abort();
+            let job_executor = self.job_executor.clone();
+            let free_gpus = self.free_gpus.clone();
+            let job_outputs_dir = self.outputs_dir.join(job_id.to_string());
+            tokio::spawn(async move {
+                if let Err(e) = job_executor.stop_job(&job_id.to_string()).await {
+                    eprintln!(
+                        "[DAEMON] Error stopping job container for job {}: {}",
+                        job_id, e
+                    );
+                }
+                // Whatever the job left behind has been uploaded by now, or
+                // was cut short by the job being cancelled.
+                let _ = tokio::fs::remove_dir_all(job_outputs_dir).await;
+                // Only hand the GPUs to other jobs once the container is gone.
+                free_gpus.lock().unwrap().extend(job.gpu_ids);
+            });
+        }
+
+        for assigned_job in assigned_jobs {
+            let job_id = assigned_job.id;
+            if jobs.contains_key(&job_id) {
+                continue;
+            }
+
+            let gpu_count = usize::try_from(assigned_job.gpu_count).unwrap_or(0);
+            let gpu_ids = {
+                let mut free_gpus = self.free_gpus.lock().unwrap();
+                if free_gpus.len() < gpu_count {
+                    println!(
+                        "[DAEMON] Waiting for {} GPU(s) to free up before starting job {}.",
+                        gpu_count, job_id
+                    );
+                    continue;
+                }
+                let remaining = free_gpus.len() - gpu_count;
+                free_gpus.split_off(remaining)
+            };
+
+            println!("[DAEMON] Starting new job with ID: {}", job_id);
+            let executor = self.job_executor.clone();
+            let control_plane = self.control_plane.clone();
+            let jobs_clone = self.jobs.clone();
+            let heartbeat_now_clone = self.heartbeat_now.clone();
+            let task_gpu_ids = gpu_ids.clone();
+            let metrics = sample_metrics(
+                control_plane.clone(),
+                self.system_monitor.clone(),
+                executor.clone(),
+                self.node_id,
+                job_id,
+                gpu_ids.clone(),
+            );
+            let mut uploader =
+                OutputUploader::new(control_plane.clone(), &assigned_job, &self.outputs_dir);
+
+            let handle = tokio::spawn(async move {
+                let set_status = |status: JobStatus| {
+                    if let Some(job) = jobs_clone.lock().unwrap().get_mut(&job_id) {
+                        job.info.status = status;
+                    }
+                };
+
+                set_status(JobStatus::Starting);
+                heartbeat_now_clone.notify_one();
+                set_status(JobStatus::Running);
+
+                let (logs, output) = mpsc::unbounded_channel();
+                let forwarder = tokio::spawn(forward_logs(control_plane, job_id, output));
+
+                // Outputs with an upload interval are uploaded, and the job's
+                // resource usage sampled, while the job runs, which stops as
+                // soon as it has finished.
+                let result = tokio::select! {
+                    result = executor.run_job(assigned_job, task_gpu_ids, logs) => result,
+                    _ = uploader.upload_checkpoints() => unreachable!(),
+                    _ = metrics => unreachable!(),
+                };
 
-                        if let Some(assigned_job) = response.assigned_job {
-                            let job_id = assigned_job.id;
-                            println!("[DAEMON] Starting new job with ID: {}", job_id);
-                            let new_job_info = JobInfo {
-                                current_job_id: job_id,
-                                status: JobStatus::Acknowledged,
-                            };
-                            *current_job_guard = Some(new_job_info);
-
-                            let executor = self.job_executor.clone();
-                            let current_job_clone = self.current_job.clone();
-                            let job_handle_clone = self.job_handle.clone();
-                            let resources_clone = resources.clone();
-                            let heartbeat_now_clone = self.heartbeat_now.clone();
-
-                            let handle = tokio::spawn(async move {
-                                if let Some(job_info) = &mut *current_job_clone.lock().unwrap() {
-                                    job_info.status = JobStatus::Starting;
-                                }
-                                heartbeat_now_clone.notify_one();
-
-                                if let Some(job_info) = &mut *current_job_clone.lock().unwrap() {
-                                    job_info.status = JobStatus::Running;
-                                }
-
-                                let final_status =
-                                    match executor.run_job(assigned_job, &resources_clone).await {
-                                        Ok(0) => {
-                                            println!("[JOB {}] Execution finished successfully.", job_id);
-                                            JobStatus::Succeeded
-                                        }
-                                        Ok(exit_code) => {
-                                            eprintln!("[JOB {}] Execution finished with a non-zero exit code: {}", job_id, exit_code);
-                                            JobStatus::Failed
-                                        }
-                                        Err(e) => {
-                                            eprintln!("[JOB {}] Execution failed: {}", job_id, e);
-                                            JobStatus::Failed
-                                        }
-                                    };
-
-                                if let Some(job_info) = &mut *current_job_clone.lock().unwrap() {
-                                    job_info.status = final_status;
-                                }
-                                heartbeat_now_clone.notify_one();
-                            });
-                            *job_handle_clone.lock().unwrap() = Some((job_id, handle));
+                let mut exit_code = None;
+                let mut oom_killed = false;
+                let mut error = None;
+                let final_status = match result {
+                    Ok(exit) => {
+                        exit_code = i32::try_from(exit.exit_code).ok();
+                        oom_killed = exit.oom_killed;
+                        if exit.exit_code == 0 && !exit.oom_killed {
+                            println!("[JOB {}] Execution finished successfully.", job_id);
+                            JobStatus::Succeeded
                         } else {
-                            *current_job_guard = None;
-                            println!("[DAEMON] Node is now available.");
+                            eprintln!(
+                                "[JOB {}] Execution finished with exit code {}{}.",
+                                job_id,
+                                exit.exit_code,
+                                if exit.oom_killed { " after running out of memory" } else { "" }
+                            );
+                            JobStatus::Failed
                         }
                     }
+                    Err(e @ JobExecutorError::TimedOut(_)) => {
+                        eprintln!("[JOB {}] Execution stopped: {}", job_id, e);
+                        JobStatus::TimedOut
+                    }
+                    Err(e) => {
+                        eprintln!("[JOB {}] Execution failed: {:#}", job_id, e);
+                        let kind = match e {
+                            JobExecutorError::ImagePull(_) => JobErrorKind::ImagePull,
+                            _ => JobErrorKind::Executor,
+                        };
+                        let message = match e {
+                            JobExecutorError::Unknown(e) => format!("{:#}", e),
+                            e => e.to_string(),
+                        };
+                        error = Some(JobError { kind, message });
+                        JobStatus::Failed
+                    }
+                };
+
+                // Send the last of the output before reporting the job as
+                // finished, so that followers of its logs see all of it.
+                let _ = forwarder.await;
+
+                // Likewise upload the job's outputs, so that they are
+                // complete once it shows up as finished.
+                if !uploader.upload_all().await {
+                    eprintln!("[JOB {}] Some outputs could not be uploaded.", job_id);
                 }
-                Err(e) => {
-                    eprintln!("[DAEMON] Error sending heartbeat: {}. Will retry.", e);
+
+                if let Some(job) = jobs_clone.lock().unwrap().get_mut(&job_id) {
+                    job.info.exit_code = exit_code;
+                    job.info.oom_killed = oom_killed;
+                    job.info.error = error;
                 }
-            }
+                set_status(final_status);
+                heartbeat_now_clone.notify_one();
+            });
+
+            jobs.insert(
+                job_id,
+                AgentJob {
+                    info: JobInfo {
+                        job_id,
+                        status: JobStatus::Acknowledged,
+                        exit_code: None,
+                        oom_killed: false,
+                        error: None,
+                    },
+                    handle,
+                    gpu_ids,
+                },
+            );
         }
     }
-}
\ No newline at end of file
+}
@@ -1,3 +1,5 @@
+pub mod cache;
 pub mod daemon;
 pub mod models;
+pub mod outputs;
 pub mod ports;
\ No newline at end of file
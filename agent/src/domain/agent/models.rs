@@ -1,3 +1,9 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -122,19 +128,91 @@ pub struct HeartbeatRequest {
     pub memory_info: i32,
     pub cpu_info: Cpu,
     pub gpu_info: Option<Gpu>,
-    pub job_info: Option<JobInfo>,
+    /// Every job the agent has started and not yet been unassigned from.
+    pub jobs: Vec<JobInfo>,
+    pub address: Option<String>,
+    /// Labels describing the node, which jobs can select nodes by.
+    pub labels: BTreeMap<String, String>,
+}
+
+/// A batch of a job's output, sent to the control plane while the job runs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostLogsRequest {
+    pub logs: String,
+}
+
+/// Resource usage samples of a job, sent to the control plane while it runs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostMetricsRequest {
+    pub samples: Vec<MetricSample>,
 }
 
-/// The response from a heartbeat call, which may include a job to run.
+/// The response from a heartbeat call, listing every job assigned to the node.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HeartbeatResponse {
-    pub assigned_job: Option<JobDetails>,
+    #[serde(default)]
+    pub assigned_jobs: Vec<JobDetails>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct JobInfo {
-    pub current_job_id: Uuid,
+    pub job_id: Uuid,
     pub status: JobStatus,
+    /// The exit code of the job's container, once it has exited.
+    pub exit_code: Option<i32>,
+    /// Whether the job's container was killed for running out of memory.
+    pub oom_killed: bool,
+    /// Why the job couldn't be run, if its container never got to exit.
+    pub error: Option<JobError>,
+}
+
+/// The CPU and memory a job's container is using.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ContainerUsage {
+    pub cpu_millicores: Option<i32>,
+    pub memory_mb: Option<i64>,
+}
+
+/// A job's resource usage on this node at one point in time.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MetricSample {
+    pub node_id: Uuid,
+    pub sampled_at: DateTime<Utc>,
+    pub cpu_millicores: Option<i32>,
+    pub memory_mb: Option<i64>,
+    pub gpus: Vec<GpuSample>,
+}
+
+/// The usage of one of the GPUs reserved for a job.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GpuSample {
+    pub index: i32,
+    pub utilization_percent: i32,
+    pub memory_used_mb: i64,
+}
+
+/// How a job's container exited.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ContainerExit {
+    pub exit_code: i64,
+    /// Whether the kernel killed the container for running out of memory.
+    pub oom_killed: bool,
+}
+
+/// A job that failed before its container could run to completion.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct JobError {
+    pub kind: JobErrorKind,
+    pub message: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobErrorKind {
+    /// The job's image could not be pulled, e.g. because it doesn't exist.
+    ImagePull,
+    /// Anything else that kept the job from running.
+    Executor,
 }
 
 /// The full details of a job, fetched by the agent when assigned.
@@ -142,6 +220,137 @@ pub struct JobInfo {
 pub struct JobDetails {
     pub id: Uuid,
     pub docker_uri: String,
+    /// Replaces the image's entrypoint. An empty list clears it.
+    #[serde(default)]
+    pub entrypoint: Option<Vec<String>>,
+    /// Replaces the image's command.
+    #[serde(default)]
+    pub command: Option<Vec<String>>,
+    /// Appended to the command, or replacing the image's command if there
+    /// is none.
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// The directory the job starts in.
+    #[serde(default)]
+    pub workdir: Option<String>,
+    /// The user the job runs as.
+    #[serde(default)]
+    pub user: Option<String>,
+    /// The number of GPUs on this node reserved for the job.
+    #[serde(default)]
+    pub gpu_count: i32,
+    /// Present when the job spans multiple nodes.
+    #[serde(default)]
+    pub distributed: Option<DistributedConfig>,
+    /// How long the job's container may run before it is stopped, in seconds.
+    #[serde(default)]
+    pub max_runtime_secs: Option<i64>,
+    /// Extra environment variables for the job's container, e.g. the
+    /// parameters of a job array.
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    /// Directories in the job's container to upload as artifacts.
+    #[serde(default)]
+    pub outputs: Vec<JobOutput>,
+    /// Storage on this node to mount into the job's container.
+    #[serde(default)]
+    pub volumes: Vec<JobVolume>,
+}
+
+impl JobDetails {
+    /// The command to start the container with, `None` to keep the image's.
+    pub fn container_command(&self) -> Option<Vec<String>> {
+        match &self.command {
+            Some(command) => Some(command.iter().chain(&self.args).cloned().collect()),
+            None if !self.args.is_empty() => Some(self.args.clone()),
+            None => None,
+        }
+    }
+
+    /// The directory on the host each of the job's outputs is kept in,
+    /// below `outputs_dir`.
+    pub fn output_dirs(&self, outputs_dir: &Path) -> Vec<PathBuf> {
+        let job_dir = outputs_dir.join(self.id.to_string());
+        (0..self.outputs.len())
+            .map(|index| job_dir.join(index.to_string()))
+            .collect()
+    }
+}
+
+/// A directory in a job's container whose files are uploaded as artifacts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobOutput {
+    /// The absolute path of the directory in the container.
+    pub path: String,
+    /// How often to upload new and changed files while the job runs, in
+    /// seconds. Only uploaded once the job has finished if not set.
+    #[serde(default)]
+    pub upload_interval_secs: Option<u64>,
+}
+
+/// Where the files of a volume come from.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum VolumeKind {
+    /// A directory on this node.
+    HostPath,
+    /// A Docker volume on this node.
+    Named,
+    /// An entry of this node's dataset cache.
+    Cache,
+}
+
+/// Storage on the node mounted into a job's container.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobVolume {
+    pub kind: VolumeKind,
+    /// The directory on the node for host paths, and the name of the volume
+    /// or cache entry otherwise.
+    pub source: String,
+    /// The absolute path the volume is mounted at in the container.
+    pub target: String,
+    #[serde(default)]
+    pub read_only: bool,
+}
+
+/// A file uploaded from a job's outputs, by its path in the container.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArtifactFile {
+    pub path: String,
+    pub size_bytes: u64,
+}
+
+/// The files of a job's outputs the agent wants to upload.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PresignUploadsRequest {
+    pub paths: Vec<String>,
+}
+
+/// Where to upload a file from a job's outputs to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArtifactUpload {
+    pub path: String,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PresignUploadsResponse {
+    pub uploads: Vec<ArtifactUpload>,
+}
+
+/// The files the agent has uploaded from a job's outputs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordArtifactsRequest {
+    pub artifacts: Vec<ArtifactFile>,
+}
+
+/// Rendezvous details for a job that runs across multiple nodes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DistributedConfig {
+    pub rank: i32,
+    pub world_size: i32,
+    pub coordinator_address: String,
+    pub coordinator_port: u16,
 }
 
 /// The status of a job, reported by the agent.
@@ -153,4 +362,7 @@ pub enum JobStatus {
     Running,
     Succeeded,
     Failed,
+    /// The job ran for longer than its maximum runtime and was stopped.
+    #[serde(rename = "timed_out")]
+    TimedOut,
 }
\ No newline at end of file
@@ -0,0 +1,436 @@
+use crate::{
+    domain::agent::{
+        models::{ArtifactFile, JobDetails},
+        ports::ControlPlaneApi,
+    },
+    errors::ControlPlaneApiError,
+};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::time::{self, Instant};
+use uuid::Uuid;
+
+/// How many files are presigned and recorded per request.
+const UPLOAD_BATCH_SIZE: usize = 100;
+
+/// One of a job's outputs, as seen from the host.
+struct Output {
+    /// The directory mounted into the job's container.
+    host_dir: PathBuf,
+    /// The artifact path of the directory, which its files' paths start with.
+    prefix: String,
+    upload_interval: Option<Duration>,
+}
+
+/// A file found in an output directory.
+struct OutputFile {
+    local_path: PathBuf,
+    path: String,
+    size_bytes: u64,
+    modified: SystemTime,
+}
+
+/// Uploads the files of a job's outputs to the artifact store, skipping the
+/// ones that haven't changed since they were last uploaded.
+pub struct OutputUploader<C: ControlPlaneApi> {
+    control_plane: Arc<C>,
+    job_id: Uuid,
+    outputs: Vec<Output>,
+    /// The size and modification time of every file when it was uploaded.
+    uploaded: HashMap<PathBuf, (u64, SystemTime)>,
+}
+
+impl<C: ControlPlaneApi> OutputUploader<C> {
+    pub fn new(control_plane: Arc<C>, job: &JobDetails, outputs_dir: &Path) -> Self {
+        // Nodes other than the coordinator of a multi-node job keep their
+        // files apart, as every node writes to the same paths.
+        let rank = match &job.distributed {
+            Some(distributed) if distributed.rank > 0 => format!("rank-{}/", distributed.rank),
+            _ => String::new(),
+        };
+        let outputs = job
+            .outputs
+            .iter()
+            .zip(job.output_dirs(outputs_dir))
+            .map(|(output, host_dir)| Output {
+                host_dir,
+                prefix: format!("{}{}", rank, output.path.trim_matches('/')),
+                upload_interval: output.upload_interval_secs.map(Duration::from_secs),
+            })
+            .collect();
+        Self {
+            control_plane,
+            job_id: job.id,
+            outputs,
+            uploaded: HashMap::new(),
+        }
+    }
+
+    /// Uploads the new and changed files of every output. Returns whether
+    /// all of them were uploaded.
+    pub async fn upload_all(&mut self) -> bool {
+        let mut uploaded = true;
+        for index in 0..self.outputs.len() {
+            uploaded &= self.upload(index).await;
+        }
+        uploaded
+    }
+
+    /// Uploads the new and changed files of an output. Returns whether all
+    /// of them were uploaded.
+    async fn upload(&mut self, index: usize) -> bool {
+        let output = &self.outputs[index];
+        let (host_dir, prefix) = (output.host_dir.clone(), output.prefix.clone());
+        let files = tokio::task::spawn_blocking(move || list_files(&host_dir, &prefix))
+            .await
+            .map_err(std::io::Error::other)
+            .and_then(|files| files);
+        let files = match files {
+            Ok(files) => files,
+            Err(e) => {
+                eprintln!("[JOB {}] Error reading outputs: {}", self.job_id, e);
+                return false;
+            }
+        };
+        let changed: Vec<OutputFile> = files
+            .into_iter()
+            .filter(|file| {
+                self.uploaded.get(&file.local_path) != Some(&(file.size_bytes, file.modified))
+            })
+            .collect();
+
+        let mut uploaded = true;
+        for batch in changed.chunks(UPLOAD_BATCH_SIZE) {
+            if let Err(e) = self.upload_batch(batch).await {
+                eprintln!("[JOB {}] Error uploading outputs: {}", self.job_id, e);
+                uploaded = false;
+            }
+        }
+        uploaded
+    }
+
+    /// Uploads the outputs that have an upload interval whenever it comes
+    /// due. Never returns, it runs alongside the job until dropped.
+    pub async fn upload_checkpoints(&mut self) {
+        let mut due: Vec<(usize, Duration, Instant)> = self
+            .outputs
+            .iter()
+            .enumerate()
+            .filter_map(|(index, output)| {
+                let interval = output.upload_interval?;
+                Some((index, interval, Instant::now() + interval))
+            })
+            .collect();
+
+        while let Some(next) = due.iter().map(|(_, _, at)| *at).min() {
+            time::sleep_until(next).await;
+            for (index, interval, at) in due.iter_mut() {
+                if *at <= Instant::now() {
+                    self.upload(*index).await;
+                    *at = Instant::now() + *interval;
+                }
+            }
+        }
+        std::future::pending().await
+    }
+
+    async fn upload_batch(&mut self, files: &[OutputFile]) -> Result<(), ControlPlaneApiError> {
+        let paths = files.iter().map(|file| file.path.clone()).collect();
+        let uploads = self
+            .control_plane
+            .presign_artifact_uploads(self.job_id, paths)
+            .await?;
+
+        let mut recorded = Vec::new();
+        let mut result = Ok(());
+        for (file, upload) in files.iter().zip(uploads) {
+            match self
+                .control_plane
+                .upload_artifact(&upload.url, &file.local_path)
+                .await
+            {
+                Ok(()) => {
+                    self.uploaded
+                        .insert(file.local_path.clone(), (file.size_bytes, file.modified));
+                    recorded.push(ArtifactFile {
+                        path: file.path.clone(),
+                        size_bytes: file.size_bytes,
+                    });
+                }
+                Err(e) => {
+                    eprintln!("[JOB {}] Error uploading {}: {}", self.job_id, file.path, e);
+                    result = Err(e);
+                }
+            }
+        }
+
+        if !recorded.is_empty() {
+            self.control_plane
+                .record_artifacts(self.job_id, recorded)
+                .await?;
+        }
+        result
+    }
+}
+
+/// Lists the regular files below an output directory along with their
+/// artifact paths. Symbolic links are skipped, as the job could point them
+/// anywhere on the host.
+fn list_files(dir: &Path, prefix: &str) -> std::io::Result<Vec<OutputFile>> {
+    let mut files = Vec::new();
+    // The job may not have got as far as creating its container.
+    if !dir.exists() {
+        return Ok(files);
+    }
+    let mut dirs = vec![(dir.to_path_buf(), prefix.to_string())];
+    while let Some((dir, prefix)) = dirs.pop() {
+        for entry in std::fs::read_dir(&dir)? {
+            let entry = entry?;
+            let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+                eprintln!("[OUTPUTS] Skipping {:?}, its name is not valid UTF-8", entry.path());
+                continue;
+            };
+            let path = format!("{}/{}", prefix, name);
+            let metadata = entry.metadata()?;
+            if metadata.is_dir() {
+                dirs.push((entry.path(), path));
+            } else if metadata.is_file() {
+                files.push(OutputFile {
+                    local_path: entry.path(),
+                    path,
+                    size_bytes: metadata.len(),
+                    modified: metadata.modified()?,
+                });
+            }
+        }
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::agent::models::{
+        ArtifactUpload, HeartbeatRequest, HeartbeatResponse, MetricSample,
+    };
+    use async_trait::async_trait;
+    use std::collections::HashSet;
+    use std::fs;
+    use std::sync::Mutex;
+
+    /// Hands out each file's artifact path as its upload URL, and fails the
+    /// uploads of the paths in `failing`.
+    #[derive(Default)]
+    struct FakeControlPlane {
+        failing: Mutex<HashSet<String>>,
+        uploaded: Mutex<Vec<String>>,
+        recorded: Mutex<Vec<String>>,
+    }
+
+    impl FakeControlPlane {
+        fn take_uploaded(&self) -> Vec<String> {
+            let mut uploaded = std::mem::take(&mut *self.uploaded.lock().unwrap());
+            uploaded.sort();
+            uploaded
+        }
+    }
+
+    #[async_trait]
+    impl ControlPlaneApi for FakeControlPlane {
+        async fn send_heartbeat(
+            &self,
+            _node_id: Uuid,
+            _req: HeartbeatRequest,
+        ) -> Result<HeartbeatResponse, ControlPlaneApiError> {
+            unimplemented!()
+        }
+
+        async fn get_job_details(&self, _job_id: Uuid) -> Result<JobDetails, ControlPlaneApiError> {
+            unimplemented!()
+        }
+
+        async fn post_logs(
+            &self,
+            _job_id: Uuid,
+            _logs: String,
+        ) -> Result<(), ControlPlaneApiError> {
+            unimplemented!()
+        }
+
+        async fn post_metrics(
+            &self,
+            _job_id: Uuid,
+            _samples: Vec<MetricSample>,
+        ) -> Result<(), ControlPlaneApiError> {
+            unimplemented!()
+        }
+
+        async fn presign_artifact_uploads(
+            &self,
+            _job_id: Uuid,
+            paths: Vec<String>,
+        ) -> Result<Vec<ArtifactUpload>, ControlPlaneApiError> {
+            Ok(paths
+                .into_iter()
+                .map(|path| ArtifactUpload {
+                    url: path.clone(),
+                    path,
+                })
+                .collect())
+        }
+
+        async fn upload_artifact(
+            &self,
+            url: &str,
+            file: &Path,
+        ) -> Result<(), ControlPlaneApiError> {
+            assert!(file.is_file());
+            if self.failing.lock().unwrap().contains(url) {
+                return Err(ControlPlaneApiError::InternalServerError);
+            }
+            self.uploaded.lock().unwrap().push(url.to_string());
+            Ok(())
+        }
+
+        async fn record_artifacts(
+            &self,
+            _job_id: Uuid,
+            artifacts: Vec<ArtifactFile>,
+        ) -> Result<(), ControlPlaneApiError> {
+            let mut recorded = self.recorded.lock().unwrap();
+            recorded.extend(artifacts.into_iter().map(|artifact| artifact.path));
+            Ok(())
+        }
+    }
+
+    fn job(outputs: serde_json::Value) -> JobDetails {
+        serde_json::from_value(serde_json::json!({
+            "id": Uuid::new_v4(),
+            "docker_uri": "trainer:latest",
+            "outputs": outputs,
+        }))
+        .unwrap()
+    }
+
+    fn write(path: &Path, contents: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn test_list_files_walks_nested_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        write(&dir.path().join("metrics.json"), "{}");
+        write(&dir.path().join("checkpoints/step-1/model.bin"), "weights");
+        fs::create_dir(dir.path().join("empty")).unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink("/etc/passwd", dir.path().join("link")).unwrap();
+
+        let mut files: Vec<(String, u64)> = list_files(dir.path(), "outputs")
+            .unwrap()
+            .into_iter()
+            .map(|file| (file.path, file.size_bytes))
+            .collect();
+        files.sort();
+        assert_eq!(
+            files,
+            vec![
+                ("outputs/checkpoints/step-1/model.bin".to_string(), 7),
+                ("outputs/metrics.json".to_string(), 2),
+            ]
+        );
+
+        // The job may not have created the directory yet.
+        assert!(list_files(&dir.path().join("missing"), "outputs")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_upload_all_skips_files_already_uploaded() {
+        let outputs_dir = tempfile::tempdir().unwrap();
+        let job = job(serde_json::json!([{ "path": "/outputs/" }]));
+        let host_dir = job.output_dirs(outputs_dir.path()).remove(0);
+        write(&host_dir.join("a.txt"), "a");
+        write(&host_dir.join("nested/b.txt"), "b");
+
+        let control_plane = Arc::new(FakeControlPlane::default());
+        let mut uploader = OutputUploader::new(control_plane.clone(), &job, outputs_dir.path());
+
+        assert!(uploader.upload_all().await);
+        assert_eq!(
+            control_plane.take_uploaded(),
+            vec!["outputs/a.txt", "outputs/nested/b.txt"]
+        );
+
+        // Nothing changed, so nothing is uploaded again.
+        assert!(uploader.upload_all().await);
+        assert!(control_plane.take_uploaded().is_empty());
+
+        // Only the changed and the new file are.
+        write(&host_dir.join("nested/b.txt"), "bb");
+        write(&host_dir.join("c.txt"), "c");
+        assert!(uploader.upload_all().await);
+        assert_eq!(
+            control_plane.take_uploaded(),
+            vec!["outputs/c.txt", "outputs/nested/b.txt"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_upload_batch_records_partial_uploads() {
+        let outputs_dir = tempfile::tempdir().unwrap();
+        let job = job(serde_json::json!([{ "path": "/outputs" }]));
+        let host_dir = job.output_dirs(outputs_dir.path()).remove(0);
+        write(&host_dir.join("a.txt"), "a");
+        write(&host_dir.join("b.txt"), "b");
+
+        let control_plane = Arc::new(FakeControlPlane::default());
+        control_plane
+            .failing
+            .lock()
+            .unwrap()
+            .insert("outputs/b.txt".to_string());
+        let mut uploader = OutputUploader::new(control_plane.clone(), &job, outputs_dir.path());
+
+        let mut files = list_files(&host_dir, "outputs").unwrap();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        assert!(uploader.upload_batch(&files).await.is_err());
+        // The file that made it is still recorded.
+        assert_eq!(
+            *control_plane.recorded.lock().unwrap(),
+            vec!["outputs/a.txt"]
+        );
+        assert_eq!(control_plane.take_uploaded(), vec!["outputs/a.txt"]);
+
+        // The failed file is tried again next time, the uploaded one isn't.
+        assert!(!uploader.upload_all().await);
+        assert!(control_plane.take_uploaded().is_empty());
+        control_plane.failing.lock().unwrap().clear();
+        assert!(uploader.upload_all().await);
+        assert_eq!(control_plane.take_uploaded(), vec!["outputs/b.txt"]);
+    }
+
+    #[tokio::test]
+    async fn test_upload_checkpoints_uploads_outputs_with_an_interval() {
+        let outputs_dir = tempfile::tempdir().unwrap();
+        let job = job(serde_json::json!([
+            { "path": "/checkpoints", "upload_interval_secs": 1 },
+            { "path": "/results" },
+        ]));
+        let host_dirs = job.output_dirs(outputs_dir.path());
+        write(&host_dirs[0].join("step-1.pt"), "weights");
+        write(&host_dirs[1].join("results.csv"), "loss");
+
+        let control_plane = Arc::new(FakeControlPlane::default());
+        let mut uploader = OutputUploader::new(control_plane.clone(), &job, outputs_dir.path());
+
+        let checkpoints = uploader.upload_checkpoints();
+        assert!(time::timeout(Duration::from_millis(1500), checkpoints)
+            .await
+            .is_err());
+        assert_eq!(control_plane.take_uploaded(), vec!["checkpoints/step-1.pt"]);
+    }
+}
@@ -1,8 +1,14 @@
+use std::path::Path;
+
 use crate::{
-    domain::agent::models::{HeartbeatRequest, HeartbeatResponse, JobDetails, NodeResources},
+    domain::agent::models::{
+        ArtifactFile, ArtifactUpload, ContainerExit, ContainerUsage, GpuSample, HeartbeatRequest,
+        HeartbeatResponse, JobDetails, MetricSample, NodeResources,
+    },
     errors::{ControlPlaneApiError, JobExecutorError, SystemMonitorError},
 };
 use async_trait::async_trait;
+use tokio::sync::mpsc::UnboundedSender;
 use uuid::Uuid;
 
 /// Port for interacting with the Lilac control plane API. update to do proper error handling
@@ -18,6 +24,35 @@ pub trait ControlPlaneApi: Send + Sync {
 
     /// Fetches the full details for an assigned job.
     async fn get_job_details(&self, job_id: Uuid) -> Result<JobDetails, ControlPlaneApiError>;
+
+    /// Appends output of a job to its logs on the control plane.
+    async fn post_logs(&self, job_id: Uuid, logs: String) -> Result<(), ControlPlaneApiError>;
+
+    /// Sends samples of a job's resource usage to the control plane.
+    async fn post_metrics(
+        &self,
+        job_id: Uuid,
+        samples: Vec<MetricSample>,
+    ) -> Result<(), ControlPlaneApiError>;
+
+    /// Gets URLs to upload files from a job's outputs to, by their path in
+    /// the container.
+    async fn presign_artifact_uploads(
+        &self,
+        job_id: Uuid,
+        paths: Vec<String>,
+    ) -> Result<Vec<ArtifactUpload>, ControlPlaneApiError>;
+
+    /// Uploads a file to a URL handed out by `presign_artifact_uploads`.
+    async fn upload_artifact(&self, url: &str, file: &Path) -> Result<(), ControlPlaneApiError>;
+
+    /// Records the files uploaded from a job's outputs, so they show up in
+    /// its artifacts.
+    async fn record_artifacts(
+        &self,
+        job_id: Uuid,
+        artifacts: Vec<ArtifactFile>,
+    ) -> Result<(), ControlPlaneApiError>;
 }
 
 /// Port for monitoring the local system's hardware resources.
@@ -25,16 +60,24 @@ pub trait ControlPlaneApi: Send + Sync {
 pub trait SystemMonitor: Send + Sync {
     /// Gathers information about the system's CPU, memory, and GPUs.
     async fn get_node_resources(&self) -> Result<NodeResources, SystemMonitorError>;
+    /// Reads the current utilization and memory use of the GPUs with the
+    /// given indices.
+    async fn get_gpu_usage(&self, gpu_ids: &[String]) -> Result<Vec<GpuSample>, SystemMonitorError>;
 }
 
 /// Port for executing jobs, typically in a containerized environment.
 #[async_trait]
 pub trait JobExecutor: Send + Sync {
-    /// Runs the specified job and returns the exit code.
+    /// Runs the specified job on the given GPUs and returns how its container
+    /// exited. The job's output is sent to `logs` as it is written.
     async fn run_job(
         &self,
         job_details: JobDetails,
-        resources: &NodeResources,
-    ) -> Result<i64, JobExecutorError>;
+        gpu_ids: Vec<String>,
+        logs: UnboundedSender<String>,
+    ) -> Result<ContainerExit, JobExecutorError>;
     async fn stop_job(&self, job_id: &str) -> Result<(), JobExecutorError>;
+    /// Reads the CPU and memory the job's container is using. Fails if the
+    /// container isn't running.
+    async fn get_usage(&self, job_id: &str) -> Result<ContainerUsage, JobExecutorError>;
 }
\ No newline at end of file
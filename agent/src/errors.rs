@@ -32,6 +32,8 @@ pub enum ConfigError {
     WriteFile,
     #[error("API key is set but empty. Please provide a valid API key.")]
     EmptyApiKey,
+    #[error("invalid label '{0}', expected key=value")]
+    InvalidLabel(String),
     #[error("unknown error")]
     Unknown(#[from] anyhow::Error),
 }
@@ -66,6 +68,12 @@ pub enum JobExecutorError {
     StopError,
     #[error("job failed with exit code {0}")]
     ExitCode(i64),
+    #[error("job exceeded its maximum runtime of {0}s")]
+    TimedOut(i64),
+    #[error("volume {0} is not allowed on this node")]
+    VolumeNotAllowed(String),
+    #[error("failed to pull image: {0}")]
+    ImagePull(String),
     #[error("unknown error")]
     Unknown(#[from] anyhow::Error),
 }
@@ -10,7 +10,13 @@ pub struct Cli {
 #[derive(Subcommand)]
 pub enum Commands {
     /// Submit a new training job
-    Submit(SubmitArgs),
+    Submit(Box<SubmitArgs>),
+    /// Explain why a job is or isn't running
+    Explain(ExplainArgs),
+    /// Print the logs of a job
+    Logs(LogsArgs),
+    /// List or download the artifacts of a job
+    Artifacts(ArtifactsArgs),
     /// Configure the Lilac CLI for submitting jobs
     Configure,
     /// Commands for the Lilac agent daemon
@@ -25,6 +31,15 @@ pub struct SubmitArgs {
     /// Docker image URI for the job
     #[arg(long)]
     pub docker_uri: Option<String>,
+    /// Run this instead of the image's entrypoint
+    #[arg(long)]
+    pub entrypoint: Option<String>,
+    /// Directory to start the job in, as an absolute path
+    #[arg(long)]
+    pub workdir: Option<String>,
+    /// User to run the job as, e.g. "1000:1000"
+    #[arg(long)]
+    pub user: Option<String>,
     /// ID of the queue to submit the job to
     #[arg(long)]
     pub queue_id: Option<String>,
@@ -37,9 +52,90 @@ pub struct SubmitArgs {
     /// Number of GPUs required
     #[arg(long)]
     pub gpu_count: Option<i32>,
+    /// GPU model required, e.g. "H100"
+    #[arg(long)]
+    pub gpu_model: Option<String>,
+    /// Minimum memory required per GPU in GB
+    #[arg(long)]
+    pub gpu_memory: Option<i32>,
+    /// Number of nodes to run the job across
+    #[arg(long, default_value_t = 1)]
+    pub nodes: i32,
+    /// ID of a job that has to succeed before this job starts. Can be repeated
+    #[arg(long)]
+    pub depends_on: Vec<String>,
+    /// How often to run the job at most if it fails, including the first attempt
+    #[arg(long)]
+    pub max_attempts: Option<i32>,
+    /// How long the job may run before it is stopped, in seconds
+    #[arg(long)]
+    pub max_runtime_secs: Option<i64>,
+    /// Sweep over a parameter, e.g. "LR=0.1,0.01". Submits one job for every
+    /// combination of values. Can be repeated
+    #[arg(long = "param", value_name = "NAME=VALUES")]
+    pub params: Vec<String>,
+    /// How many jobs of a sweep may run at the same time
+    #[arg(long)]
+    pub max_concurrent: Option<i32>,
+    /// Only run on nodes with this label, e.g. "network=infiniband". Can be repeated
+    #[arg(long, value_name = "KEY=VALUE")]
+    pub node_selector: Vec<String>,
+    /// Allow running on nodes with this taint, as "KEY" or "KEY=VALUE". Can be repeated
+    #[arg(long, value_name = "TAINT")]
+    pub tolerate: Vec<String>,
+    /// Set an environment variable in the job's container. Can be repeated
+    #[arg(long, value_name = "KEY=VALUE")]
+    pub env: Vec<String>,
+    /// Pass a secret to the job as an environment variable, e.g.
+    /// "WANDB_API_KEY=wandb". Can be repeated
+    #[arg(long, value_name = "KEY=SECRET")]
+    pub secret: Vec<String>,
+    /// Upload this directory of the job's container as artifacts once the job
+    /// has finished, and every SECS seconds while it runs if given, e.g.
+    /// "/checkpoints:600". Can be repeated
+    #[arg(long, value_name = "PATH[:SECS]")]
+    pub output: Vec<String>,
+    /// Mount a directory or Docker volume of the node, which its agent has to
+    /// allow, e.g. "/mnt/datasets:/data:ro". Can be repeated
+    #[arg(long, value_name = "SOURCE:TARGET[:ro]")]
+    pub volume: Vec<String>,
+    /// Mount a dataset from the node's cache, which is kept between jobs, e.g.
+    /// "imagenet:/data". Can be repeated
+    #[arg(long, value_name = "NAME:TARGET[:ro]")]
+    pub dataset: Vec<String>,
     /// Skip interactive prompts and submit directly
     #[arg(long, action)]
     pub non_interactive: bool,
+    /// Command to run instead of the image's, given after "--"
+    #[arg(last = true, value_name = "COMMAND")]
+    pub command: Vec<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct ExplainArgs {
+    /// ID of the job to explain
+    pub job_id: String,
+}
+
+#[derive(Args, Debug)]
+pub struct LogsArgs {
+    /// ID of the job to print the logs of
+    pub job_id: String,
+    /// Only print this many of the most recent lines
+    #[arg(long)]
+    pub tail: Option<i64>,
+    /// Keep printing new output until the job has finished
+    #[arg(short, long)]
+    pub follow: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct ArtifactsArgs {
+    /// ID of the job to list the artifacts of
+    pub job_id: String,
+    /// Download all of the job's artifacts into this directory
+    #[arg(long, value_name = "DIR")]
+    pub download: Option<std::path::PathBuf>,
 }
 
 #[derive(Args)]
@@ -1,13 +1,19 @@
 use crate::{
     config,
-    domain::agent::daemon::Daemon,
+    domain::agent::{cache::DatasetCache, daemon::Daemon, models::GpuModel},
     errors::CliError,
-    inbound::cli::SubmitArgs,
+    inbound::cli::{ArtifactsArgs, ExplainArgs, LogsArgs, SubmitArgs},
     outbound,
-    outbound::user_api::{ApiClient, GpuRequirement, ResourceRequirements, SubmitJobRequest},
+    outbound::user_api::{
+        ApiClient, ClusterOutcome, GpuRequirement, JobDefinition, JobDependency, JobOutput,
+        JobVolume, ParameterSpace,
+        RejectionReason, ResourceRequirements, RetryPolicy, SubmitJobArrayRequest,
+        SubmitJobRequest, Toleration, VolumeKind,
+    },
 };
 use dialoguer::{theme::ColorfulTheme, Confirm, Input, Select};
-use std::fs;
+use std::{collections::BTreeMap, fs, io::Write, sync::Arc};
+use strum::IntoEnumIterator;
 
 pub async fn start_agent(config: config::AgentConfig) -> Result<(), CliError> {
     println!("Initializing Lilac agent...");
@@ -15,8 +21,21 @@ pub async fn start_agent(config: config::AgentConfig) -> Result<(), CliError> {
     // 1. Initialize all the adapters.
     let control_plane_client = outbound::control_plane::ControlPlaneClient::new(config.clone());
     let system_monitor = outbound::system::HybridMonitor::new();
-    let docker_executor = outbound::docker::DockerExecutor::new(config.clone())
-        .map_err(|e| CliError::Unknown(e.into()))?;
+    let outputs_dir = match &config.outputs_dir {
+        Some(outputs_dir) => outputs_dir.clone(),
+        None => config::get_config_path("outputs")?,
+    };
+    let dataset_cache_dir = match &config.dataset_cache_dir {
+        Some(dataset_cache_dir) => dataset_cache_dir.clone(),
+        None => config::get_config_path("datasets")?,
+    };
+    let dataset_cache = DatasetCache::new(dataset_cache_dir, config.dataset_cache_max_gb);
+    let docker_executor = outbound::docker::DockerExecutor::new(
+        config.clone(),
+        outputs_dir.clone(),
+        Arc::new(dataset_cache),
+    )
+    .map_err(|e| CliError::Unknown(e.into()))?;
 
     // 2. Initialize and run the daemon.
     let daemon = Daemon::new(
@@ -24,12 +43,12 @@ pub async fn start_agent(config: config::AgentConfig) -> Result<(), CliError> {
         system_monitor,
         docker_executor,
         config.node_id,
+        config.advertise_address.clone(),
+        config.labels.clone(),
+        outputs_dir,
     );
 
-    daemon
-        .run()
-        .await
-        .map_err(|e| CliError::Unknown(e.into()))?;
+    daemon.run().await.map_err(CliError::Unknown)?;
     Ok(())
 }
 
@@ -79,11 +98,34 @@ pub async fn configure_agent(config: config::AgentConfig) -> Result<(), CliError
         .with_initial_text(config.cluster_api_key)
         .interact_text()?;
 
+    let advertise_address: String = Input::with_theme(&theme)
+        .with_prompt("Enter the address other nodes can reach this node on (optional)")
+        .with_initial_text(config.advertise_address.unwrap_or_default())
+        .allow_empty(true)
+        .interact_text()?;
+
+    let labels: String = Input::with_theme(&theme)
+        .with_prompt("Enter labels for this node as key=value pairs, separated by commas (optional)")
+        .with_initial_text(config::format_labels(&config.labels))
+        .allow_empty(true)
+        .interact_text()?;
+
     let mut new_config = config::AgentConfig {
         api_endpoint,
         cluster_api_key,
         node_id: config.node_id,
         private_registry: None,
+        advertise_address: if advertise_address.is_empty() {
+            None
+        } else {
+            Some(advertise_address)
+        },
+        labels: config::parse_labels(&labels)?,
+        outputs_dir: config.outputs_dir,
+        allowed_host_paths: config.allowed_host_paths,
+        allowed_volumes: config.allowed_volumes,
+        dataset_cache_dir: config.dataset_cache_dir,
+        dataset_cache_max_gb: config.dataset_cache_max_gb,
     };
 
     if Confirm::with_theme(&theme)
@@ -129,6 +171,40 @@ pub async fn submit_job(config: config::UserConfig, args: &SubmitArgs) -> Result
     {
         return Err(CliError::InvalidArguments);
     }
+    let params = parse_params(&args.params)?;
+    let env = parse_assignments(&args.env)?;
+    let secrets = parse_assignments(&args.secret)?;
+    let outputs = parse_outputs(&args.output)?;
+    let mut volumes = args
+        .volume
+        .iter()
+        .map(|volume| {
+            let kind = if volume.starts_with('/') {
+                VolumeKind::HostPath
+            } else {
+                VolumeKind::Named
+            };
+            parse_volume(kind, volume)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    for dataset in &args.dataset {
+        volumes.push(parse_volume(VolumeKind::Cache, dataset)?);
+    }
+    let node_selector = config::parse_labels(&args.node_selector.join(","))?;
+    let tolerations: Vec<Toleration> = args
+        .tolerate
+        .iter()
+        .map(|taint| match taint.split_once('=') {
+            Some((key, value)) => Toleration {
+                key: key.to_string(),
+                value: Some(value.to_string()),
+            },
+            None => Toleration {
+                key: taint.clone(),
+                value: None,
+            },
+        })
+        .collect();
 
     let theme = ColorfulTheme::default();
     let client = ApiClient::new(config.clone());
@@ -153,7 +229,7 @@ pub async fn submit_job(config: config::UserConfig, args: &SubmitArgs) -> Result
     let selected_queue = if let Some(queue_id) = &args.queue_id {
         queues
             .iter()
-            .find(|q| q.id.to_string() == *queue_id)
+            .find(|q| q.id == *queue_id)
             .ok_or_else(|| CliError::InvalidArguments)?
             .clone()
     } else {
@@ -183,23 +259,49 @@ pub async fn submit_job(config: config::UserConfig, args: &SubmitArgs) -> Result
     };
 
     let mut gpu_count: Option<i32> = args.gpu_count;
+    let mut gpu_model: Option<String> = args.gpu_model.clone();
 
-    if !args.non_interactive && gpu_count.is_none() {
-        if Confirm::with_theme(&theme)
+    if !args.non_interactive
+        && gpu_count.is_none()
+        && Confirm::with_theme(&theme)
             .with_prompt("Do you require GPUs?")
             .default(false)
             .interact()?
-        {
-            let count: i32 = Input::with_theme(&theme)
-                .with_prompt("How many GPUs?")
-                .interact_text()?;
-            gpu_count = Some(count);
+    {
+        let count: i32 = Input::with_theme(&theme)
+            .with_prompt("How many GPUs?")
+            .interact_text()?;
+        gpu_count = Some(count);
+
+        if gpu_model.is_none() {
+            let mut models = vec!["Any".to_string()];
+            models.extend(GpuModel::iter().map(|model| model.to_string()));
+            let model_selection = Select::with_theme(&theme)
+                .with_prompt("Which GPU model?")
+                .items(&models)
+                .default(0)
+                .interact()?;
+            if model_selection > 0 {
+                gpu_model = Some(models[model_selection].clone());
+            }
         }
     }
 
     println!("\nJob Summary:");
     println!("- Name: {}", name);
     println!("- Docker Image: {}", docker_uri);
+    if let Some(entrypoint) = &args.entrypoint {
+        println!("- Entrypoint: {}", entrypoint);
+    }
+    if !args.command.is_empty() {
+        println!("- Command: {}", args.command.join(" "));
+    }
+    if let Some(workdir) = &args.workdir {
+        println!("- Working directory: {}", workdir);
+    }
+    if let Some(user) = &args.user {
+        println!("- User: {}", user);
+    }
     println!(
         "- Queue: {} ({})",
         selected_queue.name, selected_queue.id
@@ -207,42 +309,138 @@ pub async fn submit_job(config: config::UserConfig, args: &SubmitArgs) -> Result
     println!("- CPU: {}m", requested_cpu);
     println!("- Memory: {}MB", requested_memory);
     if let Some(count) = gpu_count {
-        println!("- GPUs: {} x any", count);
+        println!(
+            "- GPUs: {} x {}",
+            count,
+            gpu_model.as_deref().unwrap_or("any")
+        );
+        if let Some(memory) = args.gpu_memory {
+            println!("- GPU Memory: at least {}GB each", memory);
+        }
+    }
+    if args.nodes > 1 {
+        println!("- Nodes: {}", args.nodes);
+    }
+    if !args.depends_on.is_empty() {
+        println!("- Depends on: {}", args.depends_on.join(", "));
+    }
+    if let Some(max_attempts) = args.max_attempts {
+        println!("- Max attempts: {}", max_attempts);
+    }
+    if let Some(max_runtime_secs) = args.max_runtime_secs {
+        println!("- Max runtime: {}s", max_runtime_secs);
+    }
+    if !node_selector.is_empty() {
+        println!("- Node selector: {}", config::format_labels(&node_selector));
+    }
+    if !args.tolerate.is_empty() {
+        println!("- Tolerates: {}", args.tolerate.join(", "));
+    }
+    if !env.is_empty() {
+        println!("- Environment: {}", config::format_labels(&env));
+    }
+    for (key, secret) in &secrets {
+        println!("- Secret: {} from '{}'", key, secret);
+    }
+    for output in &outputs {
+        match output.upload_interval_secs {
+            Some(secs) => println!("- Output: {} (uploaded every {}s)", output.path, secs),
+            None => println!("- Output: {}", output.path),
+        }
+    }
+    for volume in &volumes {
+        let mode = if volume.read_only { " (read-only)" } else { "" };
+        match volume.kind {
+            VolumeKind::Cache => {
+                println!("- Dataset: {} at {}{}", volume.source, volume.target, mode)
+            }
+            _ => println!("- Volume: {} at {}{}", volume.source, volume.target, mode),
+        }
+    }
+    if !params.is_empty() {
+        let size: usize = params.values().map(Vec::len).product();
+        println!("- Sweep: {} jobs", size);
+        for (name, values) in &params {
+            println!("  - {}: {}", name, values.join(", "));
+        }
+        if let Some(max_concurrent) = args.max_concurrent {
+            println!("  - At most {} running at once", max_concurrent);
+        }
     }
 
-    if !args.non_interactive {
-        if !Confirm::with_theme(&theme)
+    if !args.non_interactive
+        && !Confirm::with_theme(&theme)
             .with_prompt("Proceed with job submission?")
             .default(true)
             .interact()?
-        {
-            println!("Submission cancelled.");
-            return Ok(());
-        }
+    {
+        println!("Submission cancelled.");
+        return Ok(());
     }
 
     println!("\n📨 Submitting job to the Lilac scheduler...");
-    let gpus = if let Some(count) = gpu_count {
-        Some(GpuRequirement {
-            count,
-            model: None,
-            memory_gb: None,
-        })
-    } else {
-        None
-    };
+    let gpus = gpu_count.map(|count| GpuRequirement {
+        count,
+        model: gpu_model,
+        manufacturer: None,
+        memory_gb: args.gpu_memory,
+    });
 
     let request = SubmitJobRequest {
         name,
-        definition: docker_uri,
+        definition: JobDefinition {
+            image: docker_uri,
+            entrypoint: args.entrypoint.clone().map(|entrypoint| vec![entrypoint]),
+            command: (!args.command.is_empty()).then(|| args.command.clone()),
+            workdir: args.workdir.clone(),
+            user: args.user.clone(),
+        },
         queue_id: selected_queue.id.clone(),
         resource_requirements: ResourceRequirements {
             cpu_millicores: requested_cpu,
             memory_mb: requested_memory,
             gpus,
         },
+        node_count: args.nodes,
+        depends_on: args
+            .depends_on
+            .iter()
+            .map(|job_id| JobDependency {
+                job_id: job_id.clone(),
+            })
+            .collect(),
+        retry_policy: args
+            .max_attempts
+            .map(|max_attempts| RetryPolicy { max_attempts }),
+        max_runtime_secs: args.max_runtime_secs,
+        node_selector,
+        tolerations,
+        env,
+        secrets,
+        outputs,
+        volumes,
     };
 
+    if !params.is_empty() {
+        let request = SubmitJobArrayRequest {
+            job: request,
+            parameters: ParameterSpace::Grid(params),
+            max_concurrent: args.max_concurrent,
+        };
+        match client.submit_job_array(request).await {
+            Ok(response) => {
+                println!(
+                    "      ✅ Submitted {} jobs successfully! Array ID: {}",
+                    response.size, response.array_id
+                );
+            }
+            Err(e) => {
+                eprintln!("\n❌ Error submitting job array: {}", e);
+            }
+        }
+        return Ok(());
+    }
+
     match client.submit_job(request).await {
         Ok(response) => {
             println!(
@@ -260,4 +458,226 @@ pub async fn submit_job(config: config::UserConfig, args: &SubmitArgs) -> Result
         }
     }
     Ok(())
+}
+
+/// Parses `NAME=v1,v2` sweep parameters.
+fn parse_params(params: &[String]) -> Result<BTreeMap<String, Vec<String>>, CliError> {
+    params
+        .iter()
+        .map(|param| {
+            let (name, values) = param.split_once('=').ok_or(CliError::InvalidArguments)?;
+            let values: Vec<String> = values.split(',').map(str::to_string).collect();
+            Ok((name.to_string(), values))
+        })
+        .collect()
+}
+
+/// Parses repeated `KEY=VALUE` arguments. Values may contain `=` and `,`.
+fn parse_assignments(assignments: &[String]) -> Result<BTreeMap<String, String>, CliError> {
+    assignments
+        .iter()
+        .map(|assignment| match assignment.split_once('=') {
+            Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+            _ => Err(CliError::InvalidArguments),
+        })
+        .collect()
+}
+
+/// Parses `PATH[:SECS]` outputs.
+fn parse_outputs(outputs: &[String]) -> Result<Vec<JobOutput>, CliError> {
+    outputs
+        .iter()
+        .map(|output| match output.rsplit_once(':') {
+            Some((path, secs)) => Ok(JobOutput {
+                path: path.to_string(),
+                upload_interval_secs: Some(secs.parse().map_err(|_| CliError::InvalidArguments)?),
+            }),
+            None => Ok(JobOutput {
+                path: output.clone(),
+                upload_interval_secs: None,
+            }),
+        })
+        .collect()
+}
+
+/// Parses a `SOURCE:TARGET[:ro]` volume.
+fn parse_volume(kind: VolumeKind, volume: &str) -> Result<JobVolume, CliError> {
+    let (volume, read_only) = match volume.strip_suffix(":ro") {
+        Some(volume) => (volume, true),
+        None => (volume, false),
+    };
+    match volume.split_once(':') {
+        Some((source, target)) if !source.is_empty() && !target.is_empty() => Ok(JobVolume {
+            kind,
+            source: source.to_string(),
+            target: target.to_string(),
+            read_only,
+        }),
+        _ => Err(CliError::InvalidArguments),
+    }
+}
+
+fn describe_rejection(reason: &RejectionReason) -> String {
+    match reason {
+        RejectionReason::NoAdvertisedAddress => {
+            "does not advertise an address for multi-node jobs".to_string()
+        }
+        RejectionReason::InsufficientCpu {
+            required,
+            available,
+        } => format!("needs {}m CPU, node has {}m", required, available),
+        RejectionReason::InsufficientMemory {
+            required,
+            available,
+        } => format!("needs {}MB memory, node has {}MB", required, available),
+        RejectionReason::NoGpus => "node has no GPUs".to_string(),
+        RejectionReason::InsufficientGpus {
+            required,
+            available,
+        } => format!("needs {} GPUs, node has {}", required, available),
+        RejectionReason::WrongGpuModel { required, actual } => {
+            format!("needs {} GPUs, node has {}", required, actual)
+        }
+        RejectionReason::WrongGpuManufacturer { required, actual } => {
+            format!("needs {} GPUs, node has {}", required, actual)
+        }
+        RejectionReason::InsufficientGpuMemory {
+            required_gb,
+            available_mb,
+        } => format!(
+            "needs {}GB per GPU, node's GPUs have {}MB",
+            required_gb, available_mb
+        ),
+        RejectionReason::MissingLabel {
+            key,
+            required,
+            actual: Some(actual),
+        } => format!("needs label {}={}, node has {}={}", key, required, key, actual),
+        RejectionReason::MissingLabel {
+            key,
+            required,
+            actual: None,
+        } => format!("needs label {}={}, node doesn't have it", key, required),
+        RejectionReason::UntoleratedTaint { key, value } => match value {
+            Some(value) => format!("has taint {}={} that the job doesn't tolerate", key, value),
+            None => format!("has taint {} that the job doesn't tolerate", key),
+        },
+        RejectionReason::NodeBusy {
+            free_cpu_millicores,
+            free_memory_mb,
+            free_gpus,
+        } => format!(
+            "busy with other jobs ({}m CPU, {}MB memory, {} GPUs free)",
+            free_cpu_millicores, free_memory_mb, free_gpus
+        ),
+        RejectionReason::StoppingJobs => "still stopping jobs that were taken off it".to_string(),
+        RejectionReason::Cordoned => "cordoned".to_string(),
+        RejectionReason::Draining => "being drained".to_string(),
+    }
+}
+
+pub async fn print_job_logs(config: config::UserConfig, args: &LogsArgs) -> Result<(), CliError> {
+    let client = ApiClient::new(config);
+
+    if args.follow {
+        client
+            .follow_job_logs(&args.job_id, args.tail, |chunk| {
+                print!("{}", chunk.content);
+                let _ = std::io::stdout().flush();
+            })
+            .await?;
+        return Ok(());
+    }
+
+    let logs = client.get_job_logs(&args.job_id, None, args.tail).await?;
+    for chunk in &logs.chunks {
+        print!("{}", chunk.content);
+    }
+    std::io::stdout().flush()?;
+
+    Ok(())
+}
+
+pub async fn job_artifacts(
+    config: config::UserConfig,
+    args: &ArtifactsArgs,
+) -> Result<(), CliError> {
+    let client = ApiClient::new(config);
+    let artifacts = client.list_artifacts(&args.job_id).await?;
+
+    let Some(dir) = &args.download else {
+        if artifacts.is_empty() {
+            println!("Job {} has no artifacts.", args.job_id);
+        }
+        for artifact in &artifacts {
+            println!(
+                "{}  {:>12}  {}",
+                artifact.uploaded_at, artifact.size_bytes, artifact.path
+            );
+        }
+        return Ok(());
+    };
+
+    for artifact in &artifacts {
+        // Artifact paths are relative and checked by the control plane, so
+        // they stay inside the directory.
+        let target = dir.join(&artifact.path);
+        if let Some(parent) = target.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let mut file = tokio::fs::File::create(&target).await?;
+        client
+            .download_artifact(&args.job_id, &artifact.path, &mut file)
+            .await?;
+        println!("Downloaded {}", target.display());
+    }
+
+    Ok(())
+}
+
+pub async fn explain_job(config: config::UserConfig, args: &ExplainArgs) -> Result<(), CliError> {
+    let client = ApiClient::new(config);
+    let explanation = client.get_job_scheduling(&args.job_id).await?;
+
+    println!("Job {} is {}.", explanation.job_id, explanation.job_status);
+
+    let Some(attempt) = explanation.last_attempt else {
+        println!("The scheduler has not looked at this job yet.");
+        return Ok(());
+    };
+
+    println!(
+        "Last scheduling attempt at {}: {}",
+        attempt.attempted_at, attempt.outcome
+    );
+    if attempt.preempted_jobs {
+        println!("Lower priority jobs were preempted to make room for it.");
+    }
+    if !attempt.blocking_dependencies.is_empty() {
+        println!(
+            "Blocked by dependencies: {}",
+            attempt.blocking_dependencies.join(", ")
+        );
+    }
+
+    for cluster in &attempt.clusters {
+        let outcome = match &cluster.outcome {
+            ClusterOutcome::Allocated { node_ids } => {
+                format!("allocated to {}", node_ids.join(", "))
+            }
+            ClusterOutcome::NoCapacity => "not enough free capacity".to_string(),
+            ClusterOutcome::Unsatisfiable => "no nodes can run this job".to_string(),
+            ClusterOutcome::NoNodes => "no nodes".to_string(),
+            ClusterOutcome::Conflict => "nodes changed while allocating, retrying".to_string(),
+            ClusterOutcome::Error => "could not be checked".to_string(),
+        };
+        println!("\nCluster {}: {}", cluster.cluster_id, outcome);
+        for node in &cluster.rejected_nodes {
+            println!("  - Node {}:", node.node_id);
+            for reason in &node.reasons {
+                println!("      {}", describe_rejection(reason));
+            }
+        }
+    }
+    Ok(())
 }
\ No newline at end of file
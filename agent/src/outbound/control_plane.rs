@@ -1,13 +1,18 @@
 use crate::{
     config::AgentConfig,
     domain::agent::{
-        models::{HeartbeatRequest, HeartbeatResponse, JobDetails},
+        models::{
+            ArtifactFile, ArtifactUpload, HeartbeatRequest, HeartbeatResponse, JobDetails,
+            MetricSample, PostLogsRequest, PostMetricsRequest, PresignUploadsRequest,
+            PresignUploadsResponse, RecordArtifactsRequest,
+        },
         ports::ControlPlaneApi,
     },
     errors::ControlPlaneApiError,
 };
 use async_trait::async_trait;
-use reqwest::{Client, StatusCode};
+use reqwest::{header::CONTENT_LENGTH, Client, StatusCode};
+use std::path::Path;
 use uuid::Uuid;
 
 #[derive(Clone)]
@@ -86,4 +91,150 @@ impl ControlPlaneApi for ControlPlaneClient {
             ))),
         }
     }
-}
\ No newline at end of file
+
+    async fn post_logs(&self, job_id: Uuid, logs: String) -> Result<(), ControlPlaneApiError> {
+        let api_key = &self.config.cluster_api_key;
+
+        let url = format!("{}/training_jobs/{}/logs", self.config.api_endpoint, job_id);
+        let response = self
+            .client
+            .post(&url)
+            .bearer_auth(api_key)
+            .json(&PostLogsRequest { logs })
+            .send()
+            .await?;
+
+        match response.status() {
+            StatusCode::OK => Ok(()),
+            StatusCode::UNAUTHORIZED => Err(ControlPlaneApiError::Unauthorized),
+            StatusCode::NOT_FOUND => Err(ControlPlaneApiError::NotFound),
+            StatusCode::INTERNAL_SERVER_ERROR => Err(ControlPlaneApiError::InternalServerError),
+            _ => Err(ControlPlaneApiError::Unknown(anyhow::anyhow!(
+                "Failed to post logs: {}",
+                response.status()
+            ))),
+        }
+    }
+
+    async fn post_metrics(
+        &self,
+        job_id: Uuid,
+        samples: Vec<MetricSample>,
+    ) -> Result<(), ControlPlaneApiError> {
+        let api_key = &self.config.cluster_api_key;
+
+        let url = format!("{}/training_jobs/{}/metrics", self.config.api_endpoint, job_id);
+        let response = self
+            .client
+            .post(&url)
+            .bearer_auth(api_key)
+            .json(&PostMetricsRequest { samples })
+            .send()
+            .await?;
+
+        match response.status() {
+            StatusCode::OK => Ok(()),
+            StatusCode::UNAUTHORIZED => Err(ControlPlaneApiError::Unauthorized),
+            StatusCode::NOT_FOUND => Err(ControlPlaneApiError::NotFound),
+            StatusCode::INTERNAL_SERVER_ERROR => Err(ControlPlaneApiError::InternalServerError),
+            _ => Err(ControlPlaneApiError::Unknown(anyhow::anyhow!(
+                "Failed to post metrics: {}",
+                response.status()
+            ))),
+        }
+    }
+
+    async fn presign_artifact_uploads(
+        &self,
+        job_id: Uuid,
+        paths: Vec<String>,
+    ) -> Result<Vec<ArtifactUpload>, ControlPlaneApiError> {
+        let api_key = &self.config.cluster_api_key;
+
+        let url = format!(
+            "{}/training_jobs/{}/artifacts/uploads",
+            self.config.api_endpoint, job_id
+        );
+        let response = self
+            .client
+            .post(&url)
+            .bearer_auth(api_key)
+            .json(&PresignUploadsRequest { paths })
+            .send()
+            .await?;
+
+        match response.status() {
+            StatusCode::OK => Ok(response.json::<PresignUploadsResponse>().await?.uploads),
+            StatusCode::UNAUTHORIZED => Err(ControlPlaneApiError::Unauthorized),
+            StatusCode::NOT_FOUND => Err(ControlPlaneApiError::NotFound),
+            StatusCode::INTERNAL_SERVER_ERROR => Err(ControlPlaneApiError::InternalServerError),
+            status => Err(ControlPlaneApiError::Unknown(anyhow::anyhow!(
+                "Failed to get artifact upload URLs: {}: {}",
+                status,
+                response.text().await.unwrap_or_default()
+            ))),
+        }
+    }
+
+    async fn upload_artifact(&self, url: &str, file: &Path) -> Result<(), ControlPlaneApiError> {
+        let file = tokio::fs::File::open(file)
+            .await
+            .map_err(|e| ControlPlaneApiError::Unknown(e.into()))?;
+        let size = file
+            .metadata()
+            .await
+            .map_err(|e| ControlPlaneApiError::Unknown(e.into()))?
+            .len();
+
+        // Object stores reject chunked uploads, so the length is sent up
+        // front and the file streamed after it.
+        let response = self
+            .client
+            .put(url)
+            .header(CONTENT_LENGTH, size)
+            .body(file)
+            .send()
+            .await?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(ControlPlaneApiError::Unknown(anyhow::anyhow!(
+                "Failed to upload artifact: {}: {}",
+                response.status(),
+                response.text().await.unwrap_or_default()
+            )))
+        }
+    }
+
+    async fn record_artifacts(
+        &self,
+        job_id: Uuid,
+        artifacts: Vec<ArtifactFile>,
+    ) -> Result<(), ControlPlaneApiError> {
+        let api_key = &self.config.cluster_api_key;
+
+        let url = format!(
+            "{}/training_jobs/{}/artifacts",
+            self.config.api_endpoint, job_id
+        );
+        let response = self
+            .client
+            .post(&url)
+            .bearer_auth(api_key)
+            .json(&RecordArtifactsRequest { artifacts })
+            .send()
+            .await?;
+
+        match response.status() {
+            StatusCode::OK => Ok(()),
+            StatusCode::UNAUTHORIZED => Err(ControlPlaneApiError::Unauthorized),
+            StatusCode::NOT_FOUND => Err(ControlPlaneApiError::NotFound),
+            StatusCode::INTERNAL_SERVER_ERROR => Err(ControlPlaneApiError::InternalServerError),
+            _ => Err(ControlPlaneApiError::Unknown(anyhow::anyhow!(
+                "Failed to record artifacts: {}",
+                response.status()
+            ))),
+        }
+    }
+}
@@ -1,30 +1,69 @@
 use crate::{
     config::AgentConfig,
-    domain::agent::{models::JobDetails, ports::JobExecutor, models::NodeResources},
+    domain::agent::{
+        cache::DatasetCache,
+        models::{ContainerExit, ContainerUsage, JobDetails, JobVolume, VolumeKind},
+        ports::JobExecutor,
+    },
     errors::JobExecutorError,
 };
 use async_trait::async_trait;
 use bollard::container::{
-    Config, CreateContainerOptions, RemoveContainerOptions, StartContainerOptions,
-    StopContainerOptions, WaitContainerOptions,
+    Config, CreateContainerOptions, LogsOptions, MemoryStatsStats, RemoveContainerOptions,
+    StartContainerOptions, StatsOptions, StopContainerOptions, WaitContainerOptions,
 };
 use bollard::image::CreateImageOptions;
 use bollard::{auth::DockerCredentials, Docker};
 use futures_util::stream::StreamExt;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc::UnboundedSender;
 
 #[derive(Clone)]
 pub struct DockerExecutor {
     docker: Docker,
     config: AgentConfig,
+    /// Where the host directories mounted at the jobs' outputs are created.
+    outputs_dir: PathBuf,
+    /// Provides the datasets jobs mount from the cache.
+    dataset_cache: Arc<DatasetCache>,
 }
 
 impl DockerExecutor {
-    pub fn new(config: AgentConfig) -> Result<Self, JobExecutorError> {
+    pub fn new(
+        config: AgentConfig,
+        outputs_dir: PathBuf,
+        dataset_cache: Arc<DatasetCache>,
+    ) -> Result<Self, JobExecutorError> {
         // Connect to the local Docker daemon.
         // This will fail if Docker is not running.
         let docker =
             Docker::connect_with_local_defaults().map_err(|e| JobExecutorError::Unknown(e.into()))?;
-        Ok(Self { docker, config })
+        Ok(Self {
+            docker,
+            config,
+            outputs_dir,
+            dataset_cache,
+        })
+    }
+
+    /// Resolves a host path a job wants to mount, which has to be one of the
+    /// allowed host paths or below one. Symbolic links are resolved first so
+    /// they can't lead out of the allowed paths.
+    async fn allowed_host_path(&self, volume: &JobVolume) -> Result<PathBuf, JobExecutorError> {
+        let not_allowed = || JobExecutorError::VolumeNotAllowed(volume.source.clone());
+        let source = tokio::fs::canonicalize(&volume.source)
+            .await
+            .map_err(|_| not_allowed())?;
+        for allowed in &self.config.allowed_host_paths {
+            if let Ok(allowed) = tokio::fs::canonicalize(allowed).await {
+                if source.starts_with(&allowed) {
+                    return Ok(source);
+                }
+            }
+        }
+        Err(not_allowed())
     }
 }
 
@@ -33,22 +72,52 @@ impl JobExecutor for DockerExecutor {
     async fn run_job(
         &self,
         job_details: JobDetails,
-        resources: &NodeResources,
-    ) -> Result<i64, JobExecutorError> {
+        gpu_ids: Vec<String>,
+        logs: UnboundedSender<String>,
+    ) -> Result<ContainerExit, JobExecutorError> {
         println!("[DOCKER] Starting job: {}", job_details.id);
+
+        // 0. Check the job's volumes against what this node allows. Datasets
+        // from the cache stay in use for as long as the job runs.
+        let mut binds = Vec::new();
+        let mut datasets = Vec::new();
+        for volume in &job_details.volumes {
+            let source = match volume.kind {
+                VolumeKind::HostPath => self.allowed_host_path(volume).await?.display().to_string(),
+                VolumeKind::Named => {
+                    if !self.config.allowed_volumes.contains(&volume.source) {
+                        return Err(JobExecutorError::VolumeNotAllowed(volume.source.clone()));
+                    }
+                    volume.source.clone()
+                }
+                VolumeKind::Cache => {
+                    let dataset = self
+                        .dataset_cache
+                        .acquire(&volume.source)
+                        .await
+                        .map_err(|e| JobExecutorError::Unknown(e.into()))?;
+                    let source = dataset.path.display().to_string();
+                    datasets.push(dataset);
+                    source
+                }
+            };
+            let mode = if volume.read_only { ":ro" } else { "" };
+            binds.push(format!("{}:{}{}", source, volume.target, mode));
+        }
+
         println!("[DOCKER] Pulling image: {}", job_details.docker_uri);
 
         // 1. Pull the Docker image.
-        let credentials = if let Some(private_registry) = &self.config.private_registry {
-            Some(DockerCredentials {
+        let credentials = self
+            .config
+            .private_registry
+            .as_ref()
+            .map(|private_registry| DockerCredentials {
                 serveraddress: Some(private_registry.registry_url.clone()),
                 username: Some(private_registry.username.clone()),
                 password: Some(private_registry.secret.clone()),
                 ..Default::default()
-            })
-        } else {
-            None
-        };
+            });
 
         let mut stream = self.docker.create_image(
             Some(CreateImageOptions {
@@ -60,7 +129,7 @@ impl JobExecutor for DockerExecutor {
         );
 
         while let Some(result) = stream.next().await {
-            result.map_err(|e| JobExecutorError::Unknown(e.into()))?;
+            result.map_err(|e| JobExecutorError::ImagePull(e.to_string()))?;
         }
 
         // 2. Clean up any old container with the same name, just in case.
@@ -85,19 +154,78 @@ impl JobExecutor for DockerExecutor {
             ..Default::default()
         };
 
-        if !resources.gpus.is_empty() {
+        // Only expose the GPUs reserved for this job, so that jobs sharing the
+        // node don't compete for the same devices.
+        if !gpu_ids.is_empty() {
             host_config.device_requests = Some(vec![bollard::service::DeviceRequest {
                 driver: Some("".to_string()),
-                count: Some(-1),
-                device_ids: None,
+                count: None,
+                device_ids: Some(gpu_ids),
                 capabilities: Some(vec![vec!["gpu".to_string()]]),
                 options: None,
             }]);
         }
 
+        // Mount a host directory at each of the job's outputs, so that its
+        // files outlive the container and can be uploaded from the host.
+        let output_dirs = job_details.output_dirs(&self.outputs_dir);
+        for (output, host_dir) in job_details.outputs.iter().zip(output_dirs) {
+            tokio::fs::create_dir_all(&host_dir)
+                .await
+                .map_err(|e| JobExecutorError::Unknown(e.into()))?;
+            // The job may run as any user.
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                tokio::fs::set_permissions(&host_dir, std::fs::Permissions::from_mode(0o777))
+                    .await
+                    .map_err(|e| JobExecutorError::Unknown(e.into()))?;
+            }
+            binds.push(format!("{}:{}", host_dir.display(), output.path));
+        }
+        if !binds.is_empty() {
+            host_config.binds = Some(binds);
+        }
+
+        // Multi-node jobs share the host network so that peers can reach the
+        // rendezvous port on the coordinator directly.
+        if job_details.distributed.is_some() {
+            host_config.network_mode = Some("host".to_string());
+        }
+
+        let mut env: Vec<String> = job_details
+            .env
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect();
+        if let Some(distributed) = &job_details.distributed {
+            env.extend([
+                format!("MASTER_ADDR={}", distributed.coordinator_address),
+                format!("MASTER_PORT={}", distributed.coordinator_port),
+                format!("NODE_RANK={}", distributed.rank),
+                format!("NNODES={}", distributed.world_size),
+                format!("LILAC_RANK={}", distributed.rank),
+                format!("LILAC_WORLD_SIZE={}", distributed.world_size),
+            ]);
+        }
+
+        // Docker only clears an image's entrypoint when given an empty string.
+        let entrypoint = job_details.entrypoint.clone().map(|entrypoint| {
+            if entrypoint.is_empty() {
+                vec![String::new()]
+            } else {
+                entrypoint
+            }
+        });
+
         let config = Config {
             image: Some(job_details.docker_uri.clone()),
+            entrypoint,
+            cmd: job_details.container_command(),
+            working_dir: job_details.workdir.clone(),
+            user: job_details.user.clone(),
             host_config: Some(host_config),
+            env: (!env.is_empty()).then_some(env),
             ..Default::default()
         };
 
@@ -115,18 +243,79 @@ impl JobExecutor for DockerExecutor {
             .map_err(|e| JobExecutorError::Unknown(e.into()))?;
         println!("[DOCKER] Started container for job {}", job_details.id);
 
-        // 5. Wait for the container to finish.
+        // Forward the container's output until it stops, and the stream with it.
+        let mut output = self.docker.logs(
+            &container.id,
+            Some(LogsOptions::<String> {
+                follow: true,
+                stdout: true,
+                stderr: true,
+                tail: "all".to_string(),
+                ..Default::default()
+            }),
+        );
+        tokio::spawn(async move {
+            while let Some(Ok(chunk)) = output.next().await {
+                if logs.send(chunk.to_string()).is_err() {
+                    break;
+                }
+            }
+        });
+
+        // 5. Wait for the container to finish, stopping it once it exceeds the
+        // job's maximum runtime.
         let wait_options = Some(WaitContainerOptions {
             condition: "not-running",
         });
         let mut stream = self.docker.wait_container(&container.id, wait_options);
-        let wait_result = stream.next().await.unwrap().unwrap();
-        let exit_code = wait_result.status_code;
+        let wait = stream.next();
+        let wait_result = match job_details.max_runtime_secs {
+            Some(max_runtime_secs) => {
+                let max_runtime = Duration::from_secs(u64::try_from(max_runtime_secs).unwrap_or(0));
+                match tokio::time::timeout(max_runtime, wait).await {
+                    Ok(wait_result) => wait_result,
+                    Err(_) => {
+                        println!(
+                            "[JOB {}] Exceeded its maximum runtime of {}s, stopping it.",
+                            job_details.id, max_runtime_secs
+                        );
+                        self.stop_job(&job_details.id.to_string()).await?;
+                        return Err(JobExecutorError::TimedOut(max_runtime_secs));
+                    }
+                }
+            }
+            None => wait.await,
+        };
+        // Bollard reports non-zero exit codes as errors.
+        let exit_code = match wait_result {
+            Some(Ok(response)) => response.status_code,
+            Some(Err(bollard::errors::Error::DockerContainerWaitError { code, .. })) => code,
+            Some(Err(e)) => return Err(JobExecutorError::Unknown(e.into())),
+            None => {
+                return Err(JobExecutorError::Unknown(anyhow::anyhow!(
+                    "container stopped without an exit code"
+                )))
+            }
+        };
         println!(
             "[JOB {}] Execution finished with exit code: {}",
             job_details.id, exit_code
         );
 
+        // Only the container's state tells an OOM kill apart from other
+        // reasons for exit code 137.
+        let oom_killed = self
+            .docker
+            .inspect_container(&container.id, None)
+            .await
+            .ok()
+            .and_then(|container| container.state)
+            .and_then(|state| state.oom_killed)
+            .unwrap_or(false);
+        if oom_killed {
+            println!("[JOB {}] Was killed for running out of memory.", job_details.id);
+        }
+
         // 6. Remove the container.
         self.docker
             .remove_container(
@@ -147,8 +336,13 @@ impl JobExecutor for DockerExecutor {
             .await;
         println!("[DOCKER] Attempted to remove image: {}", job_details.docker_uri);
 
+        // The datasets are no longer in use, which counts as their last use.
+        drop(datasets);
 
-        Ok(exit_code)
+        Ok(ContainerExit {
+            exit_code,
+            oom_killed,
+        })
     }
 
     async fn stop_job(&self, job_id: &str) -> Result<(), JobExecutorError> {
@@ -181,4 +375,57 @@ impl JobExecutor for DockerExecutor {
 
         Ok(())
     }
+
+    async fn get_usage(&self, job_id: &str) -> Result<ContainerUsage, JobExecutorError> {
+        let container_name = format!("lilac-job-{}", job_id);
+        // Docker reads the CPU usage twice, a second apart, so that the
+        // difference between the readings gives the current usage.
+        let stats = self
+            .docker
+            .stats(
+                &container_name,
+                Some(StatsOptions {
+                    stream: false,
+                    one_shot: false,
+                }),
+            )
+            .next()
+            .await
+            .ok_or_else(|| JobExecutorError::Unknown(anyhow::anyhow!("no stats for container")))?
+            .map_err(|e| JobExecutorError::Unknown(e.into()))?;
+
+        let cpu_delta = stats
+            .cpu_stats
+            .cpu_usage
+            .total_usage
+            .saturating_sub(stats.precpu_stats.cpu_usage.total_usage);
+        let system_delta = stats
+            .cpu_stats
+            .system_cpu_usage
+            .zip(stats.precpu_stats.system_cpu_usage)
+            .map(|(system, presystem)| system.saturating_sub(presystem));
+        let cpu_millicores = match (system_delta, stats.cpu_stats.online_cpus) {
+            (Some(system_delta), Some(online_cpus)) if system_delta > 0 => Some(
+                (cpu_delta as f64 / system_delta as f64 * online_cpus as f64 * 1000.0) as i32,
+            ),
+            _ => None,
+        };
+
+        // Like `docker stats`, leave out the page cache the kernel can
+        // reclaim.
+        let inactive_file = match stats.memory_stats.stats {
+            Some(MemoryStatsStats::V1(stats)) => stats.total_inactive_file,
+            Some(MemoryStatsStats::V2(stats)) => stats.inactive_file,
+            None => 0,
+        };
+        let memory_mb = stats
+            .memory_stats
+            .usage
+            .map(|usage| (usage.saturating_sub(inactive_file) / 1024 / 1024) as i64);
+
+        Ok(ContainerUsage {
+            cpu_millicores,
+            memory_mb,
+        })
+    }
 }
\ No newline at end of file
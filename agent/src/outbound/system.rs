@@ -1,7 +1,8 @@
 use crate::{
     domain::agent::{
         models::{
-            Architecture, Cpu, CpuManufacturer, Gpu, GpuManufacturer, GpuModel, NodeResources,
+            Architecture, Cpu, CpuManufacturer, Gpu, GpuManufacturer, GpuModel, GpuSample,
+            NodeResources,
         },
         ports::SystemMonitor,
     },
@@ -18,6 +19,12 @@ use strum::IntoEnumIterator;
 
 pub struct HybridMonitor;
 
+impl Default for HybridMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl HybridMonitor {
     pub fn new() -> Self {
         Self
@@ -95,21 +102,31 @@ impl SystemMonitor for HybridMonitor {
             Ok(nvml) => {
                 let device_count =
                     nvml.device_count().map_err(|_| SystemMonitorError::ReadError)?;
-                let mut gpu_configs = Vec::with_capacity(device_count as usize);
+                let mut gpu_configs: Vec<Gpu> = Vec::with_capacity(device_count as usize);
                 for i in 0..device_count {
                     let device = nvml.device_by_index(i).map_err(|_| SystemMonitorError::ReadError)?;
                     let model_name = device.name().map_err(|_| SystemMonitorError::ReadError)?;
-                    gpu_configs.push(Gpu {
-                        manufacturer: GpuManufacturer::Nvidia,
-                        model: Self::parse_gpu_model(&model_name),
-                        count: 1,
-                        memory_mb: (device
-                            .memory_info()
-                            .map_err(|_| SystemMonitorError::ReadError)?
-                            .total
-                            / 1024
-                            / 1024) as i32,
-                    });
+                    let model = Self::parse_gpu_model(&model_name);
+                    let memory_mb = (device
+                        .memory_info()
+                        .map_err(|_| SystemMonitorError::ReadError)?
+                        .total
+                        / 1024
+                        / 1024) as i32;
+
+                    // Identical devices are reported as a single entry with a count.
+                    match gpu_configs
+                        .iter_mut()
+                        .find(|gpu| gpu.model == model && gpu.memory_mb == memory_mb)
+                    {
+                        Some(gpu) => gpu.count += 1,
+                        None => gpu_configs.push(Gpu {
+                            manufacturer: GpuManufacturer::Nvidia,
+                            model,
+                            count: 1,
+                            memory_mb,
+                        }),
+                    }
                 }
                 gpu_configs
             }
@@ -127,4 +144,30 @@ impl SystemMonitor for HybridMonitor {
 
         Ok(resources)
     }
+
+    async fn get_gpu_usage(
+        &self,
+        gpu_ids: &[String],
+    ) -> Result<Vec<GpuSample>, SystemMonitorError> {
+        if gpu_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let nvml = Nvml::init().map_err(|_| SystemMonitorError::ReadError)?;
+
+        let mut samples = Vec::with_capacity(gpu_ids.len());
+        for gpu_id in gpu_ids {
+            let index: u32 = gpu_id.parse().map_err(|_| SystemMonitorError::ReadError)?;
+            let device = nvml.device_by_index(index).map_err(|_| SystemMonitorError::ReadError)?;
+            let utilization = device
+                .utilization_rates()
+                .map_err(|_| SystemMonitorError::ReadError)?;
+            let memory = device.memory_info().map_err(|_| SystemMonitorError::ReadError)?;
+            samples.push(GpuSample {
+                index: index as i32,
+                utilization_percent: utilization.gpu as i32,
+                memory_used_mb: (memory.used / 1024 / 1024) as i64,
+            });
+        }
+        Ok(samples)
+    }
 }
\ No newline at end of file
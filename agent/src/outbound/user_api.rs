@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use crate::{config::UserConfig, errors::UserApiError};
 use reqwest::{Client, RequestBuilder, StatusCode};
 use serde::{Deserialize, Serialize};
@@ -7,6 +9,7 @@ use serde::{Deserialize, Serialize};
 pub struct GpuRequirement {
     pub count: i32,
     pub model: Option<String>,
+    pub manufacturer: Option<String>,
     pub memory_gb: Option<i32>,
 }
 
@@ -18,13 +21,77 @@ pub struct ResourceRequirements {
     pub gpus: Option<GpuRequirement>,
 }
 
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct JobDependency {
+    pub job_id: String,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct RetryPolicy {
+    pub max_attempts: i32,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Toleration {
+    pub key: String,
+    pub value: Option<String>,
+}
+
+/// The image a job runs and how to start it. Unset fields keep the image's
+/// defaults.
+#[derive(Serialize, Debug)]
+pub struct JobDefinition {
+    pub image: String,
+    pub entrypoint: Option<Vec<String>>,
+    pub command: Option<Vec<String>>,
+    pub workdir: Option<String>,
+    pub user: Option<String>,
+}
+
 #[derive(Serialize, Debug)]
 #[serde(rename_all = "snake_case")]
 pub struct SubmitJobRequest {
     pub name: String,
-    pub definition: String, // Docker image
+    pub definition: JobDefinition,
     pub queue_id: String,
     pub resource_requirements: ResourceRequirements,
+    pub node_count: i32,
+    pub depends_on: Vec<JobDependency>,
+    pub retry_policy: Option<RetryPolicy>,
+    pub max_runtime_secs: Option<i64>,
+    pub node_selector: BTreeMap<String, String>,
+    pub tolerations: Vec<Toleration>,
+    pub env: BTreeMap<String, String>,
+    /// Secret names, keyed by the environment variable each is set in.
+    pub secrets: BTreeMap<String, String>,
+    pub outputs: Vec<JobOutput>,
+    pub volumes: Vec<JobVolume>,
+}
+
+/// A directory in the job's container to upload as artifacts.
+#[derive(Serialize, Debug)]
+pub struct JobOutput {
+    pub path: String,
+    pub upload_interval_secs: Option<i64>,
+}
+
+#[derive(Serialize, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum VolumeKind {
+    HostPath,
+    Named,
+    Cache,
+}
+
+/// Storage on the node to mount into the job's container.
+#[derive(Serialize, Debug)]
+pub struct JobVolume {
+    pub kind: VolumeKind,
+    pub source: String,
+    pub target: String,
+    pub read_only: bool,
 }
 
 #[derive(Deserialize, Debug)]
@@ -32,12 +99,123 @@ pub struct SubmitJobResponse {
     pub id: String,
 }
 
+/// The values to sweep over, keyed by parameter name.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ParameterSpace {
+    Grid(BTreeMap<String, Vec<String>>),
+}
+
+#[derive(Serialize, Debug)]
+pub struct SubmitJobArrayRequest {
+    #[serde(flatten)]
+    pub job: SubmitJobRequest,
+    pub parameters: ParameterSpace,
+    pub max_concurrent: Option<i32>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SubmitJobArrayResponse {
+    pub array_id: String,
+    pub size: i32,
+}
+
 #[derive(Deserialize, Debug, Clone)]
 pub struct Queue {
     pub id: String,
     pub name: String,
 }
 
+/// Why a node could not take a job.
+#[derive(Deserialize, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RejectionReason {
+    NoAdvertisedAddress,
+    InsufficientCpu { required: i32, available: i32 },
+    InsufficientMemory { required: i32, available: i32 },
+    NoGpus,
+    InsufficientGpus { required: i32, available: i32 },
+    WrongGpuModel { required: String, actual: String },
+    WrongGpuManufacturer { required: String, actual: String },
+    InsufficientGpuMemory { required_gb: i32, available_mb: i32 },
+    MissingLabel { key: String, required: String, actual: Option<String> },
+    UntoleratedTaint { key: String, value: Option<String> },
+    NodeBusy {
+        free_cpu_millicores: i32,
+        free_memory_mb: i32,
+        free_gpus: i32,
+    },
+    StoppingJobs,
+    Cordoned,
+    Draining,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct NodeRejection {
+    pub node_id: String,
+    pub reasons: Vec<RejectionReason>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ClusterOutcome {
+    Allocated { node_ids: Vec<String> },
+    NoCapacity,
+    Unsatisfiable,
+    NoNodes,
+    Conflict,
+    Error,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ClusterReport {
+    pub cluster_id: String,
+    pub outcome: ClusterOutcome,
+    pub rejected_nodes: Vec<NodeRejection>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SchedulingAttempt {
+    pub attempted_at: String,
+    pub outcome: String,
+    pub preempted_jobs: bool,
+    pub clusters: Vec<ClusterReport>,
+    #[serde(default)]
+    pub blocking_dependencies: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SchedulingExplanation {
+    pub job_id: String,
+    pub job_status: String,
+    pub last_attempt: Option<SchedulingAttempt>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct LogChunk {
+    pub offset: i64,
+    pub content: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct JobLogs {
+    pub chunks: Vec<LogChunk>,
+    pub next_offset: i64,
+}
+
+/// A file uploaded from one of a job's outputs.
+#[derive(Deserialize, Debug)]
+pub struct Artifact {
+    pub path: String,
+    pub size_bytes: i64,
+    pub uploaded_at: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct ListArtifactsResponse {
+    artifacts: Vec<Artifact>,
+}
+
 #[derive(Clone)]
 pub struct ApiClient {
     client: Client,
@@ -89,6 +267,35 @@ impl ApiClient {
         }
     }
 
+    pub async fn submit_job_array(
+        &self,
+        request: SubmitJobArrayRequest,
+    ) -> Result<SubmitJobArrayResponse, UserApiError> {
+        let url = format!("{}/training_jobs/arrays", self.config.api_endpoint);
+
+        let req_builder = self.client.post(&url).json(&request);
+        let req_builder = self.add_auth(req_builder);
+
+        let response = req_builder.send().await?;
+
+        match response.status() {
+            StatusCode::OK | StatusCode::CREATED => {
+                let array_response = response.json::<SubmitJobArrayResponse>().await?;
+                Ok(array_response)
+            }
+            StatusCode::UNAUTHORIZED => Err(UserApiError::Unauthorized),
+            StatusCode::NOT_FOUND => Err(UserApiError::NotFound),
+            StatusCode::INTERNAL_SERVER_ERROR => Err(UserApiError::InternalServerError),
+            _ => {
+                let error_text = response.text().await?;
+                Err(UserApiError::Unknown(anyhow::anyhow!(
+                    "Failed to submit job array: {}",
+                    error_text
+                )))
+            }
+        }
+    }
+
     pub async fn get_queues(&self) -> Result<Vec<Queue>, UserApiError> {
         let url = format!("{}/queues", self.config.api_endpoint);
 
@@ -114,4 +321,219 @@ impl ApiClient {
             }
         }
     }
-}
\ No newline at end of file
+
+    pub async fn get_job_scheduling(
+        &self,
+        job_id: &str,
+    ) -> Result<SchedulingExplanation, UserApiError> {
+        let url = format!(
+            "{}/training_jobs/{}/scheduling",
+            self.config.api_endpoint, job_id
+        );
+
+        let req_builder = self.client.get(&url);
+        let req_builder = self.add_auth(req_builder);
+
+        let response = req_builder.send().await?;
+
+        match response.status() {
+            StatusCode::OK => {
+                let explanation = response.json::<SchedulingExplanation>().await?;
+                Ok(explanation)
+            }
+            StatusCode::UNAUTHORIZED => Err(UserApiError::Unauthorized),
+            StatusCode::NOT_FOUND => Err(UserApiError::NotFound),
+            StatusCode::INTERNAL_SERVER_ERROR => Err(UserApiError::InternalServerError),
+            _ => {
+                let error_text = response.text().await?;
+                Err(UserApiError::Unknown(anyhow::anyhow!(
+                    "Failed to get job scheduling: {}",
+                    error_text
+                )))
+            }
+        }
+    }
+
+    pub async fn get_job_logs(
+        &self,
+        job_id: &str,
+        offset: Option<i64>,
+        tail: Option<i64>,
+    ) -> Result<JobLogs, UserApiError> {
+        let url = format!("{}/training_jobs/{}/logs", self.config.api_endpoint, job_id);
+
+        let mut query = Vec::new();
+        if let Some(offset) = offset {
+            query.push(("offset", offset));
+        }
+        if let Some(tail) = tail {
+            query.push(("tail", tail));
+        }
+        let req_builder = self.client.get(&url).query(&query);
+        let req_builder = self.add_auth(req_builder);
+
+        let response = req_builder.send().await?;
+
+        match response.status() {
+            StatusCode::OK => {
+                let logs = response.json::<JobLogs>().await?;
+                Ok(logs)
+            }
+            StatusCode::UNAUTHORIZED => Err(UserApiError::Unauthorized),
+            StatusCode::NOT_FOUND => Err(UserApiError::NotFound),
+            StatusCode::INTERNAL_SERVER_ERROR => Err(UserApiError::InternalServerError),
+            _ => {
+                let error_text = response.text().await?;
+                Err(UserApiError::Unknown(anyhow::anyhow!(
+                    "Failed to get job logs: {}",
+                    error_text
+                )))
+            }
+        }
+    }
+
+    /// Streams the job's logs, calling `on_chunk` for every chunk as it
+    /// arrives, until the job has finished.
+    pub async fn follow_job_logs(
+        &self,
+        job_id: &str,
+        tail: Option<i64>,
+        mut on_chunk: impl FnMut(LogChunk),
+    ) -> Result<(), UserApiError> {
+        let url = format!(
+            "{}/training_jobs/{}/logs/stream",
+            self.config.api_endpoint, job_id
+        );
+
+        let mut req_builder = self.client.get(&url);
+        if let Some(tail) = tail {
+            req_builder = req_builder.query(&[("tail", tail)]);
+        }
+        let req_builder = self.add_auth(req_builder);
+
+        let mut response = req_builder.send().await?;
+
+        match response.status() {
+            StatusCode::OK => {}
+            StatusCode::UNAUTHORIZED => return Err(UserApiError::Unauthorized),
+            StatusCode::NOT_FOUND => return Err(UserApiError::NotFound),
+            StatusCode::INTERNAL_SERVER_ERROR => return Err(UserApiError::InternalServerError),
+            _ => {
+                let error_text = response.text().await?;
+                return Err(UserApiError::Unknown(anyhow::anyhow!(
+                    "Failed to follow job logs: {}",
+                    error_text
+                )));
+            }
+        }
+
+        // Server-sent events are separated by blank lines.
+        let mut buffer: Vec<u8> = Vec::new();
+        while let Some(bytes) = response.chunk().await? {
+            buffer.extend_from_slice(&bytes);
+            while let Some(end) = buffer.windows(2).position(|window| window == b"\n\n") {
+                let message: Vec<u8> = buffer.drain(..end + 2).collect();
+                let message = String::from_utf8_lossy(&message);
+
+                let mut event = "message";
+                let mut data = Vec::new();
+                for line in message.lines() {
+                    if let Some(value) = line.strip_prefix("event:") {
+                        event = value.trim();
+                    } else if let Some(value) = line.strip_prefix("data:") {
+                        data.push(value.strip_prefix(' ').unwrap_or(value));
+                    }
+                }
+
+                match event {
+                    "log" => {
+                        let chunk = serde_json::from_str(&data.join("\n"))
+                            .map_err(|e| UserApiError::Unknown(e.into()))?;
+                        on_chunk(chunk);
+                    }
+                    "end" => return Ok(()),
+                    "error" => {
+                        return Err(UserApiError::Unknown(anyhow::anyhow!(
+                            "The server stopped following the job's logs"
+                        )))
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn list_artifacts(&self, job_id: &str) -> Result<Vec<Artifact>, UserApiError> {
+        let url = format!(
+            "{}/training_jobs/{}/artifacts",
+            self.config.api_endpoint, job_id
+        );
+        let req_builder = self.add_auth(self.client.get(&url));
+
+        let response = req_builder.send().await?;
+
+        match response.status() {
+            StatusCode::OK => {
+                let artifacts = response.json::<ListArtifactsResponse>().await?;
+                Ok(artifacts.artifacts)
+            }
+            StatusCode::UNAUTHORIZED => Err(UserApiError::Unauthorized),
+            StatusCode::NOT_FOUND => Err(UserApiError::NotFound),
+            StatusCode::INTERNAL_SERVER_ERROR => Err(UserApiError::InternalServerError),
+            _ => {
+                let error_text = response.text().await?;
+                Err(UserApiError::Unknown(anyhow::anyhow!(
+                    "Failed to list artifacts: {}",
+                    error_text
+                )))
+            }
+        }
+    }
+
+    /// Downloads an artifact into `writer`. The control plane redirects to
+    /// the artifact store, which the download is made from directly.
+    pub async fn download_artifact(
+        &self,
+        job_id: &str,
+        path: &str,
+        writer: &mut (impl tokio::io::AsyncWrite + Unpin),
+    ) -> Result<(), UserApiError> {
+        use tokio::io::AsyncWriteExt;
+
+        let url = format!(
+            "{}/training_jobs/{}/artifacts/{}",
+            self.config.api_endpoint, job_id, path
+        );
+        let req_builder = self.add_auth(self.client.get(&url));
+
+        let mut response = req_builder.send().await?;
+
+        match response.status() {
+            StatusCode::OK => {}
+            StatusCode::UNAUTHORIZED => return Err(UserApiError::Unauthorized),
+            StatusCode::NOT_FOUND => return Err(UserApiError::NotFound),
+            StatusCode::INTERNAL_SERVER_ERROR => return Err(UserApiError::InternalServerError),
+            _ => {
+                let error_text = response.text().await?;
+                return Err(UserApiError::Unknown(anyhow::anyhow!(
+                    "Failed to download artifact: {}",
+                    error_text
+                )));
+            }
+        }
+
+        while let Some(bytes) = response.chunk().await? {
+            writer
+                .write_all(&bytes)
+                .await
+                .map_err(|e| UserApiError::Unknown(e.into()))?;
+        }
+        writer
+            .flush()
+            .await
+            .map_err(|e| UserApiError::Unknown(e.into()))?;
+        Ok(())
+    }
+}
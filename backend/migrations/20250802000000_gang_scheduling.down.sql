@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS training_job_nodes;
+ALTER TABLE cluster_nodes DROP COLUMN IF EXISTS address;
+ALTER TABLE training_jobs DROP COLUMN IF EXISTS node_count;
@@ -0,0 +1,21 @@
+ALTER TABLE training_jobs
+ADD COLUMN node_count INTEGER NOT NULL DEFAULT 1;
+
+ALTER TABLE cluster_nodes
+ADD COLUMN address TEXT;
+
+CREATE TABLE training_job_nodes (
+    job_id UUID NOT NULL REFERENCES training_jobs(id) ON DELETE CASCADE,
+    node_id UUID NOT NULL,
+    rank INTEGER NOT NULL,
+    PRIMARY KEY (job_id, node_id),
+    UNIQUE (job_id, rank)
+);
+
+CREATE INDEX IF NOT EXISTS idx_training_job_nodes_node_id ON training_job_nodes (node_id);
+
+-- existing single node assignments become rank 0 of their job
+INSERT INTO training_job_nodes (job_id, node_id, rank)
+SELECT n.assigned_job_id, n.node_id, 0
+FROM cluster_nodes n
+JOIN training_jobs j ON j.id = n.assigned_job_id;
@@ -0,0 +1,6 @@
+ALTER TABLE training_jobs
+DROP COLUMN IF EXISTS preemption_reason,
+DROP COLUMN IF EXISTS preempted_at;
+
+ALTER TABLE queues
+DROP COLUMN IF EXISTS preemptible;
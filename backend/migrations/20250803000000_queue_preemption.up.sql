@@ -0,0 +1,7 @@
+-- Whether jobs in this queue may be evicted to make room for higher priority queues.
+ALTER TABLE queues
+ADD COLUMN preemptible BOOLEAN NOT NULL DEFAULT FALSE;
+
+ALTER TABLE training_jobs
+ADD COLUMN preempted_at TIMESTAMPTZ,
+ADD COLUMN preemption_reason TEXT;
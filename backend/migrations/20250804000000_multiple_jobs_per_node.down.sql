@@ -0,0 +1,17 @@
+ALTER TABLE cluster_nodes
+ADD COLUMN assigned_job_id UUID,
+ADD COLUMN reported_job_id UUID;
+
+UPDATE cluster_nodes n
+SET assigned_job_id = (
+    SELECT a.job_id FROM training_job_nodes a WHERE a.node_id = n.node_id LIMIT 1
+),
+reported_job_id = n.reported_job_ids[1];
+
+ALTER TABLE cluster_nodes
+DROP COLUMN IF EXISTS reported_job_ids;
+
+ALTER TABLE training_job_nodes
+DROP COLUMN IF EXISTS gpu_count,
+DROP COLUMN IF EXISTS memory_mb,
+DROP COLUMN IF EXISTS cpu_millicores;
@@ -0,0 +1,26 @@
+-- Each allocation reserves part of a node's capacity, so that several jobs can
+-- share a node.
+ALTER TABLE training_job_nodes
+ADD COLUMN cpu_millicores INTEGER NOT NULL DEFAULT 0,
+ADD COLUMN memory_mb INTEGER NOT NULL DEFAULT 0,
+ADD COLUMN gpu_count INTEGER NOT NULL DEFAULT 0;
+
+UPDATE training_job_nodes a
+SET cpu_millicores = COALESCE((j.resource_requirements->>'cpu_millicores')::INTEGER, 0),
+    memory_mb = COALESCE((j.resource_requirements->>'memory_mb')::INTEGER, 0),
+    gpu_count = COALESCE((j.resource_requirements->'gpus'->>'count')::INTEGER, 0)
+FROM training_jobs j
+WHERE j.id = a.job_id;
+
+-- Agents report every job they are running.
+ALTER TABLE cluster_nodes
+ADD COLUMN reported_job_ids UUID[] NOT NULL DEFAULT '{}';
+
+UPDATE cluster_nodes
+SET reported_job_ids = ARRAY[reported_job_id]
+WHERE reported_job_id IS NOT NULL;
+
+-- Allocations are tracked in training_job_nodes from now on.
+ALTER TABLE cluster_nodes
+DROP COLUMN assigned_job_id,
+DROP COLUMN reported_job_id;
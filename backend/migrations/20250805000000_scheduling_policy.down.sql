@@ -0,0 +1,4 @@
+ALTER TABLE queues
+DROP COLUMN IF EXISTS scheduling_policy;
+
+DROP TYPE IF EXISTS scheduling_policy;
@@ -0,0 +1,5 @@
+CREATE TYPE scheduling_policy AS ENUM ('best_fit', 'spread', 'gpu_packing');
+
+-- How the scheduler picks nodes for jobs from this queue.
+ALTER TABLE queues
+ADD COLUMN scheduling_policy scheduling_policy NOT NULL DEFAULT 'best_fit';
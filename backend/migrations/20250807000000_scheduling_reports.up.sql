@@ -0,0 +1,7 @@
+-- The outcome of the most recent attempt to schedule each job, so users can
+-- see why a job is still queued.
+CREATE TABLE training_job_scheduling_reports (
+    job_id UUID PRIMARY KEY REFERENCES training_jobs(id) ON DELETE CASCADE,
+    attempted_at TIMESTAMPTZ NOT NULL,
+    report JSONB NOT NULL
+);
@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS training_jobs_dependencies_idx;
+
+ALTER TABLE training_jobs
+DROP COLUMN IF EXISTS dependencies;
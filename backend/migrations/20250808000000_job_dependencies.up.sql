@@ -0,0 +1,7 @@
+-- The jobs that have to finish before a job is scheduled, as a list of
+-- {"job_id", "condition"} objects.
+ALTER TABLE training_jobs
+ADD COLUMN dependencies JSONB NOT NULL DEFAULT '[]';
+
+-- Finds the jobs depending on a job when it fails.
+CREATE INDEX training_jobs_dependencies_idx ON training_jobs USING GIN (dependencies jsonb_path_ops);
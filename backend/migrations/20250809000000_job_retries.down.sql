@@ -0,0 +1,11 @@
+ALTER TABLE queues
+DROP COLUMN IF EXISTS retry_policy;
+
+ALTER TABLE training_jobs
+DROP COLUMN IF EXISTS retry_policy,
+DROP COLUMN IF EXISTS not_before,
+DROP COLUMN IF EXISTS avoid_node_id;
+
+DROP TABLE IF EXISTS training_job_attempts;
+
+DROP TYPE IF EXISTS attempt_outcome;
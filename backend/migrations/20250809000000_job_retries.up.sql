@@ -0,0 +1,23 @@
+CREATE TYPE attempt_outcome AS ENUM ('succeeded', 'failed', 'node_lost', 'preempted', 'cancelled');
+
+-- Every run of a job, so retries and lost nodes can be traced.
+CREATE TABLE training_job_attempts (
+    job_id UUID NOT NULL REFERENCES training_jobs(id) ON DELETE CASCADE,
+    attempt_number INTEGER NOT NULL,
+    node_id UUID,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    finished_at TIMESTAMPTZ,
+    outcome attempt_outcome,
+    exit_code INTEGER,
+    -- Whether the scheduler has decided if a failed attempt is retried.
+    retry_evaluated BOOLEAN NOT NULL DEFAULT FALSE,
+    PRIMARY KEY (job_id, attempt_number)
+);
+
+ALTER TABLE training_jobs
+ADD COLUMN retry_policy JSONB,
+ADD COLUMN not_before TIMESTAMPTZ,
+ADD COLUMN avoid_node_id UUID;
+
+ALTER TABLE queues
+ADD COLUMN retry_policy JSONB;
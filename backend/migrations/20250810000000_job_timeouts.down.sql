@@ -0,0 +1,12 @@
+ALTER TABLE queues
+DROP COLUMN IF EXISTS default_max_runtime_secs,
+DROP COLUMN IF EXISTS max_runtime_limit_secs;
+
+ALTER TABLE training_jobs
+DROP COLUMN IF EXISTS max_runtime_secs,
+DROP COLUMN IF EXISTS started_at;
+
+-- Postgres can't drop enum values, so timed out jobs are marked failed and
+-- the values are left in place.
+UPDATE training_jobs SET status = 'failed' WHERE status = 'timed_out';
+UPDATE training_job_attempts SET outcome = 'failed' WHERE outcome = 'timed_out';
@@ -0,0 +1,13 @@
+ALTER TYPE training_job_status ADD VALUE IF NOT EXISTS 'timed_out';
+ALTER TYPE attempt_outcome ADD VALUE IF NOT EXISTS 'timed_out';
+
+-- The longest a job may run, and when its current attempt started running.
+ALTER TABLE training_jobs
+ADD COLUMN max_runtime_secs BIGINT,
+ADD COLUMN started_at TIMESTAMPTZ;
+
+-- The runtime given to jobs that don't set their own, and the most they may
+-- ask for.
+ALTER TABLE queues
+ADD COLUMN default_max_runtime_secs BIGINT,
+ADD COLUMN max_runtime_limit_secs BIGINT;
@@ -0,0 +1,6 @@
+DROP TABLE IF EXISTS job_schedule_runs;
+
+DROP TABLE IF EXISTS job_schedules;
+
+DROP TYPE IF EXISTS schedule_run_outcome;
+DROP TYPE IF EXISTS concurrency_policy;
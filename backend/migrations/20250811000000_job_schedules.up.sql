@@ -0,0 +1,30 @@
+CREATE TYPE concurrency_policy AS ENUM ('allow', 'forbid', 'replace');
+CREATE TYPE schedule_run_outcome AS ENUM ('started', 'skipped', 'failed');
+
+-- Jobs that are submitted on a cron schedule.
+CREATE TABLE job_schedules (
+    schedule_id UUID PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    cron_expression TEXT NOT NULL,
+    job_template JSONB NOT NULL,
+    concurrency_policy concurrency_policy NOT NULL DEFAULT 'allow',
+    paused BOOLEAN NOT NULL DEFAULT FALSE,
+    next_run_at TIMESTAMPTZ,
+    -- The user the schedule's jobs are submitted on behalf of.
+    owner_id UUID REFERENCES users(user_id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_job_schedules_next_run_at ON job_schedules (next_run_at) WHERE NOT paused;
+
+-- Every time a schedule came due, and what came of it.
+CREATE TABLE job_schedule_runs (
+    schedule_id UUID NOT NULL REFERENCES job_schedules(schedule_id) ON DELETE CASCADE,
+    scheduled_at TIMESTAMPTZ NOT NULL,
+    outcome schedule_run_outcome NOT NULL,
+    job_id UUID REFERENCES training_jobs(id) ON DELETE SET NULL,
+    message TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (schedule_id, scheduled_at)
+);
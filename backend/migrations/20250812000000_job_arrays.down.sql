@@ -0,0 +1,8 @@
+DROP INDEX IF EXISTS idx_training_jobs_array_id;
+
+ALTER TABLE training_jobs
+DROP COLUMN IF EXISTS array_id,
+DROP COLUMN IF EXISTS array_index,
+DROP COLUMN IF EXISTS parameters;
+
+DROP TABLE IF EXISTS job_arrays;
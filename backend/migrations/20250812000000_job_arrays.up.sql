@@ -0,0 +1,16 @@
+-- Jobs submitted together from one template and a set of parameters.
+CREATE TABLE job_arrays (
+    array_id UUID PRIMARY KEY,
+    name TEXT NOT NULL,
+    size INTEGER NOT NULL,
+    max_concurrent INTEGER,
+    parameters JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE training_jobs
+ADD COLUMN array_id UUID REFERENCES job_arrays(array_id) ON DELETE CASCADE,
+ADD COLUMN array_index INTEGER,
+ADD COLUMN parameters JSONB NOT NULL DEFAULT '{}';
+
+CREATE INDEX IF NOT EXISTS idx_training_jobs_array_id ON training_jobs (array_id) WHERE array_id IS NOT NULL;
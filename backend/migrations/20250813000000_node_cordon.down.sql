@@ -0,0 +1,6 @@
+DROP TABLE IF EXISTS retained_node_settings;
+
+ALTER TABLE cluster_nodes
+DROP COLUMN IF EXISTS schedulability;
+
+DROP TYPE IF EXISTS node_schedulability;
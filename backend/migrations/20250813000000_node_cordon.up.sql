@@ -0,0 +1,12 @@
+-- Whether the scheduler may place new jobs on a node.
+CREATE TYPE node_schedulability AS ENUM ('schedulable', 'cordoned', 'draining');
+
+ALTER TABLE cluster_nodes
+ADD COLUMN schedulability node_schedulability NOT NULL DEFAULT 'schedulable';
+
+-- The cordon state of nodes that were removed after they stopped sending
+-- heartbeats, restored when they join again.
+CREATE TABLE retained_node_settings (
+    node_id UUID PRIMARY KEY,
+    schedulability node_schedulability NOT NULL
+);
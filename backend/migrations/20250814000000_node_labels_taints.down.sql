@@ -0,0 +1,10 @@
+ALTER TABLE retained_node_settings
+DROP COLUMN IF EXISTS taints;
+
+ALTER TABLE training_jobs
+DROP COLUMN IF EXISTS node_selector,
+DROP COLUMN IF EXISTS tolerations;
+
+ALTER TABLE cluster_nodes
+DROP COLUMN IF EXISTS labels,
+DROP COLUMN IF EXISTS taints;
@@ -0,0 +1,13 @@
+-- Labels advertised by a node's agent, and taints set on the node through the API.
+ALTER TABLE cluster_nodes
+ADD COLUMN labels JSONB NOT NULL DEFAULT '{}',
+ADD COLUMN taints JSONB NOT NULL DEFAULT '[]';
+
+-- The labels a job's nodes must have, and the taints it tolerates.
+ALTER TABLE training_jobs
+ADD COLUMN node_selector JSONB NOT NULL DEFAULT '{}',
+ADD COLUMN tolerations JSONB NOT NULL DEFAULT '[]';
+
+-- The taints of removed nodes, restored along with their cordon state.
+ALTER TABLE retained_node_settings
+ADD COLUMN taints JSONB NOT NULL DEFAULT '[]';
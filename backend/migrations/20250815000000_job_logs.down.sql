@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS training_job_log_sizes;
+
+DROP TABLE IF EXISTS training_job_logs;
@@ -0,0 +1,21 @@
+-- Log output of training jobs, in the chunks agents sent it in. The serial id
+-- orders the chunks and serves as the offset clients read from.
+CREATE TABLE training_job_logs (
+    id BIGSERIAL PRIMARY KEY,
+    job_id UUID NOT NULL REFERENCES training_jobs(id) ON DELETE CASCADE,
+    content TEXT NOT NULL,
+    -- The job's appended byte count right before the chunk was appended.
+    start_offset BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_training_job_logs_job_id ON training_job_logs (job_id, id);
+CREATE INDEX IF NOT EXISTS idx_training_job_logs_created_at ON training_job_logs (created_at);
+CREATE INDEX IF NOT EXISTS idx_training_job_logs_offset ON training_job_logs (job_id, start_offset);
+
+-- How many bytes of logs each job has appended so far. The counter only ever
+-- grows, so chunks can be trimmed by their offset without summing them.
+CREATE TABLE training_job_log_sizes (
+    job_id UUID PRIMARY KEY REFERENCES training_jobs(id) ON DELETE CASCADE,
+    appended_bytes BIGINT NOT NULL
+);
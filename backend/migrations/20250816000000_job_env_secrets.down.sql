@@ -0,0 +1,7 @@
+ALTER TABLE training_jobs
+DROP COLUMN IF EXISTS env,
+DROP COLUMN IF EXISTS secrets;
+
+DROP INDEX IF EXISTS idx_secrets_owner_id;
+
+DROP TABLE IF EXISTS secrets;
@@ -0,0 +1,19 @@
+-- Named values jobs can receive as environment variables, encrypted with a
+-- key derived from the backend's encryption key. Secrets belong to the user
+-- who created them.
+CREATE TABLE secrets (
+    name TEXT PRIMARY KEY,
+    owner_id UUID NOT NULL REFERENCES users(user_id) ON DELETE CASCADE,
+    ciphertext BYTEA NOT NULL,
+    nonce BYTEA NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_secrets_owner_id ON secrets (owner_id);
+
+-- The environment variables a job runs with, and the secrets it receives as
+-- environment variables, by variable name.
+ALTER TABLE training_jobs
+ADD COLUMN env JSONB NOT NULL DEFAULT '{}',
+ADD COLUMN secrets JSONB NOT NULL DEFAULT '{}';
@@ -0,0 +1,2 @@
+ALTER TABLE training_jobs
+ALTER COLUMN definition TYPE TEXT USING definition->>'image';
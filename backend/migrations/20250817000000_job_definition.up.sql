@@ -0,0 +1,4 @@
+-- Job definitions carry the image together with how to start it, instead of
+-- just the image.
+ALTER TABLE training_jobs
+ALTER COLUMN definition TYPE JSONB USING jsonb_build_object('image', definition);
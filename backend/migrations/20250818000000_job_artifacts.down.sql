@@ -0,0 +1,4 @@
+DROP TABLE IF EXISTS training_job_artifacts;
+
+ALTER TABLE training_jobs
+DROP COLUMN IF EXISTS outputs;
@@ -0,0 +1,14 @@
+-- The directories in a job's container whose files are uploaded as
+-- artifacts.
+ALTER TABLE training_jobs
+ADD COLUMN outputs JSONB NOT NULL DEFAULT '[]';
+
+-- The files agents have uploaded to the artifact store for a job. Uploading
+-- a path again replaces the earlier object.
+CREATE TABLE training_job_artifacts (
+    job_id UUID NOT NULL REFERENCES training_jobs(id) ON DELETE CASCADE,
+    path TEXT NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    uploaded_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (job_id, path)
+);
@@ -0,0 +1,3 @@
+-- Storage on the node mounted into a job's container.
+ALTER TABLE training_jobs
+ADD COLUMN volumes JSONB NOT NULL DEFAULT '[]';
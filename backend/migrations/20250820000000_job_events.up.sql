@@ -0,0 +1,10 @@
+-- An append-only timeline of what happened to each job, such as status
+-- changes, node assignments, preemptions and requeues.
+CREATE TABLE training_job_events (
+    id BIGSERIAL PRIMARY KEY,
+    job_id UUID NOT NULL REFERENCES training_jobs(id) ON DELETE CASCADE,
+    event JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_training_job_events_job_id ON training_job_events(job_id, id);
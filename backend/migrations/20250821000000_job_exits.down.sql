@@ -0,0 +1,2 @@
+ALTER TABLE training_jobs
+DROP COLUMN IF EXISTS exit;
@@ -0,0 +1,3 @@
+-- How a job's latest attempt ended, as reported by its node.
+ALTER TABLE training_jobs
+ADD COLUMN exit JSONB;
@@ -0,0 +1,13 @@
+-- Resource usage sampled by the agents while a job runs.
+CREATE TABLE training_job_metrics (
+    id BIGSERIAL PRIMARY KEY,
+    job_id UUID NOT NULL REFERENCES training_jobs(id) ON DELETE CASCADE,
+    node_id UUID NOT NULL,
+    sampled_at TIMESTAMPTZ NOT NULL,
+    cpu_millicores INTEGER,
+    memory_mb BIGINT,
+    gpus JSONB NOT NULL DEFAULT '[]'
+);
+
+CREATE INDEX idx_training_job_metrics_job_id ON training_job_metrics(job_id, sampled_at);
+CREATE INDEX idx_training_job_metrics_sampled_at ON training_job_metrics(sampled_at);
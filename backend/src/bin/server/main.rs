@@ -5,16 +5,30 @@ use tower_sessions::{cookie::Key, Expiry, SessionManagerLayer};
 use server::{
     config::{LilacConfig, LogFormat},
     domain::{
-        auth::service::AuthServiceImpl, cluster::service::ClusterServiceImpl,
-        queue::service::QueueServiceImpl, scheduler::service::SchedulerService,
-        training_job::service::TrainingJobServiceImpl, user::service::UserServiceImpl,
+        artifact::{ports::ObjectStore, service::ArtifactServiceImpl},
+        auth::service::AuthServiceImpl,
+        cluster::service::ClusterServiceImpl,
+        queue::service::QueueServiceImpl,
+        schedule::service::ScheduleServiceImpl,
+        scheduler::{
+            leader::LeaderElectionImpl, notifier::SchedulerNotifier, service::SchedulerService,
+        },
+        secret::{cipher::SecretCipher, service::SecretServiceImpl},
+        training_job::service::TrainingJobServiceImpl,
+        user::service::UserServiceImpl,
     },
     inbound::http::{AppState, HttpServer},
     outbound::{
         jwt::JwtManager,
+        object_store::s3::S3ObjectStore,
         persistence::postgres::{
+            artifact_repository::PostgresArtifactRepository,
             cluster_repository::PostgresClusterRepository,
-            queue_repository::PostgresQueueRepository, session_repository::PostgresSessionStore,
+            queue_repository::PostgresQueueRepository,
+            schedule_repository::PostgresScheduleRepository,
+            scheduler_lease_repository::PostgresSchedulerLeaseRepository,
+            scheduler_wakeups::PostgresSchedulerWakeups,
+            secret_repository::PostgresSecretRepository, session_repository::PostgresSessionStore,
             training_job_repository::PostgresTrainingJobRepository,
             user_repository::PostgresUserRepository,
         },
@@ -61,11 +75,21 @@ async fn main() -> anyhow::Result<()> {
     let jwt_manager = Arc::new(JwtManager::new(config.secret_key.expose_secret()));
     let training_job_repo = Arc::new(PostgresTrainingJobRepository::new(db_pool.clone()));
     let queue_repo = Arc::new(PostgresQueueRepository::new(db_pool.clone()));
+    let schedule_repo = Arc::new(PostgresScheduleRepository::new(db_pool.clone()));
+    let scheduler_lease_repo = Arc::new(PostgresSchedulerLeaseRepository::new(db_pool.clone()));
+    let secret_repo = Arc::new(PostgresSecretRepository::new(db_pool.clone()));
+    let artifact_repo = Arc::new(PostgresArtifactRepository::new(db_pool.clone()));
+    let object_store = config
+        .artifacts
+        .as_ref()
+        .map(|artifacts| Arc::new(S3ObjectStore::new(artifacts)) as Arc<dyn ObjectStore>);
 
     // 3. Construct domain services
+    let scheduler_notifier = SchedulerNotifier::new();
     let cluster_service = Arc::new(ClusterServiceImpl::new(
         cluster_repo.clone(),
         training_job_repo.clone(),
+        scheduler_notifier.clone(),
     ));
     let user_service = Arc::new(UserServiceImpl::new(user_repo.clone()));
     let session_store = PostgresSessionStore::new(db_pool.clone());
@@ -79,31 +103,67 @@ async fn main() -> anyhow::Result<()> {
     let training_job_service = Arc::new(TrainingJobServiceImpl::new(
         training_job_repo.clone(),
         cluster_repo.clone(),
+        queue_repo.clone(),
+        secret_repo.clone(),
+        scheduler_notifier.clone(),
+        config.logs.clone(),
     ));
     let queue_service = Arc::new(QueueServiceImpl::new(
         queue_repo.clone(),
         training_job_repo.clone(),
     ));
+    let secret_cipher = config
+        .encryption_key
+        .as_ref()
+        .map(|key| SecretCipher::new(key.expose_secret()))
+        .transpose()?;
+    if secret_cipher.is_none() {
+        tracing::warn!("No encryption_key is configured, secrets can't be created");
+    }
+    let secret_service = Arc::new(SecretServiceImpl::new(secret_repo, secret_cipher));
+    let artifact_service = Arc::new(ArtifactServiceImpl::new(
+        artifact_repo,
+        training_job_service.clone(),
+        object_store,
+    ));
+    let schedule_service = Arc::new(ScheduleServiceImpl::new(
+        schedule_repo,
+        training_job_service.clone(),
+    ));
 
     // 4. Construct Scheduler
+    let replica_id = config
+        .scheduler
+        .replica_id
+        .clone()
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    tracing::info!("Running as control plane replica {}", replica_id);
+    let scheduler_wakeups = PostgresSchedulerWakeups::new(db_pool.clone(), replica_id.clone());
+    let leader_election = Arc::new(LeaderElectionImpl::new(
+        scheduler_lease_repo,
+        replica_id,
+        std::time::Duration::from_secs(config.scheduler.lease_ttl_secs),
+    ));
     let agent_adapter = Arc::new(AgentSchedulerAdapter::new(cluster_repo.clone()));
     let scheduler_service = Arc::new(SchedulerService::new(
         training_job_repo.clone(),
         queue_repo.clone(),
         cluster_repo.clone(),
         agent_adapter,
+        leader_election.clone(),
+        config.scheduler.clone(),
+        config.logs.clone(),
+        config.metrics.clone(),
     ));
 
     // 5. Spawn background tasks
-    let scheduler_handle = tokio::spawn(async move {
-        let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
-        loop {
-            interval.tick().await;
-            if let Err(e) = scheduler_service.run_cycle().await {
-                tracing::error!("Scheduler cycle failed: {}", e);
-            }
-        }
-    });
+    let wakeup_notifier = scheduler_notifier.clone();
+    let wakeups_handle = tokio::spawn(async move { scheduler_wakeups.run(wakeup_notifier).await });
+    let scheduler_handle =
+        tokio::spawn(async move { scheduler_service.run(scheduler_notifier).await });
+    let schedule_interval = std::time::Duration::from_secs(config.scheduler.schedule_interval_secs);
+    let schedule_runner = schedule_service.clone();
+    let schedule_handle = tokio::spawn(async move { schedule_runner.run(schedule_interval).await });
 
     // 6. Construct and run inbound adapter (HTTP server)
     let app_state = AppState {
@@ -113,13 +173,19 @@ async fn main() -> anyhow::Result<()> {
         auth_service,
         training_job_service,
         queue_service,
+        schedule_service,
+        secret_service,
+        artifact_service,
+        leader_election,
     };
     let http_server = HttpServer::new(app_state, session_layer, config.http_port).await?;
 
-    // Run the server and wait for it and the scheduler to complete
+    // Run the server and wait for it and the background tasks to complete
     tokio::select! {
         _ = http_server.run() => {},
         _ = scheduler_handle => {},
+        _ = wakeups_handle => {},
+        _ = schedule_handle => {},
     }
 
     Ok(())
@@ -3,6 +3,7 @@ use serde::Deserialize;
 use std::path::PathBuf;
 use tracing::{level_filters::LevelFilter, Level};
 use tracing_subscriber::filter::Directive;
+use url::Url;
 
 #[derive(Clone, Debug, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -45,6 +46,104 @@ impl From<&LogLevel> for Directive {
     }
 }
 
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, rename_all = "snake_case")]
+pub struct SchedulerConfig {
+    /// How often the scheduler runs when nothing triggers it earlier.
+    pub interval_secs: u64,
+    /// How long a node may go without a heartbeat before it is considered dead
+    /// and its jobs are re-queued.
+    pub dead_node_threshold_secs: u64,
+    /// How long the scheduler leader's lease lasts without being renewed.
+    /// Another replica takes over once it has expired.
+    pub lease_ttl_secs: u64,
+    /// Identifies this replica in leader election. A random ID is generated
+    /// on startup if not set.
+    pub replica_id: Option<String>,
+    /// How often due job schedules are looked for.
+    pub schedule_interval_secs: u64,
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: 60,
+            dead_node_threshold_secs: 90,
+            lease_ttl_secs: 30,
+            replica_id: None,
+            schedule_interval_secs: 15,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, rename_all = "snake_case")]
+pub struct LogsConfig {
+    /// How many bytes of log output are kept per job. The oldest output is
+    /// dropped first once a job exceeds it.
+    pub max_bytes_per_job: i64,
+    /// How many days log output is kept for.
+    pub retention_days: i64,
+}
+
+impl Default for LogsConfig {
+    fn default() -> Self {
+        Self {
+            max_bytes_per_job: 10 * 1024 * 1024,
+            retention_days: 30,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, rename_all = "snake_case")]
+pub struct MetricsConfig {
+    /// How many days the resource usage samples of jobs are kept for.
+    pub retention_days: i64,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self { retention_days: 30 }
+    }
+}
+
+/// The S3-compatible bucket job outputs are uploaded to.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct ArtifactsConfig {
+    /// The URL of the S3 API, e.g. `https://s3.us-east-1.amazonaws.com` or
+    /// `http://localhost:9000` for MinIO.
+    pub endpoint: Url,
+    pub bucket: String,
+    #[serde(default = "default_region")]
+    pub region: String,
+    pub access_key_id: String,
+    pub secret_access_key: SecretString,
+    /// Address the bucket as part of the path instead of the host name, as
+    /// MinIO expects.
+    #[serde(default = "default_path_style")]
+    pub path_style: bool,
+    /// Prepended to the keys of all objects.
+    #[serde(default)]
+    pub prefix: String,
+    /// How long the upload and download URLs handed out stay valid.
+    #[serde(default = "default_url_expiry_secs")]
+    pub url_expiry_secs: u64,
+}
+
+fn default_region() -> String {
+    "us-east-1".to_string()
+}
+
+fn default_path_style() -> bool {
+    true
+}
+
+fn default_url_expiry_secs() -> u64 {
+    3600
+}
+
 #[derive(Clone, Debug, Deserialize, Default)]
 #[serde(rename_all = "snake_case")]
 pub struct LilacConfig {
@@ -52,6 +151,11 @@ pub struct LilacConfig {
     pub tls: Option<TlsConfig>,
     pub http_port: u16,
     pub secret_key: SecretString,
+    /// Encrypts stored secrets. Kept apart from `secret_key`, so rotating
+    /// session keys doesn't make stored secrets unreadable. Secrets can't be
+    /// created if not set.
+    #[serde(default)]
+    pub encryption_key: Option<SecretString>,
     #[serde(default)]
     pub log_format: LogFormat,
     #[serde(default)]
@@ -60,6 +164,15 @@ pub struct LilacConfig {
     pub disable_sign_up: bool,
     #[serde(default)]
     pub allowed_usernames: Option<Vec<String>>,
+    #[serde(default)]
+    pub scheduler: SchedulerConfig,
+    #[serde(default)]
+    pub logs: LogsConfig,
+    #[serde(default)]
+    pub metrics: MetricsConfig,
+    /// Where job artifacts are stored. Jobs can't upload outputs if not set.
+    #[serde(default)]
+    pub artifacts: Option<ArtifactsConfig>,
 }
 
 impl LilacConfig {
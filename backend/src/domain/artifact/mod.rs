@@ -0,0 +1,161 @@
+pub mod models;
+pub mod ports;
+pub mod service;
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, sync::Arc};
+
+    use chrono::Utc;
+    use url::Url;
+
+    use super::{
+        models::validate_artifact_path,
+        ports::{MockArtifactRepository, MockObjectStore},
+        service::{ArtifactService, ArtifactServiceError, ArtifactServiceImpl},
+    };
+    use crate::domain::{
+        cluster::models::ClusterId,
+        training_job::{
+            models::{
+                JobDefinition, JobId, JobOutput, ResourceRequirements, TrainingJob,
+                TrainingJobStatus,
+            },
+            service::{MockTrainingJobService, TrainingJobServiceError},
+        },
+    };
+
+    fn outputs() -> Vec<JobOutput> {
+        vec![JobOutput {
+            path: "/workspace/checkpoints".to_string(),
+            upload_interval_secs: Some(600),
+        }]
+    }
+
+    fn training_job() -> TrainingJob {
+        let now = Utc::now();
+        TrainingJob {
+            id: JobId::generate(),
+            name: "train".to_string(),
+            definition: JobDefinition::new("trainer:v1"),
+            status: TrainingJobStatus::Running,
+            node_id: None,
+            queue_id: None,
+            resource_requirements: ResourceRequirements {
+                cpu_millicores: 1000,
+                memory_mb: 1024,
+                gpus: None,
+            },
+            node_count: 1,
+            preempted_at: None,
+            preemption_reason: None,
+            dependencies: Vec::new(),
+            retry_policy: None,
+            not_before: None,
+            avoid_node_id: None,
+            max_runtime_secs: None,
+            started_at: None,
+            exit: None,
+            array_id: None,
+            array_index: None,
+            parameters: BTreeMap::new(),
+            node_selector: BTreeMap::new(),
+            tolerations: Vec::new(),
+            env: BTreeMap::new(),
+            secrets: BTreeMap::new(),
+            outputs: outputs(),
+            volumes: Vec::new(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn test_validate_artifact_path() {
+        for path in [
+            "workspace/checkpoints/step-100.pt",
+            "workspace/checkpoints/epoch-1/model.safetensors",
+            "rank-1/workspace/checkpoints/step-100.pt",
+        ] {
+            assert!(validate_artifact_path(path, &outputs()).is_ok(), "{path}");
+        }
+        for path in [
+            "/workspace/checkpoints/step-100.pt",
+            "workspace/checkpoints/../../etc/passwd",
+            "workspace/checkpoints",
+            "workspace/checkpoints-old/step-100.pt",
+            "workspace/other/step-100.pt",
+            "rank-x/workspace/checkpoints/step-100.pt",
+        ] {
+            assert!(validate_artifact_path(path, &outputs()).is_err(), "{path}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_presign_uploads() {
+        let job = training_job();
+        let job_id = job.id;
+        let cluster_id = ClusterId::generate();
+
+        let mut job_service = MockTrainingJobService::new();
+        job_service
+            .expect_get_job_on_cluster()
+            .returning(move |id, cluster| {
+                if *cluster == cluster_id {
+                    Ok(job.clone())
+                } else {
+                    Err(TrainingJobServiceError::JobNotOnCluster(*id))
+                }
+            });
+        let mut store = MockObjectStore::new();
+        store.expect_presign_put().returning(|key| {
+            Ok(Url::parse(&format!("https://bucket.example.com/{key}?signed")).unwrap())
+        });
+        let service = ArtifactServiceImpl::new(
+            Arc::new(MockArtifactRepository::new()),
+            Arc::new(job_service),
+            Some(Arc::new(store)),
+        );
+
+        let path = "workspace/checkpoints/step-100.pt".to_string();
+        let uploads = service
+            .presign_uploads(&job_id, &cluster_id, vec![path.clone()])
+            .await
+            .unwrap();
+        assert_eq!(uploads[0].path, path);
+        assert_eq!(
+            uploads[0].url.path(),
+            format!("/{job_id}/workspace/checkpoints/step-100.pt")
+        );
+
+        let result = service
+            .presign_uploads(&job_id, &cluster_id, vec!["etc/passwd".to_string()])
+            .await;
+        assert!(matches!(result, Err(ArtifactServiceError::InvalidPath(_))));
+
+        let result = service
+            .presign_uploads(&job_id, &ClusterId::generate(), vec![path])
+            .await;
+        assert!(matches!(
+            result,
+            Err(ArtifactServiceError::JobNotOnCluster(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_uploads_need_a_store() {
+        let service = ArtifactServiceImpl::new(
+            Arc::new(MockArtifactRepository::new()),
+            Arc::new(MockTrainingJobService::new()),
+            None,
+        );
+        let result = service
+            .presign_uploads(
+                &JobId::generate(),
+                &ClusterId::generate(),
+                vec!["workspace/checkpoints/step-100.pt".to_string()],
+            )
+            .await;
+        assert!(matches!(result, Err(ArtifactServiceError::NotConfigured)));
+    }
+}
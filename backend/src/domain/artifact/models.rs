@@ -0,0 +1,69 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::domain::training_job::models::{JobId, JobOutput};
+
+/// The longest path an artifact may have.
+const MAX_PATH_LEN: usize = 1024;
+
+/// A file uploaded from one of a job's outputs.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Artifact {
+    pub job_id: JobId,
+    /// Where the file was in the job's container, without the leading `/`.
+    /// Files from the other nodes of a multi-node job are prefixed with
+    /// `rank-<n>/`.
+    pub path: String,
+    pub size_bytes: i64,
+    pub uploaded_at: DateTime<Utc>,
+}
+
+/// A file an agent uploads from a job's outputs.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ArtifactFile {
+    pub path: String,
+    pub size_bytes: i64,
+}
+
+/// Where an agent uploads a file to.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ArtifactUpload {
+    pub path: String,
+    pub url: Url,
+}
+
+/// Checks that an agent may upload a file to the path, i.e. that it lies
+/// inside one of the job's outputs. Returns what is wrong with it otherwise.
+pub fn validate_artifact_path(path: &str, outputs: &[JobOutput]) -> Result<(), String> {
+    if path.len() > MAX_PATH_LEN {
+        return Err(format!(
+            "artifact paths may be at most {MAX_PATH_LEN} bytes long"
+        ));
+    }
+    if path
+        .split('/')
+        .any(|segment| segment.is_empty() || segment == "." || segment == "..")
+    {
+        return Err(format!("'{path}' must be a normalized relative path"));
+    }
+    let in_container = match path.split_once('/') {
+        Some((rank, rest))
+            if rank
+                .strip_prefix("rank-")
+                .is_some_and(|n| n.parse::<u32>().is_ok()) =>
+        {
+            rest
+        }
+        _ => path,
+    };
+    let in_output = outputs.iter().any(|output| {
+        in_container
+            .strip_prefix(output.path.trim_start_matches('/'))
+            .is_some_and(|rest| rest.starts_with('/'))
+    });
+    if !in_output {
+        return Err(format!("'{path}' is not inside any of the job's outputs"));
+    }
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use async_trait::async_trait;
+use url::Url;
+
+use super::models::{Artifact, ArtifactFile};
+use crate::domain::training_job::models::JobId;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ArtifactRepositoryError {
+    #[error("artifact {0} not found")]
+    NotFound(String),
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait ArtifactRepository: Send + Sync {
+    /// Records files uploaded for a job, replacing earlier uploads of the
+    /// same paths.
+    async fn record_artifacts(
+        &self,
+        job_id: &JobId,
+        files: &[ArtifactFile],
+    ) -> Result<(), ArtifactRepositoryError>;
+    /// The job's artifacts, ordered by path.
+    async fn list_artifacts(
+        &self,
+        job_id: &JobId,
+    ) -> Result<Vec<Artifact>, ArtifactRepositoryError>;
+    async fn get_artifact(
+        &self,
+        job_id: &JobId,
+        path: &str,
+    ) -> Result<Artifact, ArtifactRepositoryError>;
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ObjectStoreError {
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+/// Hands out short-lived URLs for the objects of a bucket, so that files move
+/// between agents, users and the store without passing through the backend.
+#[cfg_attr(test, mockall::automock)]
+pub trait ObjectStore: Send + Sync {
+    /// A URL the object can be uploaded to with a `PUT` request.
+    fn presign_put(&self, key: &str) -> Result<Url, ObjectStoreError>;
+    /// A URL the object can be downloaded from with a `GET` request.
+    fn presign_get(&self, key: &str) -> Result<Url, ObjectStoreError>;
+}
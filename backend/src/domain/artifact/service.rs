@@ -0,0 +1,179 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use url::Url;
+
+use crate::domain::{
+    artifact::{
+        models::{validate_artifact_path, Artifact, ArtifactFile, ArtifactUpload},
+        ports::{ArtifactRepository, ArtifactRepositoryError, ObjectStore, ObjectStoreError},
+    },
+    cluster::models::ClusterId,
+    training_job::{
+        models::{JobId, TrainingJob},
+        service::{TrainingJobService, TrainingJobServiceError},
+    },
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ArtifactServiceError {
+    #[error("no artifact store is configured")]
+    NotConfigured,
+    #[error("training job {0} not found")]
+    JobNotFound(String),
+    #[error("training job {0} was not placed on this cluster")]
+    JobNotOnCluster(JobId),
+    #[error("artifact {0} not found")]
+    ArtifactNotFound(String),
+    #[error("invalid artifact path: {0}")]
+    InvalidPath(String),
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+impl From<ArtifactRepositoryError> for ArtifactServiceError {
+    fn from(error: ArtifactRepositoryError) -> Self {
+        match error {
+            ArtifactRepositoryError::NotFound(path) => Self::ArtifactNotFound(path),
+            ArtifactRepositoryError::Unknown(error) => Self::Unknown(error),
+        }
+    }
+}
+
+impl From<ObjectStoreError> for ArtifactServiceError {
+    fn from(error: ObjectStoreError) -> Self {
+        match error {
+            ObjectStoreError::Unknown(error) => Self::Unknown(error),
+        }
+    }
+}
+
+impl From<TrainingJobServiceError> for ArtifactServiceError {
+    fn from(error: TrainingJobServiceError) -> Self {
+        match error {
+            TrainingJobServiceError::TrainingJobNotFound(id) => Self::JobNotFound(id),
+            TrainingJobServiceError::JobNotOnCluster(id) => Self::JobNotOnCluster(id),
+            TrainingJobServiceError::Unknown(error) => Self::Unknown(error),
+            _ => Self::Unknown(anyhow::anyhow!(error)),
+        }
+    }
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait ArtifactService: Send + Sync {
+    /// Hands out URLs to upload files from a job's outputs to. Only the
+    /// agents of the cluster the job was placed on may upload its files.
+    async fn presign_uploads(
+        &self,
+        job_id: &JobId,
+        cluster_id: &ClusterId,
+        paths: Vec<String>,
+    ) -> Result<Vec<ArtifactUpload>, ArtifactServiceError>;
+    /// Records the files an agent has uploaded for a job.
+    async fn record_uploads(
+        &self,
+        job_id: &JobId,
+        cluster_id: &ClusterId,
+        files: Vec<ArtifactFile>,
+    ) -> Result<(), ArtifactServiceError>;
+    async fn list_artifacts(&self, job_id: &JobId) -> Result<Vec<Artifact>, ArtifactServiceError>;
+    /// A short-lived URL to download the artifact from.
+    async fn download_url(&self, job_id: &JobId, path: &str) -> Result<Url, ArtifactServiceError>;
+}
+
+pub struct ArtifactServiceImpl {
+    repository: Arc<dyn ArtifactRepository>,
+    training_job_service: Arc<dyn TrainingJobService>,
+    store: Option<Arc<dyn ObjectStore>>,
+}
+
+impl ArtifactServiceImpl {
+    /// Without a store, jobs can't upload outputs and artifacts recorded
+    /// before can't be downloaded.
+    pub fn new(
+        repository: Arc<dyn ArtifactRepository>,
+        training_job_service: Arc<dyn TrainingJobService>,
+        store: Option<Arc<dyn ObjectStore>>,
+    ) -> Self {
+        Self {
+            repository,
+            training_job_service,
+            store,
+        }
+    }
+
+    fn store(&self) -> Result<&dyn ObjectStore, ArtifactServiceError> {
+        self.store
+            .as_deref()
+            .ok_or(ArtifactServiceError::NotConfigured)
+    }
+
+    fn check_paths<'a>(
+        job: &TrainingJob,
+        mut paths: impl Iterator<Item = &'a str>,
+    ) -> Result<(), ArtifactServiceError> {
+        paths.try_for_each(|path| {
+            validate_artifact_path(path, &job.outputs).map_err(ArtifactServiceError::InvalidPath)
+        })
+    }
+}
+
+/// The key of an artifact's object, relative to the store's prefix.
+fn object_key(job_id: &JobId, path: &str) -> String {
+    format!("{job_id}/{path}")
+}
+
+#[async_trait]
+impl ArtifactService for ArtifactServiceImpl {
+    async fn presign_uploads(
+        &self,
+        job_id: &JobId,
+        cluster_id: &ClusterId,
+        paths: Vec<String>,
+    ) -> Result<Vec<ArtifactUpload>, ArtifactServiceError> {
+        let store = self.store()?;
+        let job = self
+            .training_job_service
+            .get_job_on_cluster(job_id, cluster_id)
+            .await?;
+        Self::check_paths(&job, paths.iter().map(String::as_str))?;
+        paths
+            .into_iter()
+            .map(|path| {
+                let url = store.presign_put(&object_key(job_id, &path))?;
+                Ok(ArtifactUpload { path, url })
+            })
+            .collect()
+    }
+
+    async fn record_uploads(
+        &self,
+        job_id: &JobId,
+        cluster_id: &ClusterId,
+        files: Vec<ArtifactFile>,
+    ) -> Result<(), ArtifactServiceError> {
+        let job = self
+            .training_job_service
+            .get_job_on_cluster(job_id, cluster_id)
+            .await?;
+        Self::check_paths(&job, files.iter().map(|file| file.path.as_str()))?;
+        if files.is_empty() {
+            return Ok(());
+        }
+        Ok(self.repository.record_artifacts(job_id, &files).await?)
+    }
+
+    async fn list_artifacts(&self, job_id: &JobId) -> Result<Vec<Artifact>, ArtifactServiceError> {
+        self.training_job_service
+            .get_training_job_by_id(job_id)
+            .await?;
+        Ok(self.repository.list_artifacts(job_id).await?)
+    }
+
+    async fn download_url(&self, job_id: &JobId, path: &str) -> Result<Url, ArtifactServiceError> {
+        let store = self.store()?;
+        let artifact = self.repository.get_artifact(job_id, path).await?;
+        Ok(store.presign_get(&object_key(job_id, &artifact.path))?)
+    }
+}
@@ -2,6 +2,7 @@ use axum::{extract::FromRequestParts, http::request::Parts};
 use serde::{Deserialize, Serialize};
 
 use headers::{authorization::Bearer, Authorization, HeaderMapExt};
+use secrecy::SecretString;
 
 use crate::inbound::http::AppState;
 use crate::{domain::user::models::UserId, inbound::http::errors::ApiError};
@@ -75,6 +76,43 @@ impl FromRequestParts<AppState> for Claims {
     }
 }
 
+/// The user calling an endpoint the CLI uses as well, authenticated with
+/// either a session token or one of their API keys.
+#[derive(Debug)]
+pub struct UserOrApiKey {
+    pub user_id: UserId,
+}
+
+impl FromRequestParts<AppState> for UserOrApiKey {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let bearer_token =
+            parts
+                .headers
+                .typed_get::<Authorization<Bearer>>()
+                .ok_or(ApiError::Unauthorized(
+                    "Missing Authorization header".into(),
+                ))?;
+
+        if let Ok(token_claims) = state.auth_service.validate_token(bearer_token.token()) {
+            return Ok(Self {
+                user_id: token_claims.sub,
+            });
+        }
+
+        let user = state
+            .user_service
+            .authenticate_by_api_key(&SecretString::from(bearer_token.token().to_string()))
+            .await?;
+
+        Ok(Self { user_id: user.id })
+    }
+}
+
 #[cfg(test)]
 impl Claims {
     /// Creates mock Claims for a given user ID.
@@ -2,3 +2,379 @@ pub mod errors;
 pub mod models;
 pub mod ports;
 pub mod service;
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, sync::Arc, time::Duration};
+
+    use async_trait::async_trait;
+    use chrono::Utc;
+    use mockall::predicate::*;
+
+    use super::{
+        errors::ClusterApiKeyRepositoryError,
+        models::{
+            Architecture, Cluster, ClusterDetails, ClusterId, ClusterNode, ClusterSummary, Cpu,
+            CpuManufacturer, CreateClusterRequest, JobInfo, NodeId, NodeSchedulability, Taint,
+            UpdateNodeStatusRequest,
+        },
+        ports::{ClusterApiKeyRepository, ClusterRepository, ClusterRepositoryError},
+        service::{ClusterService, ClusterServiceError, ClusterServiceImpl},
+    };
+    use crate::domain::{
+        scheduler::notifier::SchedulerNotifier,
+        training_job::{
+            models::{
+                JobAllocation, JobDefinition, JobId, ResourceRequirements, TrainingJob,
+                TrainingJobStatus,
+            },
+            ports::MockTrainingJobRepository,
+        },
+        user::models::{ApiKey, ApiKeyId},
+    };
+
+    // The service needs a single repository for clusters and their API keys.
+    mockall::mock! {
+        Store {}
+
+        #[async_trait]
+        impl ClusterRepository for Store {
+            async fn create_cluster(
+                &self,
+                req: &CreateClusterRequest,
+            ) -> Result<Cluster, ClusterRepositoryError>;
+            async fn get_cluster_by_id(
+                &self,
+                id: &ClusterId,
+            ) -> Result<Cluster, ClusterRepositoryError>;
+            async fn get_cluster_details(
+                &self,
+                id: &ClusterId,
+            ) -> Result<ClusterDetails, ClusterRepositoryError>;
+            async fn list_clusters(&self) -> Result<Vec<ClusterSummary>, ClusterRepositoryError>;
+            async fn delete_cluster(&self, id: &ClusterId) -> Result<(), ClusterRepositoryError>;
+            async fn list_cluster_jobs(
+                &self,
+                id: &ClusterId,
+            ) -> Result<Vec<TrainingJob>, ClusterRepositoryError>;
+            async fn list_all_nodes(&self) -> Result<Vec<ClusterNode>, ClusterRepositoryError>;
+            async fn list_cluster_nodes(
+                &self,
+                id: &ClusterId,
+            ) -> Result<Vec<ClusterNode>, ClusterRepositoryError>;
+            async fn get_cluster_node_by_id(
+                &self,
+                id: &NodeId,
+            ) -> Result<ClusterNode, ClusterRepositoryError>;
+            async fn update_cluster_node_status(
+                &self,
+                req: &UpdateNodeStatusRequest,
+            ) -> Result<ClusterNode, ClusterRepositoryError>;
+            async fn set_node_schedulability(
+                &self,
+                node_id: &NodeId,
+                schedulability: NodeSchedulability,
+            ) -> Result<ClusterNode, ClusterRepositoryError>;
+            async fn set_node_taints(
+                &self,
+                node_id: &NodeId,
+                taints: &[Taint],
+            ) -> Result<ClusterNode, ClusterRepositoryError>;
+            async fn delete_cluster_node(
+                &self,
+                node_id: &NodeId,
+            ) -> Result<(), ClusterRepositoryError>;
+            async fn assign_job_to_nodes(
+                &self,
+                job_id: &JobId,
+                node_ids: &[NodeId],
+                requirements: &ResourceRequirements,
+            ) -> Result<bool, ClusterRepositoryError>;
+            async fn list_job_allocations(
+                &self,
+                job_id: &JobId,
+            ) -> Result<Vec<JobAllocation>, ClusterRepositoryError>;
+            async fn release_job_nodes(&self, job_id: &JobId) -> Result<(), ClusterRepositoryError>;
+        }
+
+        #[async_trait]
+        impl ClusterApiKeyRepository for Store {
+            async fn create_api_key(&self, key: &ApiKey) -> Result<(), ClusterApiKeyRepositoryError>;
+            async fn find_cluster_by_api_key_hash(
+                &self,
+                key_hash: &str,
+            ) -> Result<Cluster, ClusterApiKeyRepositoryError>;
+            async fn get_api_key(
+                &self,
+                cluster_id: &ClusterId,
+                key_id: &ApiKeyId,
+            ) -> Result<ApiKey, ClusterApiKeyRepositoryError>;
+            async fn list_api_keys_for_cluster(
+                &self,
+                cluster_id: &ClusterId,
+            ) -> Result<Vec<ApiKey>, ClusterApiKeyRepositoryError>;
+            async fn delete_api_key(
+                &self,
+                cluster_id: &ClusterId,
+                key_id: &ApiKeyId,
+            ) -> Result<(), ClusterApiKeyRepositoryError>;
+        }
+    }
+
+    /// The cluster the tests' nodes belong to and send their heartbeats as.
+    fn cluster_id() -> ClusterId {
+        ClusterId::default()
+    }
+
+    fn node(node_id: NodeId, reported_job_ids: Vec<JobId>) -> ClusterNode {
+        let mut node = ClusterNode::create(
+            node_id,
+            cluster_id(),
+            64 * 1024,
+            Cpu {
+                manufacturer: CpuManufacturer::Intel,
+                architecture: Architecture::X86_64,
+                millicores: 64000,
+            },
+            None,
+        );
+        node.reported_job_ids = reported_job_ids;
+        node
+    }
+
+    fn heartbeat(node_id: NodeId, jobs: Vec<JobInfo>) -> UpdateNodeStatusRequest {
+        UpdateNodeStatusRequest {
+            node_id,
+            cluster_id: cluster_id(),
+            heartbeat_timestamp: Utc::now(),
+            memory_info: 64 * 1024,
+            cpu_info: Cpu {
+                manufacturer: CpuManufacturer::Intel,
+                architecture: Architecture::X86_64,
+                millicores: 64000,
+            },
+            gpu_info: None,
+            jobs,
+            address: None,
+            labels: BTreeMap::new(),
+        }
+    }
+
+    fn running_job() -> TrainingJob {
+        let now = Utc::now();
+        TrainingJob {
+            id: JobId::generate(),
+            name: "test".to_string(),
+            definition: JobDefinition::new("definition"),
+            status: TrainingJobStatus::Running,
+            node_id: None,
+            queue_id: None,
+            resource_requirements: ResourceRequirements {
+                cpu_millicores: 1000,
+                memory_mb: 1024,
+                gpus: None,
+            },
+            node_count: 1,
+            preempted_at: None,
+            preemption_reason: None,
+            dependencies: Vec::new(),
+            retry_policy: None,
+            not_before: None,
+            avoid_node_id: None,
+            max_runtime_secs: None,
+            started_at: Some(now),
+            exit: None,
+            array_id: None,
+            array_index: None,
+            parameters: BTreeMap::new(),
+            node_selector: BTreeMap::new(),
+            tolerations: Vec::new(),
+            env: BTreeMap::new(),
+            secrets: BTreeMap::new(),
+            outputs: vec![],
+            volumes: vec![],
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Whether the scheduler was asked for a cycle.
+    async fn notified(notifier: &SchedulerNotifier) -> bool {
+        tokio::time::timeout(Duration::from_millis(100), notifier.notified())
+            .await
+            .is_ok()
+    }
+
+    #[tokio::test]
+    async fn test_new_node_notifies_scheduler() {
+        let node_id = NodeId::generate();
+        let mut mock_store = MockStore::new();
+        mock_store
+            .expect_get_cluster_node_by_id()
+            .returning(|id| Err(ClusterRepositoryError::NotFound(id.to_string())));
+        mock_store
+            .expect_update_cluster_node_status()
+            .times(1)
+            .returning(move |_| Ok(node(node_id, vec![])));
+
+        let notifier = SchedulerNotifier::new();
+        let service = ClusterServiceImpl::new(
+            Arc::new(mock_store),
+            Arc::new(MockTrainingJobRepository::new()),
+            notifier.clone(),
+        );
+        service
+            .update_node_status(heartbeat(node_id, vec![]))
+            .await
+            .unwrap();
+
+        assert!(notified(&notifier).await);
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_rejects_node_of_another_cluster() {
+        let node_id = NodeId::generate();
+        let mut mock_store = MockStore::new();
+        mock_store
+            .expect_get_cluster_node_by_id()
+            .returning(move |_| Ok(node(node_id, vec![])));
+        mock_store.expect_update_cluster_node_status().times(0);
+        let service = ClusterServiceImpl::new(
+            Arc::new(mock_store),
+            Arc::new(MockTrainingJobRepository::new()),
+            SchedulerNotifier::new(),
+        );
+
+        // The other cluster's key can't heartbeat as the node, nor report
+        // on or receive its jobs.
+        let mut other_cluster = heartbeat(node_id, vec![]);
+        other_cluster.cluster_id = ClusterId::generate();
+        let result = service.update_node_status(other_cluster).await;
+        assert!(matches!(
+            result,
+            Err(ClusterServiceError::InvalidPermissions)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_rejects_node_claimed_by_another_cluster_meanwhile() {
+        // The node was unknown when the heartbeat came in, but another
+        // cluster registered it before it could be recorded.
+        let node_id = NodeId::generate();
+        let mut mock_store = MockStore::new();
+        mock_store
+            .expect_get_cluster_node_by_id()
+            .returning(|id| Err(ClusterRepositoryError::NotFound(id.to_string())));
+        mock_store
+            .expect_update_cluster_node_status()
+            .times(1)
+            .returning(|req| Err(ClusterRepositoryError::NotFound(req.node_id.to_string())));
+        let service = ClusterServiceImpl::new(
+            Arc::new(mock_store),
+            Arc::new(MockTrainingJobRepository::new()),
+            SchedulerNotifier::new(),
+        );
+
+        let result = service.update_node_status(heartbeat(node_id, vec![])).await;
+        assert!(matches!(
+            result,
+            Err(ClusterServiceError::InvalidPermissions)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_unchanged_node_does_not_notify_scheduler() {
+        let node_id = NodeId::generate();
+        let mut mock_store = MockStore::new();
+        mock_store
+            .expect_get_cluster_node_by_id()
+            .returning(move |_| Ok(node(node_id, vec![])));
+        mock_store
+            .expect_update_cluster_node_status()
+            .returning(move |_| Ok(node(node_id, vec![])));
+
+        let notifier = SchedulerNotifier::new();
+        let service = ClusterServiceImpl::new(
+            Arc::new(mock_store),
+            Arc::new(MockTrainingJobRepository::new()),
+            notifier.clone(),
+        );
+        service
+            .update_node_status(heartbeat(node_id, vec![]))
+            .await
+            .unwrap();
+
+        assert!(!notified(&notifier).await);
+    }
+
+    #[tokio::test]
+    async fn test_finished_job_notifies_scheduler() {
+        let node_id = NodeId::generate();
+        let job = running_job();
+        let job_id = job.id;
+
+        let mut mock_store = MockStore::new();
+        // The agent keeps reporting the job until it has removed it.
+        mock_store
+            .expect_get_cluster_node_by_id()
+            .returning(move |_| Ok(node(node_id, vec![job_id])));
+        mock_store
+            .expect_list_job_allocations()
+            .with(eq(job_id))
+            .returning(move |_| {
+                Ok(vec![JobAllocation {
+                    job_id,
+                    node_id,
+                    rank: 0,
+                    cpu_millicores: 1000,
+                    memory_mb: 1024,
+                    gpu_count: 0,
+                }])
+            });
+        mock_store
+            .expect_release_job_nodes()
+            .with(eq(job_id))
+            .times(1)
+            .returning(|_| Ok(()));
+        mock_store
+            .expect_update_cluster_node_status()
+            .returning(move |_| Ok(node(node_id, vec![job_id])));
+
+        let mut mock_job_repo = MockTrainingJobRepository::new();
+        mock_job_repo
+            .expect_get_training_job_by_id()
+            .returning(move |_| Ok(job.clone()));
+        mock_job_repo
+            .expect_update_status()
+            .with(eq(job_id), eq(TrainingJobStatus::Succeeded))
+            .times(1)
+            .returning(|_, _| Ok(()));
+        mock_job_repo.expect_record_event().returning(|_, _| Ok(()));
+        mock_job_repo
+            .expect_finish_attempt()
+            .returning(|_, _, _| Ok(None));
+        mock_job_repo.expect_set_exit().returning(|_, _| Ok(()));
+
+        let notifier = SchedulerNotifier::new();
+        let service = ClusterServiceImpl::new(
+            Arc::new(mock_store),
+            Arc::new(mock_job_repo),
+            notifier.clone(),
+        );
+        service
+            .update_node_status(heartbeat(
+                node_id,
+                vec![JobInfo {
+                    job_id,
+                    status: TrainingJobStatus::Succeeded,
+                    exit_code: Some(0),
+                    oom_killed: false,
+                    error: None,
+                }],
+            ))
+            .await
+            .unwrap();
+
+        assert!(notified(&notifier).await);
+    }
+}
@@ -1,7 +1,11 @@
 use crate::{
-    domain::training_job::models::{JobId, TrainingJobStatus},
+    domain::training_job::models::{
+        JobAllocation, JobError, JobExit, JobId, ResourceRequirements, TrainingJobStatus,
+    },
     identifier,
 };
+use std::collections::BTreeMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -149,19 +153,74 @@ pub enum NodeStatus {
     Busy,
 }
 
+/// Whether the scheduler may place new jobs on a node, e.g. while it is taken
+/// out for maintenance.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeSchedulability {
+    #[default]
+    Schedulable,
+    /// The node takes no new jobs, but keeps running the ones it has.
+    Cordoned,
+    /// The node takes no new jobs, and is cordoned once the ones it has
+    /// are finished.
+    Draining,
+}
+
+/// Keeps jobs off a node unless they tolerate it, e.g. to reserve the node
+/// for one team.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Taint {
+    pub key: String,
+    #[serde(default)]
+    pub value: Option<String>,
+}
+
 #[derive(Clone, Debug)]
 pub struct ClusterNode {
     pub id: NodeId,
     pub cluster_id: ClusterId,
     pub node_status: NodeStatus,
+    pub schedulability: NodeSchedulability,
     pub heartbeat_timestamp: DateTime<Utc>,
     pub memory_mb: i32,
     pub cpu: Cpu,
     pub gpu: Option<Gpu>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
-    pub assigned_job_id: Option<JobId>,
-    pub reported_job_id: Option<JobId>,
+    /// The jobs the node has been allocated to by the scheduler.
+    pub allocations: Vec<JobAllocation>,
+    /// The jobs the node's agent reported running in its last heartbeat.
+    pub reported_job_ids: Vec<JobId>,
+    /// The address other nodes can reach this node on, as advertised by its agent.
+    pub address: Option<String>,
+    /// Labels describing the node, as advertised by its agent.
+    pub labels: BTreeMap<String, String>,
+    pub taints: Vec<Taint>,
+}
+
+/// An amount of CPU, memory and GPUs on a node.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NodeCapacity {
+    pub cpu_millicores: i32,
+    pub memory_mb: i32,
+    pub gpu_count: i32,
+}
+
+impl NodeCapacity {
+    /// Returns the capacity held by an allocation back to the node.
+    pub fn release(&mut self, allocation: &JobAllocation) {
+        self.cpu_millicores += allocation.cpu_millicores;
+        self.memory_mb += allocation.memory_mb;
+        self.gpu_count += allocation.gpu_count;
+    }
+
+    /// Whether a job with these requirements fits into this capacity.
+    pub fn can_hold(&self, requirements: &ResourceRequirements) -> bool {
+        self.cpu_millicores >= requirements.cpu_millicores
+            && self.memory_mb >= requirements.memory_mb
+            && self.gpu_count >= requirements.gpu_count()
+    }
 }
 
 impl ClusterNode {
@@ -176,22 +235,90 @@ impl ClusterNode {
             id: node_id,
             cluster_id,
             node_status: NodeStatus::Available,
+            schedulability: NodeSchedulability::Schedulable,
             heartbeat_timestamp: Utc::now(),
             memory_mb,
             cpu,
             gpu,
             created_at: Utc::now(),
             updated_at: Utc::now(),
-            assigned_job_id: None,
-            reported_job_id: None,
+            allocations: Vec::new(),
+            reported_job_ids: Vec::new(),
+            address: None,
+            labels: BTreeMap::new(),
+            taints: Vec::new(),
+        }
+    }
+
+    /// The capacity of the node that is not reserved by any of its allocations.
+    pub fn free_capacity(&self) -> NodeCapacity {
+        let mut free = NodeCapacity {
+            cpu_millicores: self.cpu.millicores,
+            memory_mb: self.memory_mb,
+            gpu_count: self.gpu.as_ref().map_or(0, |gpu| gpu.count),
+        };
+        for allocation in &self.allocations {
+            free.cpu_millicores -= allocation.cpu_millicores;
+            free.memory_mb -= allocation.memory_mb;
+            free.gpu_count -= allocation.gpu_count;
         }
+        free
+    }
+
+    /// The jobs the node has been allocated to.
+    pub fn assigned_job_ids(&self) -> impl Iterator<Item = JobId> + '_ {
+        self.allocations.iter().map(|allocation| allocation.job_id)
+    }
+
+    /// Whether the node can take on a job with these requirements right now,
+    /// next to the jobs it is already running.
+    pub fn has_room_for(&self, requirements: &ResourceRequirements) -> bool {
+        self.schedulability == NodeSchedulability::Schedulable
+            && !self.is_stopping_jobs()
+            && self.free_capacity().can_hold(requirements)
+    }
+
+    /// Whether a draining node has no jobs left and can be cordoned.
+    pub fn is_drained(&self) -> bool {
+        self.schedulability == NodeSchedulability::Draining
+            && self.allocations.is_empty()
+            && self.reported_job_ids.is_empty()
+    }
+
+    /// Whether the agent still runs jobs that are no longer allocated to the
+    /// node, e.g. because they were preempted. Their resources are only free
+    /// once the agent has stopped them.
+    pub fn is_stopping_jobs(&self) -> bool {
+        self.reported_job_ids
+            .iter()
+            .any(|job_id| !self.assigned_job_ids().any(|assigned| assigned == *job_id))
     }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct JobInfo {
-    pub current_job_id: JobId,
+    pub job_id: JobId,
     pub status: TrainingJobStatus,
+    /// The exit code of the job's container, once it has exited.
+    #[serde(default)]
+    pub exit_code: Option<i32>,
+    /// Whether the job's container was killed for running out of memory.
+    #[serde(default)]
+    pub oom_killed: bool,
+    /// Why the node couldn't run the job, if its container never got to exit.
+    #[serde(default)]
+    pub error: Option<JobError>,
+}
+
+impl JobInfo {
+    /// How the job ended, once its node has anything to say about it.
+    pub fn exit(&self) -> Option<JobExit> {
+        (self.exit_code.is_some() || self.oom_killed || self.error.is_some()).then(|| JobExit {
+            exit_code: self.exit_code,
+            oom_killed: self.oom_killed,
+            error: self.error.clone(),
+        })
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -202,7 +329,21 @@ pub struct UpdateNodeStatusRequest {
     pub memory_info: i32,
     pub cpu_info: Cpu,
     pub gpu_info: Option<Gpu>,
-    pub job_info: Option<JobInfo>,
+    pub jobs: Vec<JobInfo>,
+    pub address: Option<String>,
+    pub labels: BTreeMap<String, String>,
+}
+
+/// The port the rank 0 node of a multi-node job listens on for rendezvous.
+pub const DEFAULT_RENDEZVOUS_PORT: u16 = 29500;
+
+/// Everything a node needs to join the other nodes of a multi-node job.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct DistributedContext {
+    pub rank: i32,
+    pub world_size: i32,
+    pub coordinator_address: String,
+    pub coordinator_port: u16,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -3,9 +3,10 @@ use thiserror::Error;
 
 use crate::domain::{
     cluster::models::{
-        ClusterDetails, ClusterNode, ClusterSummary, NodeId, UpdateNodeStatusRequest,
+        ClusterDetails, ClusterNode, ClusterSummary, NodeId, NodeSchedulability, Taint,
+        UpdateNodeStatusRequest,
     },
-    training_job::models::{JobId, TrainingJob},
+    training_job::models::{JobAllocation, JobId, ResourceRequirements, TrainingJob},
     user::models::{ApiKey, ApiKeyId},
 };
 
@@ -48,17 +49,47 @@ pub trait ClusterRepository: Send + Sync {
         &self,
         id: &NodeId,
     ) -> Result<ClusterNode, ClusterRepositoryError>;
+    /// Records a node's heartbeat, registering the node if it is new.
+    /// Returns `NotFound` if the node belongs to another cluster than the
+    /// one in the request.
     async fn update_cluster_node_status(
         &self,
         req: &UpdateNodeStatusRequest,
     ) -> Result<ClusterNode, ClusterRepositoryError>;
-    async fn delete_cluster_node(&self, node_id: &NodeId) -> Result<(), ClusterRepositoryError>;
-    async fn clear_assigned_job_id(&self, node_id: &NodeId) -> Result<(), ClusterRepositoryError>;
-    async fn assign_job_to_node(
+    async fn set_node_schedulability(
         &self,
         node_id: &NodeId,
+        schedulability: NodeSchedulability,
+    ) -> Result<ClusterNode, ClusterRepositoryError>;
+    /// Replaces the node's taints.
+    async fn set_node_taints(
+        &self,
+        node_id: &NodeId,
+        taints: &[Taint],
+    ) -> Result<ClusterNode, ClusterRepositoryError>;
+    /// Removes the node. Whether it is cordoned and its taints are kept, and
+    /// restored if the node joins again.
+    async fn delete_cluster_node(&self, node_id: &NodeId) -> Result<(), ClusterRepositoryError>;
+    /// Reserves the job's resources on all of the given nodes, in rank order,
+    /// replacing any allocations left over from a previous run of the job,
+    /// marks the queued job as starting on the first node and records a new
+    /// attempt of it there.
+    /// All of it happens in one transaction, after checking again that every
+    /// node still has room, so concurrent allocations cannot overcommit a
+    /// node. Returns `false`, changing nothing, if a node no longer has room
+    /// or the job is no longer queued.
+    async fn assign_job_to_nodes(
+        &self,
+        job_id: &JobId,
+        node_ids: &[NodeId],
+        requirements: &ResourceRequirements,
+    ) -> Result<bool, ClusterRepositoryError>;
+    async fn list_job_allocations(
+        &self,
         job_id: &JobId,
-    ) -> Result<(), ClusterRepositoryError>;
+    ) -> Result<Vec<JobAllocation>, ClusterRepositoryError>;
+    /// Frees the resources reserved for a job on every node.
+    async fn release_job_nodes(&self, job_id: &JobId) -> Result<(), ClusterRepositoryError>;
 }
 
 use super::errors::ClusterApiKeyRepositoryError;
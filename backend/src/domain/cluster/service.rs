@@ -1,12 +1,17 @@
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 
 use async_trait::async_trait;
 
 use crate::domain::{
-    cluster::models::{ClusterDetails, ClusterNode, ClusterSummary, UpdateNodeStatusRequest},
+    cluster::models::{
+        ClusterDetails, ClusterNode, ClusterSummary, DistributedContext, NodeId,
+        NodeSchedulability, Taint, UpdateNodeStatusRequest, DEFAULT_RENDEZVOUS_PORT,
+    },
+    scheduler::notifier::SchedulerNotifier,
     training_job::{
-        models::{TrainingJob, TrainingJobStatus},
+        models::{AttemptOutcome, JobEventKind, JobId, TrainingJob, TrainingJobStatus},
         ports::TrainingJobRepository,
+        service::record_event,
     },
     user::models::{ApiKey, ApiKeyId},
 };
@@ -41,6 +46,12 @@ pub enum ClusterServiceError {
     ClusterExists { field: String, value: String },
     #[error("cluster {0} not found")]
     ClusterNotFound(String),
+    #[error("node {0} not found")]
+    NodeNotFound(String),
+    #[error("invalid taint: {0}")]
+    InvalidTaint(String),
+    #[error("multi-node job {job_id} can't be started yet: {reason}")]
+    IncompleteDistributedContext { job_id: JobId, reason: String },
     #[error(transparent)]
     Unknown(#[from] anyhow::Error),
 }
@@ -126,14 +137,33 @@ pub trait ClusterService: Send + Sync {
         cluster_id: &ClusterId,
         key_id: &ApiKeyId,
     ) -> Result<(), ClusterServiceError>;
-    async fn clear_assigned_job_id(
-        &self,
-        node_id: &super::models::NodeId,
-    ) -> Result<(), ClusterServiceError>;
     async fn get_node_by_id(
         &self,
         node_id: &super::models::NodeId,
     ) -> Result<ClusterNode, ClusterServiceError>;
+    /// Stops the scheduler from placing new jobs on the node.
+    async fn cordon_node(&self, node_id: &NodeId) -> Result<ClusterNode, ClusterServiceError>;
+    /// Stops the scheduler from placing new jobs on the node, and cordons it
+    /// once its current jobs have finished.
+    async fn drain_node(&self, node_id: &NodeId) -> Result<ClusterNode, ClusterServiceError>;
+    /// Lets the scheduler place jobs on a cordoned or draining node again.
+    async fn uncordon_node(&self, node_id: &NodeId) -> Result<ClusterNode, ClusterServiceError>;
+    /// Replaces the node's taints. Jobs already running on the node are left
+    /// alone.
+    async fn set_node_taints(
+        &self,
+        node_id: &NodeId,
+        taints: Vec<Taint>,
+    ) -> Result<ClusterNode, ClusterServiceError>;
+    /// Returns the rendezvous details a node needs to run its part of a
+    /// multi-node job, or `None` for single node jobs. Fails with
+    /// `IncompleteDistributedContext` while any of the details are missing,
+    /// since a rank started without them would train alone or hang.
+    async fn get_distributed_context(
+        &self,
+        job: &TrainingJob,
+        node_id: &super::models::NodeId,
+    ) -> Result<Option<DistributedContext>, ClusterServiceError>;
 }
 
 #[derive(Clone)]
@@ -143,17 +173,46 @@ pub struct ClusterServiceImpl<
 > {
     cluster_repo: Arc<R>,
     training_job_repo: Arc<T>,
+    scheduler: SchedulerNotifier,
 }
 
 impl<R: ClusterRepository + ClusterApiKeyRepository, T: TrainingJobRepository>
     ClusterServiceImpl<R, T>
 {
-    pub fn new(cluster_repo: Arc<R>, training_job_repo: Arc<T>) -> Self {
+    pub fn new(
+        cluster_repo: Arc<R>,
+        training_job_repo: Arc<T>,
+        scheduler: SchedulerNotifier,
+    ) -> Self {
         Self {
             cluster_repo,
             training_job_repo,
+            scheduler,
         }
     }
+
+    async fn set_node_schedulability(
+        &self,
+        node_id: &NodeId,
+        schedulability: NodeSchedulability,
+    ) -> Result<ClusterNode, ClusterServiceError> {
+        let node = self
+            .cluster_repo
+            .set_node_schedulability(node_id, schedulability)
+            .await
+            .map_err(|e| match e {
+                ClusterRepositoryError::NotFound(id) => ClusterServiceError::NodeNotFound(id),
+                e => e.into(),
+            })?;
+        // A node without jobs is drained right away.
+        if node.is_drained() {
+            return Ok(self
+                .cluster_repo
+                .set_node_schedulability(node_id, NodeSchedulability::Cordoned)
+                .await?);
+        }
+        Ok(node)
+    }
 }
 
 #[async_trait]
@@ -201,42 +260,123 @@ impl<R: ClusterRepository + ClusterApiKeyRepository, T: TrainingJobRepository> C
         &self,
         req: UpdateNodeStatusRequest,
     ) -> Result<ClusterNode, ClusterServiceError> {
-        if let Some(job_info) = &req.job_info {
-            let job_id = job_info.current_job_id;
+        let previous = self
+            .cluster_repo
+            .get_cluster_node_by_id(&req.node_id)
+            .await
+            .ok();
+        // A cluster's API key only vouches for the cluster's own nodes, so it
+        // can't be used to report on, or receive the jobs of, other nodes.
+        if previous
+            .as_ref()
+            .is_some_and(|node| node.cluster_id != req.cluster_id)
+        {
+            return Err(ClusterServiceError::InvalidPermissions);
+        }
+        let mut status_changed = false;
+
+        for job_info in &req.jobs {
+            let job_id = job_info.job_id;
             let job = self
                 .training_job_repo
                 .get_training_job_by_id(&job_id)
                 .await?;
 
-            if !matches!(
-                job.status,
-                TrainingJobStatus::Succeeded
-                    | TrainingJobStatus::Failed
-                    | TrainingJobStatus::Cancelled
-            ) {
-                self.training_job_repo
-                    .update_status(&job_id, job_info.status.clone())
-                    .await?;
-
-                if matches!(
-                    job_info.status,
-                    TrainingJobStatus::Succeeded | TrainingJobStatus::Failed
-                ) {
-                    self.cluster_repo
-                        .clear_assigned_job_id(&req.node_id)
+            if !job.status.is_terminal() {
+                // The rank 0 node reports the status of a multi-node job. Any
+                // other rank can only fail it or time it out, which tears down
+                // the whole gang.
+                // Reports from nodes the job is no longer allocated to, e.g.
+                // after it was preempted, are ignored.
+                let allocations = self.cluster_repo.list_job_allocations(&job_id).await?;
+                let may_update = allocations
+                    .iter()
+                    .find(|allocation| allocation.node_id == req.node_id)
+                    .is_some_and(|allocation| {
+                        allocation.rank == 0
+                            || matches!(
+                                job_info.status,
+                                TrainingJobStatus::Failed | TrainingJobStatus::TimedOut
+                            )
+                    });
+
+                if may_update && job.status != job_info.status {
+                    self.training_job_repo
+                        .update_status(&job_id, job_info.status.clone())
                         .await?;
+                    status_changed = true;
+                    let exit = job_info.exit();
+                    let reason = match &exit {
+                        Some(exit) => {
+                            format!("{}, reported by node {}", exit.describe(), req.node_id)
+                        }
+                        None => format!("Reported by node {}", req.node_id),
+                    };
+                    record_event(
+                        &*self.training_job_repo,
+                        &[job_id],
+                        JobEventKind::StatusChanged {
+                            status: job_info.status.clone(),
+                            reason: Some(reason),
+                        },
+                    )
+                    .await;
+
+                    let outcome = match job_info.status {
+                        TrainingJobStatus::Succeeded => Some(AttemptOutcome::Succeeded),
+                        TrainingJobStatus::Failed => Some(AttemptOutcome::Failed),
+                        TrainingJobStatus::TimedOut => Some(AttemptOutcome::TimedOut),
+                        _ => None,
+                    };
+                    if let Some(outcome) = outcome {
+                        self.cluster_repo.release_job_nodes(&job_id).await?;
+                        // The scheduler decides whether a failed attempt is retried.
+                        self.training_job_repo
+                            .finish_attempt(&job_id, outcome, job_info.exit_code)
+                            .await?;
+                        if let Some(exit) = &exit {
+                            self.training_job_repo.set_exit(&job_id, exit).await?;
+                        }
+                    }
                 }
             }
         }
 
-        let node = self.cluster_repo.update_cluster_node_status(&req).await?;
+        let mut node = self
+            .cluster_repo
+            .update_cluster_node_status(&req)
+            .await
+            .map_err(|e| match e {
+                ClusterRepositoryError::NotFound(_) => ClusterServiceError::InvalidPermissions,
+                e => e.into(),
+            })?;
+        if node.is_drained() {
+            tracing::info!(node_id = %node.id, "Node has been drained and is now cordoned");
+            node = self
+                .cluster_repo
+                .set_node_schedulability(&node.id, NodeSchedulability::Cordoned)
+                .await?;
+        }
 
-        if node.assigned_job_id != node.reported_job_id {
+        // A new node, a finished job or a job the agent has stopped may all
+        // make room for queued jobs.
+        let reported: HashSet<_> = node.reported_job_ids.iter().collect();
+        let jobs_changed = previous.is_none_or(|previous| {
+            previous.reported_job_ids.iter().collect::<HashSet<_>>() != reported
+        });
+        if status_changed || jobs_changed {
+            self.scheduler.notify();
+        }
+
+        let unreported: Vec<_> = node
+            .assigned_job_ids()
+            .filter(|job_id| !node.reported_job_ids.contains(job_id))
+            .collect();
+        if !unreported.is_empty() {
             tracing::warn!(
                 node_id = %node.id,
-                assigned_job_id = ?node.assigned_job_id,
-                reported_job_id = ?node.reported_job_id,
-                "Mismatched job ID reported by agent. This may be expected during job transitions."
+                unreported_job_ids = ?unreported,
+                "Assigned jobs not reported by agent. This may be expected during job transitions."
             );
 
             // The scheduler will handle requeueing of jobs.
@@ -332,19 +472,93 @@ impl<R: ClusterRepository + ClusterApiKeyRepository, T: TrainingJobRepository> C
         Ok(())
     }
 
-    async fn clear_assigned_job_id(
+    async fn get_node_by_id(
         &self,
         node_id: &super::models::NodeId,
-    ) -> Result<(), ClusterServiceError> {
-        self.cluster_repo.clear_assigned_job_id(node_id).await?;
-        Ok(())
+    ) -> Result<ClusterNode, ClusterServiceError> {
+        let node = self.cluster_repo.get_cluster_node_by_id(node_id).await?;
+        Ok(node)
     }
 
-    async fn get_node_by_id(
+    async fn cordon_node(&self, node_id: &NodeId) -> Result<ClusterNode, ClusterServiceError> {
+        self.set_node_schedulability(node_id, NodeSchedulability::Cordoned)
+            .await
+    }
+
+    async fn drain_node(&self, node_id: &NodeId) -> Result<ClusterNode, ClusterServiceError> {
+        self.set_node_schedulability(node_id, NodeSchedulability::Draining)
+            .await
+    }
+
+    async fn uncordon_node(&self, node_id: &NodeId) -> Result<ClusterNode, ClusterServiceError> {
+        let node = self
+            .set_node_schedulability(node_id, NodeSchedulability::Schedulable)
+            .await?;
+        // Queued jobs may fit on the node again.
+        self.scheduler.notify();
+        Ok(node)
+    }
+
+    async fn set_node_taints(
         &self,
-        node_id: &super::models::NodeId,
+        node_id: &NodeId,
+        taints: Vec<Taint>,
     ) -> Result<ClusterNode, ClusterServiceError> {
-        let node = self.cluster_repo.get_cluster_node_by_id(node_id).await?;
+        if let Some(taint) = taints.iter().find(|taint| taint.key.trim().is_empty()) {
+            return Err(ClusterServiceError::InvalidTaint(format!(
+                "the key of {taint:?} is empty"
+            )));
+        }
+        let node = self
+            .cluster_repo
+            .set_node_taints(node_id, &taints)
+            .await
+            .map_err(|e| match e {
+                ClusterRepositoryError::NotFound(id) => ClusterServiceError::NodeNotFound(id),
+                e => e.into(),
+            })?;
+        // Queued jobs may fit on the node once a taint is removed.
+        self.scheduler.notify();
         Ok(node)
     }
+
+    async fn get_distributed_context(
+        &self,
+        job: &TrainingJob,
+        node_id: &super::models::NodeId,
+    ) -> Result<Option<DistributedContext>, ClusterServiceError> {
+        if job.node_count <= 1 {
+            return Ok(None);
+        }
+
+        let incomplete = |reason: String| ClusterServiceError::IncompleteDistributedContext {
+            job_id: job.id,
+            reason,
+        };
+        let allocations = self.cluster_repo.list_job_allocations(&job.id).await?;
+        let Some(allocation) = allocations.iter().find(|a| &a.node_id == node_id) else {
+            return Err(incomplete(format!("node {} has no allocation", node_id)));
+        };
+        let Some(coordinator) = allocations.iter().find(|a| a.rank == 0) else {
+            return Err(incomplete("no node has rank 0".to_string()));
+        };
+
+        let coordinator_node = self
+            .cluster_repo
+            .get_cluster_node_by_id(&coordinator.node_id)
+            .await?;
+        let Some(coordinator_address) = coordinator_node.address else {
+            return Err(incomplete(format!(
+                "coordinator node {} has no advertised address",
+                coordinator.node_id
+            )));
+        };
+
+        Ok(Some(DistributedContext {
+            rank: allocation.rank,
+            world_size: job.node_count,
+            coordinator_address,
+            coordinator_port: DEFAULT_RENDEZVOUS_PORT,
+        }))
+    }
 }
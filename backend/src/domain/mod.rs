@@ -1,9 +1,12 @@
 use secrecy::{ExposeSecret, SecretString};
 
+pub mod artifact;
 pub mod auth;
 pub mod cluster;
 pub mod queue;
+pub mod schedule;
 pub mod scheduler;
+pub mod secret;
 pub mod training_job;
 pub mod user;
 
@@ -1,6 +1,12 @@
 use serde::{Deserialize, Serialize};
 
-use crate::{domain::cluster::models::ClusterId, identifier};
+use crate::{
+    domain::{
+        cluster::models::ClusterId, scheduler::models::SchedulingPolicyKind,
+        training_job::models::RetryPolicy,
+    },
+    identifier,
+};
 
 identifier!(QueueId);
 
@@ -10,6 +16,53 @@ pub struct Queue {
     pub name: String,
     pub priority: i32,
     pub cluster_targets: Vec<ClusterId>,
+    /// Whether jobs in this queue may be preempted by jobs from queues with a
+    /// higher priority.
+    pub preemptible: bool,
+    /// How the scheduler picks nodes for jobs from this queue.
+    pub scheduling_policy: SchedulingPolicyKind,
+    /// How failed jobs from this queue are retried, unless a job has its own
+    /// policy. Jobs are not retried when `None`.
+    pub retry_policy: Option<RetryPolicy>,
+    /// The maximum runtime of jobs that don't set their own, in seconds.
+    pub default_max_runtime_secs: Option<i64>,
+    /// The longest maximum runtime a job in this queue may ask for, in
+    /// seconds.
+    pub max_runtime_limit_secs: Option<i64>,
+}
+
+impl Queue {
+    /// Checks that the queue's runtime limits are consistent, returning what
+    /// is wrong with them otherwise.
+    pub fn validate_runtime_limits(&self) -> Result<(), String> {
+        if self.default_max_runtime_secs.is_some_and(|secs| secs < 1)
+            || self.max_runtime_limit_secs.is_some_and(|secs| secs < 1)
+        {
+            return Err("runtimes must be at least 1 second".to_string());
+        }
+        if let (Some(default), Some(limit)) =
+            (self.default_max_runtime_secs, self.max_runtime_limit_secs)
+        {
+            if default > limit {
+                return Err(format!(
+                    "the default maximum runtime of {default}s exceeds the limit of {limit}s"
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// The maximum runtime of a job in this queue that asked for `requested`
+    /// seconds, falling back to the queue's default and then its limit.
+    pub fn max_runtime_for(&self, requested: Option<i64>) -> Result<Option<i64>, String> {
+        match (requested, self.max_runtime_limit_secs) {
+            (Some(requested), Some(limit)) if requested > limit => Err(format!(
+                "a maximum runtime of {requested}s exceeds the queue's limit of {limit}s"
+            )),
+            (Some(requested), _) => Ok(Some(requested)),
+            (None, limit) => Ok(self.default_max_runtime_secs.or(limit)),
+        }
+    }
 }
 
 /// DTO for creating a new queue.
@@ -17,6 +70,11 @@ pub struct CreateQueueRequest {
     pub name: String,
     pub priority: i32,
     pub cluster_targets: Vec<ClusterId>,
+    pub preemptible: bool,
+    pub scheduling_policy: SchedulingPolicyKind,
+    pub retry_policy: Option<RetryPolicy>,
+    pub default_max_runtime_secs: Option<i64>,
+    pub max_runtime_limit_secs: Option<i64>,
 }
 
 /// DTO for updating an existing queue.
@@ -25,4 +83,9 @@ pub struct UpdateQueueRequest {
     pub name: String,
     pub priority: i32,
     pub cluster_targets: Vec<ClusterId>,
+    pub preemptible: bool,
+    pub scheduling_policy: SchedulingPolicyKind,
+    pub retry_policy: Option<RetryPolicy>,
+    pub default_max_runtime_secs: Option<i64>,
+    pub max_runtime_limit_secs: Option<i64>,
 }
@@ -21,6 +21,10 @@ pub enum QueueServiceError {
     QueueExists { field: String, value: String },
     #[error("queue {0} not found")]
     QueueNotFound(String),
+    #[error("invalid retry policy: {0}")]
+    InvalidRetryPolicy(String),
+    #[error("invalid runtime limits: {0}")]
+    InvalidRuntimeLimits(String),
     #[error(transparent)]
     Unknown(#[from] anyhow::Error),
 }
@@ -72,12 +76,26 @@ impl<Q: QueueRepository, T: TrainingJobRepository> QueueServiceImpl<Q, T> {
 #[async_trait]
 impl<Q: QueueRepository, T: TrainingJobRepository> QueueService for QueueServiceImpl<Q, T> {
     async fn create_queue(&self, request: CreateQueueRequest) -> Result<Queue, QueueServiceError> {
+        if let Some(retry_policy) = &request.retry_policy {
+            retry_policy
+                .validate()
+                .map_err(QueueServiceError::InvalidRetryPolicy)?;
+        }
+
         let queue = Queue {
             id: QueueId::generate(),
             name: request.name,
             priority: request.priority,
             cluster_targets: request.cluster_targets,
+            preemptible: request.preemptible,
+            scheduling_policy: request.scheduling_policy,
+            retry_policy: request.retry_policy,
+            default_max_runtime_secs: request.default_max_runtime_secs,
+            max_runtime_limit_secs: request.max_runtime_limit_secs,
         };
+        queue
+            .validate_runtime_limits()
+            .map_err(QueueServiceError::InvalidRuntimeLimits)?;
 
         self.queue_repo.create(&queue).await?;
 
@@ -103,12 +121,26 @@ impl<Q: QueueRepository, T: TrainingJobRepository> QueueService for QueueService
         &self,
         updated_queue: UpdateQueueRequest,
     ) -> Result<Queue, QueueServiceError> {
+        if let Some(retry_policy) = &updated_queue.retry_policy {
+            retry_policy
+                .validate()
+                .map_err(QueueServiceError::InvalidRetryPolicy)?;
+        }
+
         let queue = Queue {
             id: updated_queue.id,
             name: updated_queue.name,
             priority: updated_queue.priority,
             cluster_targets: updated_queue.cluster_targets,
+            preemptible: updated_queue.preemptible,
+            scheduling_policy: updated_queue.scheduling_policy,
+            retry_policy: updated_queue.retry_policy,
+            default_max_runtime_secs: updated_queue.default_max_runtime_secs,
+            max_runtime_limit_secs: updated_queue.max_runtime_limit_secs,
         };
+        queue
+            .validate_runtime_limits()
+            .map_err(QueueServiceError::InvalidRuntimeLimits)?;
 
         self.queue_repo.update(&queue).await?;
 
@@ -125,7 +157,7 @@ mod tests {
     use super::*;
     use crate::domain::{
         cluster::models::ClusterId, queue::ports::MockQueueRepository,
-        training_job::ports::MockTrainingJobRepository,
+        scheduler::models::SchedulingPolicyKind, training_job::ports::MockTrainingJobRepository,
     };
     use mockall::predicate::eq;
     use std::sync::Arc;
@@ -138,6 +170,11 @@ mod tests {
             name: "test_queue".to_string(),
             priority: 10,
             cluster_targets: vec![ClusterId::generate()],
+            preemptible: false,
+            scheduling_policy: SchedulingPolicyKind::default(),
+            retry_policy: None,
+            default_max_runtime_secs: None,
+            max_runtime_limit_secs: None,
         };
 
         let expected_name = new_queue_dto.name.clone();
@@ -169,6 +206,11 @@ mod tests {
             name: "test".to_string(),
             priority: 1,
             cluster_targets: vec![],
+            preemptible: false,
+            scheduling_policy: SchedulingPolicyKind::default(),
+            retry_policy: None,
+            default_max_runtime_secs: None,
+            max_runtime_limit_secs: None,
         };
 
         mock_repo
@@ -212,6 +254,11 @@ mod tests {
             name: "test".to_string(),
             priority: 1,
             cluster_targets: vec![],
+            preemptible: false,
+            scheduling_policy: SchedulingPolicyKind::default(),
+            retry_policy: None,
+            default_max_runtime_secs: None,
+            max_runtime_limit_secs: None,
         }];
 
         mock_repo
@@ -235,6 +282,11 @@ mod tests {
             name: "updated_queue".to_string(),
             priority: 20,
             cluster_targets: vec![],
+            preemptible: true,
+            scheduling_policy: SchedulingPolicyKind::Spread,
+            retry_policy: None,
+            default_max_runtime_secs: None,
+            max_runtime_limit_secs: None,
         };
 
         let expected_queue = Queue {
@@ -242,6 +294,11 @@ mod tests {
             name: updated_queue_dto.name.clone(),
             priority: updated_queue_dto.priority,
             cluster_targets: updated_queue_dto.cluster_targets.clone(),
+            preemptible: updated_queue_dto.preemptible,
+            scheduling_policy: updated_queue_dto.scheduling_policy,
+            retry_policy: updated_queue_dto.retry_policy.clone(),
+            default_max_runtime_secs: None,
+            max_runtime_limit_secs: None,
         };
 
         mock_repo
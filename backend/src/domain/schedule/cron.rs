@@ -0,0 +1,213 @@
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+
+const MONTH_NAMES: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const DAY_NAMES: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+/// How many days ahead to look for the next match. Long enough for Feb 29
+/// to come around, even across a century that skips a leap year.
+const SEARCH_DAYS: u32 = 366 * 9;
+
+/// The values one field of a cron expression matches, as a bit set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Field {
+    values: u64,
+    /// Whether the field was given as anything other than `*`. Matters for
+    /// the day fields, see [CronExpression::matches_day].
+    restricted: bool,
+}
+
+impl Field {
+    fn parse(
+        spec: &str,
+        min: u32,
+        max: u32,
+        names: &[&str],
+        name_offset: u32,
+    ) -> Result<Self, String> {
+        let value = |value: &str| -> Result<u32, String> {
+            let parsed = match names
+                .iter()
+                .position(|name| name.eq_ignore_ascii_case(value))
+            {
+                Some(index) => index as u32 + name_offset,
+                None => value
+                    .parse()
+                    .map_err(|_| format!("invalid value '{value}'"))?,
+            };
+            if parsed < min || parsed > max {
+                return Err(format!("{parsed} is not between {min} and {max}"));
+            }
+            Ok(parsed)
+        };
+
+        let mut values = 0;
+        for part in spec.split(',') {
+            let (range, step) = match part.split_once('/') {
+                Some((range, step)) => match step.parse::<u32>() {
+                    Ok(step) if step > 0 => (range, step),
+                    _ => return Err(format!("invalid step '{step}'")),
+                },
+                None => (part, 1),
+            };
+            let (start, end) = match range.split_once('-') {
+                _ if range == "*" => (min, max),
+                Some((start, end)) => (value(start)?, value(end)?),
+                // `5/15` runs from 5 to the end of the range.
+                None if step > 1 => (value(range)?, max),
+                None => {
+                    let value = value(range)?;
+                    (value, value)
+                }
+            };
+            if start > end {
+                return Err(format!("invalid range '{range}'"));
+            }
+            for value in (start..=end).step_by(step as usize) {
+                values |= 1 << value;
+            }
+        }
+
+        Ok(Self {
+            values,
+            restricted: !spec.starts_with('*'),
+        })
+    }
+
+    fn contains(&self, value: u32) -> bool {
+        self.values & (1 << value) != 0
+    }
+}
+
+/// A standard five field cron expression (minute, hour, day of month, month,
+/// day of week), evaluated in UTC. Also accepts `@hourly`, `@daily`,
+/// `@weekly`, `@monthly` and `@yearly`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct CronExpression {
+    expression: String,
+    minutes: Field,
+    hours: Field,
+    days_of_month: Field,
+    months: Field,
+    days_of_week: Field,
+}
+
+impl CronExpression {
+    /// The first time after `after` that the expression matches, if any.
+    pub fn next_after(&self, after: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let start = after
+            .with_second(0)?
+            .with_nanosecond(0)?
+            .checked_add_signed(Duration::minutes(1))?;
+        let mut date = start.date_naive();
+
+        for _ in 0..SEARCH_DAYS {
+            if self.months.contains(date.month()) && self.matches_day(date) {
+                let first_hour = if date == start.date_naive() {
+                    start.hour()
+                } else {
+                    0
+                };
+                for hour in (first_hour..24).filter(|hour| self.hours.contains(*hour)) {
+                    let first_minute = if date == start.date_naive() && hour == start.hour() {
+                        start.minute()
+                    } else {
+                        0
+                    };
+                    if let Some(minute) =
+                        (first_minute..60).find(|minute| self.minutes.contains(*minute))
+                    {
+                        let time = date.and_hms_opt(hour, minute, 0)?;
+                        return Some(Utc.from_utc_datetime(&time));
+                    }
+                }
+            }
+            date = date.succ_opt()?;
+        }
+        None
+    }
+
+    /// Like cron, a day matches if it matches both day fields, unless both
+    /// are restricted, in which case matching either is enough.
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        let day_of_month = self.days_of_month.contains(date.day());
+        let day_of_week = self
+            .days_of_week
+            .contains(date.weekday().num_days_from_sunday());
+        if self.days_of_month.restricted && self.days_of_week.restricted {
+            day_of_month || day_of_week
+        } else {
+            day_of_month && day_of_week
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.expression
+    }
+}
+
+impl FromStr for CronExpression {
+    type Err = String;
+
+    fn from_str(expression: &str) -> Result<Self, Self::Err> {
+        let expanded = match expression.trim() {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            other => other,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minutes, hours, days_of_month, months, days_of_week] = fields[..] else {
+            return Err(format!(
+                "expected 5 fields (minute hour day-of-month month day-of-week), got {}",
+                fields.len()
+            ));
+        };
+
+        let field = |name: &str, spec, min, max, names: &[&str], name_offset| {
+            Field::parse(spec, min, max, names, name_offset)
+                .map_err(|reason| format!("invalid {name} field: {reason}"))
+        };
+        let mut days_of_week = field("day-of-week", days_of_week, 0, 7, &DAY_NAMES, 0)?;
+        // Both 0 and 7 stand for Sunday.
+        if days_of_week.contains(7) {
+            days_of_week.values |= 1;
+        }
+
+        Ok(Self {
+            expression: expression.trim().to_string(),
+            minutes: field("minute", minutes, 0, 59, &[], 0)?,
+            hours: field("hour", hours, 0, 23, &[], 0)?,
+            days_of_month: field("day-of-month", days_of_month, 1, 31, &[], 0)?,
+            months: field("month", months, 1, 12, &MONTH_NAMES, 1)?,
+            days_of_week,
+        })
+    }
+}
+
+impl fmt::Display for CronExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.expression)
+    }
+}
+
+impl TryFrom<String> for CronExpression {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<CronExpression> for String {
+    fn from(value: CronExpression) -> Self {
+        value.expression
+    }
+}
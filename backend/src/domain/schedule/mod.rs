@@ -0,0 +1,322 @@
+pub mod cron;
+pub mod models;
+pub mod ports;
+pub mod service;
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, sync::Arc};
+
+    use chrono::{DateTime, TimeZone, Utc};
+
+    use super::{
+        cron::CronExpression,
+        models::{
+            ConcurrencyPolicy, CreateScheduleRequest, JobSchedule, JobTemplate, ScheduleId,
+            ScheduleRunOutcome, UpdateScheduleRequest,
+        },
+        ports::{MockScheduleRepository, ScheduleRepositoryError},
+        service::{ScheduleService, ScheduleServiceError, ScheduleServiceImpl},
+    };
+    use crate::domain::{
+        queue::models::QueueId,
+        training_job::{
+            models::{JobDefinition, JobId, ResourceRequirements, TrainingJob, TrainingJobStatus},
+            service::MockTrainingJobService,
+        },
+        user::models::UserId,
+    };
+
+    fn at(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    fn next(expression: &str, after: DateTime<Utc>) -> DateTime<Utc> {
+        expression
+            .parse::<CronExpression>()
+            .unwrap()
+            .next_after(&after)
+            .unwrap()
+    }
+
+    fn schedule(concurrency_policy: ConcurrencyPolicy, due_at: DateTime<Utc>) -> JobSchedule {
+        JobSchedule {
+            id: ScheduleId::generate(),
+            name: "nightly".to_string(),
+            cron: "0 2 * * *".parse().unwrap(),
+            job_template: JobTemplate {
+                definition: JobDefinition::new("retrain:latest"),
+                queue_id: QueueId::generate(),
+                resource_requirements: ResourceRequirements {
+                    cpu_millicores: 1000,
+                    memory_mb: 1024,
+                    gpus: None,
+                },
+                node_count: 1,
+                retry_policy: None,
+                max_runtime_secs: None,
+                node_selector: BTreeMap::new(),
+                tolerations: Vec::new(),
+                env: BTreeMap::new(),
+                secrets: BTreeMap::new(),
+                outputs: vec![],
+                volumes: vec![],
+            },
+            concurrency_policy,
+            paused: false,
+            next_run_at: Some(due_at),
+            owner_id: Some(UserId::generate()),
+            created_at: due_at,
+            updated_at: due_at,
+        }
+    }
+
+    fn training_job(name: String) -> TrainingJob {
+        let now = Utc::now();
+        TrainingJob {
+            id: JobId::generate(),
+            name,
+            definition: JobDefinition::new("retrain:latest"),
+            status: TrainingJobStatus::Queued,
+            node_id: None,
+            queue_id: None,
+            resource_requirements: ResourceRequirements {
+                cpu_millicores: 1000,
+                memory_mb: 1024,
+                gpus: None,
+            },
+            node_count: 1,
+            preempted_at: None,
+            preemption_reason: None,
+            dependencies: Vec::new(),
+            retry_policy: None,
+            not_before: None,
+            avoid_node_id: None,
+            max_runtime_secs: None,
+            started_at: None,
+            exit: None,
+            array_id: None,
+            array_index: None,
+            parameters: BTreeMap::new(),
+            node_selector: BTreeMap::new(),
+            tolerations: Vec::new(),
+            env: BTreeMap::new(),
+            secrets: BTreeMap::new(),
+            outputs: vec![],
+            volumes: vec![],
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// A repository holding a single due schedule with one job still active.
+    fn repository(schedule: &JobSchedule, active_job: JobId) -> MockScheduleRepository {
+        let mut repo = MockScheduleRepository::new();
+        let due = schedule.clone();
+        repo.expect_get_due_schedules()
+            .returning(move |_| Ok(vec![due.clone()]));
+        repo.expect_claim_run().returning(|_, _, _| Ok(true));
+        repo.expect_get_active_jobs()
+            .returning(move |_| Ok(vec![active_job]));
+        repo
+    }
+
+    #[test]
+    fn test_cron_next_run() {
+        // Saturday, 2025-08-09 12:30.
+        let now = at(2025, 8, 9, 12, 30);
+
+        assert_eq!(next("*/15 * * * *", now), at(2025, 8, 9, 12, 45));
+        assert_eq!(next("0 2 * * *", now), at(2025, 8, 10, 2, 0));
+        assert_eq!(next("@hourly", now), at(2025, 8, 9, 13, 0));
+        assert_eq!(next("30 9 * * 1-5", now), at(2025, 8, 11, 9, 30));
+        assert_eq!(next("0 0 * * sun", now), at(2025, 8, 10, 0, 0));
+        assert_eq!(next("0 0 * * 7", now), at(2025, 8, 10, 0, 0));
+        assert_eq!(next("0 0 1 jan *", now), at(2026, 1, 1, 0, 0));
+        assert_eq!(next("0 0 29 2 *", now), at(2028, 2, 29, 0, 0));
+        // With both day fields restricted, either one matching is enough.
+        assert_eq!(next("0 0 15 * mon", now), at(2025, 8, 11, 0, 0));
+        // A time that matches exactly is not the next run.
+        assert_eq!(next("30 12 * * *", now), at(2025, 8, 10, 12, 30));
+    }
+
+    #[test]
+    fn test_cron_rejects_invalid_expressions() {
+        for expression in [
+            "",
+            "* * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "* * * 13 *",
+            "* * * * 8",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "@sometimes",
+            "a * * * *",
+        ] {
+            assert!(
+                expression.parse::<CronExpression>().is_err(),
+                "{expression:?} should be rejected"
+            );
+        }
+        assert!("0 31 2 *".parse::<CronExpression>().is_err());
+        assert!("0 0 31 2 *"
+            .parse::<CronExpression>()
+            .unwrap()
+            .next_after(&Utc::now())
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_forbid_skips_run_while_jobs_are_active() {
+        let due_at = at(2025, 8, 10, 2, 0);
+        let schedule = schedule(ConcurrencyPolicy::Forbid, due_at);
+        let mut repo = repository(&schedule, JobId::generate());
+        repo.expect_record_run()
+            .withf(move |run| {
+                run.outcome == ScheduleRunOutcome::Skipped
+                    && run.job_id.is_none()
+                    && run.scheduled_at == due_at
+            })
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let mut job_service = MockTrainingJobService::new();
+        job_service.expect_create().never();
+        job_service.expect_cancel().never();
+
+        let service = ScheduleServiceImpl::new(Arc::new(repo), Arc::new(job_service));
+        service
+            .run_due_schedules(at(2025, 8, 10, 2, 1))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_replace_cancels_active_jobs() {
+        let due_at = at(2025, 8, 10, 2, 0);
+        let schedule = schedule(ConcurrencyPolicy::Replace, due_at);
+        let owner = schedule.owner_id.unwrap();
+        let active_job = JobId::generate();
+        let mut repo = repository(&schedule, active_job);
+        repo.expect_record_run()
+            .withf(|run| run.outcome == ScheduleRunOutcome::Started && run.job_id.is_some())
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let mut job_service = MockTrainingJobService::new();
+        job_service
+            .expect_cancel()
+            .withf(move |id| *id == active_job)
+            .times(1)
+            .returning(|_| Ok(()));
+        job_service
+            .expect_create()
+            .withf(move |request, submitted_by| {
+                request.name == "nightly-20250810-0200" && *submitted_by == Some(owner)
+            })
+            .times(1)
+            .returning(|request, _| Ok(training_job(request.name)));
+
+        let service = ScheduleServiceImpl::new(Arc::new(repo), Arc::new(job_service));
+        service
+            .run_due_schedules(at(2025, 8, 10, 2, 1))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_failing_schedule_does_not_stop_the_others() {
+        let due_at = at(2025, 8, 10, 2, 0);
+        let failing = schedule(ConcurrencyPolicy::Allow, due_at);
+        let healthy = schedule(ConcurrencyPolicy::Allow, due_at);
+        let (failing_id, healthy_id) = (failing.id, healthy.id);
+
+        let mut repo = MockScheduleRepository::new();
+        repo.expect_get_due_schedules()
+            .returning(move |_| Ok(vec![failing.clone(), healthy.clone()]));
+        repo.expect_claim_run()
+            .withf(move |id, _, _| *id == failing_id)
+            .returning(|_, _, _| Err(ScheduleRepositoryError::Unknown(anyhow::anyhow!("boom"))));
+        repo.expect_claim_run()
+            .withf(move |id, _, _| *id == healthy_id)
+            .returning(|_, _, _| Ok(true));
+        repo.expect_record_run()
+            .withf(move |run| {
+                run.schedule_id == healthy_id && run.outcome == ScheduleRunOutcome::Started
+            })
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let mut job_service = MockTrainingJobService::new();
+        job_service
+            .expect_create()
+            .times(1)
+            .returning(|request, _| Ok(training_job(request.name)));
+
+        let service = ScheduleServiceImpl::new(Arc::new(repo), Arc::new(job_service));
+        service
+            .run_due_schedules(at(2025, 8, 10, 2, 1))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_create_rejects_cron_that_never_fires() {
+        let mut repo = MockScheduleRepository::new();
+        repo.expect_create().never();
+        let service =
+            ScheduleServiceImpl::new(Arc::new(repo), Arc::new(MockTrainingJobService::new()));
+
+        let template = schedule(ConcurrencyPolicy::Allow, Utc::now()).job_template;
+        let result = service
+            .create_schedule(CreateScheduleRequest {
+                name: "never".to_string(),
+                cron: "0 0 30 2 *".to_string(),
+                job_template: template,
+                concurrency_policy: ConcurrencyPolicy::Allow,
+                paused: false,
+                owner_id: UserId::generate(),
+            })
+            .await;
+        assert!(matches!(result, Err(ScheduleServiceError::InvalidCron(_))));
+    }
+
+    #[tokio::test]
+    async fn test_only_the_owner_updates_a_schedule() {
+        let existing = schedule(ConcurrencyPolicy::Allow, Utc::now());
+        let owner = existing.owner_id.unwrap();
+        let update = |updated_by| UpdateScheduleRequest {
+            id: existing.id,
+            name: "nightly".to_string(),
+            cron: "0 3 * * *".to_string(),
+            job_template: existing.job_template.clone(),
+            concurrency_policy: ConcurrencyPolicy::Forbid,
+            paused: false,
+            updated_by,
+        };
+
+        let mut repo = MockScheduleRepository::new();
+        let stored = existing.clone();
+        repo.expect_get_schedule_by_id()
+            .returning(move |_| Ok(stored.clone()));
+        repo.expect_update()
+            .withf(move |schedule| schedule.owner_id == Some(owner))
+            .times(1)
+            .returning(|_| Ok(()));
+        let service =
+            ScheduleServiceImpl::new(Arc::new(repo), Arc::new(MockTrainingJobService::new()));
+
+        let result = service.update_schedule(update(UserId::generate())).await;
+        assert!(matches!(
+            result,
+            Err(ScheduleServiceError::InvalidPermissions)
+        ));
+
+        let schedule = service.update_schedule(update(owner)).await.unwrap();
+        assert_eq!(schedule.owner_id, Some(owner));
+        assert_eq!(schedule.concurrency_policy, ConcurrencyPolicy::Forbid);
+    }
+}
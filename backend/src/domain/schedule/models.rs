@@ -0,0 +1,181 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{
+        queue::models::QueueId,
+        training_job::models::{
+            validate_env, JobDefinition, JobId, JobOutput, JobVolume, ResourceRequirements,
+            RetryPolicy, Toleration,
+        },
+        user::models::UserId,
+    },
+    identifier,
+    inbound::http::routes::training_jobs::models::CreateTrainingJobRequest,
+};
+
+use super::cron::CronExpression;
+
+identifier!(ScheduleId);
+
+/// What happens when a schedule comes due while jobs it started earlier are
+/// still queued or running.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConcurrencyPolicy {
+    /// Start another job regardless.
+    #[default]
+    Allow,
+    /// Skip the run.
+    Forbid,
+    /// Cancel the earlier jobs and start a new one.
+    Replace,
+}
+
+/// The job a schedule submits every time it comes due.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobTemplate {
+    pub definition: JobDefinition,
+    pub queue_id: QueueId,
+    pub resource_requirements: ResourceRequirements,
+    #[serde(default = "default_node_count")]
+    pub node_count: i32,
+    #[serde(default)]
+    pub retry_policy: Option<RetryPolicy>,
+    #[serde(default)]
+    pub max_runtime_secs: Option<i64>,
+    #[serde(default)]
+    pub node_selector: BTreeMap<String, String>,
+    #[serde(default)]
+    pub tolerations: Vec<Toleration>,
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    #[serde(default)]
+    pub secrets: BTreeMap<String, String>,
+    #[serde(default)]
+    pub outputs: Vec<JobOutput>,
+    #[serde(default)]
+    pub volumes: Vec<JobVolume>,
+}
+
+fn default_node_count() -> i32 {
+    1
+}
+
+impl JobTemplate {
+    /// Checks the parts of the template that don't depend on other
+    /// resources, returning what is wrong with it otherwise.
+    pub fn validate(&self) -> Result<(), String> {
+        self.definition.validate()?;
+        if self.node_count < 1 {
+            return Err(format!(
+                "a job must run on at least one node, got {}",
+                self.node_count
+            ));
+        }
+        if let Some(retry_policy) = &self.retry_policy {
+            retry_policy.validate()?;
+        }
+        if self.max_runtime_secs.is_some_and(|secs| secs < 1) {
+            return Err("the maximum runtime must be at least 1 second".to_string());
+        }
+        validate_env(&self.env, &self.secrets)?;
+        JobOutput::validate_all(&self.outputs)?;
+        JobVolume::validate_all(&self.volumes, &self.outputs)?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobSchedule {
+    pub id: ScheduleId,
+    pub name: String,
+    pub cron: CronExpression,
+    pub job_template: JobTemplate,
+    pub concurrency_policy: ConcurrencyPolicy,
+    /// Paused schedules don't submit any jobs.
+    pub paused: bool,
+    /// When the schedule comes due next. `None` if it is paused or its cron
+    /// expression never matches again.
+    pub next_run_at: Option<DateTime<Utc>>,
+    /// The user who created the schedule. Its jobs are submitted on their
+    /// behalf, so they can only receive that user's secrets.
+    pub owner_id: Option<UserId>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl JobSchedule {
+    /// The request for the job submitted by the run due at `scheduled_at`.
+    /// Jobs are named after the schedule and the time they were due.
+    pub fn job_request(&self, scheduled_at: DateTime<Utc>) -> CreateTrainingJobRequest {
+        let template = &self.job_template;
+        CreateTrainingJobRequest {
+            name: format!("{}-{}", self.name, scheduled_at.format("%Y%m%d-%H%M")),
+            definition: template.definition.clone(),
+            queue_id: template.queue_id,
+            resource_requirements: serde_json::to_value(&template.resource_requirements)
+                .unwrap_or_default(),
+            node_count: template.node_count,
+            depends_on: Vec::new(),
+            retry_policy: template.retry_policy.clone(),
+            max_runtime_secs: template.max_runtime_secs,
+            node_selector: template.node_selector.clone(),
+            tolerations: template.tolerations.clone(),
+            env: template.env.clone(),
+            secrets: template.secrets.clone(),
+            outputs: template.outputs.clone(),
+            volumes: template.volumes.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduleRunOutcome {
+    /// A job was submitted.
+    Started,
+    /// The concurrency policy held the run back.
+    Skipped,
+    /// The job could not be submitted.
+    Failed,
+}
+
+/// A time a schedule came due.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleRun {
+    pub schedule_id: ScheduleId,
+    pub scheduled_at: DateTime<Utc>,
+    pub outcome: ScheduleRunOutcome,
+    /// The job that was submitted, unless it was skipped, failed or has been
+    /// deleted since.
+    pub job_id: Option<JobId>,
+    /// Why the run was skipped or failed.
+    pub message: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// DTO for creating a new schedule.
+pub struct CreateScheduleRequest {
+    pub name: String,
+    pub cron: String,
+    pub job_template: JobTemplate,
+    pub concurrency_policy: ConcurrencyPolicy,
+    pub paused: bool,
+    pub owner_id: UserId,
+}
+
+/// DTO for updating an existing schedule.
+pub struct UpdateScheduleRequest {
+    pub id: ScheduleId,
+    pub name: String,
+    pub cron: String,
+    pub job_template: JobTemplate,
+    pub concurrency_policy: ConcurrencyPolicy,
+    pub paused: bool,
+    /// Must be the schedule's owner, as its jobs keep being submitted on the
+    /// owner's behalf.
+    pub updated_by: UserId,
+}
@@ -0,0 +1,48 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+use crate::domain::training_job::models::JobId;
+
+use super::models::{JobSchedule, ScheduleId, ScheduleRun};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ScheduleRepositoryError {
+    #[error("schedule with {field} {value} already exists")]
+    Duplicate { field: String, value: String },
+    #[error("schedule with id {0} not found")]
+    NotFound(String),
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait ScheduleRepository: Send + Sync {
+    async fn create(&self, schedule: &JobSchedule) -> Result<(), ScheduleRepositoryError>;
+    async fn get_schedule_by_id(
+        &self,
+        id: &ScheduleId,
+    ) -> Result<JobSchedule, ScheduleRepositoryError>;
+    async fn list_schedules(&self) -> Result<Vec<JobSchedule>, ScheduleRepositoryError>;
+    async fn update(&self, schedule: &JobSchedule) -> Result<(), ScheduleRepositoryError>;
+    async fn delete(&self, id: &ScheduleId) -> Result<(), ScheduleRepositoryError>;
+    /// Unpaused schedules whose next run is due at `now` or earlier.
+    async fn get_due_schedules(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<JobSchedule>, ScheduleRepositoryError>;
+    /// Moves a schedule's next run from `due_at` on to `next_run_at`. Returns
+    /// false if the run has already been claimed, e.g. by another replica.
+    async fn claim_run(
+        &self,
+        id: &ScheduleId,
+        due_at: DateTime<Utc>,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<bool, ScheduleRepositoryError>;
+    async fn record_run(&self, run: &ScheduleRun) -> Result<(), ScheduleRepositoryError>;
+    /// The schedule's runs, newest first.
+    async fn get_runs(&self, id: &ScheduleId) -> Result<Vec<ScheduleRun>, ScheduleRepositoryError>;
+    /// The jobs started by the schedule that haven't finished yet.
+    async fn get_active_jobs(&self, id: &ScheduleId)
+        -> Result<Vec<JobId>, ScheduleRepositoryError>;
+}
@@ -0,0 +1,307 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+use tokio::time::MissedTickBehavior;
+use tracing::{error, info, warn};
+
+use crate::domain::{
+    schedule::{
+        cron::CronExpression,
+        models::{
+            ConcurrencyPolicy, CreateScheduleRequest, JobSchedule, ScheduleId, ScheduleRun,
+            ScheduleRunOutcome, UpdateScheduleRequest,
+        },
+        ports::{ScheduleRepository, ScheduleRepositoryError},
+    },
+    training_job::service::TrainingJobService,
+};
+
+#[derive(Debug, Error)]
+pub enum ScheduleServiceError {
+    #[error("schedule with {field} {value} already exists")]
+    ScheduleExists { field: String, value: String },
+    #[error("schedule {0} not found")]
+    ScheduleNotFound(String),
+    #[error("invalid cron expression: {0}")]
+    InvalidCron(String),
+    #[error("invalid job template: {0}")]
+    InvalidTemplate(String),
+    #[error("invalid permissions")]
+    InvalidPermissions,
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+impl From<ScheduleRepositoryError> for ScheduleServiceError {
+    fn from(error: ScheduleRepositoryError) -> Self {
+        match error {
+            ScheduleRepositoryError::Duplicate { field, value } => {
+                Self::ScheduleExists { field, value }
+            }
+            ScheduleRepositoryError::NotFound(id) => Self::ScheduleNotFound(id),
+            ScheduleRepositoryError::Unknown(error) => Self::Unknown(error),
+        }
+    }
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait ScheduleService: Send + Sync {
+    async fn create_schedule(
+        &self,
+        request: CreateScheduleRequest,
+    ) -> Result<JobSchedule, ScheduleServiceError>;
+    async fn get_schedule_by_id(
+        &self,
+        id: &ScheduleId,
+    ) -> Result<JobSchedule, ScheduleServiceError>;
+    async fn list_schedules(&self) -> Result<Vec<JobSchedule>, ScheduleServiceError>;
+    async fn update_schedule(
+        &self,
+        request: UpdateScheduleRequest,
+    ) -> Result<JobSchedule, ScheduleServiceError>;
+    async fn delete_schedule(&self, id: &ScheduleId) -> Result<(), ScheduleServiceError>;
+    /// Every time the schedule came due, newest first.
+    async fn list_schedule_runs(
+        &self,
+        id: &ScheduleId,
+    ) -> Result<Vec<ScheduleRun>, ScheduleServiceError>;
+}
+
+pub struct ScheduleServiceImpl {
+    schedule_repo: Arc<dyn ScheduleRepository>,
+    training_job_service: Arc<dyn TrainingJobService>,
+}
+
+impl ScheduleServiceImpl {
+    pub fn new(
+        schedule_repo: Arc<dyn ScheduleRepository>,
+        training_job_service: Arc<dyn TrainingJobService>,
+    ) -> Self {
+        Self {
+            schedule_repo,
+            training_job_service,
+        }
+    }
+
+    /// Submits the jobs of due schedules every `interval`. Every replica runs
+    /// this loop, each run is claimed by exactly one of them.
+    pub async fn run(&self, interval: std::time::Duration) {
+        let mut interval = tokio::time::interval(interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.run_due_schedules(Utc::now()).await {
+                error!("Failed to run due schedules: {}", e);
+            }
+        }
+    }
+
+    /// Runs every schedule that is due at `now`. A schedule that missed
+    /// several runs, e.g. while the backend was down, only runs once. A
+    /// schedule that fails to run doesn't hold up the others.
+    pub async fn run_due_schedules(&self, now: DateTime<Utc>) -> Result<(), ScheduleServiceError> {
+        for schedule in self.schedule_repo.get_due_schedules(now).await? {
+            if let Err(e) = self.run_due_schedule(&schedule, now).await {
+                error!("Failed to run schedule '{}': {}", schedule.name, e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Claims the schedule's due run and, if no other replica got to it
+    /// first, runs it and records the outcome.
+    async fn run_due_schedule(
+        &self,
+        schedule: &JobSchedule,
+        now: DateTime<Utc>,
+    ) -> Result<(), ScheduleServiceError> {
+        let Some(due_at) = schedule.next_run_at else {
+            return Ok(());
+        };
+        let next_run_at = schedule.cron.next_after(&now);
+        if !self
+            .schedule_repo
+            .claim_run(&schedule.id, due_at, next_run_at)
+            .await?
+        {
+            return Ok(());
+        }
+
+        let run = self.run_schedule(schedule, due_at).await;
+        info!(
+            "Schedule '{}' came due at {}: {:?}",
+            schedule.name, due_at, run.outcome
+        );
+        self.schedule_repo.record_run(&run).await?;
+        Ok(())
+    }
+
+    /// Applies the schedule's concurrency policy and submits its job.
+    async fn run_schedule(
+        &self,
+        schedule: &JobSchedule,
+        scheduled_at: DateTime<Utc>,
+    ) -> ScheduleRun {
+        let run = |outcome, job_id, message| ScheduleRun {
+            schedule_id: schedule.id,
+            scheduled_at,
+            outcome,
+            job_id,
+            message,
+            created_at: Utc::now(),
+        };
+
+        if schedule.concurrency_policy != ConcurrencyPolicy::Allow {
+            let active_jobs = match self.schedule_repo.get_active_jobs(&schedule.id).await {
+                Ok(active_jobs) => active_jobs,
+                Err(e) => return run(ScheduleRunOutcome::Failed, None, Some(e.to_string())),
+            };
+            match schedule.concurrency_policy {
+                ConcurrencyPolicy::Forbid if !active_jobs.is_empty() => {
+                    let message = format!(
+                        "{} job(s) from earlier runs are still active",
+                        active_jobs.len()
+                    );
+                    return run(ScheduleRunOutcome::Skipped, None, Some(message));
+                }
+                ConcurrencyPolicy::Replace => {
+                    for job_id in active_jobs {
+                        if let Err(e) = self.training_job_service.cancel(&job_id).await {
+                            warn!(
+                                "Failed to cancel job {} replaced by schedule '{}': {}",
+                                job_id, schedule.name, e
+                            );
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        match self
+            .training_job_service
+            .create(schedule.job_request(scheduled_at), schedule.owner_id)
+            .await
+        {
+            Ok(job) => run(ScheduleRunOutcome::Started, Some(job.id), None),
+            Err(e) => run(ScheduleRunOutcome::Failed, None, Some(e.to_string())),
+        }
+    }
+
+    /// Parses a cron expression, rejecting one that never fires, such as
+    /// `0 0 30 2 *`.
+    fn parse_cron(expression: &str) -> Result<CronExpression, ScheduleServiceError> {
+        let cron: CronExpression = expression
+            .parse()
+            .map_err(ScheduleServiceError::InvalidCron)?;
+        if cron.next_after(&Utc::now()).is_none() {
+            return Err(ScheduleServiceError::InvalidCron(format!(
+                "'{expression}' never fires"
+            )));
+        }
+        Ok(cron)
+    }
+
+    fn next_run_at(schedule: &JobSchedule) -> Option<DateTime<Utc>> {
+        if schedule.paused {
+            None
+        } else {
+            schedule.cron.next_after(&Utc::now())
+        }
+    }
+}
+
+#[async_trait]
+impl ScheduleService for ScheduleServiceImpl {
+    async fn create_schedule(
+        &self,
+        request: CreateScheduleRequest,
+    ) -> Result<JobSchedule, ScheduleServiceError> {
+        request
+            .job_template
+            .validate()
+            .map_err(ScheduleServiceError::InvalidTemplate)?;
+
+        let now = Utc::now();
+        let mut schedule = JobSchedule {
+            id: ScheduleId::generate(),
+            name: request.name,
+            cron: Self::parse_cron(&request.cron)?,
+            job_template: request.job_template,
+            concurrency_policy: request.concurrency_policy,
+            paused: request.paused,
+            next_run_at: None,
+            owner_id: Some(request.owner_id),
+            created_at: now,
+            updated_at: now,
+        };
+        schedule.next_run_at = Self::next_run_at(&schedule);
+
+        self.schedule_repo.create(&schedule).await?;
+
+        Ok(schedule)
+    }
+
+    async fn get_schedule_by_id(
+        &self,
+        id: &ScheduleId,
+    ) -> Result<JobSchedule, ScheduleServiceError> {
+        Ok(self.schedule_repo.get_schedule_by_id(id).await?)
+    }
+
+    async fn list_schedules(&self) -> Result<Vec<JobSchedule>, ScheduleServiceError> {
+        Ok(self.schedule_repo.list_schedules().await?)
+    }
+
+    async fn update_schedule(
+        &self,
+        request: UpdateScheduleRequest,
+    ) -> Result<JobSchedule, ScheduleServiceError> {
+        request
+            .job_template
+            .validate()
+            .map_err(ScheduleServiceError::InvalidTemplate)?;
+
+        let existing = self.schedule_repo.get_schedule_by_id(&request.id).await?;
+        if existing
+            .owner_id
+            .is_some_and(|owner_id| owner_id != request.updated_by)
+        {
+            return Err(ScheduleServiceError::InvalidPermissions);
+        }
+        let mut schedule = JobSchedule {
+            id: request.id,
+            name: request.name,
+            cron: Self::parse_cron(&request.cron)?,
+            job_template: request.job_template,
+            concurrency_policy: request.concurrency_policy,
+            paused: request.paused,
+            next_run_at: None,
+            owner_id: existing.owner_id,
+            created_at: existing.created_at,
+            updated_at: Utc::now(),
+        };
+        schedule.next_run_at = Self::next_run_at(&schedule);
+
+        self.schedule_repo.update(&schedule).await?;
+
+        Ok(schedule)
+    }
+
+    async fn delete_schedule(&self, id: &ScheduleId) -> Result<(), ScheduleServiceError> {
+        Ok(self.schedule_repo.delete(id).await?)
+    }
+
+    async fn list_schedule_runs(
+        &self,
+        id: &ScheduleId,
+    ) -> Result<Vec<ScheduleRun>, ScheduleServiceError> {
+        // Tell a schedule without runs apart from one that doesn't exist.
+        self.schedule_repo.get_schedule_by_id(id).await?;
+        Ok(self.schedule_repo.get_runs(id).await?)
+    }
+}
@@ -0,0 +1,97 @@
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use chrono::Utc;
+
+use super::{
+    models::SchedulerLease,
+    ports::{SchedulerLeaseRepository, SchedulerLeaseRepositoryError},
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum LeaderElectionError {
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+impl From<SchedulerLeaseRepositoryError> for LeaderElectionError {
+    fn from(err: SchedulerLeaseRepositoryError) -> Self {
+        match err {
+            SchedulerLeaseRepositoryError::Unknown(err) => Self::Unknown(err),
+        }
+    }
+}
+
+/// The scheduler leader as seen by one control plane replica.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchedulerLeader {
+    /// The replica answering the request.
+    pub replica_id: String,
+    /// Whether the answering replica is the leader.
+    pub is_leader: bool,
+    /// The current lease, if any replica holds an unexpired one.
+    pub lease: Option<SchedulerLease>,
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait LeaderElection: Send + Sync {
+    /// Acquires or renews the scheduler lease for this replica. Returns
+    /// whether this replica is the leader.
+    async fn try_acquire_leadership(&self) -> Result<bool, LeaderElectionError>;
+    async fn current_leader(&self) -> Result<SchedulerLeader, LeaderElectionError>;
+    /// How often the leader has to renew its lease to keep it.
+    fn renewal_interval(&self) -> Duration;
+}
+
+pub struct LeaderElectionImpl {
+    lease_repo: Arc<dyn SchedulerLeaseRepository>,
+    replica_id: String,
+    lease_ttl: Duration,
+}
+
+impl LeaderElectionImpl {
+    pub fn new(
+        lease_repo: Arc<dyn SchedulerLeaseRepository>,
+        replica_id: String,
+        lease_ttl: Duration,
+    ) -> Self {
+        Self {
+            lease_repo,
+            replica_id,
+            lease_ttl,
+        }
+    }
+}
+
+#[async_trait]
+impl LeaderElection for LeaderElectionImpl {
+    async fn try_acquire_leadership(&self) -> Result<bool, LeaderElectionError> {
+        Ok(self
+            .lease_repo
+            .try_acquire(&self.replica_id, self.lease_ttl)
+            .await?)
+    }
+
+    async fn current_leader(&self) -> Result<SchedulerLeader, LeaderElectionError> {
+        let lease = self
+            .lease_repo
+            .get_lease()
+            .await?
+            .filter(|lease| lease.expires_at > Utc::now());
+
+        Ok(SchedulerLeader {
+            replica_id: self.replica_id.clone(),
+            is_leader: lease
+                .as_ref()
+                .is_some_and(|lease| lease.holder_id == self.replica_id),
+            lease,
+        })
+    }
+
+    fn renewal_interval(&self) -> Duration {
+        // Renew well before the lease runs out, so a single slow renewal
+        // doesn't hand leadership to another replica.
+        (self.lease_ttl / 3).max(Duration::from_secs(1))
+    }
+}
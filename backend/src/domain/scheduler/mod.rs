@@ -1,3 +1,650 @@
+pub mod leader;
 pub mod models;
+pub mod notifier;
+pub mod policies;
 pub mod ports;
 pub mod service;
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{BTreeMap, HashMap},
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    use chrono::Utc;
+    use mockall::predicate::*;
+
+    use super::{
+        leader::{LeaderElection, LeaderElectionImpl, MockLeaderElection},
+        models::{
+            ClusterOutcome, RejectionReason, SchedulerLease, SchedulingOutcome,
+            SchedulingPolicyKind, SchedulingReport,
+        },
+        notifier::SchedulerNotifier,
+        ports::MockSchedulerLeaseRepository,
+        service::SchedulerService,
+    };
+    use crate::{
+        config::{LogsConfig, MetricsConfig, SchedulerConfig},
+        domain::{
+            cluster::{
+                models::{
+                    Architecture, ClusterId, ClusterNode, Cpu, CpuManufacturer, Gpu,
+                    GpuManufacturer, GpuModel, NodeId, NodeSchedulability, Taint,
+                },
+                ports::MockClusterRepository,
+            },
+            queue::{
+                models::{Queue, QueueId},
+                ports::MockQueueRepository,
+            },
+            training_job::{
+                models::{
+                    AttemptOutcome, DependencyCondition, GpuRequirement, JobAllocation, JobAttempt,
+                    JobDefinition, JobDependency, JobEventKind, JobId, ResourceRequirements,
+                    Toleration, TrainingJob, TrainingJobStatus,
+                },
+                ports::MockTrainingJobRepository,
+            },
+        },
+        outbound::scheduler::agent_adapter::{
+            capacity_rejections, node_rejections, placement_rejections, AgentSchedulerAdapter,
+        },
+    };
+
+    fn node(memory_mb: i32, gpu_count: i32, used: &[(i32, i32)]) -> ClusterNode {
+        let mut node = ClusterNode::create(
+            NodeId::generate(),
+            ClusterId::generate(),
+            memory_mb,
+            Cpu {
+                manufacturer: CpuManufacturer::Intel,
+                architecture: Architecture::X86_64,
+                millicores: 64000,
+            },
+            (gpu_count > 0).then_some(Gpu {
+                manufacturer: GpuManufacturer::Nvidia,
+                model: GpuModel::H100,
+                count: gpu_count,
+                memory_mb: 80 * 1024,
+            }),
+        );
+        node.allocations = used
+            .iter()
+            .map(|&(memory_mb, gpu_count)| JobAllocation {
+                job_id: JobId::generate(),
+                node_id: node.id,
+                rank: 0,
+                cpu_millicores: 1000,
+                memory_mb,
+                gpu_count,
+            })
+            .collect();
+        node
+    }
+
+    fn ranked(kind: SchedulingPolicyKind, nodes: &[ClusterNode]) -> Vec<NodeId> {
+        let requirements = ResourceRequirements {
+            cpu_millicores: 1000,
+            memory_mb: 1024,
+            gpus: None,
+        };
+        let mut candidates = nodes.to_vec();
+        kind.policy().rank(&mut candidates, &requirements);
+        candidates.into_iter().map(|node| node.id).collect()
+    }
+
+    #[test]
+    fn test_best_fit_prefers_least_free_memory() {
+        let large = node(64 * 1024, 0, &[]);
+        let small = node(16 * 1024, 0, &[]);
+        let busy = node(64 * 1024, 0, &[(56 * 1024, 0)]);
+
+        assert_eq!(
+            ranked(
+                SchedulingPolicyKind::BestFit,
+                &[large.clone(), small.clone(), busy.clone()]
+            ),
+            vec![busy.id, small.id, large.id]
+        );
+    }
+
+    #[test]
+    fn test_spread_prefers_fewest_jobs() {
+        let two_jobs = node(64 * 1024, 0, &[(1024, 0), (1024, 0)]);
+        let one_job = node(64 * 1024, 0, &[(1024, 0)]);
+        let idle = node(32 * 1024, 0, &[]);
+
+        assert_eq!(
+            ranked(
+                SchedulingPolicyKind::Spread,
+                &[two_jobs.clone(), one_job.clone(), idle.clone()]
+            ),
+            vec![idle.id, one_job.id, two_jobs.id]
+        );
+    }
+
+    #[test]
+    fn test_gpu_packing_keeps_idle_gpu_nodes_whole() {
+        let idle = node(64 * 1024, 8, &[]);
+        let partial = node(256 * 1024, 8, &[(1024, 6)]);
+        let cpu_only = node(256 * 1024, 0, &[]);
+
+        assert_eq!(
+            ranked(
+                SchedulingPolicyKind::GpuPacking,
+                &[idle.clone(), partial.clone(), cpu_only.clone()]
+            ),
+            vec![cpu_only.id, partial.id, idle.id]
+        );
+    }
+
+    #[test]
+    fn test_rejection_reasons() {
+        let requirements = ResourceRequirements {
+            cpu_millicores: 1000,
+            memory_mb: 32 * 1024,
+            gpus: Some(GpuRequirement {
+                count: 4,
+                model: Some("A100".to_string()),
+                manufacturer: None,
+                memory_gb: None,
+            }),
+        };
+
+        assert_eq!(
+            node_rejections(&node(16 * 1024, 0, &[]), &requirements, 2),
+            vec![
+                RejectionReason::NoAdvertisedAddress,
+                RejectionReason::InsufficientMemory {
+                    required: 32 * 1024,
+                    available: 16 * 1024,
+                },
+                RejectionReason::NoGpus,
+            ]
+        );
+        assert_eq!(
+            node_rejections(&node(64 * 1024, 2, &[]), &requirements, 1),
+            vec![
+                RejectionReason::InsufficientGpus {
+                    required: 4,
+                    available: 2,
+                },
+                RejectionReason::WrongGpuModel {
+                    required: "A100".to_string(),
+                    actual: "H100".to_string(),
+                },
+            ]
+        );
+
+        let requirements = ResourceRequirements {
+            gpus: None,
+            ..requirements
+        };
+        let busy = node(64 * 1024, 0, &[(48 * 1024, 0)]);
+        assert!(node_rejections(&busy, &requirements, 1).is_empty());
+        assert_eq!(
+            capacity_rejections(&busy, &requirements),
+            vec![RejectionReason::NodeBusy {
+                free_cpu_millicores: 63000,
+                free_memory_mb: 16 * 1024,
+                free_gpus: 0,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_cordoned_nodes_take_no_jobs() {
+        let requirements = ResourceRequirements {
+            cpu_millicores: 1000,
+            memory_mb: 1024,
+            gpus: None,
+        };
+        let mut cordoned = node(64 * 1024, 0, &[]);
+        cordoned.schedulability = NodeSchedulability::Cordoned;
+        assert!(node_rejections(&cordoned, &requirements, 1).is_empty());
+        assert_eq!(
+            capacity_rejections(&cordoned, &requirements),
+            vec![RejectionReason::Cordoned]
+        );
+
+        let mut draining = node(64 * 1024, 0, &[(1024, 0)]);
+        draining.schedulability = NodeSchedulability::Draining;
+        assert_eq!(
+            capacity_rejections(&draining, &requirements),
+            vec![RejectionReason::Draining]
+        );
+        assert!(!draining.is_drained());
+        draining.allocations.clear();
+        assert!(draining.is_drained());
+    }
+
+    #[test]
+    fn test_labels_and_taints() {
+        let mut node = node(64 * 1024, 0, &[]);
+        node.labels = BTreeMap::from([("network".to_string(), "infiniband".to_string())]);
+        node.taints = vec![Taint {
+            key: "team".to_string(),
+            value: Some("research".to_string()),
+        }];
+        let selector =
+            |key: &str, value: &str| BTreeMap::from([(key.to_string(), value.to_string())]);
+        let toleration = |value: Option<&str>| Toleration {
+            key: "team".to_string(),
+            value: value.map(str::to_string),
+        };
+
+        assert!(placement_rejections(
+            &node,
+            &selector("network", "infiniband"),
+            &[toleration(None)]
+        )
+        .is_empty());
+        assert!(
+            placement_rejections(&node, &BTreeMap::new(), &[toleration(Some("research"))])
+                .is_empty()
+        );
+        assert_eq!(
+            placement_rejections(
+                &node,
+                &selector("network", "ethernet"),
+                &[toleration(Some("ops"))]
+            ),
+            vec![
+                RejectionReason::MissingLabel {
+                    key: "network".to_string(),
+                    required: "ethernet".to_string(),
+                    actual: Some("infiniband".to_string()),
+                },
+                RejectionReason::UntoleratedTaint {
+                    key: "team".to_string(),
+                    value: Some("research".to_string()),
+                },
+            ]
+        );
+    }
+
+    fn lease(holder_id: &str, expires_in: chrono::Duration) -> SchedulerLease {
+        let now = Utc::now();
+        SchedulerLease {
+            holder_id: holder_id.to_string(),
+            acquired_at: now,
+            renewed_at: now,
+            expires_at: now + expires_in,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_current_leader() {
+        let mut mock_repo = MockSchedulerLeaseRepository::new();
+        mock_repo
+            .expect_get_lease()
+            .times(1)
+            .returning(|| Ok(Some(lease("replica-a", chrono::Duration::seconds(30)))));
+        let election = LeaderElectionImpl::new(
+            Arc::new(mock_repo),
+            "replica-b".to_string(),
+            Duration::from_secs(30),
+        );
+
+        let leader = election.current_leader().await.unwrap();
+
+        assert!(!leader.is_leader);
+        assert_eq!(leader.replica_id, "replica-b");
+        assert_eq!(leader.lease.unwrap().holder_id, "replica-a");
+    }
+
+    #[tokio::test]
+    async fn test_notifications_are_forwarded_to_other_replicas() {
+        let notifier = SchedulerNotifier::new();
+        let wait = Duration::from_millis(100);
+
+        notifier.notify();
+        assert!(tokio::time::timeout(wait, notifier.notified())
+            .await
+            .is_ok());
+        assert!(tokio::time::timeout(wait, notifier.outgoing())
+            .await
+            .is_ok());
+
+        // Notifications from other replicas are not sent back out.
+        notifier.notify_local();
+        assert!(tokio::time::timeout(wait, notifier.notified())
+            .await
+            .is_ok());
+        assert!(tokio::time::timeout(wait, notifier.outgoing())
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_expired_lease_has_no_leader() {
+        let mut mock_repo = MockSchedulerLeaseRepository::new();
+        mock_repo
+            .expect_get_lease()
+            .times(1)
+            .returning(|| Ok(Some(lease("replica-a", chrono::Duration::seconds(-1)))));
+        let election = LeaderElectionImpl::new(
+            Arc::new(mock_repo),
+            "replica-a".to_string(),
+            Duration::from_secs(30),
+        );
+
+        let leader = election.current_leader().await.unwrap();
+
+        assert!(!leader.is_leader);
+        assert!(leader.lease.is_none());
+        assert_eq!(election.renewal_interval(), Duration::from_secs(10));
+    }
+
+    fn queue(priority: i32, preemptible: bool, cluster_id: ClusterId) -> Queue {
+        Queue {
+            id: QueueId::generate(),
+            name: format!("priority-{priority}"),
+            priority,
+            cluster_targets: vec![cluster_id],
+            preemptible,
+            scheduling_policy: SchedulingPolicyKind::default(),
+            retry_policy: None,
+            default_max_runtime_secs: None,
+            max_runtime_limit_secs: None,
+        }
+    }
+
+    fn job(queue: &Queue, status: TrainingJobStatus) -> TrainingJob {
+        let now = Utc::now();
+        TrainingJob {
+            id: JobId::generate(),
+            name: "test".to_string(),
+            definition: JobDefinition::new("definition"),
+            status,
+            node_id: None,
+            queue_id: Some(queue.id),
+            resource_requirements: ResourceRequirements {
+                cpu_millicores: 1000,
+                memory_mb: 32 * 1024,
+                gpus: None,
+            },
+            node_count: 1,
+            preempted_at: None,
+            preemption_reason: None,
+            dependencies: Vec::new(),
+            retry_policy: None,
+            not_before: None,
+            avoid_node_id: None,
+            max_runtime_secs: None,
+            started_at: None,
+            exit: None,
+            array_id: None,
+            array_index: None,
+            parameters: BTreeMap::new(),
+            node_selector: BTreeMap::new(),
+            tolerations: Vec::new(),
+            env: BTreeMap::new(),
+            secrets: BTreeMap::new(),
+            outputs: vec![],
+            volumes: vec![],
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// A scheduler whose only cluster has a single node that is full with
+    /// `running`, one half each.
+    fn preempting_scheduler(
+        cluster_id: ClusterId,
+        running: [TrainingJob; 2],
+        mut mock_job_repo: MockTrainingJobRepository,
+    ) -> SchedulerService {
+        let mut node = node(64 * 1024, 0, &[(32 * 1024, 0), (32 * 1024, 0)]);
+        node.cluster_id = cluster_id;
+        for (allocation, job) in node.allocations.iter_mut().zip(&running) {
+            allocation.job_id = job.id;
+        }
+        let mut mock_cluster_repo = MockClusterRepository::new();
+        mock_cluster_repo
+            .expect_list_cluster_nodes()
+            .with(eq(cluster_id))
+            .returning(move |_| Ok(vec![node.clone()]));
+        let jobs: HashMap<JobId, TrainingJob> =
+            running.into_iter().map(|job| (job.id, job)).collect();
+        mock_job_repo
+            .expect_get_training_job_by_id()
+            .returning(move |id| Ok(jobs[id].clone()));
+        mock_job_repo.expect_record_event().returning(|_, _| Ok(()));
+
+        scheduler(mock_job_repo, mock_cluster_repo)
+    }
+
+    fn scheduler(
+        mock_job_repo: MockTrainingJobRepository,
+        mock_cluster_repo: MockClusterRepository,
+    ) -> SchedulerService {
+        let cluster_repo = Arc::new(mock_cluster_repo);
+        SchedulerService::new(
+            Arc::new(mock_job_repo),
+            Arc::new(MockQueueRepository::new()),
+            cluster_repo.clone(),
+            Arc::new(AgentSchedulerAdapter::new(cluster_repo)),
+            Arc::new(MockLeaderElection::new()),
+            SchedulerConfig::default(),
+            LogsConfig::default(),
+            MetricsConfig::default(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_preempts_lowest_priority_job() {
+        let cluster_id = ClusterId::generate();
+        let urgent = queue(1, false, cluster_id);
+        let low = queue(10, true, cluster_id);
+        let lowest = queue(20, true, cluster_id);
+        let queues = HashMap::from([
+            (urgent.id, urgent.clone()),
+            (low.id, low.clone()),
+            (lowest.id, lowest.clone()),
+        ]);
+        let low_job = job(&low, TrainingJobStatus::Running);
+        let lowest_job = job(&lowest, TrainingJobStatus::Running);
+        let victim_id = lowest_job.id;
+
+        let mut mock_job_repo = MockTrainingJobRepository::new();
+        mock_job_repo
+            .expect_preempt_job()
+            .withf(move |id, reason| *id == victim_id && reason.contains("priority-1"))
+            .times(1)
+            .returning(|_, _| Ok(true));
+        let scheduler = preempting_scheduler(cluster_id, [low_job, lowest_job], mock_job_repo);
+
+        let preempted = scheduler
+            .preempt_for(&job(&urgent, TrainingJobStatus::Queued), &urgent, &queues)
+            .await
+            .unwrap();
+
+        assert!(preempted);
+    }
+
+    #[tokio::test]
+    async fn test_preempts_nothing_without_victims() {
+        let cluster_id = ClusterId::generate();
+        let urgent = queue(1, true, cluster_id);
+        let pinned = queue(10, false, cluster_id);
+        let queues = HashMap::from([(urgent.id, urgent.clone()), (pinned.id, pinned.clone())]);
+        // Neither a job from a queue that can't be preempted nor one from a
+        // queue of the same priority makes way.
+        let running = [
+            job(&pinned, TrainingJobStatus::Running),
+            job(&urgent, TrainingJobStatus::Running),
+        ];
+
+        let mut mock_job_repo = MockTrainingJobRepository::new();
+        mock_job_repo.expect_preempt_job().never();
+        let scheduler = preempting_scheduler(cluster_id, running, mock_job_repo);
+
+        let preempted = scheduler
+            .preempt_for(&job(&urgent, TrainingJobStatus::Queued), &urgent, &queues)
+            .await
+            .unwrap();
+
+        assert!(!preempted);
+    }
+
+    #[tokio::test]
+    async fn test_unschedulable_job_is_reported_once() {
+        let queue = queue(1, false, ClusterId::generate());
+        let job = job(&queue, TrainingJobStatus::Queued);
+        let report = |outcome| SchedulingReport {
+            job_id: job.id,
+            attempted_at: Utc::now(),
+            outcome,
+            clusters: Vec::new(),
+            preempted_jobs: false,
+            blocking_dependencies: Vec::new(),
+        };
+
+        let mut mock_job_repo = MockTrainingJobRepository::new();
+        let pending = report(SchedulingOutcome::Pending);
+        mock_job_repo
+            .expect_get_scheduling_report()
+            .times(1)
+            .returning(move |_| Ok(Some(pending.clone())));
+        mock_job_repo
+            .expect_record_event()
+            .withf(|_, event| matches!(event, JobEventKind::Unschedulable { reason } if reason.contains("priority-1")))
+            .times(1)
+            .returning(|_, _| Ok(()));
+        scheduler(mock_job_repo, MockClusterRepository::new())
+            .report_unschedulable(&job, &queue)
+            .await;
+
+        let mut mock_job_repo = MockTrainingJobRepository::new();
+        let unsatisfiable = report(SchedulingOutcome::Unsatisfiable);
+        mock_job_repo
+            .expect_get_scheduling_report()
+            .times(1)
+            .returning(move |_| Ok(Some(unsatisfiable.clone())));
+        mock_job_repo.expect_record_event().never();
+        scheduler(mock_job_repo, MockClusterRepository::new())
+            .report_unschedulable(&job, &queue)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_failed_dependency_awaiting_retry_is_not_violated() {
+        let queue = queue(1, false, ClusterId::generate());
+        let mut upstream = job(&queue, TrainingJobStatus::Queued);
+        upstream.status = TrainingJobStatus::Failed;
+        let mut downstream = job(&queue, TrainingJobStatus::Queued);
+        downstream.dependencies = vec![JobDependency {
+            job_id: upstream.id,
+            condition: DependencyCondition::Succeeded,
+        }];
+        let upstream_id = upstream.id;
+        let downstream_id = downstream.id;
+
+        let scheduler_with = |awaiting_retry: bool, cancellations: usize| {
+            let mut mock_job_repo = MockTrainingJobRepository::new();
+            let attempt = JobAttempt {
+                job_id: upstream_id,
+                attempt_number: 1,
+                node_id: None,
+                started_at: Utc::now(),
+                finished_at: Some(Utc::now()),
+                outcome: Some(AttemptOutcome::Failed),
+                exit_code: Some(1),
+            };
+            mock_job_repo
+                .expect_get_failed_attempts_to_evaluate()
+                .returning(move || {
+                    Ok(if awaiting_retry {
+                        vec![attempt.clone()]
+                    } else {
+                        vec![]
+                    })
+                });
+            let queued = downstream.clone();
+            mock_job_repo
+                .expect_get_jobs_by_status()
+                .with(eq(TrainingJobStatus::Queued))
+                .returning(move |_| Ok(vec![queued.clone()]));
+            let failed = upstream.clone();
+            mock_job_repo
+                .expect_get_training_job_by_id()
+                .with(eq(upstream_id))
+                .returning(move |_| Ok(failed.clone()));
+            mock_job_repo
+                .expect_update_status()
+                .with(eq(downstream_id), eq(TrainingJobStatus::Cancelled))
+                .times(cancellations)
+                .returning(|_, _| Ok(()));
+            mock_job_repo.expect_record_event().returning(|_, _| Ok(()));
+            mock_job_repo
+                .expect_record_scheduling_report()
+                .returning(|_| Ok(()));
+            mock_job_repo
+                .expect_get_dependent_jobs()
+                .returning(|_| Ok(vec![]));
+            scheduler(mock_job_repo, MockClusterRepository::new())
+        };
+
+        // The upstream job may still be retried.
+        scheduler_with(true, 0)
+            .cleanup_failed_dependencies()
+            .await
+            .unwrap();
+        // It failed for good.
+        scheduler_with(false, 1)
+            .cleanup_failed_dependencies()
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_competing_allocations_take_a_node_once() {
+        let cluster_id = ClusterId::generate();
+        let queue = queue(1, false, cluster_id);
+        // The node has room for one of the jobs, but not for both.
+        let mut free = node(48 * 1024, 0, &[]);
+        free.cluster_id = cluster_id;
+        let node_id = free.id;
+
+        let mut mock_cluster_repo = MockClusterRepository::new();
+        mock_cluster_repo
+            .expect_list_cluster_nodes()
+            .returning(move |_| Ok(vec![free.clone()]));
+        // The repository only lets the first allocation through, as the
+        // second finds the node full once it gets the lock.
+        let taken = Arc::new(AtomicBool::new(false));
+        mock_cluster_repo
+            .expect_assign_job_to_nodes()
+            .withf(move |_, node_ids, _| node_ids == [node_id])
+            .times(2)
+            .returning(move |_, _, _| Ok(!taken.swap(true, Ordering::SeqCst)));
+        let adapter = AgentSchedulerAdapter::new(Arc::new(mock_cluster_repo));
+
+        let first = job(&queue, TrainingJobStatus::Queued);
+        let second = job(&queue, TrainingJobStatus::Queued);
+        let policy = SchedulingPolicyKind::default().policy();
+        let (first, second) = tokio::join!(
+            adapter.find_and_allocate_job(&first, &cluster_id, policy),
+            adapter.find_and_allocate_job(&second, &cluster_id, policy),
+        );
+
+        let mut outcomes = [first.unwrap().outcome, second.unwrap().outcome];
+        outcomes.sort_by_key(|outcome| *outcome == ClusterOutcome::Conflict);
+        assert_eq!(
+            outcomes,
+            [
+                ClusterOutcome::Allocated {
+                    node_ids: vec![node_id]
+                },
+                ClusterOutcome::Conflict,
+            ]
+        );
+    }
+}
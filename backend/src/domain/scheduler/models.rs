@@ -1,4 +1,8 @@
-use crate::domain::cluster::models::ClusterId;
+use crate::domain::{
+    cluster::models::{ClusterId, NodeId},
+    training_job::models::JobId,
+};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,3 +26,152 @@ pub struct GpuInfo {
     pub model: String,  // e.g., "NVIDIA A100-SXM4-40GB"
     pub memory_gb: i32, // e.g., 40
 }
+
+/// The built-in strategies for choosing which nodes a job runs on.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SchedulingPolicyKind {
+    /// Prefer the node with the least free memory that still has room.
+    #[default]
+    BestFit,
+    /// Prefer the node running the fewest jobs.
+    Spread,
+    /// Prefer nodes with the fewest free GPUs, keeping idle GPU nodes whole.
+    GpuPacking,
+}
+
+/// The lease that makes a control plane replica the scheduler leader.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SchedulerLease {
+    /// The replica holding the lease.
+    pub holder_id: String,
+    /// When the current holder took over the lease.
+    pub acquired_at: DateTime<Utc>,
+    pub renewed_at: DateTime<Utc>,
+    /// When other replicas may take over unless the holder renews the lease.
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Why a node cannot take a job.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RejectionReason {
+    /// Multi-node jobs need nodes that advertise an address to their peers.
+    NoAdvertisedAddress,
+    InsufficientCpu {
+        required: i32,
+        available: i32,
+    },
+    InsufficientMemory {
+        required: i32,
+        available: i32,
+    },
+    NoGpus,
+    InsufficientGpus {
+        required: i32,
+        available: i32,
+    },
+    WrongGpuModel {
+        required: String,
+        actual: String,
+    },
+    WrongGpuManufacturer {
+        required: String,
+        actual: String,
+    },
+    InsufficientGpuMemory {
+        required_gb: i32,
+        available_mb: i32,
+    },
+    /// The node lacks a label the job selects nodes by, or has a different
+    /// value for it.
+    MissingLabel {
+        key: String,
+        required: String,
+        actual: Option<String>,
+    },
+    /// The node has a taint the job doesn't tolerate.
+    UntoleratedTaint {
+        key: String,
+        value: Option<String>,
+    },
+    /// The node could run the job, but its capacity is taken by other jobs.
+    NodeBusy {
+        free_cpu_millicores: i32,
+        free_memory_mb: i32,
+        free_gpus: i32,
+    },
+    /// The node is still stopping jobs that were taken off it.
+    StoppingJobs,
+    /// The node has been cordoned and takes no new jobs.
+    Cordoned,
+    /// The node is being drained and takes no new jobs.
+    Draining,
+}
+
+/// A node that was passed over for a job, and why.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NodeRejection {
+    pub node_id: NodeId,
+    pub reasons: Vec<RejectionReason>,
+}
+
+/// What happened when a job was tried on one cluster.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ClusterOutcome {
+    /// The job was assigned to these nodes, in rank order.
+    Allocated { node_ids: Vec<NodeId> },
+    /// Enough nodes could run the job, but not enough of them have room.
+    NoCapacity,
+    /// No combination of nodes on the cluster could ever run the job.
+    Unsatisfiable,
+    /// The cluster has no nodes at all.
+    NoNodes,
+    /// The nodes or the job changed while the job was being allocated, e.g.
+    /// because another job took the nodes first.
+    Conflict,
+    /// The cluster could not be checked, e.g. because of a database error.
+    Error,
+}
+
+/// The result of trying a job on one cluster.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ClusterReport {
+    pub cluster_id: ClusterId,
+    pub outcome: ClusterOutcome,
+    /// The nodes the job could not be placed on.
+    pub rejected_nodes: Vec<NodeRejection>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SchedulingOutcome {
+    Scheduled,
+    /// The job waits for capacity to free up.
+    Pending,
+    /// No cluster of the job's queue could ever run it.
+    Unsatisfiable,
+    /// The job waits for the jobs it depends on to finish.
+    WaitingForDependencies,
+    /// The job's array already runs as many jobs at once as it may.
+    WaitingForArraySlot,
+    /// A job it depends on ended in a way that doesn't allow the job to run,
+    /// so it was cancelled.
+    DependencyFailed,
+}
+
+/// The outcome of the most recent attempt to schedule a job.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SchedulingReport {
+    pub job_id: JobId,
+    pub attempted_at: DateTime<Utc>,
+    pub outcome: SchedulingOutcome,
+    /// One entry per cluster targeted by the job's queue, in queue order.
+    pub clusters: Vec<ClusterReport>,
+    /// Whether lower priority jobs were preempted to make room for the job.
+    pub preempted_jobs: bool,
+    /// The dependencies that kept the job from being scheduled.
+    #[serde(default)]
+    pub blocking_dependencies: Vec<JobId>,
+}
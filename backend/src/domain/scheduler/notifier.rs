@@ -0,0 +1,47 @@
+use std::sync::Arc;
+
+use tokio::sync::Notify;
+
+/// Wakes the scheduler up ahead of its next periodic cycle.
+///
+/// Services notify it whenever something happens that may let a queued job
+/// run, e.g. a job being created or finishing. Notifications that arrive while
+/// a cycle is running are coalesced into a single follow-up cycle.
+///
+/// Only the leader replica runs cycles, so notifications are also handed to
+/// a relay that forwards them to the other replicas, see
+/// `PostgresSchedulerWakeups`.
+#[derive(Clone, Default)]
+pub struct SchedulerNotifier {
+    notify: Arc<Notify>,
+    outgoing: Arc<Notify>,
+}
+
+impl SchedulerNotifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests a scheduler cycle as soon as possible, on whichever replica
+    /// is the leader.
+    pub fn notify(&self) {
+        self.notify.notify_one();
+        self.outgoing.notify_one();
+    }
+
+    /// Requests a scheduler cycle on this replica only, for notifications
+    /// that were forwarded from another replica.
+    pub fn notify_local(&self) {
+        self.notify.notify_one();
+    }
+
+    /// Waits until a scheduler cycle has been requested.
+    pub async fn notified(&self) {
+        self.notify.notified().await;
+    }
+
+    /// Waits until a notification has to be forwarded to the other replicas.
+    pub async fn outgoing(&self) {
+        self.outgoing.notified().await;
+    }
+}
@@ -0,0 +1,54 @@
+use std::cmp::Reverse;
+
+use super::{models::SchedulingPolicyKind, ports::SchedulingPolicy};
+use crate::domain::{cluster::models::ClusterNode, training_job::models::ResourceRequirements};
+
+/// Packs jobs onto the nodes with the least free memory, so that larger nodes
+/// stay available for larger jobs.
+pub struct BestFit;
+
+impl SchedulingPolicy for BestFit {
+    fn rank(&self, candidates: &mut [ClusterNode], _requirements: &ResourceRequirements) {
+        candidates.sort_by_key(|node| node.free_capacity().memory_mb);
+    }
+}
+
+/// Places jobs on the least loaded nodes, so that losing a node affects as
+/// few jobs as possible.
+pub struct Spread;
+
+impl SchedulingPolicy for Spread {
+    fn rank(&self, candidates: &mut [ClusterNode], _requirements: &ResourceRequirements) {
+        candidates.sort_by_key(|node| {
+            (
+                node.allocations.len(),
+                Reverse(node.free_capacity().memory_mb),
+            )
+        });
+    }
+}
+
+/// Fills up partially used GPU nodes before touching idle ones, so that
+/// whole nodes remain free for jobs that need all of their GPUs. Jobs without
+/// GPUs go to nodes without free GPUs first.
+pub struct GpuPacking;
+
+impl SchedulingPolicy for GpuPacking {
+    fn rank(&self, candidates: &mut [ClusterNode], _requirements: &ResourceRequirements) {
+        candidates.sort_by_key(|node| {
+            let free = node.free_capacity();
+            (free.gpu_count, free.memory_mb)
+        });
+    }
+}
+
+impl SchedulingPolicyKind {
+    /// The policy implementing this strategy.
+    pub fn policy(&self) -> &'static dyn SchedulingPolicy {
+        match self {
+            Self::BestFit => &BestFit,
+            Self::Spread => &Spread,
+            Self::GpuPacking => &GpuPacking,
+        }
+    }
+}
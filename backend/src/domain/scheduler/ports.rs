@@ -1 +1,36 @@
-// TODO: Define the `SchedulerPlugin` trait here with `find_suitable_node`, 'deallocate_job' and `allocate_job` methods.
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use super::models::SchedulerLease;
+use crate::domain::{cluster::models::ClusterNode, training_job::models::ResourceRequirements};
+
+/// Decides which nodes a job is placed on.
+///
+/// The scheduler only hands a policy nodes that can run the job and have room
+/// for it right now, so a policy is purely a matter of preference.
+pub trait SchedulingPolicy: Send + Sync {
+    /// Orders `candidates` from most to least preferred. The job is placed on
+    /// the first `node_count` nodes.
+    fn rank(&self, candidates: &mut [ClusterNode], requirements: &ResourceRequirements);
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SchedulerLeaseRepositoryError {
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait SchedulerLeaseRepository: Send + Sync {
+    /// Takes the lease for `holder_id` if it is free or has expired, or renews
+    /// it if `holder_id` already holds it. Returns whether `holder_id` holds
+    /// the lease afterwards.
+    async fn try_acquire(
+        &self,
+        holder_id: &str,
+        ttl: Duration,
+    ) -> Result<bool, SchedulerLeaseRepositoryError>;
+    async fn get_lease(&self) -> Result<Option<SchedulerLease>, SchedulerLeaseRepositoryError>;
+}
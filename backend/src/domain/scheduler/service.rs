@@ -1,16 +1,40 @@
-use std::sync::Arc;
+use std::{
+    collections::{hash_map::Entry, HashMap, HashSet},
+    sync::Arc,
+};
 
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 use crate::{
+    config::{LogsConfig, MetricsConfig, SchedulerConfig},
     domain::{
-        cluster::ports::ClusterRepository, queue::ports::QueueRepository,
-        training_job::ports::TrainingJobRepository,
+        cluster::{
+            models::{NodeCapacity, NodeId, NodeSchedulability},
+            ports::ClusterRepository,
+        },
+        queue::{
+            models::{Queue, QueueId},
+            ports::QueueRepository,
+        },
+        scheduler::{
+            leader::LeaderElection,
+            models::{ClusterOutcome, ClusterReport, SchedulingOutcome, SchedulingReport},
+            notifier::SchedulerNotifier,
+        },
+        training_job::{
+            models::{
+                ArrayId, AttemptOutcome, JobAllocation, JobAttempt, JobEventKind, JobId,
+                RetryPolicy, TrainingJob, TrainingJobStatus,
+            },
+            ports::TrainingJobRepository,
+            service::record_event,
+        },
     },
-    outbound::scheduler::agent_adapter::{AgentSchedulerAdapter, AgentSchedulerError},
+    outbound::scheduler::agent_adapter::{node_fits, AgentSchedulerAdapter, AgentSchedulerError},
 };
 use chrono::Utc;
 use thiserror::Error;
+use tokio::time::MissedTickBehavior;
 
 use crate::domain::{
     cluster::ports::ClusterRepositoryError, queue::ports::QueueRepositoryError,
@@ -36,20 +60,112 @@ pub struct SchedulerService {
     queue_repo: Arc<dyn QueueRepository>,
     cluster_repo: Arc<dyn ClusterRepository>,
     agent_adapter: Arc<AgentSchedulerAdapter>,
+    leader_election: Arc<dyn LeaderElection>,
+    config: SchedulerConfig,
+    logs_config: LogsConfig,
+    metrics_config: MetricsConfig,
 }
 
 impl SchedulerService {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         job_repo: Arc<dyn TrainingJobRepository>,
         queue_repo: Arc<dyn QueueRepository>,
         cluster_repo: Arc<dyn ClusterRepository>,
         agent_adapter: Arc<AgentSchedulerAdapter>,
+        leader_election: Arc<dyn LeaderElection>,
+        config: SchedulerConfig,
+        logs_config: LogsConfig,
+        metrics_config: MetricsConfig,
     ) -> Self {
         Self {
             job_repo,
             queue_repo,
             cluster_repo,
             agent_adapter,
+            leader_election,
+            config,
+            logs_config,
+            metrics_config,
+        }
+    }
+
+    /// Runs scheduler cycles forever. A cycle starts whenever `notifier` is
+    /// notified, and at least every `interval_secs` as a safety net for
+    /// anything that doesn't notify, such as nodes going silent.
+    ///
+    /// Only the replica holding the scheduler lease runs cycles. Every replica
+    /// keeps trying to acquire the lease, so another one takes over when the
+    /// leader stops renewing it.
+    pub async fn run(&self, notifier: SchedulerNotifier) {
+        let mut interval =
+            tokio::time::interval(std::time::Duration::from_secs(self.config.interval_secs));
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut renewal = tokio::time::interval(self.leader_election.renewal_interval());
+        renewal.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut is_leader = false;
+
+        loop {
+            let renewal_only = tokio::select! {
+                _ = interval.tick() => false,
+                _ = notifier.notified() => {
+                    // Push the periodic cycle back, it would find nothing new.
+                    interval.reset();
+                    false
+                }
+                _ = renewal.tick() => true,
+            };
+
+            let was_leader = is_leader;
+            is_leader = match self.leader_election.try_acquire_leadership().await {
+                Ok(is_leader) => is_leader,
+                Err(e) => {
+                    error!("Failed to acquire the scheduler lease: {}", e);
+                    false
+                }
+            };
+            match (was_leader, is_leader) {
+                (false, true) => info!("Acquired the scheduler lease, this replica is the leader"),
+                (true, false) => warn!("Lost the scheduler lease, another replica is the leader"),
+                _ => {}
+            }
+
+            // A replica that just became the leader catches up right away.
+            if !is_leader || (renewal_only && was_leader) {
+                continue;
+            }
+            if let Err(e) = self.run_cycle_as_leader().await {
+                error!("Scheduler cycle failed: {}", e);
+            }
+        }
+    }
+
+    /// Runs a cycle while renewing the lease alongside it, so that a long
+    /// cycle doesn't let the lease expire. The cycle is abandoned as soon as
+    /// the lease can't be renewed, since another replica may be scheduling
+    /// by then. Whatever it was doing is left to the next leader's cleanup.
+    async fn run_cycle_as_leader(&self) -> Result<(), SchedulerServiceError> {
+        let keep_lease = async {
+            let mut renewal = tokio::time::interval(self.leader_election.renewal_interval());
+            renewal.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            // The lease was just renewed.
+            renewal.tick().await;
+            loop {
+                renewal.tick().await;
+                match self.leader_election.try_acquire_leadership().await {
+                    Ok(true) => {}
+                    Ok(false) => return "another replica holds it".to_string(),
+                    Err(e) => return e.to_string(),
+                }
+            }
+        };
+
+        tokio::select! {
+            result = self.run_cycle() => result,
+            reason = keep_lease => {
+                warn!("Abandoning the scheduler cycle, the lease couldn't be renewed: {}", reason);
+                Ok(())
+            }
         }
     }
 
@@ -58,23 +174,24 @@ impl SchedulerService {
         let nodes = self.cluster_repo.list_all_nodes().await?;
         for node in nodes {
             let since_heartbeat = Utc::now() - node.heartbeat_timestamp;
-            if since_heartbeat > chrono::Duration::seconds(90) {
+            if since_heartbeat.num_seconds()
+                > i64::try_from(self.config.dead_node_threshold_secs).unwrap_or(i64::MAX)
+            {
                 info!("Found dead node {}. Cleaning up.", node.id);
 
-                if let Some(job_id) = node.assigned_job_id {
-                    info!(
-                        "Re-queueing assigned job {} from dead node {}",
-                        job_id, node.id
-                    );
-                    self.job_repo.reset_job_status(&job_id).await?;
-                }
+                let mut job_ids: Vec<JobId> = node.assigned_job_ids().collect();
+                job_ids.extend(node.reported_job_ids.iter().copied());
+                job_ids.sort_by_key(|job_id| job_id.into_inner());
+                job_ids.dedup();
 
-                if let Some(job_id) = node.reported_job_id {
-                    info!(
-                        "Re-queueing reported job {} from dead node {}",
-                        job_id, node.id
-                    );
-                    self.job_repo.reset_job_status(&job_id).await?;
+                for job_id in job_ids {
+                    let job = self.job_repo.get_training_job_by_id(&job_id).await?;
+                    if job.status.is_terminal() {
+                        continue;
+                    }
+                    info!("Job {} lost its node {}", job_id, node.id);
+                    let reason = format!("Node {} stopped sending heartbeats", node.id);
+                    self.handle_lost_job(&job, &reason).await?;
                 }
 
                 self.cluster_repo.delete_cluster_node(&node.id).await?;
@@ -83,6 +200,67 @@ impl SchedulerService {
         Ok(())
     }
 
+    /// Stops jobs that ran past their maximum runtime. Agents stop such jobs
+    /// themselves, so the scheduler only steps in once the agent had as long
+    /// to report it as a dead node has to send a heartbeat.
+    async fn cleanup_timed_out_jobs(&self) -> Result<(), SchedulerServiceError> {
+        info!("Running timed out job cleanup...");
+        let grace = chrono::Duration::seconds(
+            i64::try_from(self.config.dead_node_threshold_secs).unwrap_or(i64::MAX),
+        );
+        let jobs = self
+            .job_repo
+            .get_jobs_timed_out_before(Utc::now() - grace)
+            .await?;
+        for job in jobs {
+            info!(
+                "Job {} ran past its maximum runtime of {:?}s. Timing it out.",
+                job.id, job.max_runtime_secs
+            );
+            self.cluster_repo.release_job_nodes(&job.id).await?;
+            self.job_repo
+                .update_status(&job.id, TrainingJobStatus::TimedOut)
+                .await?;
+            self.job_repo
+                .finish_attempt(&job.id, AttemptOutcome::TimedOut, None)
+                .await?;
+            record_event(
+                &*self.job_repo,
+                &[job.id],
+                JobEventKind::StatusChanged {
+                    status: TrainingJobStatus::TimedOut,
+                    reason: Some(
+                        "Ran past its maximum runtime without its agent reporting it".to_string(),
+                    ),
+                },
+            )
+            .await;
+        }
+        Ok(())
+    }
+
+    /// Deletes log output older than the configured retention.
+    async fn cleanup_expired_logs(&self) -> Result<(), SchedulerServiceError> {
+        info!("Running expired log cleanup...");
+        let cutoff = Utc::now() - chrono::Duration::days(self.logs_config.retention_days);
+        let deleted = self.job_repo.delete_logs_before(cutoff).await?;
+        if deleted > 0 {
+            info!("Deleted {} expired log chunk(s)", deleted);
+        }
+        Ok(())
+    }
+
+    /// Deletes resource usage samples older than the configured retention.
+    async fn cleanup_expired_metrics(&self) -> Result<(), SchedulerServiceError> {
+        info!("Running expired metrics cleanup...");
+        let cutoff = Utc::now() - chrono::Duration::days(self.metrics_config.retention_days);
+        let deleted = self.job_repo.delete_metrics_before(cutoff).await?;
+        if deleted > 0 {
+            info!("Deleted {} expired metric sample(s)", deleted);
+        }
+        Ok(())
+    }
+
     async fn cleanup_stale_starting_jobs(&self) -> Result<(), SchedulerServiceError> {
         info!("Running stale job cleanup...");
         let jobs = self
@@ -91,8 +269,8 @@ impl SchedulerService {
             .await?;
 
         for job in jobs {
-            let mut requeue = false;
-            let mut cancel = false;
+            let mut requeue = None;
+            let mut cancel = None;
 
             if let Some(node_id) = job.node_id {
                 if self
@@ -105,7 +283,7 @@ impl SchedulerService {
                         "Found stale job {} assigned to non-existent node {}. Re-queueing.",
                         job.id, node_id
                     );
-                    requeue = true;
+                    requeue = Some(format!("Node {} no longer exists", node_id));
                 }
             }
 
@@ -115,19 +293,31 @@ impl SchedulerService {
                         "Found stale job {} assigned to non-existent queue {}. Cancelling.",
                         job.id, queue_id
                     );
-                    cancel = true;
+                    cancel = Some(format!("Queue {} no longer exists", queue_id));
                 }
             }
 
-            if cancel {
+            if let Some(reason) = cancel {
                 self.job_repo
                     .update_status(
                         &job.id,
                         super::super::training_job::models::TrainingJobStatus::Cancelled,
                     )
                     .await?;
-            } else if requeue {
-                self.job_repo.reset_job_status(&job.id).await?;
+                self.job_repo
+                    .finish_attempt(&job.id, AttemptOutcome::Cancelled, None)
+                    .await?;
+                record_event(
+                    &*self.job_repo,
+                    &[job.id],
+                    JobEventKind::StatusChanged {
+                        status: TrainingJobStatus::Cancelled,
+                        reason: Some(reason),
+                    },
+                )
+                .await;
+            } else if let Some(reason) = requeue {
+                self.handle_lost_job(&job, &reason).await?;
             }
         }
         Ok(())
@@ -137,24 +327,45 @@ impl SchedulerService {
         info!("Running preempted job cleanup...");
         let nodes = self.cluster_repo.list_all_nodes().await?;
         for node in nodes {
-            if node.assigned_job_id.is_none() {
-                if let Some(reported_job_id) = node.reported_job_id {
-                    let job = self
-                        .job_repo
-                        .get_training_job_by_id(&reported_job_id)
+            let unassigned = node
+                .reported_job_ids
+                .iter()
+                .filter(|job_id| !node.assigned_job_ids().any(|assigned| assigned == **job_id));
+            for reported_job_id in unassigned {
+                let job = self
+                    .job_repo
+                    .get_training_job_by_id(reported_job_id)
+                    .await?;
+                // The job has already been re-queued, or it was moved to
+                // other nodes, e.g. after being preempted.
+                if job.status == super::super::training_job::models::TrainingJobStatus::Queued
+                    || !self
+                        .cluster_repo
+                        .list_job_allocations(&job.id)
+                        .await?
+                        .is_empty()
+                {
+                    continue;
+                }
+
+                if !job.status.is_terminal() {
+                    info!(
+                        "Found preempted job {} on node {}. Re-queueing.",
+                        job.id, node.id
+                    );
+                    self.cluster_repo.release_job_nodes(&job.id).await?;
+                    self.job_repo.reset_job_status(&job.id).await?;
+                    self.job_repo
+                        .finish_attempt(&job.id, AttemptOutcome::NodeLost, None)
                         .await?;
-                    if !matches!(
-                        job.status,
-                        super::super::training_job::models::TrainingJobStatus::Succeeded
-                            | super::super::training_job::models::TrainingJobStatus::Failed
-                            | super::super::training_job::models::TrainingJobStatus::Cancelled
-                    ) {
-                        info!(
-                            "Found preempted job {} on node {}. Re-queueing.",
-                            job.id, node.id
-                        );
-                        self.job_repo.reset_job_status(&job.id).await?;
-                    }
+                    record_event(
+                        &*self.job_repo,
+                        &[job.id],
+                        JobEventKind::Requeued {
+                            reason: format!("Node {} no longer runs the job", node.id),
+                        },
+                    )
+                    .await;
                 }
             }
         }
@@ -176,14 +387,401 @@ impl SchedulerService {
                         super::super::training_job::models::TrainingJobStatus::Cancelled,
                     )
                     .await?;
+                record_event(
+                    &*self.job_repo,
+                    &[job.id],
+                    JobEventKind::StatusChanged {
+                        status: TrainingJobStatus::Cancelled,
+                        reason: Some("The job has no queue".to_string()),
+                    },
+                )
+                .await;
+            }
+        }
+        Ok(())
+    }
+
+    /// The retry policy of the job, falling back to the one of its queue.
+    async fn retry_policy_for(
+        &self,
+        job: &TrainingJob,
+    ) -> Result<Option<RetryPolicy>, SchedulerServiceError> {
+        if job.retry_policy.is_some() {
+            return Ok(job.retry_policy.clone());
+        }
+        let Some(queue_id) = &job.queue_id else {
+            return Ok(None);
+        };
+        match self.queue_repo.get_queue_by_id(queue_id).await {
+            Ok(queue) => Ok(queue.retry_policy),
+            Err(QueueRepositoryError::NotFound(_)) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Queues the job again after the attempt if its retry policy allows it,
+    /// giving `reason` for the requeue. Returns whether the job was requeued.
+    async fn retry(
+        &self,
+        job: &TrainingJob,
+        attempt: &JobAttempt,
+        policy: &RetryPolicy,
+        reason: &str,
+    ) -> Result<bool, SchedulerServiceError> {
+        if !policy.should_retry(attempt) {
+            return Ok(false);
+        }
+
+        let not_before =
+            attempt.finished_at.unwrap_or_else(Utc::now) + policy.backoff(attempt.attempt_number);
+        info!(
+            "Retrying job {} after attempt {}, not before {}",
+            job.id, attempt.attempt_number, not_before
+        );
+        self.job_repo
+            .requeue_for_retry(&job.id, not_before, attempt.node_id)
+            .await?;
+        record_event(
+            &*self.job_repo,
+            &[job.id],
+            JobEventKind::Requeued {
+                reason: format!("{reason}, retrying it no earlier than {not_before}"),
+            },
+        )
+        .await;
+        Ok(true)
+    }
+
+    /// Releases a job whose node went away for `reason`. Without a retry
+    /// policy the job is requeued right away, otherwise the policy decides
+    /// whether it is retried or fails.
+    async fn handle_lost_job(
+        &self,
+        job: &TrainingJob,
+        reason: &str,
+    ) -> Result<(), SchedulerServiceError> {
+        self.cluster_repo.release_job_nodes(&job.id).await?;
+        let attempt = self
+            .job_repo
+            .finish_attempt(&job.id, AttemptOutcome::NodeLost, None)
+            .await?;
+
+        match (self.retry_policy_for(job).await?, attempt) {
+            (Some(policy), Some(attempt)) => {
+                if !self.retry(job, &attempt, &policy, reason).await? {
+                    info!(
+                        "Job {} lost its node and has no attempts left. Failing it.",
+                        job.id
+                    );
+                    self.job_repo
+                        .update_status(&job.id, TrainingJobStatus::Failed)
+                        .await?;
+                    record_event(
+                        &*self.job_repo,
+                        &[job.id],
+                        JobEventKind::StatusChanged {
+                            status: TrainingJobStatus::Failed,
+                            reason: Some(format!("{reason}, and the job has no attempts left")),
+                        },
+                    )
+                    .await;
+                }
+            }
+            _ => {
+                info!("Re-queueing job {}", job.id);
+                self.job_repo.reset_job_status(&job.id).await?;
+                record_event(
+                    &*self.job_repo,
+                    &[job.id],
+                    JobEventKind::Requeued {
+                        reason: reason.to_string(),
+                    },
+                )
+                .await;
+            }
+        }
+        Ok(())
+    }
+
+    /// Requeues failed jobs whose retry policy allows another attempt.
+    async fn retry_failed_jobs(&self) -> Result<(), SchedulerServiceError> {
+        info!("Running failed job retries...");
+        for attempt in self.job_repo.get_failed_attempts_to_evaluate().await? {
+            let job = self
+                .job_repo
+                .get_training_job_by_id(&attempt.job_id)
+                .await?;
+            if let Some(policy) = self.retry_policy_for(&job).await? {
+                let reason = match attempt.exit_code {
+                    Some(exit_code) => format!(
+                        "Attempt {} failed with exit code {}",
+                        attempt.attempt_number, exit_code
+                    ),
+                    None => format!("Attempt {} failed", attempt.attempt_number),
+                };
+                self.retry(&job, &attempt, &policy, &reason).await?;
+            }
+            self.job_repo.mark_retry_evaluated(&attempt).await?;
+        }
+        Ok(())
+    }
+
+    /// Cancels queued jobs that depend on a job which ended in a way that
+    /// doesn't let them run, e.g. because it failed. Cancelling a job in turn
+    /// cancels the jobs depending on it. Failed jobs that may still be retried
+    /// haven't failed for good, so their dependents are left alone.
+    pub(super) async fn cleanup_failed_dependencies(&self) -> Result<(), SchedulerServiceError> {
+        info!("Running failed dependency cleanup...");
+        let awaiting_retry: HashSet<JobId> = self
+            .job_repo
+            .get_failed_attempts_to_evaluate()
+            .await?
+            .into_iter()
+            .map(|attempt| attempt.job_id)
+            .collect();
+        let mut pending: Vec<TrainingJob> = self
+            .job_repo
+            .get_jobs_by_status(TrainingJobStatus::Queued)
+            .await?
+            .into_iter()
+            .filter(|job| !job.dependencies.is_empty())
+            .collect();
+
+        while let Some(job) = pending.pop() {
+            if job.status != TrainingJobStatus::Queued {
+                continue;
+            }
+
+            let mut failed = Vec::new();
+            for dependency in &job.dependencies {
+                let upstream = self
+                    .job_repo
+                    .get_training_job_by_id(&dependency.job_id)
+                    .await?;
+                if dependency.condition.is_violated_by(&upstream.status)
+                    && !awaiting_retry.contains(&upstream.id)
+                {
+                    failed.push(upstream.id);
+                }
+            }
+            if failed.is_empty() {
+                continue;
             }
+
+            info!(
+                "Cancelling job {} because its dependencies {:?} did not succeed",
+                job.id, failed
+            );
+            self.job_repo
+                .update_status(&job.id, TrainingJobStatus::Cancelled)
+                .await?;
+            let upstream: Vec<String> = failed.iter().map(ToString::to_string).collect();
+            record_event(
+                &*self.job_repo,
+                &[job.id],
+                JobEventKind::StatusChanged {
+                    status: TrainingJobStatus::Cancelled,
+                    reason: Some(format!(
+                        "Its dependencies {} did not succeed",
+                        upstream.join(", ")
+                    )),
+                },
+            )
+            .await;
+            let report = SchedulingReport {
+                job_id: job.id,
+                attempted_at: Utc::now(),
+                outcome: SchedulingOutcome::DependencyFailed,
+                clusters: Vec::new(),
+                preempted_jobs: false,
+                blocking_dependencies: failed,
+            };
+            if let Err(e) = self.job_repo.record_scheduling_report(&report).await {
+                error!(
+                    "Error recording scheduling report for job {}: {}",
+                    job.id, e
+                );
+            }
+
+            pending.extend(self.job_repo.get_dependent_jobs(&job.id).await?);
         }
         Ok(())
     }
 
+    /// The dependencies of `job` that have not finished yet.
+    async fn unfinished_dependencies(
+        &self,
+        job: &TrainingJob,
+    ) -> Result<Vec<JobId>, SchedulerServiceError> {
+        let mut unfinished = Vec::new();
+        for dependency in &job.dependencies {
+            let upstream = self
+                .job_repo
+                .get_training_job_by_id(&dependency.job_id)
+                .await?;
+            if !dependency.condition.is_satisfied_by(&upstream.status) {
+                unfinished.push(upstream.id);
+            }
+        }
+        Ok(unfinished)
+    }
+
+    /// How many more jobs of the array may be started right now.
+    async fn array_slots(&self, array_id: &ArrayId) -> Result<i64, SchedulerServiceError> {
+        let array = self.job_repo.get_job_array(array_id).await?;
+        let Some(max_concurrent) = array.max_concurrent else {
+            return Ok(i64::MAX);
+        };
+        let active = self.job_repo.count_active_array_jobs(array_id).await?;
+        Ok(i64::from(max_concurrent) - active)
+    }
+
+    /// Records on the job that no cluster in its queue can run it, unless the
+    /// previous cycle already found as much.
+    pub(super) async fn report_unschedulable(&self, job: &TrainingJob, queue: &Queue) {
+        match self.job_repo.get_scheduling_report(&job.id).await {
+            Ok(Some(report)) if report.outcome == SchedulingOutcome::Unsatisfiable => return,
+            Ok(_) => {}
+            Err(e) => {
+                error!(
+                    "Error reading the scheduling report of job {}: {}",
+                    job.id, e
+                );
+                return;
+            }
+        }
+        record_event(
+            &*self.job_repo,
+            &[job.id],
+            JobEventKind::Unschedulable {
+                reason: format!(
+                    "No cluster in queue '{}' has a node that can satisfy the job's requirements",
+                    queue.name
+                ),
+            },
+        )
+        .await;
+    }
+
+    /// Frees up capacity for `job` by preempting jobs from preemptible queues
+    /// with a lower priority than `queue`. Queues are sorted in ascending order,
+    /// so a larger `priority` value means a lower priority.
+    ///
+    /// The job itself is placed on the freed capacity in a later cycle, once
+    /// the agents have stopped the preempted jobs. Returns whether any job was
+    /// preempted.
+    pub(super) async fn preempt_for(
+        &self,
+        job: &TrainingJob,
+        queue: &Queue,
+        queues: &HashMap<QueueId, Queue>,
+    ) -> Result<bool, SchedulerServiceError> {
+        let node_count = usize::try_from(job.node_count).unwrap_or(0).max(1);
+
+        for cluster_id in &queue.cluster_targets {
+            let mut nodes = self.cluster_repo.list_cluster_nodes(cluster_id).await?;
+            // Freeing up a node that takes no new jobs doesn't help.
+            nodes.retain(|node| {
+                node.schedulability == NodeSchedulability::Schedulable && node_fits(node, job)
+            });
+
+            // Capacity held by jobs that are no longer allocated frees up once
+            // their agents stop them, without preempting anything.
+            let fits = |free: &HashMap<NodeId, NodeCapacity>| {
+                free.values()
+                    .filter(|capacity| capacity.can_hold(&job.resource_requirements))
+                    .count()
+            };
+            let mut free: HashMap<NodeId, NodeCapacity> = nodes
+                .iter()
+                .map(|node| (node.id, node.free_capacity()))
+                .collect();
+            if fits(&free) >= node_count {
+                return Ok(false);
+            }
+
+            let mut allocations_by_job: HashMap<JobId, Vec<JobAllocation>> = HashMap::new();
+            for allocation in nodes.iter().flat_map(|node| node.allocations.iter()) {
+                allocations_by_job
+                    .entry(allocation.job_id)
+                    .or_default()
+                    .push(allocation.clone());
+            }
+
+            let mut candidates = Vec::new();
+            for (victim_id, allocations) in allocations_by_job {
+                let victim = self.job_repo.get_training_job_by_id(&victim_id).await?;
+                let Some(victim_queue) = victim.queue_id.and_then(|id| queues.get(&id)) else {
+                    continue;
+                };
+                if victim_queue.preemptible
+                    && victim_queue.priority > queue.priority
+                    && !victim.status.is_terminal()
+                {
+                    candidates.push((victim_queue.priority, victim, allocations));
+                }
+            }
+
+            // Evict jobs from the lowest priority queues first, and the most
+            // recently created among those since they have the least to lose.
+            candidates.sort_by(|a, b| {
+                b.0.cmp(&a.0)
+                    .then_with(|| b.1.created_at.cmp(&a.1.created_at))
+            });
+
+            let mut victims = Vec::new();
+            for (_, victim, allocations) in candidates {
+                if fits(&free) >= node_count {
+                    break;
+                }
+                for allocation in &allocations {
+                    if let Some(capacity) = free.get_mut(&allocation.node_id) {
+                        capacity.release(allocation);
+                    }
+                }
+                victims.push(victim);
+            }
+
+            if fits(&free) < node_count {
+                continue;
+            }
+
+            let reason = format!(
+                "Preempted by job {} from higher priority queue '{}'",
+                job.id, queue.name
+            );
+            for victim in victims {
+                info!(
+                    "Preempting job {} to make room for job {}",
+                    victim.id, job.id
+                );
+                if !self.job_repo.preempt_job(&victim.id, &reason).await? {
+                    // It finished in the meantime, which frees its nodes too.
+                    continue;
+                }
+                record_event(
+                    &*self.job_repo,
+                    &[victim.id],
+                    JobEventKind::Preempted {
+                        reason: reason.clone(),
+                    },
+                )
+                .await;
+            }
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+
     pub async fn run_cycle(&self) -> Result<(), SchedulerServiceError> {
         info!("Starting scheduler cycle");
 
+        // Timed out jobs go first, so that a job that hung on a node that then
+        // died ends up timed out rather than retried.
+        if let Err(e) = self.cleanup_timed_out_jobs().await {
+            error!("Error during timed out job cleanup: {}", e);
+        }
         if let Err(e) = self.cleanup_dead_nodes().await {
             error!("Error during dead node cleanup: {}", e);
         }
@@ -196,11 +794,30 @@ impl SchedulerService {
         if let Err(e) = self.cleanup_orphaned_queued_jobs().await {
             error!("Error during orphaned queued job cleanup: {}", e);
         }
+        if let Err(e) = self.retry_failed_jobs().await {
+            error!("Error while retrying failed jobs: {}", e);
+        }
+        if let Err(e) = self.cleanup_failed_dependencies().await {
+            error!("Error during failed dependency cleanup: {}", e);
+        }
+        if let Err(e) = self.cleanup_expired_logs().await {
+            error!("Error during expired log cleanup: {}", e);
+        }
+        if let Err(e) = self.cleanup_expired_metrics().await {
+            error!("Error during expired metrics cleanup: {}", e);
+        }
 
         let queues = self.queue_repo.get_all_queues_sorted().await?;
+        let queues_by_id: HashMap<QueueId, Queue> = queues
+            .iter()
+            .map(|queue| (queue.id, queue.clone()))
+            .collect();
 
         info!("Processing {} queues", queues.len());
 
+        // The jobs each array may still start in this cycle.
+        let mut array_slots: HashMap<ArrayId, i64> = HashMap::new();
+
         for queue in queues {
             let queued_jobs = self.job_repo.get_queued_jobs_for_queue(&queue.id).await?;
 
@@ -216,42 +833,183 @@ impl SchedulerService {
 
             for job in queued_jobs {
                 info!("Processing job {}", job.id);
+
+                let unfinished = self.unfinished_dependencies(&job).await?;
+                if !unfinished.is_empty() {
+                    info!("Job {} waits for its dependencies {:?}", job.id, unfinished);
+                    let report = SchedulingReport {
+                        job_id: job.id,
+                        attempted_at: Utc::now(),
+                        outcome: SchedulingOutcome::WaitingForDependencies,
+                        clusters: Vec::new(),
+                        preempted_jobs: false,
+                        blocking_dependencies: unfinished,
+                    };
+                    if let Err(e) = self.job_repo.record_scheduling_report(&report).await {
+                        error!(
+                            "Error recording scheduling report for job {}: {}",
+                            job.id, e
+                        );
+                    }
+                    continue;
+                }
+
+                if let Some(array_id) = job.array_id {
+                    let slots = match array_slots.entry(array_id) {
+                        Entry::Occupied(entry) => *entry.get(),
+                        Entry::Vacant(entry) => *entry.insert(self.array_slots(&array_id).await?),
+                    };
+                    if slots <= 0 {
+                        info!("Job {} waits for a free slot in array {}", job.id, array_id);
+                        let report = SchedulingReport {
+                            job_id: job.id,
+                            attempted_at: Utc::now(),
+                            outcome: SchedulingOutcome::WaitingForArraySlot,
+                            clusters: Vec::new(),
+                            preempted_jobs: false,
+                            blocking_dependencies: Vec::new(),
+                        };
+                        if let Err(e) = self.job_repo.record_scheduling_report(&report).await {
+                            error!(
+                                "Error recording scheduling report for job {}: {}",
+                                job.id, e
+                            );
+                        }
+                        continue;
+                    }
+                }
+
                 let mut scheduled = false;
+                let mut satisfiable = false;
+                let mut conflicted = false;
+                let mut clusters = Vec::new();
 
                 for cluster_id in &queue.cluster_targets {
-                    match self
+                    let report = match self
                         .agent_adapter
-                        .find_and_allocate_job(&job.id, cluster_id, &job.resource_requirements)
+                        .find_and_allocate_job(&job, cluster_id, queue.scheduling_policy.policy())
                         .await
                     {
-                        Ok(Some(node_id)) => {
-                            info!("Successfully allocated job {} to node {}", job.id, node_id);
-                            self.job_repo.mark_as_starting(&job.id, &node_id).await?;
+                        Ok(report) => report,
+                        Err(e) => {
+                            // This is an unexpected error during the node search.
+                            error!(
+                                "Error finding suitable node for job {} on cluster {}: {}",
+                                job.id, cluster_id, e
+                            );
+                            ClusterReport {
+                                cluster_id: *cluster_id,
+                                outcome: ClusterOutcome::Error,
+                                rejected_nodes: Vec::new(),
+                            }
+                        }
+                    };
+
+                    match &report.outcome {
+                        ClusterOutcome::Allocated { node_ids } => {
+                            // The rank 0 node is recorded as the job's primary node.
+                            let node_id = node_ids[0];
+                            info!(
+                                "Successfully allocated job {} to {} node(s), coordinator {}",
+                                job.id,
+                                node_ids.len(),
+                                node_id
+                            );
+                            record_event(
+                                &*self.job_repo,
+                                &[job.id],
+                                JobEventKind::Assigned {
+                                    node_ids: node_ids.clone(),
+                                },
+                            )
+                            .await;
+                            if let Some(slots) =
+                                job.array_id.and_then(|id| array_slots.get_mut(&id))
+                            {
+                                *slots -= 1;
+                            }
                             scheduled = true;
-                            break; // Break from cluster loop, move to next job
                         }
-                        Ok(None) => {
-                            // This is the expected case when no node is found, just info log.
+                        ClusterOutcome::NoCapacity => {
+                            // This is the expected case when no node is free, just info log.
                             info!(
                                 "No suitable node found for job {} on cluster {}",
                                 job.id, cluster_id
                             );
+                            satisfiable = true;
                         }
-                        Err(e) => {
-                            // This is an unexpected error during the node search.
-                            error!(
-                                "Error finding suitable node for job {} on cluster {}: {}",
-                                job.id, cluster_id, e
+                        ClusterOutcome::Unsatisfiable | ClusterOutcome::NoNodes => {
+                            info!(
+                                "No node on cluster {} can satisfy the requirements of job {}",
+                                cluster_id, job.id
+                            );
+                        }
+                        ClusterOutcome::Conflict => {
+                            info!(
+                                "Nodes for job {} on cluster {} changed while allocating",
+                                job.id, cluster_id
                             );
+                            satisfiable = true;
+                            conflicted = true;
                         }
+                        ClusterOutcome::Error => satisfiable = true,
+                    }
+
+                    clusters.push(report);
+                    if scheduled {
+                        break; // Break from cluster loop, move to next job
                     }
                 }
 
-                if !scheduled {
+                let mut preempted_jobs = false;
+                let outcome = if scheduled {
+                    SchedulingOutcome::Scheduled
+                } else if !satisfiable {
+                    // Preempting other jobs cannot help, so don't try.
+                    warn!(
+                        "Job {} cannot be satisfied by any cluster in queue '{}' and will wait until matching nodes join",
+                        job.id, queue.name
+                    );
+                    self.report_unschedulable(&job, &queue).await;
+                    SchedulingOutcome::Unsatisfiable
+                } else if conflicted {
+                    // The job may have been cancelled or the nodes taken in
+                    // the meantime, so look at it again next cycle before
+                    // preempting anything for it.
+                    SchedulingOutcome::Pending
+                } else {
                     info!(
                         "Could not schedule job {} on any cluster in queue '{}'",
                         job.id, queue.name
                     );
+
+                    match self.preempt_for(&job, &queue, &queues_by_id).await {
+                        Ok(true) => {
+                            info!(
+                                "Preempted lower priority jobs to make room for job {}",
+                                job.id
+                            );
+                            preempted_jobs = true;
+                        }
+                        Ok(false) => {}
+                        Err(e) => error!("Error preempting jobs for job {}: {}", job.id, e),
+                    }
+                    SchedulingOutcome::Pending
+                };
+
+                let report = SchedulingReport {
+                    job_id: job.id,
+                    attempted_at: Utc::now(),
+                    outcome,
+                    clusters,
+                    preempted_jobs,
+                    blocking_dependencies: Vec::new(),
+                };
+                if let Err(e) = self.job_repo.record_scheduling_report(&report).await {
+                    error!(
+                        "Error recording scheduling report for job {}: {}",
+                        job.id, e
+                    );
                 }
             }
         }
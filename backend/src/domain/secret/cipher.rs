@@ -0,0 +1,60 @@
+use anyhow::anyhow;
+use aws_lc_rs::{
+    aead::{Aad, Nonce, RandomizedNonceKey, AES_256_GCM},
+    hkdf::{Salt, HKDF_SHA256},
+};
+
+/// Binds the derived key to its purpose, so the same encryption key can't
+/// yield it for anything else.
+const KEY_CONTEXT: &[u8] = b"lilac secret encryption key";
+
+/// The shortest encryption key accepted, so it can't be easily guessed.
+pub const MIN_KEY_LEN: usize = 32;
+
+/// Encrypts secret values with AES-256-GCM under a key derived with HKDF from
+/// the backend's encryption key. The secret's name is authenticated along with
+/// its value, so a ciphertext can't be passed off as another secret's.
+pub struct SecretCipher {
+    key: RandomizedNonceKey,
+}
+
+impl SecretCipher {
+    pub fn new(encryption_key: &str) -> anyhow::Result<Self> {
+        if encryption_key.len() < MIN_KEY_LEN {
+            return Err(anyhow!(
+                "encryption_key must be at least {MIN_KEY_LEN} characters long"
+            ));
+        }
+        let mut key_bytes = [0u8; 32];
+        Salt::new(HKDF_SHA256, &[])
+            .extract(encryption_key.as_bytes())
+            .expand(&[KEY_CONTEXT], &AES_256_GCM)
+            .and_then(|okm| okm.fill(&mut key_bytes))
+            .expect("HKDF to derive a key of the AES-256 key length");
+        let key = RandomizedNonceKey::new(&AES_256_GCM, &key_bytes)
+            .expect("a 32-byte key to be a valid AES-256 key");
+        Ok(Self { key })
+    }
+
+    /// Encrypts the value of the named secret, returning the ciphertext and
+    /// the nonce it was encrypted with.
+    pub fn encrypt(&self, name: &str, value: &str) -> anyhow::Result<(Vec<u8>, Vec<u8>)> {
+        let mut in_out = value.as_bytes().to_vec();
+        let nonce = self
+            .key
+            .seal_in_place_append_tag(Aad::from(name.as_bytes()), &mut in_out)
+            .map_err(|_| anyhow!("failed to encrypt secret {name}"))?;
+        Ok((in_out, nonce.as_ref().to_vec()))
+    }
+
+    pub fn decrypt(&self, name: &str, ciphertext: &[u8], nonce: &[u8]) -> anyhow::Result<String> {
+        let nonce = Nonce::try_assume_unique_for_key(nonce)
+            .map_err(|_| anyhow!("secret {name} has an invalid nonce"))?;
+        let mut in_out = ciphertext.to_vec();
+        let value = self
+            .key
+            .open_in_place(nonce, Aad::from(name.as_bytes()), &mut in_out)
+            .map_err(|_| anyhow!("failed to decrypt secret {name}"))?;
+        Ok(String::from_utf8(value.to_vec())?)
+    }
+}
@@ -0,0 +1,137 @@
+pub mod cipher;
+pub mod models;
+pub mod ports;
+pub mod service;
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, sync::Arc};
+
+    use chrono::Utc;
+    use secrecy::SecretString;
+
+    use super::{
+        cipher::SecretCipher,
+        models::{validate_secret_name, EncryptedSecret, PutSecretRequest, Secret},
+        ports::{MockSecretRepository, SecretRepositoryError},
+        service::{SecretService, SecretServiceError, SecretServiceImpl},
+    };
+    use crate::domain::user::models::UserId;
+
+    const KEY: &str = "an encryption key of 32 or more characters";
+
+    #[test]
+    fn test_cipher_round_trip() {
+        let cipher = SecretCipher::new(KEY).unwrap();
+        let (ciphertext, nonce) = cipher.encrypt("wandb", "api-key").unwrap();
+        assert_ne!(ciphertext, b"api-key");
+        assert_eq!(
+            cipher.decrypt("wandb", &ciphertext, &nonce).unwrap(),
+            "api-key"
+        );
+
+        // The value is bound to the secret's name and the backend's key.
+        assert!(cipher.decrypt("hf", &ciphertext, &nonce).is_err());
+        assert!(SecretCipher::new(&"k".repeat(32))
+            .unwrap()
+            .decrypt("wandb", &ciphertext, &nonce)
+            .is_err());
+    }
+
+    #[test]
+    fn test_cipher_rejects_short_keys() {
+        assert!(SecretCipher::new("").is_err());
+        assert!(SecretCipher::new(&"k".repeat(31)).is_err());
+    }
+
+    #[test]
+    fn test_validate_secret_name() {
+        assert!(validate_secret_name("hf-token.prod_2").is_ok());
+        assert!(validate_secret_name("").is_err());
+        assert!(validate_secret_name("hf token").is_err());
+        assert!(validate_secret_name(&"a".repeat(129)).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_put_and_resolve_secrets() {
+        let owner = UserId::generate();
+        let cipher = SecretCipher::new(KEY).unwrap();
+        let (ciphertext, nonce) = cipher.encrypt("wandb", "api-key").unwrap();
+        let created_at = Utc::now();
+        let stored = EncryptedSecret {
+            name: "wandb".to_string(),
+            owner_id: owner,
+            ciphertext,
+            nonce,
+            created_at,
+            updated_at: created_at,
+        };
+
+        let mut mock_repo = MockSecretRepository::new();
+        mock_repo
+            .expect_upsert()
+            .withf(move |secret| {
+                secret.name == "wandb"
+                    && secret.owner_id == owner
+                    && secret.ciphertext != b"api-key"
+            })
+            .returning(|secret| Ok(Secret::from(secret.clone())));
+        mock_repo
+            .expect_get_secret_by_name()
+            .returning(move |name| match name {
+                "wandb" => Ok(stored.clone()),
+                _ => Err(SecretRepositoryError::NotFound(name.to_string())),
+            });
+        let service = SecretServiceImpl::new(Arc::new(mock_repo), Some(cipher));
+
+        let secret = service
+            .put_secret(PutSecretRequest {
+                name: "wandb".to_string(),
+                owner_id: owner,
+                value: SecretString::from("api-key"),
+            })
+            .await
+            .unwrap();
+        assert_eq!(secret.name, "wandb");
+
+        let result = service
+            .put_secret(PutSecretRequest {
+                name: "not valid".to_string(),
+                owner_id: owner,
+                value: SecretString::from("api-key"),
+            })
+            .await;
+        assert!(matches!(result, Err(SecretServiceError::InvalidName(_))));
+
+        let secrets = BTreeMap::from([
+            ("WANDB_API_KEY".to_string(), "wandb".to_string()),
+            ("HF_TOKEN".to_string(), "deleted".to_string()),
+        ]);
+        let env = service.resolve_secrets(&secrets, created_at).await.unwrap();
+        assert_eq!(
+            env,
+            BTreeMap::from([("WANDB_API_KEY".to_string(), "api-key".to_string())])
+        );
+
+        // A secret created after the job took over the name of a deleted one,
+        // and isn't the secret the job was submitted with.
+        let env = service
+            .resolve_secrets(&secrets, created_at - chrono::Duration::seconds(1))
+            .await
+            .unwrap();
+        assert!(env.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_secrets_need_an_encryption_key() {
+        let service = SecretServiceImpl::new(Arc::new(MockSecretRepository::new()), None);
+        let result = service
+            .put_secret(PutSecretRequest {
+                name: "wandb".to_string(),
+                owner_id: UserId::generate(),
+                value: SecretString::from("api-key"),
+            })
+            .await;
+        assert!(matches!(result, Err(SecretServiceError::NotConfigured)));
+    }
+}
@@ -0,0 +1,66 @@
+use chrono::{DateTime, Utc};
+use secrecy::SecretString;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::user::models::UserId;
+
+/// The longest name a secret may have.
+const MAX_NAME_LEN: usize = 128;
+
+/// A named value, such as an API token, that jobs receive as an environment
+/// variable. Only the name is ever returned, the value is only handed to the
+/// agents running jobs that reference it. Names are unique across users, but
+/// only the user who created a secret can see, change or use it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Secret {
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A secret as it is stored, with its value encrypted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EncryptedSecret {
+    pub name: String,
+    pub owner_id: UserId,
+    pub ciphertext: Vec<u8>,
+    pub nonce: Vec<u8>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<EncryptedSecret> for Secret {
+    fn from(value: EncryptedSecret) -> Self {
+        Self {
+            name: value.name,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PutSecretRequest {
+    pub name: String,
+    pub owner_id: UserId,
+    pub value: SecretString,
+}
+
+/// Checks that a secret name is made of letters, digits, `-`, `_` and `.`,
+/// returning what is wrong with it otherwise.
+pub fn validate_secret_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.len() > MAX_NAME_LEN {
+        return Err(format!(
+            "secret names must be between 1 and {MAX_NAME_LEN} characters long"
+        ));
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    {
+        return Err(format!(
+            "'{name}' may only contain letters, digits, '-', '_' and '.'"
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use async_trait::async_trait;
+
+use super::models::{EncryptedSecret, Secret};
+use crate::domain::user::models::UserId;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SecretRepositoryError {
+    #[error("secret {0} not found")]
+    NotFound(String),
+    #[error("secret {0} belongs to another user")]
+    NameTaken(String),
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait SecretRepository: Send + Sync {
+    /// Stores the secret, replacing the value of an existing secret with the
+    /// same name if it has the same owner. Returns the stored secret, which
+    /// keeps its original creation time when replaced.
+    async fn upsert(&self, secret: &EncryptedSecret) -> Result<Secret, SecretRepositoryError>;
+    async fn get_secret_by_name(
+        &self,
+        name: &str,
+    ) -> Result<EncryptedSecret, SecretRepositoryError>;
+    /// The secrets owned by `owner_id`, ordered by name.
+    async fn list_secrets(&self, owner_id: &UserId) -> Result<Vec<Secret>, SecretRepositoryError>;
+    /// Deletes the secret if it is owned by `owner_id`.
+    async fn delete(&self, owner_id: &UserId, name: &str) -> Result<(), SecretRepositoryError>;
+}
@@ -0,0 +1,130 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use secrecy::ExposeSecret;
+use tracing::warn;
+
+use crate::domain::{
+    secret::{
+        cipher::SecretCipher,
+        models::{validate_secret_name, EncryptedSecret, PutSecretRequest, Secret},
+        ports::{SecretRepository, SecretRepositoryError},
+    },
+    user::models::UserId,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum SecretServiceError {
+    #[error("invalid secret name: {0}")]
+    InvalidName(String),
+    #[error("secret {0} not found")]
+    SecretNotFound(String),
+    #[error("secret {0} belongs to another user")]
+    NameTaken(String),
+    #[error("no encryption key is configured")]
+    NotConfigured,
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+impl From<SecretRepositoryError> for SecretServiceError {
+    fn from(error: SecretRepositoryError) -> Self {
+        match error {
+            SecretRepositoryError::NotFound(name) => Self::SecretNotFound(name),
+            SecretRepositoryError::NameTaken(name) => Self::NameTaken(name),
+            SecretRepositoryError::Unknown(error) => Self::Unknown(error),
+        }
+    }
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait SecretService: Send + Sync {
+    /// Creates the secret, or replaces the value of an existing one. Only the
+    /// owner of an existing secret may replace it. Fails if no encryption key
+    /// is configured.
+    async fn put_secret(&self, request: PutSecretRequest) -> Result<Secret, SecretServiceError>;
+    async fn list_secrets(&self, owner_id: &UserId) -> Result<Vec<Secret>, SecretServiceError>;
+    async fn delete_secret(&self, owner_id: &UserId, name: &str) -> Result<(), SecretServiceError>;
+    /// Looks up the values of a job's secrets, keyed by the environment
+    /// variable each is passed in. Secrets deleted since the job was created
+    /// at `created_at` are left out, as are secrets created after it, which
+    /// reuse the name of a deleted one and may belong to someone else.
+    async fn resolve_secrets(
+        &self,
+        secrets: &BTreeMap<String, String>,
+        created_at: DateTime<Utc>,
+    ) -> Result<BTreeMap<String, String>, SecretServiceError>;
+}
+
+pub struct SecretServiceImpl {
+    repository: Arc<dyn SecretRepository>,
+    cipher: Option<SecretCipher>,
+}
+
+impl SecretServiceImpl {
+    pub fn new(repository: Arc<dyn SecretRepository>, cipher: Option<SecretCipher>) -> Self {
+        Self { repository, cipher }
+    }
+
+    fn cipher(&self) -> Result<&SecretCipher, SecretServiceError> {
+        self.cipher
+            .as_ref()
+            .ok_or(SecretServiceError::NotConfigured)
+    }
+}
+
+#[async_trait]
+impl SecretService for SecretServiceImpl {
+    async fn put_secret(&self, request: PutSecretRequest) -> Result<Secret, SecretServiceError> {
+        validate_secret_name(&request.name).map_err(SecretServiceError::InvalidName)?;
+        let (ciphertext, nonce) = self
+            .cipher()?
+            .encrypt(&request.name, request.value.expose_secret())?;
+        let now = chrono::Utc::now();
+        let secret = EncryptedSecret {
+            name: request.name,
+            owner_id: request.owner_id,
+            ciphertext,
+            nonce,
+            created_at: now,
+            updated_at: now,
+        };
+        Ok(self.repository.upsert(&secret).await?)
+    }
+
+    async fn list_secrets(&self, owner_id: &UserId) -> Result<Vec<Secret>, SecretServiceError> {
+        Ok(self.repository.list_secrets(owner_id).await?)
+    }
+
+    async fn delete_secret(&self, owner_id: &UserId, name: &str) -> Result<(), SecretServiceError> {
+        Ok(self.repository.delete(owner_id, name).await?)
+    }
+
+    async fn resolve_secrets(
+        &self,
+        secrets: &BTreeMap<String, String>,
+        created_at: DateTime<Utc>,
+    ) -> Result<BTreeMap<String, String>, SecretServiceError> {
+        let mut env = BTreeMap::new();
+        for (variable, name) in secrets {
+            let secret = match self.repository.get_secret_by_name(name).await {
+                Ok(secret) if secret.created_at <= created_at => secret,
+                Ok(_) | Err(SecretRepositoryError::NotFound(_)) => {
+                    warn!(
+                        "Secret {} no longer exists, leaving {} unset",
+                        name, variable
+                    );
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+            };
+            let value = self
+                .cipher()?
+                .decrypt(&secret.name, &secret.ciphertext, &secret.nonce)?;
+            env.insert(variable.clone(), value);
+        }
+        Ok(env)
+    }
+}
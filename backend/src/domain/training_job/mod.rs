@@ -5,20 +5,70 @@ pub mod service;
 #[cfg(test)]
 mod tests {
     use super::{
-        models::{GetTrainingJobsFilters, TrainingJobStatus},
+        models::{
+            AttemptOutcome, DependencyCondition, GetTrainingJobsFilters, GpuRequirement, GpuSample,
+            JobAllocation, JobArrayCounts, JobAttempt, JobDefinition, JobErrorKind, JobEventKind,
+            JobLogs, JobOutput, JobVolume, LogChunk, LogQuery, MetricSample, MetricsSummary,
+            ParameterSpace, ResourceRequirements, RetryPolicy, TrainingJob, TrainingJobStatus,
+            VolumeKind,
+        },
         ports::MockTrainingJobRepository,
-        service::TrainingJobServiceImpl,
+        service::{TrainingJobServiceError, TrainingJobServiceImpl},
     };
     use crate::{
+        config::LogsConfig,
         domain::{
-            cluster::ports::MockClusterRepository,
-            queue::models::QueueId,
+            cluster::{
+                models::{
+                    Architecture, ClusterId, ClusterNode, Cpu, CpuManufacturer, Gpu,
+                    GpuManufacturer, GpuModel, JobInfo, NodeId,
+                },
+                ports::MockClusterRepository,
+            },
+            queue::{
+                models::{Queue, QueueId},
+                ports::MockQueueRepository,
+            },
+            scheduler::{models::SchedulingPolicyKind, notifier::SchedulerNotifier},
+            secret::{
+                models::EncryptedSecret,
+                ports::{MockSecretRepository, SecretRepositoryError},
+            },
             training_job::{models::JobId, service::TrainingJobService},
+            user::models::UserId,
+        },
+        inbound::http::routes::training_jobs::models::{
+            CreateJobArrayRequest, CreatePipelineRequest, CreateTrainingJobRequest,
+            PipelineDependency, PipelineJobRequest,
         },
-        inbound::http::routes::training_jobs::models::CreateTrainingJobRequest,
     };
     use mockall::predicate::*;
-    use std::sync::Arc;
+    use serde_json::json;
+    use std::{collections::BTreeMap, sync::Arc};
+
+    /// A queue repository where every queue has the given runtime limits.
+    fn queue_repo(
+        default_max_runtime_secs: Option<i64>,
+        max_runtime_limit_secs: Option<i64>,
+    ) -> MockQueueRepository {
+        let mut mock_queue_repo = MockQueueRepository::new();
+        mock_queue_repo
+            .expect_get_queue_by_id()
+            .returning(move |id| {
+                Ok(Queue {
+                    id: *id,
+                    name: "queue".to_string(),
+                    priority: 1,
+                    cluster_targets: vec![],
+                    preemptible: false,
+                    scheduling_policy: SchedulingPolicyKind::default(),
+                    retry_policy: None,
+                    default_max_runtime_secs,
+                    max_runtime_limit_secs,
+                })
+            });
+        mock_queue_repo
+    }
 
     #[tokio::test]
     async fn test_create_training_job() {
@@ -27,13 +77,23 @@ mod tests {
         let queue_id = QueueId::generate();
         let request = CreateTrainingJobRequest {
             name: "test".to_string(),
-            definition: "definition".to_string(),
+            definition: JobDefinition::new("definition"),
             queue_id,
             resource_requirements: serde_json::json!({
                 "cpu_millicores": 1000,
                 "memory_mb": 1024,
                 "gpus": null
             }),
+            node_count: 1,
+            depends_on: vec![],
+            retry_policy: None,
+            max_runtime_secs: None,
+            node_selector: BTreeMap::new(),
+            tolerations: vec![],
+            env: BTreeMap::new(),
+            secrets: BTreeMap::new(),
+            outputs: vec![],
+            volumes: vec![],
         };
 
         mock_repo
@@ -41,16 +101,34 @@ mod tests {
             .withf(move |job| job.name == "test" && job.queue_id == Some(queue_id))
             .times(1)
             .returning(|_| Ok(()));
+        mock_repo
+            .expect_record_event()
+            .withf(|job_ids, event| job_ids.len() == 1 && *event == JobEventKind::Created)
+            .times(1)
+            .returning(|_, _| Ok(()));
 
-        let service = TrainingJobServiceImpl::new(Arc::new(mock_repo), Arc::new(mock_cluster_repo));
-        let result = service.create(request).await;
+        let notifier = SchedulerNotifier::new();
+        let service = TrainingJobServiceImpl::new(
+            Arc::new(mock_repo),
+            Arc::new(mock_cluster_repo),
+            Arc::new(queue_repo(None, None)),
+            Arc::new(MockSecretRepository::new()),
+            notifier.clone(),
+            LogsConfig::default(),
+        );
+        let result = service.create(request, None).await;
 
         assert!(result.is_ok());
         let training_job = result.unwrap();
         assert_eq!(training_job.name, "test");
-        assert_eq!(training_job.definition, "definition");
+        assert_eq!(training_job.definition, JobDefinition::new("definition"));
         assert_eq!(training_job.status, TrainingJobStatus::Queued);
         assert_eq!(training_job.queue_id, Some(queue_id));
+
+        // Creating a job wakes the scheduler up right away.
+        tokio::time::timeout(std::time::Duration::from_secs(1), notifier.notified())
+            .await
+            .expect("scheduler to be notified");
     }
 
     #[tokio::test]
@@ -68,7 +146,14 @@ mod tests {
             .times(1)
             .returning(|_| Ok(vec![]));
 
-        let service = TrainingJobServiceImpl::new(Arc::new(mock_repo), Arc::new(mock_cluster_repo));
+        let service = TrainingJobServiceImpl::new(
+            Arc::new(mock_repo),
+            Arc::new(mock_cluster_repo),
+            Arc::new(queue_repo(None, None)),
+            Arc::new(MockSecretRepository::new()),
+            SchedulerNotifier::new(),
+            LogsConfig::default(),
+        );
         let result = service.get_training_jobs(filters).await;
 
         assert!(result.is_ok());
@@ -86,28 +171,1139 @@ mod tests {
             .with(eq(id), eq(status.clone()))
             .times(1)
             .returning(|_, _| Ok(()));
+        mock_repo
+            .expect_record_event()
+            .withf(move |job_ids, event| {
+                job_ids == [id]
+                    && *event
+                        == JobEventKind::StatusChanged {
+                            status: TrainingJobStatus::Running,
+                            reason: None,
+                        }
+            })
+            .times(1)
+            .returning(|_, _| Ok(()));
 
-        let service = TrainingJobServiceImpl::new(Arc::new(mock_repo), Arc::new(mock_cluster_repo));
+        let service = TrainingJobServiceImpl::new(
+            Arc::new(mock_repo),
+            Arc::new(mock_cluster_repo),
+            Arc::new(queue_repo(None, None)),
+            Arc::new(MockSecretRepository::new()),
+            SchedulerNotifier::new(),
+            LogsConfig::default(),
+        );
         let result = service.update_status(&id, status).await;
 
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_cancel_notifies_scheduler() {
+        let mut mock_repo = MockTrainingJobRepository::new();
+        let mut mock_cluster_repo = MockClusterRepository::new();
+        let job = placed_job(NodeId::generate());
+        let id = job.id;
+
+        mock_repo
+            .expect_get_training_job_by_id()
+            .returning(move |_| Ok(job.clone()));
+        mock_cluster_repo
+            .expect_release_job_nodes()
+            .with(eq(id))
+            .times(1)
+            .returning(|_| Ok(()));
+        mock_repo
+            .expect_update_status()
+            .with(eq(id), eq(TrainingJobStatus::Cancelled))
+            .times(1)
+            .returning(|_, _| Ok(()));
+        mock_repo
+            .expect_finish_attempt()
+            .returning(|_, _, _| Ok(None));
+        mock_repo.expect_record_event().returning(|_, _| Ok(()));
+
+        let notifier = SchedulerNotifier::new();
+        let service = TrainingJobServiceImpl::new(
+            Arc::new(mock_repo),
+            Arc::new(mock_cluster_repo),
+            Arc::new(queue_repo(None, None)),
+            Arc::new(MockSecretRepository::new()),
+            notifier.clone(),
+            LogsConfig::default(),
+        );
+        service.cancel(&id).await.unwrap();
+
+        // The job's nodes are free for queued jobs.
+        tokio::time::timeout(std::time::Duration::from_secs(1), notifier.notified())
+            .await
+            .expect("scheduler to be notified");
+    }
+
+    #[tokio::test]
+    async fn test_fail_releases_nodes_and_records_reason() {
+        let mut mock_repo = MockTrainingJobRepository::new();
+        let mut mock_cluster_repo = MockClusterRepository::new();
+        let id = JobId::generate();
+
+        mock_cluster_repo
+            .expect_release_job_nodes()
+            .with(eq(id))
+            .times(1)
+            .returning(|_| Ok(()));
+        mock_repo
+            .expect_update_status()
+            .with(eq(id), eq(TrainingJobStatus::Failed))
+            .times(1)
+            .returning(|_, _| Ok(()));
+        mock_repo
+            .expect_finish_attempt()
+            .with(eq(id), eq(AttemptOutcome::Failed), eq(None))
+            .times(1)
+            .returning(|_, _, _| Ok(None));
+        mock_repo
+            .expect_record_event()
+            .withf(|_, event| {
+                matches!(
+                    event,
+                    JobEventKind::StatusChanged {
+                        status: TrainingJobStatus::Failed,
+                        reason: Some(reason),
+                    } if reason == "secrets unavailable"
+                )
+            })
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let notifier = SchedulerNotifier::new();
+        let service = TrainingJobServiceImpl::new(
+            Arc::new(mock_repo),
+            Arc::new(mock_cluster_repo),
+            Arc::new(queue_repo(None, None)),
+            Arc::new(MockSecretRepository::new()),
+            notifier.clone(),
+            LogsConfig::default(),
+        );
+        service
+            .fail(&id, "secrets unavailable".to_string())
+            .await
+            .unwrap();
+
+        tokio::time::timeout(std::time::Duration::from_secs(1), notifier.notified())
+            .await
+            .expect("scheduler to be notified");
+    }
+
+    fn placed_job(node_id: NodeId) -> TrainingJob {
+        let now = chrono::Utc::now();
+        TrainingJob {
+            id: JobId::generate(),
+            name: "test".to_string(),
+            definition: JobDefinition::new("definition"),
+            status: TrainingJobStatus::Running,
+            node_id: Some(node_id),
+            queue_id: None,
+            resource_requirements: ResourceRequirements {
+                cpu_millicores: 1000,
+                memory_mb: 1024,
+                gpus: None,
+            },
+            node_count: 1,
+            preempted_at: None,
+            preemption_reason: None,
+            dependencies: Vec::new(),
+            retry_policy: None,
+            not_before: None,
+            avoid_node_id: None,
+            max_runtime_secs: None,
+            started_at: Some(now),
+            exit: None,
+            array_id: None,
+            array_index: None,
+            parameters: BTreeMap::new(),
+            node_selector: BTreeMap::new(),
+            tolerations: Vec::new(),
+            env: BTreeMap::new(),
+            secrets: BTreeMap::new(),
+            outputs: vec![],
+            volumes: vec![],
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
     #[tokio::test]
     async fn test_post_logs() {
         let mut mock_repo = MockTrainingJobRepository::new();
+        let mut mock_cluster_repo = MockClusterRepository::new();
+        let node = ClusterNode::create(
+            NodeId::generate(),
+            ClusterId::generate(),
+            1024,
+            Cpu {
+                manufacturer: CpuManufacturer::Intel,
+                architecture: Architecture::X86_64,
+                millicores: 1000,
+            },
+            None,
+        );
+        let cluster_id = node.cluster_id;
+        let job = placed_job(node.id);
+        let id = job.id;
+
+        mock_repo
+            .expect_get_training_job_by_id()
+            .returning(move |_| Ok(job.clone()));
+        mock_cluster_repo
+            .expect_get_cluster_node_by_id()
+            .returning(move |_| Ok(node.clone()));
+        mock_repo
+            .expect_append_logs()
+            .with(
+                eq(id),
+                eq("logs"),
+                eq(LogsConfig::default().max_bytes_per_job),
+            )
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
+        let service = TrainingJobServiceImpl::new(
+            Arc::new(mock_repo),
+            Arc::new(mock_cluster_repo),
+            Arc::new(queue_repo(None, None)),
+            Arc::new(MockSecretRepository::new()),
+            SchedulerNotifier::new(),
+            LogsConfig::default(),
+        );
+        let result = service
+            .post_logs(&id, &cluster_id, "logs".to_string())
+            .await;
+        assert!(result.is_ok());
+
+        // Other clusters may not add to the job's logs.
+        let result = service
+            .post_logs(&id, &ClusterId::generate(), "logs".to_string())
+            .await;
+        assert!(matches!(
+            result,
+            Err(TrainingJobServiceError::JobNotOnCluster(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_follow_logs_ends_with_finished_job() {
+        let mut mock_repo = MockTrainingJobRepository::new();
+        let mut job = placed_job(NodeId::generate());
+        job.status = TrainingJobStatus::Succeeded;
+        let id = job.id;
+
+        mock_repo
+            .expect_get_training_job_by_id()
+            .returning(move |_| Ok(job.clone()));
+        mock_repo
+            .expect_get_logs()
+            .with(eq(id), eq(Some(0)), eq(None))
+            .returning(|_, _, _| {
+                Ok(vec![LogChunk {
+                    offset: 5,
+                    content: "done\n".to_string(),
+                    created_at: chrono::Utc::now(),
+                }])
+            });
+        mock_repo
+            .expect_get_logs()
+            .with(eq(id), eq(Some(5)), eq(None))
+            .returning(|_, _, _| Ok(Vec::new()));
+
+        let service = TrainingJobServiceImpl::new(
+            Arc::new(mock_repo),
+            Arc::new(MockClusterRepository::new()),
+            Arc::new(queue_repo(None, None)),
+            Arc::new(MockSecretRepository::new()),
+            SchedulerNotifier::new(),
+            LogsConfig::default(),
+        );
+
+        // Output left over from before the job finished is still returned.
+        let logs = service.follow_logs(&id, 0).await.unwrap().unwrap();
+        assert_eq!(logs.next_offset, 5);
+        assert!(service.follow_logs(&id, 5).await.unwrap().is_none());
+    }
+
+    #[test]
+    fn test_job_logs_tail() {
+        let chunk = |offset, content: &str| LogChunk {
+            offset,
+            content: content.to_string(),
+            created_at: chrono::Utc::now(),
+        };
+        let chunks = vec![chunk(3, "a\nb\n"), chunk(7, "c\nd\ne\n"), chunk(9, "f")];
+        let query = |offset, tail| LogQuery { offset, tail };
+
+        let all = JobLogs::new(chunks.clone(), &query(None, None));
+        assert_eq!(all.chunks, chunks);
+        assert_eq!(all.next_offset, 9);
+
+        let tail = JobLogs::new(chunks.clone(), &query(None, Some(3)));
+        let contents: Vec<&str> = tail.chunks.iter().map(|c| c.content.as_str()).collect();
+        assert_eq!(contents, vec!["d\ne\n", "f"]);
+        assert_eq!(tail.next_offset, 9);
+
+        let tail = JobLogs::new(chunks, &query(None, Some(10)));
+        assert_eq!(tail.chunks.len(), 3);
+
+        // Without new chunks, the client keeps reading from where it was.
+        let empty = JobLogs::new(Vec::new(), &query(Some(9), None));
+        assert_eq!(empty.next_offset, 9);
+    }
+
+    #[tokio::test]
+    async fn test_create_training_job_rejects_invalid_node_count() {
+        let mock_repo = MockTrainingJobRepository::new();
         let mock_cluster_repo = MockClusterRepository::new();
-        let id = JobId::generate();
+        let request = CreateTrainingJobRequest {
+            name: "test".to_string(),
+            definition: JobDefinition::new("definition"),
+            queue_id: QueueId::generate(),
+            resource_requirements: serde_json::json!({
+                "cpu_millicores": 1000,
+                "memory_mb": 1024,
+                "gpus": null
+            }),
+            node_count: 0,
+            depends_on: vec![],
+            retry_policy: None,
+            max_runtime_secs: None,
+            node_selector: BTreeMap::new(),
+            tolerations: vec![],
+            env: BTreeMap::new(),
+            secrets: BTreeMap::new(),
+            outputs: vec![],
+            volumes: vec![],
+        };
+
+        let service = TrainingJobServiceImpl::new(
+            Arc::new(mock_repo),
+            Arc::new(mock_cluster_repo),
+            Arc::new(queue_repo(None, None)),
+            Arc::new(MockSecretRepository::new()),
+            SchedulerNotifier::new(),
+            LogsConfig::default(),
+        );
+        let result = service.create(request, None).await;
 
+        assert!(matches!(
+            result,
+            Err(TrainingJobServiceError::InvalidNodeCount(0))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_create_training_job_rejects_unknown_gpu_model() {
+        let mock_repo = MockTrainingJobRepository::new();
+        let mock_cluster_repo = MockClusterRepository::new();
+        let request = CreateTrainingJobRequest {
+            name: "test".to_string(),
+            definition: JobDefinition::new("definition"),
+            queue_id: QueueId::generate(),
+            resource_requirements: serde_json::json!({
+                "cpu_millicores": 1000,
+                "memory_mb": 1024,
+                "gpus": { "count": 1, "model": "RTX9999", "memory_gb": null }
+            }),
+            node_count: 1,
+            depends_on: vec![],
+            retry_policy: None,
+            max_runtime_secs: None,
+            node_selector: BTreeMap::new(),
+            tolerations: vec![],
+            env: BTreeMap::new(),
+            secrets: BTreeMap::new(),
+            outputs: vec![],
+            volumes: vec![],
+        };
+
+        let service = TrainingJobServiceImpl::new(
+            Arc::new(mock_repo),
+            Arc::new(mock_cluster_repo),
+            Arc::new(queue_repo(None, None)),
+            Arc::new(MockSecretRepository::new()),
+            SchedulerNotifier::new(),
+            LogsConfig::default(),
+        );
+        let result = service.create(request, None).await;
+
+        assert!(matches!(
+            result,
+            Err(TrainingJobServiceError::UnknownGpuModel(model)) if model == "RTX9999"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_create_training_job_applies_queue_runtime_limits() {
+        let mut mock_repo = MockTrainingJobRepository::new();
+        mock_repo.expect_create().times(1).returning(|_| Ok(()));
+        mock_repo.expect_record_event().returning(|_, _| Ok(()));
+        let service = TrainingJobServiceImpl::new(
+            Arc::new(mock_repo),
+            Arc::new(MockClusterRepository::new()),
+            Arc::new(queue_repo(Some(3600), Some(7200))),
+            Arc::new(MockSecretRepository::new()),
+            SchedulerNotifier::new(),
+            LogsConfig::default(),
+        );
+        let request = |max_runtime_secs| CreateTrainingJobRequest {
+            name: "test".to_string(),
+            definition: JobDefinition::new("definition"),
+            queue_id: QueueId::generate(),
+            resource_requirements: serde_json::json!({
+                "cpu_millicores": 1000,
+                "memory_mb": 1024,
+                "gpus": null
+            }),
+            node_count: 1,
+            depends_on: vec![],
+            retry_policy: None,
+            max_runtime_secs,
+            node_selector: BTreeMap::new(),
+            tolerations: vec![],
+            env: BTreeMap::new(),
+            secrets: BTreeMap::new(),
+            outputs: vec![],
+            volumes: vec![],
+        };
+
+        let job = service.create(request(None), None).await.unwrap();
+        assert_eq!(job.max_runtime_secs, Some(3600));
+
+        let result = service.create(request(Some(10000)), None).await;
+        assert!(matches!(
+            result,
+            Err(TrainingJobServiceError::InvalidMaxRuntime(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_create_training_job_checks_env_and_secrets() {
+        let owner = UserId::generate();
+        let mut mock_repo = MockTrainingJobRepository::new();
         mock_repo
-            .expect_post_logs()
-            .with(eq(id), eq("logs".to_string()))
+            .expect_create()
+            .withf(|job| job.secrets["WANDB_API_KEY"] == "wandb")
+            .times(1)
+            .returning(|_| Ok(()));
+        mock_repo.expect_record_event().returning(|_, _| Ok(()));
+        let mut mock_secret_repo = MockSecretRepository::new();
+        mock_secret_repo
+            .expect_get_secret_by_name()
+            .returning(move |name| match name {
+                "wandb" => Ok(EncryptedSecret {
+                    name: name.to_string(),
+                    owner_id: owner,
+                    ciphertext: vec![],
+                    nonce: vec![],
+                    created_at: chrono::Utc::now(),
+                    updated_at: chrono::Utc::now(),
+                }),
+                _ => Err(SecretRepositoryError::NotFound(name.to_string())),
+            });
+        let service = TrainingJobServiceImpl::new(
+            Arc::new(mock_repo),
+            Arc::new(MockClusterRepository::new()),
+            Arc::new(queue_repo(None, None)),
+            Arc::new(mock_secret_repo),
+            SchedulerNotifier::new(),
+            LogsConfig::default(),
+        );
+        let request = |env: &[(&str, &str)], secrets: &[(&str, &str)]| CreateTrainingJobRequest {
+            name: "test".to_string(),
+            definition: JobDefinition::new("definition"),
+            queue_id: QueueId::generate(),
+            resource_requirements: serde_json::json!({
+                "cpu_millicores": 1000,
+                "memory_mb": 1024,
+                "gpus": null
+            }),
+            node_count: 1,
+            depends_on: vec![],
+            retry_policy: None,
+            max_runtime_secs: None,
+            node_selector: BTreeMap::new(),
+            tolerations: vec![],
+            env: env
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            secrets: secrets
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            outputs: vec![],
+            volumes: vec![],
+        };
+
+        let job = service
+            .create(
+                request(&[("DEBUG", "1")], &[("WANDB_API_KEY", "wandb")]),
+                Some(owner),
+            )
+            .await
+            .unwrap();
+        assert_eq!(job.env["DEBUG"], "1");
+
+        let result = service
+            .create(request(&[], &[("HF_TOKEN", "missing")]), Some(owner))
+            .await;
+        assert!(matches!(
+            result,
+            Err(TrainingJobServiceError::SecretNotFound(name)) if name == "missing"
+        ));
+
+        // Other users' secrets look as if they didn't exist, and jobs
+        // submitted on behalf of no one can't receive any.
+        for submitted_by in [Some(UserId::generate()), None] {
+            let result = service
+                .create(request(&[], &[("WANDB_API_KEY", "wandb")]), submitted_by)
+                .await;
+            assert!(matches!(
+                result,
+                Err(TrainingJobServiceError::SecretNotFound(name)) if name == "wandb"
+            ));
+        }
+
+        for (env, secrets) in [
+            (vec![("NODE_RANK", "1")], vec![]),
+            (vec![("1ST", "1")], vec![]),
+            (vec![("TOKEN", "1")], vec![("TOKEN", "wandb")]),
+        ] {
+            let result = service.create(request(&env, &secrets), Some(owner)).await;
+            assert!(matches!(
+                result,
+                Err(TrainingJobServiceError::InvalidEnv(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_job_definition() {
+        // A plain string is taken as the image, as before definitions had
+        // more to them.
+        let definition: JobDefinition = serde_json::from_value(json!("trainer:v1")).unwrap();
+        assert_eq!(definition, JobDefinition::new("trainer:v1"));
+
+        let definition: JobDefinition = serde_json::from_value(json!({
+            "image": "trainer:v1",
+            "command": ["python", "train.py"],
+            "args": ["--lr", "0.1"],
+            "workdir": "/workspace",
+            "user": "1000:1000"
+        }))
+        .unwrap();
+        assert_eq!(
+            definition.command.as_deref().unwrap(),
+            ["python", "train.py"]
+        );
+        assert_eq!(definition.args, ["--lr", "0.1"]);
+        assert!(definition.validate().is_ok());
+
+        let invalid = [
+            JobDefinition::new(" "),
+            JobDefinition {
+                command: Some(vec![]),
+                ..JobDefinition::new("trainer:v1")
+            },
+            JobDefinition {
+                workdir: Some("workspace".to_string()),
+                ..JobDefinition::new("trainer:v1")
+            },
+        ];
+        for definition in invalid {
+            assert!(definition.validate().is_err());
+        }
+    }
+
+    #[test]
+    fn test_validate_outputs() {
+        let output = |path: &str, upload_interval_secs| JobOutput {
+            path: path.to_string(),
+            upload_interval_secs,
+        };
+        assert!(JobOutput::validate_all(&[
+            output("/outputs", None),
+            output("/workspace/checkpoints", Some(600)),
+        ])
+        .is_ok());
+
+        for outputs in [
+            vec![output("outputs", None)],
+            vec![output("/", None)],
+            vec![output("/workspace/../etc", None)],
+            vec![output("/workspace//checkpoints", None)],
+            vec![output("/checkpoints", Some(1))],
+            vec![output("/outputs", None), output("/outputs", Some(600))],
+        ] {
+            assert!(JobOutput::validate_all(&outputs).is_err(), "{outputs:?}");
+        }
+    }
+
+    #[test]
+    fn test_validate_volumes() {
+        let volume = |kind, source: &str, target: &str| JobVolume {
+            kind,
+            source: source.to_string(),
+            target: target.to_string(),
+            read_only: false,
+        };
+        let outputs = [JobOutput {
+            path: "/outputs".to_string(),
+            upload_interval_secs: None,
+        }];
+        assert!(JobVolume::validate_all(
+            &[
+                volume(VolumeKind::HostPath, "/mnt/datasets", "/data"),
+                volume(VolumeKind::Named, "pip-cache", "/root/.cache/pip"),
+                volume(VolumeKind::Cache, "imagenet-2012", "/datasets/imagenet"),
+            ],
+            &outputs,
+        )
+        .is_ok());
+
+        for volumes in [
+            vec![volume(VolumeKind::HostPath, "mnt/datasets", "/data")],
+            vec![volume(VolumeKind::HostPath, "/mnt/../etc", "/data")],
+            vec![volume(VolumeKind::Named, "/mnt/datasets", "/data")],
+            vec![volume(VolumeKind::Cache, "../imagenet", "/data")],
+            vec![volume(VolumeKind::Cache, ".imagenet", "/data")],
+            vec![volume(VolumeKind::Cache, "imagenet", "data")],
+            vec![volume(VolumeKind::Cache, "imagenet", "/outputs")],
+            vec![
+                volume(VolumeKind::Cache, "imagenet", "/data"),
+                volume(VolumeKind::Named, "coco", "/data"),
+            ],
+        ] {
+            assert!(
+                JobVolume::validate_all(&volumes, &outputs).is_err(),
+                "{volumes:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_job_exit_from_heartbeat() {
+        let job_info: JobInfo = serde_json::from_value(serde_json::json!({
+            "job_id": JobId::generate(),
+            "status": "running",
+        }))
+        .unwrap();
+        assert_eq!(job_info.exit(), None);
+
+        let job_info: JobInfo = serde_json::from_value(serde_json::json!({
+            "job_id": JobId::generate(),
+            "status": "failed",
+            "exit_code": 137,
+            "oom_killed": true,
+        }))
+        .unwrap();
+        let exit = job_info.exit().unwrap();
+        assert!(exit.oom_killed);
+        assert_eq!(
+            exit.describe(),
+            "Ran out of memory and was killed with exit code 137"
+        );
+
+        let job_info: JobInfo = serde_json::from_value(serde_json::json!({
+            "job_id": JobId::generate(),
+            "status": "failed",
+            "error": { "kind": "image_pull", "message": "manifest unknown" },
+        }))
+        .unwrap();
+        let exit = job_info.exit().unwrap();
+        assert_eq!(exit.exit_code, None);
+        assert_eq!(
+            exit.error.as_ref().map(|error| error.kind),
+            Some(JobErrorKind::ImagePull)
+        );
+        assert_eq!(
+            exit.describe(),
+            "Could not pull its image: manifest unknown"
+        );
+    }
+
+    #[test]
+    fn test_metrics_summary() {
+        let resources = ResourceRequirements {
+            cpu_millicores: 8000,
+            memory_mb: 65536,
+            gpus: Some(GpuRequirement {
+                count: 2,
+                model: None,
+                manufacturer: None,
+                memory_gb: None,
+            }),
+        };
+        let (node, other_node) = (NodeId::generate(), NodeId::generate());
+        let sample = |node_id, cpu_millicores, memory_mb, utilization: [i32; 2]| MetricSample {
+            node_id,
+            sampled_at: chrono::Utc::now(),
+            cpu_millicores,
+            memory_mb,
+            gpus: utilization
+                .iter()
+                .enumerate()
+                .map(|(index, utilization_percent)| GpuSample {
+                    index: index as i32,
+                    utilization_percent: *utilization_percent,
+                    memory_used_mb: 1024,
+                })
+                .collect(),
+        };
+
+        let summary = MetricsSummary::new(
+            &resources,
+            &[
+                sample(node, Some(1000), Some(2048), [90, 0]),
+                sample(node, Some(3000), Some(4096), [70, 2]),
+                sample(other_node, None, None, [80, 60]),
+            ],
+        );
+        assert_eq!(summary.avg_cpu_millicores, Some(2000.0));
+        assert_eq!(summary.peak_memory_mb, Some(4096));
+        assert_eq!(summary.requested_gpus, 2);
+        // The second GPU of the first node averages 1%.
+        assert_eq!(summary.idle_gpus, 1);
+        assert_eq!(summary.avg_gpu_utilization_percent, Some(55.25));
+
+        let summary = MetricsSummary::new(&resources, &[]);
+        assert_eq!(summary.avg_cpu_millicores, None);
+        assert_eq!(summary.avg_gpu_utilization_percent, None);
+        assert_eq!(summary.idle_gpus, 0);
+    }
+
+    #[test]
+    fn test_gpu_requirement_matching() {
+        let gpu = Gpu {
+            manufacturer: GpuManufacturer::Nvidia,
+            model: GpuModel::H100,
+            count: 8,
+            memory_mb: 80 * 1024,
+        };
+        let requirement = |count, model: Option<&str>, memory_gb| GpuRequirement {
+            count,
+            model: model.map(str::to_string),
+            manufacturer: None,
+            memory_gb,
+        };
+
+        assert!(requirement(8, Some("H100"), Some(80)).is_satisfied_by(&gpu));
+        assert!(requirement(2, None, None).is_satisfied_by(&gpu));
+        assert!(!requirement(16, None, None).is_satisfied_by(&gpu));
+        assert!(!requirement(1, Some("T4"), None).is_satisfied_by(&gpu));
+        assert!(!requirement(1, None, Some(141)).is_satisfied_by(&gpu));
+        assert!(!requirement(1, Some("not-a-gpu"), None).is_satisfied_by(&gpu));
+        assert!(!GpuRequirement {
+            manufacturer: Some("AMD".to_string()),
+            ..requirement(1, None, None)
+        }
+        .is_satisfied_by(&gpu));
+    }
+
+    #[test]
+    fn test_node_free_capacity_accounts_for_allocations() {
+        let mut node = ClusterNode::create(
+            NodeId::generate(),
+            ClusterId::generate(),
+            64 * 1024,
+            Cpu {
+                manufacturer: CpuManufacturer::Intel,
+                architecture: Architecture::X86_64,
+                millicores: 32000,
+            },
+            Some(Gpu {
+                manufacturer: GpuManufacturer::Nvidia,
+                model: GpuModel::H100,
+                count: 4,
+                memory_mb: 80 * 1024,
+            }),
+        );
+        let requirements = ResourceRequirements {
+            cpu_millicores: 8000,
+            memory_mb: 16 * 1024,
+            gpus: Some(GpuRequirement {
+                count: 2,
+                model: None,
+                manufacturer: None,
+                memory_gb: None,
+            }),
+        };
+        let allocation = |job_id| JobAllocation {
+            job_id,
+            node_id: node.id,
+            rank: 0,
+            cpu_millicores: requirements.cpu_millicores,
+            memory_mb: requirements.memory_mb,
+            gpu_count: requirements.gpu_count(),
+        };
+
+        let first = allocation(JobId::generate());
+        let second = allocation(JobId::generate());
+        node.allocations = vec![first.clone(), second];
+        let mut free = node.free_capacity();
+        assert_eq!(free.cpu_millicores, 16000);
+        assert_eq!(free.memory_mb, 32 * 1024);
+        assert_eq!(free.gpu_count, 0);
+        assert!(!free.can_hold(&requirements));
+        assert!(!node.has_room_for(&requirements));
+
+        free.release(&first);
+        assert!(free.can_hold(&requirements));
+        node.allocations = vec![first];
+        assert!(node.has_room_for(&requirements));
+
+        // A job the agent still runs after losing its allocation blocks the node.
+        node.reported_job_ids = vec![JobId::generate()];
+        assert!(node.is_stopping_jobs());
+        assert!(!node.has_room_for(&requirements));
+    }
+
+    fn pipeline_job(key: &str, needs: &[&str]) -> PipelineJobRequest {
+        PipelineJobRequest {
+            key: key.to_string(),
+            needs: needs
+                .iter()
+                .map(|key| PipelineDependency {
+                    key: key.to_string(),
+                    condition: DependencyCondition::Succeeded,
+                })
+                .collect(),
+            job: CreateTrainingJobRequest {
+                name: key.to_string(),
+                definition: JobDefinition::new("definition"),
+                queue_id: QueueId::generate(),
+                resource_requirements: serde_json::json!({
+                    "cpu_millicores": 1000,
+                    "memory_mb": 1024,
+                    "gpus": null
+                }),
+                node_count: 1,
+                depends_on: vec![],
+                retry_policy: None,
+                max_runtime_secs: None,
+                node_selector: BTreeMap::new(),
+                tolerations: vec![],
+                env: BTreeMap::new(),
+                secrets: BTreeMap::new(),
+                outputs: vec![],
+                volumes: vec![],
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_pipeline_links_dependencies() {
+        let mut mock_repo = MockTrainingJobRepository::new();
+        mock_repo
+            .expect_create_all()
+            .withf(|jobs| jobs.len() == 3)
+            .times(1)
+            .returning(|_| Ok(()));
+        mock_repo
+            .expect_record_event()
+            .withf(|job_ids, _| job_ids.len() == 3)
             .times(1)
             .returning(|_, _| Ok(()));
 
-        let service = TrainingJobServiceImpl::new(Arc::new(mock_repo), Arc::new(mock_cluster_repo));
-        let result = service.post_logs(&id, "logs".to_string()).await;
+        let notifier = SchedulerNotifier::new();
+        let service = TrainingJobServiceImpl::new(
+            Arc::new(mock_repo),
+            Arc::new(MockClusterRepository::new()),
+            Arc::new(queue_repo(None, None)),
+            Arc::new(MockSecretRepository::new()),
+            notifier.clone(),
+            LogsConfig::default(),
+        );
+        let jobs = service
+            .create_pipeline(
+                CreatePipelineRequest {
+                    jobs: vec![
+                        pipeline_job("preprocess", &[]),
+                        pipeline_job("train", &["preprocess"]),
+                        pipeline_job("evaluate", &["train"]),
+                    ],
+                },
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert!(jobs[0].dependencies.is_empty());
+        assert_eq!(jobs[1].dependencies[0].job_id, jobs[0].id);
+        assert_eq!(jobs[2].dependencies[0].job_id, jobs[1].id);
+        tokio::time::timeout(std::time::Duration::from_secs(1), notifier.notified())
+            .await
+            .expect("scheduler to be notified");
+    }
+
+    #[tokio::test]
+    async fn test_create_pipeline_rejects_cycles() {
+        let service = TrainingJobServiceImpl::new(
+            Arc::new(MockTrainingJobRepository::new()),
+            Arc::new(MockClusterRepository::new()),
+            Arc::new(queue_repo(None, None)),
+            Arc::new(MockSecretRepository::new()),
+            SchedulerNotifier::new(),
+            LogsConfig::default(),
+        );
+        let result = service
+            .create_pipeline(
+                CreatePipelineRequest {
+                    jobs: vec![
+                        pipeline_job("preprocess", &["evaluate"]),
+                        pipeline_job("train", &["preprocess"]),
+                        pipeline_job("evaluate", &["train"]),
+                    ],
+                },
+                None,
+            )
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(TrainingJobServiceError::InvalidPipeline(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_create_pipeline_allows_repeated_needs() {
+        let mut mock_repo = MockTrainingJobRepository::new();
+        mock_repo
+            .expect_create_all()
+            .withf(|jobs| jobs.len() == 2)
+            .times(1)
+            .returning(|_| Ok(()));
+        mock_repo.expect_record_event().returning(|_, _| Ok(()));
+
+        let service = TrainingJobServiceImpl::new(
+            Arc::new(mock_repo),
+            Arc::new(MockClusterRepository::new()),
+            Arc::new(queue_repo(None, None)),
+            Arc::new(MockSecretRepository::new()),
+            SchedulerNotifier::new(),
+            LogsConfig::default(),
+        );
+        let result = service
+            .create_pipeline(
+                CreatePipelineRequest {
+                    jobs: vec![
+                        pipeline_job("preprocess", &[]),
+                        pipeline_job("train", &["preprocess", "preprocess"]),
+                    ],
+                },
+                None,
+            )
+            .await;
 
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_dependency_conditions() {
+        let succeeded = DependencyCondition::Succeeded;
+        let completed = DependencyCondition::Completed;
+
+        assert!(succeeded.is_satisfied_by(&TrainingJobStatus::Succeeded));
+        assert!(!succeeded.is_satisfied_by(&TrainingJobStatus::Running));
+        assert!(succeeded.is_violated_by(&TrainingJobStatus::Failed));
+        assert!(succeeded.is_violated_by(&TrainingJobStatus::Cancelled));
+        assert!(completed.is_satisfied_by(&TrainingJobStatus::Failed));
+        assert!(!completed.is_violated_by(&TrainingJobStatus::Failed));
+        assert!(!completed.is_violated_by(&TrainingJobStatus::Queued));
+    }
+
+    #[test]
+    fn test_retry_policy() {
+        let policy = RetryPolicy {
+            max_attempts: 3,
+            backoff_secs: 30,
+            max_backoff_secs: 100,
+            retry_on_exit_codes: vec![137],
+            node_loss_only: false,
+        };
+        let attempt = |attempt_number, outcome, exit_code| JobAttempt {
+            job_id: JobId::generate(),
+            attempt_number,
+            node_id: Some(NodeId::generate()),
+            started_at: chrono::Utc::now(),
+            finished_at: Some(chrono::Utc::now()),
+            outcome: Some(outcome),
+            exit_code,
+        };
+
+        assert!(policy.should_retry(&attempt(1, AttemptOutcome::Failed, Some(137))));
+        assert!(!policy.should_retry(&attempt(1, AttemptOutcome::Failed, Some(1))));
+        assert!(policy.should_retry(&attempt(2, AttemptOutcome::NodeLost, None)));
+        assert!(!policy.should_retry(&attempt(3, AttemptOutcome::NodeLost, None)));
+        assert!(!policy.should_retry(&attempt(1, AttemptOutcome::Cancelled, None)));
+        assert!(!RetryPolicy {
+            node_loss_only: true,
+            ..policy.clone()
+        }
+        .should_retry(&attempt(1, AttemptOutcome::Failed, Some(137))));
+
+        assert_eq!(policy.backoff(1), chrono::Duration::seconds(30));
+        assert_eq!(policy.backoff(2), chrono::Duration::seconds(60));
+        assert_eq!(policy.backoff(3), chrono::Duration::seconds(100));
+    }
+
+    #[test]
+    fn test_parameter_space_expansion() {
+        let grid: ParameterSpace = serde_json::from_value(json!({
+            "grid": { "LR": [0.1, 0.01], "BATCH_SIZE": [32, 64, 128] }
+        }))
+        .unwrap();
+        let jobs = grid.expand().unwrap();
+        assert_eq!(jobs.len(), 6);
+        assert_eq!(jobs[0]["BATCH_SIZE"], "32");
+        assert_eq!(jobs[0]["LR"], "0.1");
+        assert_eq!(jobs[1]["BATCH_SIZE"], "32");
+        assert_eq!(jobs[1]["LR"], "0.01");
+        assert_eq!(jobs[5]["BATCH_SIZE"], "128");
+
+        let list: ParameterSpace = serde_json::from_value(json!({
+            "list": [{ "MODEL": "small", "EPOCHS": 10 }, { "MODEL": "large", "EPOCHS": 3 }]
+        }))
+        .unwrap();
+        let jobs = list.expand().unwrap();
+        assert_eq!(jobs.len(), 2);
+        assert_eq!(jobs[1]["MODEL"], "large");
+        assert_eq!(jobs[1]["EPOCHS"], "3");
+
+        let invalid = |space: serde_json::Value| {
+            serde_json::from_value::<ParameterSpace>(space)
+                .unwrap()
+                .expand()
+                .is_err()
+        };
+        assert!(invalid(json!({ "grid": { "LR": [] } })));
+        assert!(invalid(json!({ "grid": {} })));
+        assert!(invalid(json!({ "list": [] })));
+        assert!(invalid(json!({ "grid": { "1LR": [1] } })));
+        assert!(invalid(json!({ "grid": { "MASTER_PORT": [1] } })));
+        assert!(invalid(json!({ "grid": { "LILAC_ARRAY_INDEX": [1] } })));
+        assert!(invalid(json!({ "grid": { "LR": [[0.1]] } })));
+        assert!(invalid(json!({
+            "grid": { "A": (0..100).collect::<Vec<_>>(), "B": (0..101).collect::<Vec<_>>() }
+        })));
+    }
+
+    #[test]
+    fn test_job_array_status() {
+        let counts = |queued, running, succeeded, failed| JobArrayCounts {
+            queued,
+            running,
+            succeeded,
+            failed,
+            ..Default::default()
+        };
+
+        assert_eq!(counts(3, 0, 0, 0).status(), TrainingJobStatus::Queued);
+        assert_eq!(counts(2, 1, 0, 0).status(), TrainingJobStatus::Running);
+        assert_eq!(counts(2, 0, 1, 0).status(), TrainingJobStatus::Running);
+        assert_eq!(counts(0, 0, 3, 0).status(), TrainingJobStatus::Succeeded);
+        assert_eq!(counts(0, 0, 2, 1).status(), TrainingJobStatus::Failed);
+    }
+
+    #[tokio::test]
+    async fn test_create_job_array() {
+        let mut mock_repo = MockTrainingJobRepository::new();
+        let request = CreateJobArrayRequest {
+            job: CreateTrainingJobRequest {
+                name: "sweep".to_string(),
+                definition: JobDefinition::new("definition"),
+                queue_id: QueueId::generate(),
+                resource_requirements: json!({
+                    "cpu_millicores": 1000,
+                    "memory_mb": 1024,
+                    "gpus": null
+                }),
+                node_count: 1,
+                depends_on: vec![],
+                retry_policy: None,
+                max_runtime_secs: None,
+                node_selector: BTreeMap::new(),
+                tolerations: vec![],
+                env: BTreeMap::new(),
+                secrets: BTreeMap::new(),
+                outputs: vec![],
+                volumes: vec![],
+            },
+            parameters: ParameterSpace::Grid(BTreeMap::from([(
+                "LR".to_string(),
+                vec![json!(0.1), json!(0.01), json!(0.001)],
+            )])),
+            max_concurrent: Some(2),
+        };
+
+        mock_repo
+            .expect_create_array()
+            .withf(|array, jobs| {
+                array.size == 3
+                    && jobs.iter().all(|job| {
+                        job.array_id == Some(array.id) && job.status == TrainingJobStatus::Queued
+                    })
+            })
+            .times(1)
+            .returning(|_, _| Ok(()));
+        mock_repo
+            .expect_record_event()
+            .withf(|job_ids, _| job_ids.len() == 3)
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let notifier = SchedulerNotifier::new();
+        let service = TrainingJobServiceImpl::new(
+            Arc::new(mock_repo),
+            Arc::new(MockClusterRepository::new()),
+            Arc::new(queue_repo(None, None)),
+            Arc::new(MockSecretRepository::new()),
+            notifier.clone(),
+            LogsConfig::default(),
+        );
+        let (array, jobs) = service.create_array(request, None).await.unwrap();
+
+        assert_eq!(array.max_concurrent, Some(2));
+        let names: Vec<_> = jobs.iter().map(|job| job.name.as_str()).collect();
+        assert_eq!(names, ["sweep-0", "sweep-1", "sweep-2"]);
+        assert_eq!(jobs[2].array_index, Some(2));
+        assert_eq!(jobs[2].parameters["LR"], "0.001");
+        tokio::time::timeout(std::time::Duration::from_secs(1), notifier.notified())
+            .await
+            .expect("scheduler to be notified");
+    }
+
+    #[tokio::test]
+    async fn test_create_job_array_rejects_invalid_max_concurrent() {
+        let service = TrainingJobServiceImpl::new(
+            Arc::new(MockTrainingJobRepository::new()),
+            Arc::new(MockClusterRepository::new()),
+            Arc::new(queue_repo(None, None)),
+            Arc::new(MockSecretRepository::new()),
+            SchedulerNotifier::new(),
+            LogsConfig::default(),
+        );
+        let request: CreateJobArrayRequest = serde_json::from_value(json!({
+            "name": "sweep",
+            "definition": "definition",
+            "queue_id": QueueId::generate(),
+            "resource_requirements": { "cpu_millicores": 1000, "memory_mb": 1024, "gpus": null },
+            "parameters": { "list": [{ "SEED": 1 }] },
+            "max_concurrent": 0
+        }))
+        .unwrap();
+
+        let result = service.create_array(request, None).await;
+
+        assert!(matches!(
+            result,
+            Err(TrainingJobServiceError::InvalidArray(_))
+        ));
+    }
 }
@@ -1,12 +1,19 @@
+use std::{collections::BTreeMap, str::FromStr};
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    domain::{cluster::models::NodeId, queue::models::QueueId},
+    domain::{
+        cluster::models::{Gpu, GpuManufacturer, GpuModel, NodeId, Taint},
+        queue::models::QueueId,
+        scheduler::models::RejectionReason,
+    },
     identifier,
 };
 
 identifier!(JobId);
+identifier!(ArrayId);
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
@@ -17,6 +24,314 @@ pub enum TrainingJobStatus {
     Succeeded,
     Failed,
     Cancelled,
+    /// The job ran for longer than its maximum runtime and was stopped.
+    #[serde(rename = "timed_out")]
+    TimedOut,
+}
+
+impl TrainingJobStatus {
+    /// Whether the job has finished and will not be run again.
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            Self::Succeeded | Self::Failed | Self::Cancelled | Self::TimedOut
+        )
+    }
+}
+
+/// When a job may start relative to a job it depends on.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DependencyCondition {
+    /// The upstream job must succeed. The job is cancelled if it doesn't.
+    #[default]
+    Succeeded,
+    /// The upstream job must finish, however it ends.
+    Completed,
+}
+
+impl DependencyCondition {
+    /// Whether an upstream job with this status lets the job start.
+    pub fn is_satisfied_by(&self, status: &TrainingJobStatus) -> bool {
+        match self {
+            Self::Succeeded => *status == TrainingJobStatus::Succeeded,
+            Self::Completed => status.is_terminal(),
+        }
+    }
+
+    /// Whether an upstream job with this status means the job can never start.
+    pub fn is_violated_by(&self, status: &TrainingJobStatus) -> bool {
+        status.is_terminal() && !self.is_satisfied_by(status)
+    }
+}
+
+/// A job that has to finish before another job may start.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct JobDependency {
+    pub job_id: JobId,
+    #[serde(default)]
+    pub condition: DependencyCondition,
+}
+
+fn default_backoff_secs() -> i64 {
+    30
+}
+
+fn default_max_backoff_secs() -> i64 {
+    3600
+}
+
+/// Lets a job run on nodes with a matching taint. A toleration without a
+/// value tolerates every taint with its key.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Toleration {
+    pub key: String,
+    #[serde(default)]
+    pub value: Option<String>,
+}
+
+impl Toleration {
+    pub fn tolerates(&self, taint: &Taint) -> bool {
+        self.key == taint.key && (self.value.is_none() || self.value == taint.value)
+    }
+}
+
+/// How often and when a job that didn't succeed is run again.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RetryPolicy {
+    /// How often the job is run at most, including the first attempt.
+    pub max_attempts: i32,
+    /// How long to wait before the first retry. The wait doubles with every
+    /// further retry.
+    #[serde(default = "default_backoff_secs")]
+    pub backoff_secs: i64,
+    /// The longest the scheduler waits before a retry.
+    #[serde(default = "default_max_backoff_secs")]
+    pub max_backoff_secs: i64,
+    /// The exit codes a failed job is retried on. Any failure is retried if
+    /// empty.
+    #[serde(default)]
+    pub retry_on_exit_codes: Vec<i32>,
+    /// Only retry jobs whose node was lost, not jobs that failed by themselves.
+    #[serde(default)]
+    pub node_loss_only: bool,
+}
+
+impl RetryPolicy {
+    /// Checks that the policy can be applied, returning what is wrong with it
+    /// otherwise.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_attempts < 1 {
+            return Err(format!(
+                "max_attempts must be at least 1, got {}",
+                self.max_attempts
+            ));
+        }
+        if self.backoff_secs < 0 || self.max_backoff_secs < 0 {
+            return Err("backoff durations must not be negative".to_string());
+        }
+        Ok(())
+    }
+
+    /// Whether the job is run again after the given attempt ended.
+    pub fn should_retry(&self, attempt: &JobAttempt) -> bool {
+        if attempt.attempt_number >= self.max_attempts {
+            return false;
+        }
+        match attempt.outcome {
+            Some(AttemptOutcome::NodeLost) => true,
+            Some(AttemptOutcome::Failed) => {
+                !self.node_loss_only
+                    && (self.retry_on_exit_codes.is_empty()
+                        || attempt
+                            .exit_code
+                            .is_some_and(|code| self.retry_on_exit_codes.contains(&code)))
+            }
+            _ => false,
+        }
+    }
+
+    /// How long to wait before running the job again after the given attempt.
+    pub fn backoff(&self, attempt_number: i32) -> chrono::Duration {
+        let doublings = u32::try_from(attempt_number.saturating_sub(1))
+            .unwrap_or(0)
+            .min(32);
+        let secs = self
+            .backoff_secs
+            .saturating_mul(1 << doublings)
+            .min(self.max_backoff_secs);
+        chrono::Duration::seconds(secs)
+    }
+}
+
+/// How a single run of a job ended.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AttemptOutcome {
+    Succeeded,
+    Failed,
+    /// The node running the job went away.
+    NodeLost,
+    /// The job was evicted to make room for a higher priority job.
+    Preempted,
+    Cancelled,
+    /// The job ran for longer than its maximum runtime.
+    TimedOut,
+}
+
+/// A single run of a job. A job is attempted again when it is retried, lost
+/// its node or was preempted.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct JobAttempt {
+    pub job_id: JobId,
+    /// Counts up from 1 for every time the job is started.
+    pub attempt_number: i32,
+    /// The rank 0 node the attempt ran on.
+    pub node_id: Option<NodeId>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    /// How the attempt ended, `None` while it is still running.
+    pub outcome: Option<AttemptOutcome>,
+    /// The exit code of the job's container, if it exited by itself.
+    pub exit_code: Option<i32>,
+}
+
+/// Something that happened to a job. A job's events make up its timeline,
+/// e.g. how long it was queued for and why it was requeued.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct JobEvent {
+    /// Counts up in the order events were recorded.
+    pub id: i64,
+    pub job_id: JobId,
+    #[serde(flatten)]
+    pub kind: JobEventKind,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum JobEventKind {
+    /// The job was submitted and queued.
+    Created,
+    /// The scheduler reserved nodes for the job and is starting it. The rank
+    /// 0 node comes first.
+    Assigned { node_ids: Vec<NodeId> },
+    /// The job moved to a new status, e.g. when its agent reported it as
+    /// running or finished.
+    StatusChanged {
+        status: TrainingJobStatus,
+        reason: Option<String>,
+    },
+    /// The job was evicted from its nodes to make room for a higher priority
+    /// job, and queued again.
+    Preempted { reason: String },
+    /// The job was queued again, e.g. to be retried or because it lost its
+    /// node.
+    Requeued { reason: String },
+    /// No cluster in the job's queue can run the job. It stays queued until
+    /// nodes that can join.
+    Unschedulable { reason: String },
+}
+
+/// The most metric samples returned at once.
+pub const MAX_METRIC_SAMPLES: i64 = 10_000;
+
+/// GPUs whose average utilization stays below this are counted as idle.
+pub const IDLE_GPU_UTILIZATION_PERCENT: f64 = 5.0;
+
+/// A job's resource usage on one of its nodes, as sampled by the node's agent
+/// while the job runs.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MetricSample {
+    pub node_id: NodeId,
+    pub sampled_at: DateTime<Utc>,
+    /// The CPU used by the job's container, in millicores. Not set if the
+    /// agent couldn't read it.
+    pub cpu_millicores: Option<i32>,
+    /// The memory used by the job's container, in MB.
+    pub memory_mb: Option<i64>,
+    /// The GPUs reserved for the job on the node.
+    #[serde(default)]
+    pub gpus: Vec<GpuSample>,
+}
+
+/// The usage of a single GPU.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GpuSample {
+    /// The GPU's index on its node.
+    pub index: i32,
+    /// How much of the time the GPU was busy, from 0 to 100.
+    pub utilization_percent: i32,
+    pub memory_used_mb: i64,
+}
+
+/// Which samples of a job's metrics to read.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MetricsQuery {
+    /// Only samples taken after this time.
+    pub since: Option<DateTime<Utc>>,
+}
+
+/// A job's metrics along with how they compare to what it requested.
+#[derive(Debug, Clone, Serialize)]
+pub struct JobMetrics {
+    pub job_id: JobId,
+    pub summary: MetricsSummary,
+    /// Oldest first.
+    pub samples: Vec<MetricSample>,
+}
+
+/// How much of the resources it requested on each node a job has used, to
+/// tell jobs that ask for more than they need.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct MetricsSummary {
+    pub requested_cpu_millicores: i32,
+    pub avg_cpu_millicores: Option<f64>,
+    pub requested_memory_mb: i32,
+    pub peak_memory_mb: Option<i64>,
+    pub requested_gpus: i32,
+    /// The average utilization of the job's GPUs across all of its nodes.
+    pub avg_gpu_utilization_percent: Option<f64>,
+    /// How many of the job's GPUs stayed idle, across all of its nodes.
+    pub idle_gpus: usize,
+}
+
+impl MetricsSummary {
+    pub fn new(resources: &ResourceRequirements, samples: &[MetricSample]) -> Self {
+        let cpu_millicores: Vec<f64> = samples
+            .iter()
+            .filter_map(|sample| sample.cpu_millicores)
+            .map(f64::from)
+            .collect();
+
+        let mut gpus: BTreeMap<(NodeId, i32), Vec<f64>> = BTreeMap::new();
+        for sample in samples {
+            for gpu in &sample.gpus {
+                gpus.entry((sample.node_id, gpu.index))
+                    .or_default()
+                    .push(f64::from(gpu.utilization_percent));
+            }
+        }
+        let gpu_utilization: Vec<f64> =
+            gpus.values().filter_map(|values| average(values)).collect();
+
+        Self {
+            requested_cpu_millicores: resources.cpu_millicores,
+            avg_cpu_millicores: average(&cpu_millicores),
+            requested_memory_mb: resources.memory_mb,
+            peak_memory_mb: samples.iter().filter_map(|sample| sample.memory_mb).max(),
+            requested_gpus: resources.gpu_count(),
+            avg_gpu_utilization_percent: average(&gpu_utilization),
+            idle_gpus: gpu_utilization
+                .iter()
+                .filter(|utilization| **utilization < IDLE_GPU_UTILIZATION_PERCENT)
+                .count(),
+        }
+    }
+}
+
+fn average(values: &[f64]) -> Option<f64> {
+    (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
 }
 
 /// Describes a specific requirement for a GPU.
@@ -24,14 +339,65 @@ pub enum TrainingJobStatus {
 pub struct GpuRequirement {
     /// The number of GPUs required.
     pub count: i32,
-    /// The specific model of the GPU (e.g., "A100", "V100", "RTX4090").
+    /// The specific model of the GPU (e.g., "A100", "V100", "H100").
     /// If None, any GPU model is acceptable.
     pub model: Option<String>,
+    /// The manufacturer of the GPU (e.g., "Nvidia", "AMD").
+    /// If None, any manufacturer is acceptable.
+    #[serde(default)]
+    pub manufacturer: Option<String>,
     /// The minimum required memory for each GPU in gigabytes (e.g., 40, 80).
     /// If None, any GPU memory size is acceptable.
     pub memory_gb: Option<i32>,
 }
 
+impl GpuRequirement {
+    /// Whether the GPUs on a node can satisfy this requirement.
+    ///
+    /// Model and manufacturer names that are not recognised never match.
+    pub fn is_satisfied_by(&self, gpu: &Gpu) -> bool {
+        self.rejections(gpu).is_empty()
+    }
+
+    /// The reasons the GPUs on a node cannot satisfy this requirement, if any.
+    pub fn rejections(&self, gpu: &Gpu) -> Vec<RejectionReason> {
+        let mut reasons = Vec::new();
+        if gpu.count < self.count {
+            reasons.push(RejectionReason::InsufficientGpus {
+                required: self.count,
+                available: gpu.count,
+            });
+        }
+        if let Some(model) = self.model.as_deref() {
+            if !GpuModel::from_str(model).is_ok_and(|model| model == gpu.model) {
+                reasons.push(RejectionReason::WrongGpuModel {
+                    required: model.to_string(),
+                    actual: gpu.model.to_string(),
+                });
+            }
+        }
+        if let Some(manufacturer) = self.manufacturer.as_deref() {
+            if !GpuManufacturer::from_str(manufacturer)
+                .is_ok_and(|manufacturer| manufacturer == gpu.manufacturer)
+            {
+                reasons.push(RejectionReason::WrongGpuManufacturer {
+                    required: manufacturer.to_string(),
+                    actual: gpu.manufacturer.to_string(),
+                });
+            }
+        }
+        if let Some(memory_gb) = self.memory_gb {
+            if gpu.memory_mb < memory_gb.saturating_mul(1024) {
+                reasons.push(RejectionReason::InsufficientGpuMemory {
+                    required_gb: memory_gb,
+                    available_mb: gpu.memory_mb,
+                });
+            }
+        }
+        reasons
+    }
+}
+
 /// Represents the computational resources required for a training job.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResourceRequirements {
@@ -41,20 +407,616 @@ pub struct ResourceRequirements {
     pub gpus: Option<GpuRequirement>,
 }
 
+impl ResourceRequirements {
+    /// The number of GPUs the job needs on each node.
+    pub fn gpu_count(&self) -> i32 {
+        self.gpus.as_ref().map_or(0, |gpus| gpus.count)
+    }
+}
+
+/// What a job runs: a Docker image, and optionally how to start it in place
+/// of the image's defaults.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(from = "JobDefinitionRepr")]
+pub struct JobDefinition {
+    /// The Docker image to run.
+    pub image: String,
+    /// Replaces the image's entrypoint. An empty list clears it.
+    pub entrypoint: Option<Vec<String>>,
+    /// Replaces the image's command.
+    pub command: Option<Vec<String>>,
+    /// Appended to the command. Without a command, they replace the image's
+    /// command, like the arguments of `docker run`.
+    pub args: Vec<String>,
+    /// The directory the job starts in, as an absolute path.
+    pub workdir: Option<String>,
+    /// The user the job runs as, as `user`, `uid` or `uid:gid`.
+    pub user: Option<String>,
+}
+
+/// Job definitions used to be just the image, which is still accepted.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JobDefinitionRepr {
+    Image(String),
+    Structured {
+        image: String,
+        #[serde(default)]
+        entrypoint: Option<Vec<String>>,
+        #[serde(default)]
+        command: Option<Vec<String>>,
+        #[serde(default)]
+        args: Vec<String>,
+        #[serde(default)]
+        workdir: Option<String>,
+        #[serde(default)]
+        user: Option<String>,
+    },
+}
+
+impl From<JobDefinitionRepr> for JobDefinition {
+    fn from(value: JobDefinitionRepr) -> Self {
+        match value {
+            JobDefinitionRepr::Image(image) => Self::new(image),
+            JobDefinitionRepr::Structured {
+                image,
+                entrypoint,
+                command,
+                args,
+                workdir,
+                user,
+            } => Self {
+                image,
+                entrypoint,
+                command,
+                args,
+                workdir,
+                user,
+            },
+        }
+    }
+}
+
+impl JobDefinition {
+    /// Runs the image with its own entrypoint and command.
+    pub fn new(image: impl Into<String>) -> Self {
+        Self {
+            image: image.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Checks that the definition can be run, returning what is wrong with it
+    /// otherwise.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.image.trim().is_empty() {
+            return Err("the image must not be empty".to_string());
+        }
+        if self.command.as_ref().is_some_and(Vec::is_empty) {
+            return Err(
+                "the command must not be empty, leave it out to use the image's".to_string(),
+            );
+        }
+        if let Some(workdir) = &self.workdir {
+            if !workdir.starts_with('/') {
+                return Err(format!(
+                    "the working directory '{workdir}' must be an absolute path"
+                ));
+            }
+        }
+        if self
+            .user
+            .as_ref()
+            .is_some_and(|user| user.trim().is_empty())
+        {
+            return Err("the user must not be empty".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// The most output directories a job may declare.
+pub const MAX_JOB_OUTPUTS: usize = 16;
+
+/// The shortest interval at which outputs may be uploaded while a job runs.
+const MIN_UPLOAD_INTERVAL_SECS: i64 = 60;
+
+/// A directory in a job's container whose files are uploaded to the artifact
+/// store once the job has finished.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct JobOutput {
+    /// The absolute path of the directory in the container.
+    pub path: String,
+    /// Also upload the directory's new and changed files this often while the
+    /// job runs, e.g. for checkpoints.
+    #[serde(default)]
+    pub upload_interval_secs: Option<i64>,
+}
+
+impl JobOutput {
+    /// Checks the outputs a job is created with, returning what is wrong with
+    /// them otherwise.
+    pub fn validate_all(outputs: &[JobOutput]) -> Result<(), String> {
+        if outputs.len() > MAX_JOB_OUTPUTS {
+            return Err(format!("a job may have at most {MAX_JOB_OUTPUTS} outputs"));
+        }
+        for (i, output) in outputs.iter().enumerate() {
+            output.validate()?;
+            if outputs[..i].iter().any(|other| other.path == output.path) {
+                return Err(format!("'{}' is listed more than once", output.path));
+            }
+        }
+        Ok(())
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        validate_absolute_path(&self.path)?;
+        if self
+            .upload_interval_secs
+            .is_some_and(|secs| secs < MIN_UPLOAD_INTERVAL_SECS)
+        {
+            return Err(format!(
+                "outputs may be uploaded at most every {MIN_UPLOAD_INTERVAL_SECS} seconds"
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Checks that a path is absolute and normalized, and not the root itself.
+fn validate_absolute_path(path: &str) -> Result<(), String> {
+    let Some(relative) = path.strip_prefix('/') else {
+        return Err(format!("'{path}' must be an absolute path"));
+    };
+    if relative
+        .split('/')
+        .any(|segment| segment.is_empty() || segment == "." || segment == "..")
+    {
+        return Err(format!("'{path}' must be a normalized path below the root"));
+    }
+    Ok(())
+}
+
+/// The most volumes a job may mount.
+pub const MAX_JOB_VOLUMES: usize = 16;
+
+/// Where the files of a volume come from.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum VolumeKind {
+    /// A directory on the node, which the node's agent has to allow.
+    HostPath,
+    /// A Docker volume on the node, which the node's agent has to allow.
+    Named,
+    /// An entry of the node's dataset cache, which is kept between jobs until
+    /// the cache runs out of space.
+    Cache,
+}
+
+/// Storage on the node mounted into a job's container.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct JobVolume {
+    pub kind: VolumeKind,
+    /// The directory on the node for host paths, and the name of the volume
+    /// or cache entry otherwise.
+    pub source: String,
+    /// The absolute path the volume is mounted at in the container.
+    pub target: String,
+    #[serde(default)]
+    pub read_only: bool,
+}
+
+impl JobVolume {
+    /// Checks the volumes a job is created with, returning what is wrong with
+    /// them otherwise. Volumes can't be mounted over one of the job's outputs.
+    pub fn validate_all(volumes: &[JobVolume], outputs: &[JobOutput]) -> Result<(), String> {
+        if volumes.len() > MAX_JOB_VOLUMES {
+            return Err(format!("a job may have at most {MAX_JOB_VOLUMES} volumes"));
+        }
+        for (i, volume) in volumes.iter().enumerate() {
+            volume.validate()?;
+            if volumes[..i]
+                .iter()
+                .any(|other| other.target == volume.target)
+                || outputs.iter().any(|output| output.path == volume.target)
+            {
+                return Err(format!("'{}' is mounted more than once", volume.target));
+            }
+        }
+        Ok(())
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        validate_absolute_path(&self.target)?;
+        match self.kind {
+            VolumeKind::HostPath => validate_absolute_path(&self.source),
+            VolumeKind::Named | VolumeKind::Cache => {
+                // The names Docker accepts for volumes, which are also safe to
+                // use as a directory name.
+                let valid = self.source.len() <= 128
+                    && self
+                        .source
+                        .chars()
+                        .next()
+                        .is_some_and(|c| c.is_ascii_alphanumeric())
+                    && self
+                        .source
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'));
+                if valid {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "'{}' must start with a letter or digit and only contain letters, \
+                         digits, '_', '.' and '-'",
+                        self.source
+                    ))
+                }
+            }
+        }
+    }
+}
+
+/// How a job's container ended, as reported by the node that ran it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct JobExit {
+    /// The exit code of the job's container, if it got to run.
+    pub exit_code: Option<i32>,
+    /// Whether the container was killed for running out of memory.
+    pub oom_killed: bool,
+    /// Why the job couldn't be run, if its container never got to exit.
+    pub error: Option<JobError>,
+}
+
+impl JobExit {
+    /// A short description of how the job ended, e.g. for its events.
+    pub fn describe(&self) -> String {
+        match (&self.error, self.oom_killed, self.exit_code) {
+            (Some(error), _, _) => match error.kind {
+                JobErrorKind::ImagePull => format!("Could not pull its image: {}", error.message),
+                JobErrorKind::Executor => format!("Could not be run: {}", error.message),
+            },
+            (None, true, Some(exit_code)) => {
+                format!(
+                    "Ran out of memory and was killed with exit code {}",
+                    exit_code
+                )
+            }
+            (None, true, None) => "Ran out of memory and was killed".to_string(),
+            (None, false, Some(exit_code)) => format!("Exited with code {}", exit_code),
+            (None, false, None) => "Exited".to_string(),
+        }
+    }
+}
+
+/// A job that failed before its container could run to completion.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct JobError {
+    pub kind: JobErrorKind,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum JobErrorKind {
+    /// The job's image could not be pulled, e.g. because it doesn't exist or
+    /// the node may not access it.
+    ImagePull,
+    /// Anything else that kept the node from running the job.
+    Executor,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrainingJob {
     pub id: JobId,
     pub name: String,
-    // rename to docker uri
-    pub definition: String,
+    pub definition: JobDefinition,
     pub status: TrainingJobStatus,
     pub node_id: Option<NodeId>,
     pub queue_id: Option<QueueId>,
     pub resource_requirements: ResourceRequirements,
+    /// The number of nodes that must be launched together to run this job.
+    pub node_count: i32,
+    /// When the job was last preempted by a higher priority job, if ever.
+    pub preempted_at: Option<DateTime<Utc>>,
+    /// Why the job was last preempted.
+    pub preemption_reason: Option<String>,
+    /// The jobs that have to finish before this job is scheduled.
+    pub dependencies: Vec<JobDependency>,
+    /// Overrides the retry policy of the job's queue.
+    pub retry_policy: Option<RetryPolicy>,
+    /// The job is not scheduled before this time, e.g. while backing off
+    /// before a retry.
+    pub not_before: Option<DateTime<Utc>>,
+    /// The node the job's last attempt failed on. The scheduler only places
+    /// the job on it again when no other node has room.
+    pub avoid_node_id: Option<NodeId>,
+    /// How long the job may run before it is stopped, in seconds. `None`
+    /// lets it run indefinitely.
+    pub max_runtime_secs: Option<i64>,
+    /// When the job's current attempt started running.
+    pub started_at: Option<DateTime<Utc>>,
+    /// How the job's latest attempt ended, as reported by its node.
+    pub exit: Option<JobExit>,
+    /// The array the job was submitted as part of, if any.
+    pub array_id: Option<ArrayId>,
+    /// The job's position in its array, starting at 0.
+    pub array_index: Option<i32>,
+    /// Parameter values passed to the job as environment variables.
+    pub parameters: BTreeMap<String, String>,
+    /// Labels a node must have, with these values, to run the job.
+    pub node_selector: BTreeMap<String, String>,
+    /// The node taints the job may be placed on despite.
+    pub tolerations: Vec<Toleration>,
+    /// Environment variables to set in the job's container.
+    pub env: BTreeMap<String, String>,
+    /// Secrets passed to the job as environment variables, by variable name.
+    /// Their values are only resolved for the agents running the job.
+    pub secrets: BTreeMap<String, String>,
+    /// Directories in the job's container whose files are uploaded as
+    /// artifacts.
+    pub outputs: Vec<JobOutput>,
+    /// Storage on the node mounted into the job's container.
+    pub volumes: Vec<JobVolume>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// The most jobs a single array may expand into.
+pub const MAX_ARRAY_SIZE: usize = 10_000;
+
+/// Environment variables the agent sets itself, which parameters may not
+/// override.
+const RESERVED_PARAMETERS: [&str; 4] = ["MASTER_ADDR", "MASTER_PORT", "NODE_RANK", "NNODES"];
+
+/// The parameter values an array's jobs are run with.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ParameterSpace {
+    /// One job for every combination of the given values.
+    Grid(BTreeMap<String, Vec<serde_json::Value>>),
+    /// One job for every entry.
+    List(Vec<BTreeMap<String, serde_json::Value>>),
+}
+
+impl ParameterSpace {
+    /// The parameters of each job of the array, in index order. Values are
+    /// turned into strings, as they end up in environment variables.
+    pub fn expand(&self) -> Result<Vec<BTreeMap<String, String>>, String> {
+        let combinations = match self {
+            Self::Grid(grid) => {
+                let size = grid.values().try_fold(1usize, |size, values| {
+                    size.checked_mul(values.len())
+                        .filter(|size| *size <= MAX_ARRAY_SIZE)
+                        .ok_or_else(|| format!("an array may have at most {MAX_ARRAY_SIZE} jobs"))
+                })?;
+                if grid.is_empty() || size == 0 {
+                    return Err("every parameter needs at least one value".to_string());
+                }
+                // Parameters are nested in name order, the first one changes slowest.
+                let mut combinations = vec![BTreeMap::new()];
+                for (name, values) in grid {
+                    combinations = combinations
+                        .into_iter()
+                        .flat_map(|combination: BTreeMap<&String, &serde_json::Value>| {
+                            values.iter().map(move |value| {
+                                let mut combination = combination.clone();
+                                combination.insert(name, value);
+                                combination
+                            })
+                        })
+                        .collect();
+                }
+                combinations
+            }
+            Self::List(list) => {
+                if list.is_empty() {
+                    return Err("the list of parameters is empty".to_string());
+                }
+                if list.len() > MAX_ARRAY_SIZE {
+                    return Err(format!("an array may have at most {MAX_ARRAY_SIZE} jobs"));
+                }
+                list.iter().map(|entry| entry.iter().collect()).collect()
+            }
+        };
+
+        combinations
+            .into_iter()
+            .map(|combination| {
+                combination
+                    .into_iter()
+                    .map(|(name, value)| Ok((parameter_name(name)?, parameter_value(name, value)?)))
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+fn parameter_name(name: &str) -> Result<String, String> {
+    let valid = name
+        .chars()
+        .enumerate()
+        .all(|(i, c)| c == '_' || c.is_ascii_alphabetic() || (i > 0 && c.is_ascii_digit()));
+    if name.is_empty() || !valid {
+        return Err(format!("'{name}' is not a valid environment variable name"));
+    }
+    if name.starts_with("LILAC_") || RESERVED_PARAMETERS.contains(&name) {
+        return Err(format!("'{name}' is reserved for variables set by Lilac"));
+    }
+    Ok(name.to_string())
+}
+
+/// Checks the environment variables a job is created with, returning what is
+/// wrong with them otherwise. A variable may either have a value or come
+/// from a secret.
+pub fn validate_env(
+    env: &BTreeMap<String, String>,
+    secrets: &BTreeMap<String, String>,
+) -> Result<(), String> {
+    for name in env.keys().chain(secrets.keys()) {
+        parameter_name(name)?;
+    }
+    if let Some(name) = env.keys().find(|name| secrets.contains_key(*name)) {
+        return Err(format!("'{name}' is set both as a value and a secret"));
+    }
+    Ok(())
+}
+
+fn parameter_value(name: &str, value: &serde_json::Value) -> Result<String, String> {
+    match value {
+        serde_json::Value::String(value) => Ok(value.clone()),
+        serde_json::Value::Number(_) | serde_json::Value::Bool(_) => Ok(value.to_string()),
+        _ => Err(format!(
+            "the values of '{name}' must be strings, numbers or booleans"
+        )),
+    }
+}
+
+/// A set of jobs submitted together from one template, each with its own
+/// parameters.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobArray {
+    pub id: ArrayId,
+    pub name: String,
+    /// The number of jobs in the array.
+    pub size: i32,
+    /// How many of the array's jobs may be starting or running at once. No
+    /// limit applies when `None`.
+    pub max_concurrent: Option<i32>,
+    pub parameters: ParameterSpace,
+    pub created_at: DateTime<Utc>,
+}
+
+/// How many of an array's jobs are in each status.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct JobArrayCounts {
+    pub queued: i32,
+    pub starting: i32,
+    pub running: i32,
+    pub succeeded: i32,
+    pub failed: i32,
+    pub cancelled: i32,
+    pub timed_out: i32,
+}
+
+impl JobArrayCounts {
+    pub fn from_jobs(jobs: &[TrainingJob]) -> Self {
+        let mut counts = Self::default();
+        for job in jobs {
+            let count = match job.status {
+                TrainingJobStatus::Queued => &mut counts.queued,
+                TrainingJobStatus::Starting => &mut counts.starting,
+                TrainingJobStatus::Running => &mut counts.running,
+                TrainingJobStatus::Succeeded => &mut counts.succeeded,
+                TrainingJobStatus::Failed => &mut counts.failed,
+                TrainingJobStatus::Cancelled => &mut counts.cancelled,
+                TrainingJobStatus::TimedOut => &mut counts.timed_out,
+            };
+            *count += 1;
+        }
+        counts
+    }
+
+    /// The status of the array as a whole. It is running until all of its
+    /// jobs have finished, and succeeded only if every one of them did.
+    pub fn status(&self) -> TrainingJobStatus {
+        let finished = self.succeeded + self.failed + self.cancelled + self.timed_out;
+        if self.starting + self.running > 0 || (self.queued > 0 && finished > 0) {
+            TrainingJobStatus::Running
+        } else if self.queued > 0 {
+            TrainingJobStatus::Queued
+        } else if self.failed + self.timed_out > 0 {
+            TrainingJobStatus::Failed
+        } else if self.cancelled > 0 {
+            TrainingJobStatus::Cancelled
+        } else {
+            TrainingJobStatus::Succeeded
+        }
+    }
+}
+
+/// The share of a node reserved for a job, together with the rank the node
+/// runs as.
+///
+/// Single node jobs have exactly one allocation with rank 0. Multi-node jobs
+/// have one allocation per node and rank 0 acts as the rendezvous coordinator.
+/// A node can hold allocations for several jobs as long as it has capacity.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct JobAllocation {
+    pub job_id: JobId,
+    pub node_id: NodeId,
+    pub rank: i32,
+    pub cpu_millicores: i32,
+    pub memory_mb: i32,
+    pub gpu_count: i32,
+}
+
+/// A piece of a job's log output, in the form an agent sent it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LogChunk {
+    /// Orders the chunks of all jobs. Reading from a chunk's offset returns
+    /// only the chunks that came after it.
+    pub offset: i64,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Which part of a job's logs to read.
+#[derive(Debug, Default, Deserialize, PartialEq, Clone)]
+pub struct LogQuery {
+    /// Only return the chunks that came after the chunk with this offset.
+    pub offset: Option<i64>,
+    /// Only return this many of the most recent lines.
+    pub tail: Option<i64>,
+}
+
+/// The part of a job's logs a [LogQuery] asked for.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct JobLogs {
+    pub chunks: Vec<LogChunk>,
+    /// The offset to read the logs that come after these from.
+    pub next_offset: i64,
+}
+
+impl JobLogs {
+    /// Builds the logs from chunks in offset order, keeping only the last
+    /// `tail` lines if given.
+    pub fn new(mut chunks: Vec<LogChunk>, query: &LogQuery) -> Self {
+        let next_offset = chunks
+            .last()
+            .map_or(query.offset.unwrap_or(0), |chunk| chunk.offset);
+
+        if let Some(tail) = query.tail {
+            let mut lines = 0;
+            let mut first = chunks.len();
+            while first > 0 && lines < tail {
+                first -= 1;
+                let chunk = &mut chunks[first];
+                let content = chunk.content.strip_suffix('\n').unwrap_or(&chunk.content);
+                let chunk_lines = content.split('\n').count() as i64;
+                if lines + chunk_lines > tail {
+                    // Cut the chunk down to the lines that are still missing.
+                    let skip = usize::try_from(chunk_lines - (tail - lines)).unwrap_or(0);
+                    let start = content
+                        .match_indices('\n')
+                        .nth(skip - 1)
+                        .map_or(0, |(index, _)| index + 1);
+                    chunk.content = chunk.content[start..].to_string();
+                    lines = tail;
+                } else {
+                    lines += chunk_lines;
+                }
+            }
+            chunks.drain(..first);
+        }
+
+        Self {
+            chunks,
+            next_offset,
+        }
+    }
+}
+
 #[derive(Debug, Default, Deserialize, PartialEq, Clone)]
 pub struct GetTrainingJobsFilters {
     pub id: Option<JobId>,
@@ -1,6 +1,13 @@
-use super::models::{GetTrainingJobsFilters, TrainingJob, TrainingJobStatus};
-use crate::domain::{cluster::models::NodeId, queue::models::QueueId, training_job::models::JobId};
+use super::models::{
+    ArrayId, AttemptOutcome, GetTrainingJobsFilters, JobArray, JobAttempt, JobEvent, JobEventKind,
+    JobExit, LogChunk, MetricSample, MetricsQuery, TrainingJob, TrainingJobStatus,
+};
+use crate::domain::{
+    cluster::models::NodeId, queue::models::QueueId, scheduler::models::SchedulingReport,
+    training_job::models::JobId,
+};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 
 #[derive(Debug, thiserror::Error)]
 pub enum TrainingJobRepositoryError {
@@ -16,6 +23,32 @@ pub enum TrainingJobRepositoryError {
 #[async_trait]
 pub trait TrainingJobRepository: Send + Sync {
     async fn create(&self, training_job: &TrainingJob) -> Result<(), TrainingJobRepositoryError>;
+    /// Creates several jobs in one transaction, so either all or none of them
+    /// exist afterwards.
+    async fn create_all(
+        &self,
+        training_jobs: &[TrainingJob],
+    ) -> Result<(), TrainingJobRepositoryError>;
+    /// Creates an array together with its jobs in one transaction.
+    async fn create_array(
+        &self,
+        array: &JobArray,
+        training_jobs: &[TrainingJob],
+    ) -> Result<(), TrainingJobRepositoryError>;
+    async fn get_job_array(
+        &self,
+        array_id: &ArrayId,
+    ) -> Result<JobArray, TrainingJobRepositoryError>;
+    /// The jobs of an array, in index order.
+    async fn get_array_jobs(
+        &self,
+        array_id: &ArrayId,
+    ) -> Result<Vec<TrainingJob>, TrainingJobRepositoryError>;
+    /// How many jobs of the array are starting or running.
+    async fn count_active_array_jobs(
+        &self,
+        array_id: &ArrayId,
+    ) -> Result<i64, TrainingJobRepositoryError>;
     async fn get_training_jobs(
         &self,
         filters: GetTrainingJobsFilters,
@@ -33,15 +66,126 @@ pub trait TrainingJobRepository: Send + Sync {
         id: &JobId,
         status: TrainingJobStatus,
     ) -> Result<(), TrainingJobRepositoryError>;
-    async fn mark_as_starting(
+    /// Appends a chunk to the job's logs, then drops the job's oldest chunks
+    /// until its logs fit in `max_bytes`.
+    async fn append_logs(
         &self,
         id: &JobId,
-        node_id: &NodeId,
+        content: &str,
+        max_bytes: i64,
     ) -> Result<(), TrainingJobRepositoryError>;
-    async fn post_logs(&self, id: &JobId, logs: String) -> Result<(), TrainingJobRepositoryError>;
+    /// The job's log chunks in offset order, starting after `after` if given.
+    /// With `tail_lines`, only the most recent chunks holding at least that
+    /// many lines are returned.
+    async fn get_logs(
+        &self,
+        id: &JobId,
+        after: Option<i64>,
+        tail_lines: Option<i64>,
+    ) -> Result<Vec<LogChunk>, TrainingJobRepositoryError>;
+    /// Deletes the log chunks of every job that were written before `cutoff`,
+    /// returning how many were deleted.
+    async fn delete_logs_before(
+        &self,
+        cutoff: DateTime<Utc>,
+    ) -> Result<u64, TrainingJobRepositoryError>;
+    /// Stores resource usage samples taken while the job ran.
+    async fn record_metrics(
+        &self,
+        job_id: &JobId,
+        samples: &[MetricSample],
+    ) -> Result<(), TrainingJobRepositoryError>;
+    /// The job's samples the query asks for, oldest first and at most
+    /// `MAX_METRIC_SAMPLES` of them.
+    async fn get_metrics(
+        &self,
+        job_id: &JobId,
+        query: &MetricsQuery,
+    ) -> Result<Vec<MetricSample>, TrainingJobRepositoryError>;
+    /// Deletes the samples of every job that were taken before `cutoff`,
+    /// returning how many were deleted.
+    async fn delete_metrics_before(
+        &self,
+        cutoff: DateTime<Utc>,
+    ) -> Result<u64, TrainingJobRepositoryError>;
     async fn reset_job_status(&self, job_id: &JobId) -> Result<(), TrainingJobRepositoryError>;
+    /// Evicts the job from its nodes to make room for a higher priority job:
+    /// releases the nodes, queues the job again, records the preemption and
+    /// finishes its attempt, all in one transaction. Returns `false` without
+    /// changing anything if the job is no longer starting or running.
+    async fn preempt_job(
+        &self,
+        job_id: &JobId,
+        reason: &str,
+    ) -> Result<bool, TrainingJobRepositoryError>;
+    /// The jobs that depend on the given job.
+    async fn get_dependent_jobs(
+        &self,
+        job_id: &JobId,
+    ) -> Result<Vec<TrainingJob>, TrainingJobRepositoryError>;
     async fn get_jobs_by_status(
         &self,
         status: TrainingJobStatus,
     ) -> Result<Vec<TrainingJob>, TrainingJobRepositoryError>;
+    /// Stores the outcome of a scheduling attempt, replacing the previous one.
+    async fn record_scheduling_report(
+        &self,
+        report: &SchedulingReport,
+    ) -> Result<(), TrainingJobRepositoryError>;
+    /// The outcome of the most recent attempt to schedule the job, if any.
+    async fn get_scheduling_report(
+        &self,
+        job_id: &JobId,
+    ) -> Result<Option<SchedulingReport>, TrainingJobRepositoryError>;
+    /// Records how the job's running attempt ended. Returns the attempt, or
+    /// `None` if the job had no running attempt.
+    async fn finish_attempt(
+        &self,
+        job_id: &JobId,
+        outcome: AttemptOutcome,
+        exit_code: Option<i32>,
+    ) -> Result<Option<JobAttempt>, TrainingJobRepositoryError>;
+    /// Stores how the job's latest attempt ended.
+    async fn set_exit(
+        &self,
+        job_id: &JobId,
+        exit: &JobExit,
+    ) -> Result<(), TrainingJobRepositoryError>;
+    /// All attempts of the job, oldest first.
+    async fn get_attempts(
+        &self,
+        job_id: &JobId,
+    ) -> Result<Vec<JobAttempt>, TrainingJobRepositoryError>;
+    /// Failed attempts of failed jobs that the scheduler hasn't yet decided
+    /// whether to retry.
+    async fn get_failed_attempts_to_evaluate(
+        &self,
+    ) -> Result<Vec<JobAttempt>, TrainingJobRepositoryError>;
+    /// Records that the scheduler has decided whether to retry the attempt.
+    async fn mark_retry_evaluated(
+        &self,
+        attempt: &JobAttempt,
+    ) -> Result<(), TrainingJobRepositoryError>;
+    /// Queues the job again, to be scheduled no earlier than `not_before` and
+    /// preferably away from `avoid_node_id`.
+    async fn requeue_for_retry(
+        &self,
+        job_id: &JobId,
+        not_before: DateTime<Utc>,
+        avoid_node_id: Option<NodeId>,
+    ) -> Result<(), TrainingJobRepositoryError>;
+    /// Appends the same event to the timeline of each of the jobs.
+    async fn record_event(
+        &self,
+        job_ids: &[JobId],
+        event: &JobEventKind,
+    ) -> Result<(), TrainingJobRepositoryError>;
+    /// The job's events, oldest first.
+    async fn get_events(&self, job_id: &JobId)
+        -> Result<Vec<JobEvent>, TrainingJobRepositoryError>;
+    /// Running jobs with a maximum runtime that ran out before `deadline`.
+    async fn get_jobs_timed_out_before(
+        &self,
+        deadline: DateTime<Utc>,
+    ) -> Result<Vec<TrainingJob>, TrainingJobRepositoryError>;
 }
@@ -1,21 +1,39 @@
-use std::sync::Arc;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
 
 use super::{
-    models::{GetTrainingJobsFilters, TrainingJob, TrainingJobStatus},
+    models::{
+        validate_env, ArrayId, AttemptOutcome, GetTrainingJobsFilters, JobArray, JobAttempt,
+        JobDependency, JobEvent, JobEventKind, JobLogs, JobMetrics, JobOutput, JobVolume, LogQuery,
+        MetricSample, MetricsQuery, MetricsSummary, ResourceRequirements, TrainingJob,
+        TrainingJobStatus,
+    },
     ports::TrainingJobRepository,
 };
 use crate::{
+    config::LogsConfig,
     domain::{
         cluster::{
-            models::NodeId,
+            models::{ClusterId, GpuManufacturer, GpuModel},
             ports::{ClusterRepository, ClusterRepositoryError},
         },
+        queue::ports::{QueueRepository, QueueRepositoryError},
+        scheduler::{models::SchedulingReport, notifier::SchedulerNotifier},
+        secret::ports::{SecretRepository, SecretRepositoryError},
         training_job::{models::JobId, ports::TrainingJobRepositoryError},
+        user::models::UserId,
+    },
+    inbound::http::routes::training_jobs::models::{
+        CreateJobArrayRequest, CreatePipelineRequest, CreateTrainingJobRequest,
     },
-    inbound::http::routes::training_jobs::models::CreateTrainingJobRequest,
 };
 use async_trait::async_trait;
 use thiserror::Error;
+use tokio::sync::Notify;
 
 #[derive(Debug, Error)]
 pub enum TrainingJobServiceError {
@@ -25,6 +43,36 @@ pub enum TrainingJobServiceError {
     TrainingJobNotFound(String),
     #[error("invalid training job definition: {0}")]
     InvalidDefinition(#[from] serde_json::Error),
+    #[error("invalid job definition: {0}")]
+    InvalidJobDefinition(String),
+    #[error("invalid node count {0}, a job must run on at least one node")]
+    InvalidNodeCount(i32),
+    #[error("unknown GPU model {0}")]
+    UnknownGpuModel(String),
+    #[error("unknown GPU manufacturer {0}")]
+    UnknownGpuManufacturer(String),
+    #[error("dependency {0} not found")]
+    DependencyNotFound(JobId),
+    #[error("invalid pipeline: {0}")]
+    InvalidPipeline(String),
+    #[error("invalid job array: {0}")]
+    InvalidArray(String),
+    #[error("job array {0} not found")]
+    ArrayNotFound(String),
+    #[error("invalid retry policy: {0}")]
+    InvalidRetryPolicy(String),
+    #[error("invalid maximum runtime: {0}")]
+    InvalidMaxRuntime(String),
+    #[error("invalid environment variables: {0}")]
+    InvalidEnv(String),
+    #[error("invalid outputs: {0}")]
+    InvalidOutputs(String),
+    #[error("invalid volumes: {0}")]
+    InvalidVolumes(String),
+    #[error("secret {0} not found")]
+    SecretNotFound(String),
+    #[error("training job {0} was not placed on this cluster")]
+    JobNotOnCluster(JobId),
     #[error(transparent)]
     Unknown(#[from] anyhow::Error),
 }
@@ -57,10 +105,35 @@ impl From<ClusterRepositoryError> for TrainingJobServiceError {
 #[cfg_attr(test, mockall::automock)]
 #[async_trait]
 pub trait TrainingJobService: Send + Sync {
+    /// Creates a job on behalf of `submitted_by`, who has to own every secret
+    /// the job receives. Jobs submitted on behalf of no one can't receive any.
     async fn create(
         &self,
         request: CreateTrainingJobRequest,
+        submitted_by: Option<UserId>,
     ) -> Result<TrainingJob, TrainingJobServiceError>;
+    /// Creates all jobs of a pipeline at once. The jobs are returned in the
+    /// order they were requested in.
+    async fn create_pipeline(
+        &self,
+        request: CreatePipelineRequest,
+        submitted_by: Option<UserId>,
+    ) -> Result<Vec<TrainingJob>, TrainingJobServiceError>;
+    /// Creates one job for every set of parameters of the array. The jobs are
+    /// returned in index order.
+    async fn create_array(
+        &self,
+        request: CreateJobArrayRequest,
+        submitted_by: Option<UserId>,
+    ) -> Result<(JobArray, Vec<TrainingJob>), TrainingJobServiceError>;
+    /// The array together with its jobs, in index order.
+    async fn get_job_array(
+        &self,
+        id: &ArrayId,
+    ) -> Result<(JobArray, Vec<TrainingJob>), TrainingJobServiceError>;
+    /// Cancels every job of the array that hasn't finished yet, returning
+    /// the IDs of the cancelled jobs.
+    async fn cancel_array(&self, id: &ArrayId) -> Result<Vec<JobId>, TrainingJobServiceError>;
     async fn get_training_jobs(
         &self,
         filters: GetTrainingJobsFilters,
@@ -74,58 +147,471 @@ pub trait TrainingJobService: Send + Sync {
         &self,
         id: &JobId,
     ) -> Result<TrainingJob, TrainingJobServiceError>;
-    async fn mark_as_starting(
+    /// Gets a job that was placed on a node of the given cluster, so agents
+    /// can only act on their own cluster's jobs.
+    async fn get_job_on_cluster(
         &self,
         id: &JobId,
-        node_id: &NodeId,
+        cluster_id: &ClusterId,
+    ) -> Result<TrainingJob, TrainingJobServiceError>;
+    /// Appends log output sent by the agent of a node on `cluster_id`. Only
+    /// the cluster the job was placed on may add to its logs.
+    async fn post_logs(
+        &self,
+        id: &JobId,
+        cluster_id: &ClusterId,
+        logs: String,
     ) -> Result<(), TrainingJobServiceError>;
-    async fn post_logs(&self, id: &JobId, logs: String) -> Result<(), TrainingJobServiceError>;
+    /// Reads the part of the job's logs the query asks for.
+    async fn get_logs(
+        &self,
+        id: &JobId,
+        query: LogQuery,
+    ) -> Result<JobLogs, TrainingJobServiceError>;
+    /// Waits for log output after `offset` and returns it. Returns `None`
+    /// once the job has finished and all of its output has been read.
+    async fn follow_logs(
+        &self,
+        id: &JobId,
+        offset: i64,
+    ) -> Result<Option<JobLogs>, TrainingJobServiceError>;
     async fn cancel(&self, id: &JobId) -> Result<(), TrainingJobServiceError>;
+    /// Fails the job before its agent could start it, e.g. because the job
+    /// can't be handed over, releasing its nodes.
+    async fn fail(&self, id: &JobId, reason: String) -> Result<(), TrainingJobServiceError>;
+    /// The outcome of the most recent attempt to schedule the job, if the
+    /// scheduler has looked at it yet.
+    async fn get_scheduling_report(
+        &self,
+        id: &JobId,
+    ) -> Result<Option<SchedulingReport>, TrainingJobServiceError>;
+    /// Every time the job was run, oldest first.
+    async fn get_attempts(&self, id: &JobId) -> Result<Vec<JobAttempt>, TrainingJobServiceError>;
+    /// Everything that happened to the job, oldest first.
+    async fn get_events(&self, id: &JobId) -> Result<Vec<JobEvent>, TrainingJobServiceError>;
+    /// Stores resource usage samples sent by the agent of a node on
+    /// `cluster_id`. Only the cluster the job was placed on may add to them.
+    async fn post_metrics(
+        &self,
+        id: &JobId,
+        cluster_id: &ClusterId,
+        samples: Vec<MetricSample>,
+    ) -> Result<(), TrainingJobServiceError>;
+    /// The job's resource usage samples the query asks for, summarized
+    /// against the resources the job requested.
+    async fn get_metrics(
+        &self,
+        id: &JobId,
+        query: MetricsQuery,
+    ) -> Result<JobMetrics, TrainingJobServiceError>;
+}
+
+/// Appends an event to the timeline of each of the jobs. The timeline only
+/// describes changes, so failing to record an event doesn't fail the change.
+pub async fn record_event<R: TrainingJobRepository + ?Sized>(
+    repository: &R,
+    job_ids: &[JobId],
+    event: JobEventKind,
+) {
+    if let Err(e) = repository.record_event(job_ids, &event).await {
+        tracing::error!(
+            "Error recording event {:?} for jobs {:?}: {}",
+            event,
+            job_ids,
+            e
+        );
+    }
 }
 
 pub struct TrainingJobServiceImpl {
     repository: Arc<dyn TrainingJobRepository>,
     cluster_repo: Arc<dyn ClusterRepository>,
+    queue_repo: Arc<dyn QueueRepository>,
+    secret_repo: Arc<dyn SecretRepository>,
+    scheduler: SchedulerNotifier,
+    logs_config: LogsConfig,
+    /// Wakes up the readers following logs on this replica when output is
+    /// appended. Output posted to other replicas is picked up by polling.
+    new_logs: Arc<Notify>,
 }
 
+/// How often followed logs are checked for output posted to other replicas.
+const LOG_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
 impl TrainingJobServiceImpl {
     pub fn new(
         repository: Arc<dyn TrainingJobRepository>,
         cluster_repo: Arc<dyn ClusterRepository>,
+        queue_repo: Arc<dyn QueueRepository>,
+        secret_repo: Arc<dyn SecretRepository>,
+        scheduler: SchedulerNotifier,
+        logs_config: LogsConfig,
     ) -> Self {
         Self {
             repository,
             cluster_repo,
+            queue_repo,
+            secret_repo,
+            scheduler,
+            logs_config,
+            new_logs: Arc::new(Notify::new()),
         }
     }
-}
 
-#[async_trait]
-impl TrainingJobService for TrainingJobServiceImpl {
-    async fn create(
-        &self,
+    /// Builds a new queued job from a request, rejecting requirements the
+    /// scheduler would never be able to match.
+    fn new_training_job(
         request: CreateTrainingJobRequest,
     ) -> Result<TrainingJob, TrainingJobServiceError> {
-        let job_id = JobId::generate();
+        request
+            .definition
+            .validate()
+            .map_err(TrainingJobServiceError::InvalidJobDefinition)?;
+
+        if request.node_count < 1 {
+            return Err(TrainingJobServiceError::InvalidNodeCount(
+                request.node_count,
+            ));
+        }
+
+        let resource_requirements: ResourceRequirements =
+            serde_json::from_value(request.resource_requirements)?;
+
+        // Reject GPU names the scheduler would never be able to match.
+        if let Some(gpus) = &resource_requirements.gpus {
+            if let Some(model) = &gpus.model {
+                GpuModel::from_str(model)
+                    .map_err(|_| TrainingJobServiceError::UnknownGpuModel(model.clone()))?;
+            }
+            if let Some(manufacturer) = &gpus.manufacturer {
+                GpuManufacturer::from_str(manufacturer).map_err(|_| {
+                    TrainingJobServiceError::UnknownGpuManufacturer(manufacturer.clone())
+                })?;
+            }
+        }
+
+        if let Some(retry_policy) = &request.retry_policy {
+            retry_policy
+                .validate()
+                .map_err(TrainingJobServiceError::InvalidRetryPolicy)?;
+        }
+
+        if let Some(max_runtime_secs) = request.max_runtime_secs {
+            if max_runtime_secs < 1 {
+                return Err(TrainingJobServiceError::InvalidMaxRuntime(format!(
+                    "must be at least 1 second, got {max_runtime_secs}s"
+                )));
+            }
+        }
+
+        validate_env(&request.env, &request.secrets)
+            .map_err(TrainingJobServiceError::InvalidEnv)?;
+        JobOutput::validate_all(&request.outputs)
+            .map_err(TrainingJobServiceError::InvalidOutputs)?;
+        JobVolume::validate_all(&request.volumes, &request.outputs)
+            .map_err(TrainingJobServiceError::InvalidVolumes)?;
+
         let now = chrono::Utc::now();
 
-        let training_job = TrainingJob {
-            id: job_id,
+        Ok(TrainingJob {
+            id: JobId::generate(),
             name: request.name,
             definition: request.definition,
             status: TrainingJobStatus::Queued,
             node_id: None,
             queue_id: Some(request.queue_id),
-            resource_requirements: serde_json::from_value(request.resource_requirements)?,
+            resource_requirements,
+            node_count: request.node_count,
+            preempted_at: None,
+            preemption_reason: None,
+            dependencies: request.depends_on,
+            retry_policy: request.retry_policy,
+            not_before: None,
+            avoid_node_id: None,
+            max_runtime_secs: request.max_runtime_secs,
+            started_at: None,
+            exit: None,
+            array_id: None,
+            array_index: None,
+            parameters: BTreeMap::new(),
+            node_selector: request.node_selector,
+            tolerations: request.tolerations,
+            env: request.env,
+            secrets: request.secrets,
+            outputs: request.outputs,
+            volumes: request.volumes,
             created_at: now,
             updated_at: now,
+        })
+    }
+
+    /// Applies the runtime default and limit of the job's queue.
+    async fn apply_queue_limits(
+        &self,
+        job: &mut TrainingJob,
+    ) -> Result<(), TrainingJobServiceError> {
+        let Some(queue_id) = &job.queue_id else {
+            return Ok(());
+        };
+        let queue = match self.queue_repo.get_queue_by_id(queue_id).await {
+            Ok(queue) => queue,
+            // Storing the job fails by itself when its queue doesn't exist.
+            Err(QueueRepositoryError::NotFound(_)) => return Ok(()),
+            Err(e) => return Err(TrainingJobServiceError::Unknown(e.into())),
         };
+        job.max_runtime_secs = queue
+            .max_runtime_for(job.max_runtime_secs)
+            .map_err(TrainingJobServiceError::InvalidMaxRuntime)?;
+        Ok(())
+    }
+
+    /// Checks that every job depended on exists.
+    async fn check_dependencies(
+        &self,
+        dependencies: &[JobDependency],
+    ) -> Result<(), TrainingJobServiceError> {
+        for dependency in dependencies {
+            match self
+                .repository
+                .get_training_job_by_id(&dependency.job_id)
+                .await
+            {
+                Ok(_) => {}
+                Err(TrainingJobRepositoryError::NotFound(_)) => {
+                    return Err(TrainingJobServiceError::DependencyNotFound(
+                        dependency.job_id,
+                    ))
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that every secret the job receives exists and is owned by
+    /// whoever submits the job. Other users' secrets are reported as missing,
+    /// so their names don't give away that they exist.
+    async fn check_secrets(
+        &self,
+        secrets: &BTreeMap<String, String>,
+        submitted_by: Option<UserId>,
+    ) -> Result<(), TrainingJobServiceError> {
+        for name in secrets.values() {
+            match self.secret_repo.get_secret_by_name(name).await {
+                Ok(secret) if Some(secret.owner_id) == submitted_by => {}
+                Ok(_) | Err(SecretRepositoryError::NotFound(_)) => {
+                    return Err(TrainingJobServiceError::SecretNotFound(name.clone()))
+                }
+                Err(e) => return Err(TrainingJobServiceError::Unknown(e.into())),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Whether the dependencies between the given jobs form a cycle. Dependencies
+/// on jobs outside of the given ones are ignored.
+fn has_dependency_cycle(jobs: &[TrainingJob]) -> bool {
+    let ids: HashSet<JobId> = jobs.iter().map(|job| job.id).collect();
+    let mut remaining: HashMap<JobId, usize> = jobs
+        .iter()
+        .map(|job| {
+            // A job may list the same upstream job more than once, but is
+            // only freed up once when it is taken out.
+            let upstream: HashSet<JobId> = job
+                .dependencies
+                .iter()
+                .map(|dependency| dependency.job_id)
+                .filter(|job_id| ids.contains(job_id))
+                .collect();
+            (job.id, upstream.len())
+        })
+        .collect();
+
+    // Repeatedly take out jobs whose upstream jobs have all been taken out.
+    // Whatever is left over depends on itself.
+    let mut ready: Vec<JobId> = remaining
+        .iter()
+        .filter(|(_, upstream)| **upstream == 0)
+        .map(|(id, _)| *id)
+        .collect();
+    let mut visited = 0;
+    while let Some(id) = ready.pop() {
+        visited += 1;
+        for job in jobs {
+            if job
+                .dependencies
+                .iter()
+                .any(|dependency| dependency.job_id == id)
+            {
+                let upstream = remaining.entry(job.id).or_default();
+                *upstream -= 1;
+                if *upstream == 0 {
+                    ready.push(job.id);
+                }
+            }
+        }
+    }
+
+    visited < jobs.len()
+}
+
+#[async_trait]
+impl TrainingJobService for TrainingJobServiceImpl {
+    async fn create(
+        &self,
+        request: CreateTrainingJobRequest,
+        submitted_by: Option<UserId>,
+    ) -> Result<TrainingJob, TrainingJobServiceError> {
+        let mut training_job = Self::new_training_job(request)?;
+        self.apply_queue_limits(&mut training_job).await?;
+        self.check_dependencies(&training_job.dependencies).await?;
+        self.check_secrets(&training_job.secrets, submitted_by)
+            .await?;
 
         self.repository.create(&training_job).await?;
+        record_event(&*self.repository, &[training_job.id], JobEventKind::Created).await;
+        self.scheduler.notify();
 
         Ok(training_job)
     }
 
+    async fn create_pipeline(
+        &self,
+        request: CreatePipelineRequest,
+        submitted_by: Option<UserId>,
+    ) -> Result<Vec<TrainingJob>, TrainingJobServiceError> {
+        if request.jobs.is_empty() {
+            return Err(TrainingJobServiceError::InvalidPipeline(
+                "a pipeline needs at least one job".to_string(),
+            ));
+        }
+
+        let mut ids = HashMap::new();
+        let mut jobs = Vec::with_capacity(request.jobs.len());
+        for pipeline_job in request.jobs {
+            let mut job = Self::new_training_job(pipeline_job.job)?;
+            self.apply_queue_limits(&mut job).await?;
+            self.check_dependencies(&job.dependencies).await?;
+            self.check_secrets(&job.secrets, submitted_by).await?;
+            if ids.insert(pipeline_job.key.clone(), job.id).is_some() {
+                return Err(TrainingJobServiceError::InvalidPipeline(format!(
+                    "duplicate job key '{}'",
+                    pipeline_job.key
+                )));
+            }
+            jobs.push((pipeline_job.needs, job));
+        }
+
+        let jobs = jobs
+            .into_iter()
+            .map(|(needs, mut job)| {
+                for need in needs {
+                    let job_id = ids.get(&need.key).ok_or_else(|| {
+                        TrainingJobServiceError::InvalidPipeline(format!(
+                            "unknown job key '{}'",
+                            need.key
+                        ))
+                    })?;
+                    job.dependencies.push(JobDependency {
+                        job_id: *job_id,
+                        condition: need.condition,
+                    });
+                }
+                Ok(job)
+            })
+            .collect::<Result<Vec<_>, TrainingJobServiceError>>()?;
+
+        if has_dependency_cycle(&jobs) {
+            return Err(TrainingJobServiceError::InvalidPipeline(
+                "the jobs' dependencies form a cycle".to_string(),
+            ));
+        }
+
+        self.repository.create_all(&jobs).await?;
+        let job_ids: Vec<JobId> = jobs.iter().map(|job| job.id).collect();
+        record_event(&*self.repository, &job_ids, JobEventKind::Created).await;
+        self.scheduler.notify();
+
+        Ok(jobs)
+    }
+
+    async fn create_array(
+        &self,
+        request: CreateJobArrayRequest,
+        submitted_by: Option<UserId>,
+    ) -> Result<(JobArray, Vec<TrainingJob>), TrainingJobServiceError> {
+        if let Some(max_concurrent) = request.max_concurrent {
+            if max_concurrent < 1 {
+                return Err(TrainingJobServiceError::InvalidArray(format!(
+                    "max_concurrent must be at least 1, got {max_concurrent}"
+                )));
+            }
+        }
+        let parameters = request
+            .parameters
+            .expand()
+            .map_err(TrainingJobServiceError::InvalidArray)?;
+
+        let mut template = Self::new_training_job(request.job)?;
+        self.apply_queue_limits(&mut template).await?;
+        self.check_dependencies(&template.dependencies).await?;
+        self.check_secrets(&template.secrets, submitted_by).await?;
+
+        let array = JobArray {
+            id: ArrayId::generate(),
+            name: template.name.clone(),
+            size: parameters.len() as i32,
+            max_concurrent: request.max_concurrent,
+            parameters: request.parameters,
+            created_at: template.created_at,
+        };
+        let jobs: Vec<TrainingJob> = parameters
+            .into_iter()
+            .enumerate()
+            .map(|(index, parameters)| TrainingJob {
+                id: JobId::generate(),
+                name: format!("{}-{}", array.name, index),
+                array_id: Some(array.id),
+                array_index: Some(index as i32),
+                parameters,
+                ..template.clone()
+            })
+            .collect();
+
+        self.repository.create_array(&array, &jobs).await?;
+        let job_ids: Vec<JobId> = jobs.iter().map(|job| job.id).collect();
+        record_event(&*self.repository, &job_ids, JobEventKind::Created).await;
+        self.scheduler.notify();
+
+        Ok((array, jobs))
+    }
+
+    async fn get_job_array(
+        &self,
+        id: &ArrayId,
+    ) -> Result<(JobArray, Vec<TrainingJob>), TrainingJobServiceError> {
+        let array = match self.repository.get_job_array(id).await {
+            Ok(array) => array,
+            Err(TrainingJobRepositoryError::NotFound(id)) => {
+                return Err(TrainingJobServiceError::ArrayNotFound(id))
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let jobs = self.repository.get_array_jobs(id).await?;
+        Ok((array, jobs))
+    }
+
+    async fn cancel_array(&self, id: &ArrayId) -> Result<Vec<JobId>, TrainingJobServiceError> {
+        let (_, jobs) = self.get_job_array(id).await?;
+        let mut cancelled = Vec::new();
+        for job in jobs.into_iter().filter(|job| !job.status.is_terminal()) {
+            self.cancel(&job.id).await?;
+            cancelled.push(job.id);
+        }
+        Ok(cancelled)
+    }
+
     async fn get_training_jobs(
         &self,
         filters: GetTrainingJobsFilters,
@@ -138,7 +624,17 @@ impl TrainingJobService for TrainingJobServiceImpl {
         id: &JobId,
         status: TrainingJobStatus,
     ) -> Result<(), TrainingJobServiceError> {
-        Ok(self.repository.update_status(id, status).await?)
+        self.repository.update_status(id, status.clone()).await?;
+        record_event(
+            &*self.repository,
+            &[*id],
+            JobEventKind::StatusChanged {
+                status,
+                reason: None,
+            },
+        )
+        .await;
+        Ok(())
     }
 
     async fn get_training_job_by_id(
@@ -148,24 +644,96 @@ impl TrainingJobService for TrainingJobServiceImpl {
         Ok(self.repository.get_training_job_by_id(id).await?)
     }
 
-    async fn mark_as_starting(
+    async fn get_job_on_cluster(
         &self,
         id: &JobId,
-        node_id: &NodeId,
+        cluster_id: &ClusterId,
+    ) -> Result<TrainingJob, TrainingJobServiceError> {
+        let job = self.repository.get_training_job_by_id(id).await?;
+        let node_id = job
+            .node_id
+            .ok_or(TrainingJobServiceError::JobNotOnCluster(*id))?;
+        let node = match self.cluster_repo.get_cluster_node_by_id(&node_id).await {
+            Ok(node) => node,
+            Err(ClusterRepositoryError::NotFound(_)) => {
+                return Err(TrainingJobServiceError::JobNotOnCluster(*id))
+            }
+            Err(e) => return Err(e.into()),
+        };
+        if node.cluster_id != *cluster_id {
+            return Err(TrainingJobServiceError::JobNotOnCluster(*id));
+        }
+        Ok(job)
+    }
+
+    async fn post_logs(
+        &self,
+        id: &JobId,
+        cluster_id: &ClusterId,
+        logs: String,
     ) -> Result<(), TrainingJobServiceError> {
-        Ok(self.repository.mark_as_starting(id, node_id).await?)
+        self.get_job_on_cluster(id, cluster_id).await?;
+
+        if logs.is_empty() {
+            return Ok(());
+        }
+        self.repository
+            .append_logs(id, &logs, self.logs_config.max_bytes_per_job)
+            .await?;
+        self.new_logs.notify_waiters();
+        Ok(())
     }
 
-    async fn post_logs(&self, id: &JobId, logs: String) -> Result<(), TrainingJobServiceError> {
-        Ok(self.repository.post_logs(id, logs).await?)
+    async fn get_logs(
+        &self,
+        id: &JobId,
+        query: LogQuery,
+    ) -> Result<JobLogs, TrainingJobServiceError> {
+        // Fail with not found for unknown jobs rather than returning nothing.
+        self.repository.get_training_job_by_id(id).await?;
+        let chunks = self
+            .repository
+            .get_logs(id, query.offset, query.tail)
+            .await?;
+        Ok(JobLogs::new(chunks, &query))
+    }
+
+    async fn follow_logs(
+        &self,
+        id: &JobId,
+        offset: i64,
+    ) -> Result<Option<JobLogs>, TrainingJobServiceError> {
+        let query = LogQuery {
+            offset: Some(offset),
+            tail: None,
+        };
+        loop {
+            // Listen before reading, so output appended in between isn't missed.
+            let new_logs = self.new_logs.notified();
+            tokio::pin!(new_logs);
+            new_logs.as_mut().enable();
+
+            // The status is read first, so that output written right before
+            // the job finished is still returned.
+            let job = self.repository.get_training_job_by_id(id).await?;
+            let chunks = self.repository.get_logs(id, query.offset, None).await?;
+            if !chunks.is_empty() {
+                return Ok(Some(JobLogs::new(chunks, &query)));
+            }
+            if job.status.is_terminal() {
+                return Ok(None);
+            }
+
+            let _ = tokio::time::timeout(LOG_POLL_INTERVAL, new_logs).await;
+        }
     }
 
     async fn cancel(&self, id: &JobId) -> Result<(), TrainingJobServiceError> {
         let job = self.repository.get_training_job_by_id(id).await?;
 
-        if let Some(node_id) = job.node_id {
+        if job.node_id.is_some() {
             self.cluster_repo
-                .clear_assigned_job_id(&node_id)
+                .release_job_nodes(&job.id)
                 .await
                 .map_err(|e| TrainingJobServiceError::Unknown(e.into()))?;
         }
@@ -173,7 +741,94 @@ impl TrainingJobService for TrainingJobServiceImpl {
         self.repository
             .update_status(id, TrainingJobStatus::Cancelled)
             .await?;
+        self.repository
+            .finish_attempt(id, AttemptOutcome::Cancelled, None)
+            .await?;
+        record_event(
+            &*self.repository,
+            &[*id],
+            JobEventKind::StatusChanged {
+                status: TrainingJobStatus::Cancelled,
+                reason: None,
+            },
+        )
+        .await;
+        // The job's nodes are free for other jobs now.
+        self.scheduler.notify();
+
+        Ok(())
+    }
+
+    async fn fail(&self, id: &JobId, reason: String) -> Result<(), TrainingJobServiceError> {
+        self.cluster_repo
+            .release_job_nodes(id)
+            .await
+            .map_err(|e| TrainingJobServiceError::Unknown(e.into()))?;
+        self.repository
+            .update_status(id, TrainingJobStatus::Failed)
+            .await?;
+        // The scheduler decides whether the failed attempt is retried.
+        self.repository
+            .finish_attempt(id, AttemptOutcome::Failed, None)
+            .await?;
+        record_event(
+            &*self.repository,
+            &[*id],
+            JobEventKind::StatusChanged {
+                status: TrainingJobStatus::Failed,
+                reason: Some(reason),
+            },
+        )
+        .await;
+        self.scheduler.notify();
 
         Ok(())
     }
+
+    async fn get_scheduling_report(
+        &self,
+        id: &JobId,
+    ) -> Result<Option<SchedulingReport>, TrainingJobServiceError> {
+        Ok(self.repository.get_scheduling_report(id).await?)
+    }
+
+    async fn get_attempts(&self, id: &JobId) -> Result<Vec<JobAttempt>, TrainingJobServiceError> {
+        // Fail with not found for unknown jobs rather than returning nothing.
+        self.repository.get_training_job_by_id(id).await?;
+        Ok(self.repository.get_attempts(id).await?)
+    }
+
+    async fn get_events(&self, id: &JobId) -> Result<Vec<JobEvent>, TrainingJobServiceError> {
+        // Fail with not found for unknown jobs rather than returning nothing.
+        self.repository.get_training_job_by_id(id).await?;
+        Ok(self.repository.get_events(id).await?)
+    }
+
+    async fn post_metrics(
+        &self,
+        id: &JobId,
+        cluster_id: &ClusterId,
+        samples: Vec<MetricSample>,
+    ) -> Result<(), TrainingJobServiceError> {
+        self.get_job_on_cluster(id, cluster_id).await?;
+
+        if samples.is_empty() {
+            return Ok(());
+        }
+        Ok(self.repository.record_metrics(id, &samples).await?)
+    }
+
+    async fn get_metrics(
+        &self,
+        id: &JobId,
+        query: MetricsQuery,
+    ) -> Result<JobMetrics, TrainingJobServiceError> {
+        let job = self.repository.get_training_job_by_id(id).await?;
+        let samples = self.repository.get_metrics(id, &query).await?;
+        Ok(JobMetrics {
+            job_id: *id,
+            summary: MetricsSummary::new(&job.resource_requirements, &samples),
+            samples,
+        })
+    }
 }
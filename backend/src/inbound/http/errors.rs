@@ -1,6 +1,8 @@
 use crate::domain::{
-    auth::service::AuthServiceError, cluster::service::ClusterServiceError,
-    queue::service::QueueServiceError, training_job::service::TrainingJobServiceError,
+    artifact::service::ArtifactServiceError, auth::service::AuthServiceError,
+    cluster::service::ClusterServiceError, queue::service::QueueServiceError,
+    schedule::service::ScheduleServiceError, scheduler::leader::LeaderElectionError,
+    secret::service::SecretServiceError, training_job::service::TrainingJobServiceError,
     user::service::UserServiceError,
 };
 
@@ -26,6 +28,12 @@ impl From<QueueServiceError> for ApiError {
     fn from(err: QueueServiceError) -> Self {
         match err {
             QueueServiceError::InvalidPermissions => Self::Forbidden,
+            QueueServiceError::InvalidRetryPolicy(reason) => {
+                Self::BadRequest(format!("Invalid retry policy: {reason}"))
+            }
+            QueueServiceError::InvalidRuntimeLimits(reason) => {
+                Self::BadRequest(format!("Invalid runtime limits: {reason}"))
+            }
             QueueServiceError::QueueExists { .. } => Self::Conflict("Queue already exists".into()),
             QueueServiceError::QueueNotFound(_) => Self::NotFound("Queue not found".to_string()),
             QueueServiceError::Unknown(e) => {
@@ -36,6 +44,86 @@ impl From<QueueServiceError> for ApiError {
     }
 }
 
+impl From<ArtifactServiceError> for ApiError {
+    fn from(err: ArtifactServiceError) -> Self {
+        match err {
+            ArtifactServiceError::NotConfigured => {
+                Self::BadRequest("No artifact store is configured".to_string())
+            }
+            ArtifactServiceError::JobNotFound(_) => {
+                Self::NotFound("Training job not found".to_string())
+            }
+            ArtifactServiceError::JobNotOnCluster(_) => Self::Forbidden,
+            ArtifactServiceError::ArtifactNotFound(_) => {
+                Self::NotFound("Artifact not found".to_string())
+            }
+            ArtifactServiceError::InvalidPath(reason) => {
+                Self::BadRequest(format!("Invalid artifact path: {reason}"))
+            }
+            ArtifactServiceError::Unknown(e) => {
+                tracing::error!(error = ?e, backtrace = %e.backtrace(), "unknown error occurred");
+                Self::InternalServerError("Something went wrong".to_string())
+            }
+        }
+    }
+}
+
+impl From<SecretServiceError> for ApiError {
+    fn from(err: SecretServiceError) -> Self {
+        match err {
+            SecretServiceError::InvalidName(reason) => {
+                Self::BadRequest(format!("Invalid secret name: {reason}"))
+            }
+            SecretServiceError::SecretNotFound(_) => Self::NotFound("Secret not found".to_string()),
+            SecretServiceError::NameTaken(name) => {
+                Self::Conflict(format!("Secret {name} belongs to another user"))
+            }
+            SecretServiceError::NotConfigured => {
+                Self::BadRequest("No encryption key is configured".to_string())
+            }
+            SecretServiceError::Unknown(e) => {
+                tracing::error!(error = ?e, backtrace = %e.backtrace(), "unknown error occurred");
+                Self::InternalServerError("Something went wrong".to_string())
+            }
+        }
+    }
+}
+
+impl From<ScheduleServiceError> for ApiError {
+    fn from(err: ScheduleServiceError) -> Self {
+        match err {
+            ScheduleServiceError::InvalidCron(reason) => {
+                Self::BadRequest(format!("Invalid cron expression: {reason}"))
+            }
+            ScheduleServiceError::InvalidTemplate(reason) => {
+                Self::BadRequest(format!("Invalid job template: {reason}"))
+            }
+            ScheduleServiceError::ScheduleExists { .. } => {
+                Self::Conflict("Schedule already exists".into())
+            }
+            ScheduleServiceError::ScheduleNotFound(_) => {
+                Self::NotFound("Schedule not found".to_string())
+            }
+            ScheduleServiceError::InvalidPermissions => Self::Forbidden,
+            ScheduleServiceError::Unknown(e) => {
+                tracing::error!(error = ?e, backtrace = %e.backtrace(), "unknown error occurred");
+                Self::InternalServerError("Something went wrong".to_string())
+            }
+        }
+    }
+}
+
+impl From<LeaderElectionError> for ApiError {
+    fn from(err: LeaderElectionError) -> Self {
+        match err {
+            LeaderElectionError::Unknown(e) => {
+                tracing::error!(error = ?e, backtrace = %e.backtrace(), "unknown error occurred");
+                Self::InternalServerError("Something went wrong".to_string())
+            }
+        }
+    }
+}
+
 impl From<ClusterServiceError> for ApiError {
     fn from(err: ClusterServiceError) -> Self {
         match err {
@@ -46,6 +134,13 @@ impl From<ClusterServiceError> for ApiError {
             ClusterServiceError::ClusterNotFound(_) => {
                 Self::NotFound("Cluster not found".to_string())
             }
+            ClusterServiceError::NodeNotFound(_) => Self::NotFound("Node not found".to_string()),
+            ClusterServiceError::InvalidTaint(reason) => {
+                Self::BadRequest(format!("Invalid taint: {reason}"))
+            }
+            e @ ClusterServiceError::IncompleteDistributedContext { .. } => {
+                Self::Conflict(e.to_string())
+            }
             ClusterServiceError::Unknown(e) => {
                 tracing::error!(error = ?e, backtrace = %e.backtrace(), "unknown error occurred");
                 Self::InternalServerError("Something went wrong".to_string())
@@ -93,6 +188,49 @@ impl From<TrainingJobServiceError> for ApiError {
             TrainingJobServiceError::InvalidDefinition(e) => {
                 Self::BadRequest(format!("Invalid job definition: {e}"))
             }
+            TrainingJobServiceError::InvalidJobDefinition(reason) => {
+                Self::BadRequest(format!("Invalid job definition: {reason}"))
+            }
+            TrainingJobServiceError::UnknownGpuModel(model) => {
+                Self::BadRequest(format!("Unknown GPU model: {model}"))
+            }
+            TrainingJobServiceError::UnknownGpuManufacturer(manufacturer) => {
+                Self::BadRequest(format!("Unknown GPU manufacturer: {manufacturer}"))
+            }
+            TrainingJobServiceError::InvalidNodeCount(count) => {
+                Self::BadRequest(format!("Invalid node count: {count}"))
+            }
+            TrainingJobServiceError::DependencyNotFound(job_id) => {
+                Self::UnprocessableEntity(format!("Dependency {job_id} not found"))
+            }
+            TrainingJobServiceError::InvalidPipeline(reason) => {
+                Self::BadRequest(format!("Invalid pipeline: {reason}"))
+            }
+            TrainingJobServiceError::InvalidArray(reason) => {
+                Self::BadRequest(format!("Invalid job array: {reason}"))
+            }
+            TrainingJobServiceError::ArrayNotFound(_) => {
+                Self::NotFound("Job array not found".to_string())
+            }
+            TrainingJobServiceError::InvalidRetryPolicy(reason) => {
+                Self::BadRequest(format!("Invalid retry policy: {reason}"))
+            }
+            TrainingJobServiceError::InvalidMaxRuntime(reason) => {
+                Self::BadRequest(format!("Invalid maximum runtime: {reason}"))
+            }
+            TrainingJobServiceError::InvalidEnv(reason) => {
+                Self::BadRequest(format!("Invalid environment variables: {reason}"))
+            }
+            TrainingJobServiceError::InvalidOutputs(reason) => {
+                Self::BadRequest(format!("Invalid outputs: {reason}"))
+            }
+            TrainingJobServiceError::InvalidVolumes(reason) => {
+                Self::BadRequest(format!("Invalid volumes: {reason}"))
+            }
+            TrainingJobServiceError::SecretNotFound(name) => {
+                Self::UnprocessableEntity(format!("Secret {name} not found"))
+            }
+            TrainingJobServiceError::JobNotOnCluster(_) => Self::Forbidden,
             TrainingJobServiceError::Unknown(e) => {
                 tracing::error!(error = ?e, backtrace = %e.backtrace(), "unknown error occurred");
                 Self::InternalServerError("Something went wrong".to_string())
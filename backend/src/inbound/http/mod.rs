@@ -18,10 +18,15 @@ use uuid::Uuid;
 use crate::{
     config::LilacConfig,
     domain::{
-        auth::service::AuthService, cluster::service::ClusterService, queue::service::QueueService,
-        training_job::service::TrainingJobService, user::service::UserService,
+        artifact::service::ArtifactService, auth::service::AuthService,
+        cluster::service::ClusterService, queue::service::QueueService,
+        schedule::service::ScheduleService, scheduler::leader::LeaderElection,
+        secret::service::SecretService, training_job::service::TrainingJobService,
+        user::service::UserService,
+    },
+    inbound::http::routes::{
+        artifacts, clusters, queues, scheduler, schedules, secrets, training_jobs,
     },
-    inbound::http::routes::{clusters, queues, training_jobs},
     outbound::persistence::postgres::session_repository::PostgresSessionStore,
 };
 
@@ -35,6 +40,10 @@ pub struct AppState {
     pub auth_service: Arc<dyn AuthService>,
     pub training_job_service: Arc<dyn TrainingJobService>,
     pub queue_service: Arc<dyn QueueService>,
+    pub schedule_service: Arc<dyn ScheduleService>,
+    pub secret_service: Arc<dyn SecretService>,
+    pub artifact_service: Arc<dyn ArtifactService>,
+    pub leader_election: Arc<dyn LeaderElection>,
 }
 
 impl FromRef<AppState> for Arc<LilacConfig> {
@@ -73,6 +82,30 @@ impl FromRef<AppState> for Arc<dyn QueueService> {
     }
 }
 
+impl FromRef<AppState> for Arc<dyn ScheduleService> {
+    fn from_ref(state: &AppState) -> Self {
+        state.schedule_service.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<dyn SecretService> {
+    fn from_ref(state: &AppState) -> Self {
+        state.secret_service.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<dyn ArtifactService> {
+    fn from_ref(state: &AppState) -> Self {
+        state.artifact_service.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<dyn LeaderElection> {
+    fn from_ref(state: &AppState) -> Self {
+        state.leader_election.clone()
+    }
+}
+
 pub struct HttpServer {
     app: Router,
     listener: TcpListener,
@@ -105,6 +138,10 @@ impl HttpServer {
             .merge(clusters::router())
             .merge(training_jobs::training_jobs_router())
             .merge(queues::routes())
+            .merge(schedules::routes())
+            .merge(secrets::routes())
+            .merge(artifacts::routes())
+            .merge(scheduler::router())
             .layer(
                 ServiceBuilder::new()
                     .layer(
@@ -142,8 +179,10 @@ impl AppState {
     /// Creates a new mock AppState with the provided configuration.
     pub fn new_mock_with_config(config: LilacConfig) -> Self {
         use crate::domain::{
-            auth::service::MockAuthService, cluster::service::MockClusterService,
-            queue::service::MockQueueService, training_job::service::MockTrainingJobService,
+            artifact::service::MockArtifactService, auth::service::MockAuthService,
+            cluster::service::MockClusterService, queue::service::MockQueueService,
+            schedule::service::MockScheduleService, scheduler::leader::MockLeaderElection,
+            secret::service::MockSecretService, training_job::service::MockTrainingJobService,
             user::service::MockUserService,
         };
 
@@ -154,6 +193,10 @@ impl AppState {
             auth_service: Arc::new(MockAuthService::new()),
             training_job_service: Arc::new(MockTrainingJobService::new()),
             queue_service: Arc::new(MockQueueService::new()),
+            schedule_service: Arc::new(MockScheduleService::new()),
+            secret_service: Arc::new(MockSecretService::new()),
+            artifact_service: Arc::new(MockArtifactService::new()),
+            leader_election: Arc::new(MockLeaderElection::new()),
         }
     }
 
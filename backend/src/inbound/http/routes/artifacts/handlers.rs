@@ -0,0 +1,85 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Redirect},
+    Json,
+};
+use axum_extra::{
+    headers::{authorization::Bearer, Authorization},
+    TypedHeader,
+};
+use secrecy::SecretString;
+
+use crate::{
+    domain::{auth::models::UserOrApiKey, training_job::models::JobId},
+    inbound::http::{errors::ApiError, AppState},
+};
+
+use super::models::{
+    HttpListArtifactsResponse, HttpPresignUploadsRequest, HttpPresignUploadsResponse,
+    HttpRecordArtifactsRequest,
+};
+
+/// Hands out URLs for the agent running the job to upload its output files
+/// to, authenticated with its cluster's API key.
+pub async fn presign_artifact_uploads(
+    State(state): State<AppState>,
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    Path(job_id): Path<JobId>,
+    Json(request): Json<HttpPresignUploadsRequest>,
+) -> Result<Json<HttpPresignUploadsResponse>, ApiError> {
+    let cluster = state
+        .cluster_service
+        .authenticate_by_api_key(&SecretString::from(auth.token().to_string()))
+        .await?;
+
+    let uploads = state
+        .artifact_service
+        .presign_uploads(&job_id, &cluster.id, request.paths)
+        .await?;
+
+    Ok(Json(HttpPresignUploadsResponse { uploads }))
+}
+
+/// Records the files the agent running the job has uploaded, authenticated
+/// with its cluster's API key.
+pub async fn record_artifacts(
+    State(state): State<AppState>,
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    Path(job_id): Path<JobId>,
+    Json(request): Json<HttpRecordArtifactsRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let cluster = state
+        .cluster_service
+        .authenticate_by_api_key(&SecretString::from(auth.token().to_string()))
+        .await?;
+
+    state
+        .artifact_service
+        .record_uploads(&job_id, &cluster.id, request.artifacts)
+        .await?;
+
+    Ok((StatusCode::OK, Json(())))
+}
+
+/// Lists a job's artifacts.
+pub async fn list_artifacts(
+    _user: UserOrApiKey,
+    State(state): State<AppState>,
+    Path(job_id): Path<JobId>,
+) -> Result<Json<HttpListArtifactsResponse>, ApiError> {
+    let artifacts = state.artifact_service.list_artifacts(&job_id).await?;
+
+    Ok(Json(HttpListArtifactsResponse { artifacts }))
+}
+
+/// Redirects to a short-lived URL the artifact can be downloaded from.
+pub async fn download_artifact(
+    _user: UserOrApiKey,
+    State(state): State<AppState>,
+    Path((job_id, path)): Path<(JobId, String)>,
+) -> Result<Redirect, ApiError> {
+    let url = state.artifact_service.download_url(&job_id, &path).await?;
+
+    Ok(Redirect::temporary(url.as_str()))
+}
@@ -0,0 +1,25 @@
+pub mod handlers;
+pub mod models;
+
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+use crate::inbound::http::AppState;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/training_jobs/{job_id}/artifacts",
+            get(handlers::list_artifacts).post(handlers::record_artifacts),
+        )
+        .route(
+            "/training_jobs/{job_id}/artifacts/uploads",
+            post(handlers::presign_artifact_uploads),
+        )
+        .route(
+            "/training_jobs/{job_id}/artifacts/{*path}",
+            get(handlers::download_artifact),
+        )
+}
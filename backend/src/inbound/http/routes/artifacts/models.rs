@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+use crate::domain::artifact::models::{Artifact, ArtifactFile, ArtifactUpload};
+
+/// The files an agent wants to upload from a job's outputs.
+#[derive(Clone, Debug, Deserialize)]
+pub struct HttpPresignUploadsRequest {
+    pub paths: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct HttpPresignUploadsResponse {
+    pub uploads: Vec<ArtifactUpload>,
+}
+
+/// The files an agent has uploaded from a job's outputs.
+#[derive(Clone, Debug, Deserialize)]
+pub struct HttpRecordArtifactsRequest {
+    pub artifacts: Vec<ArtifactFile>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct HttpListArtifactsResponse {
+    pub artifacts: Vec<Artifact>,
+}
@@ -12,8 +12,9 @@ use crate::{
         auth::models::Claims,
         cluster::{
             models::{ClusterId, NodeId, UpdateNodeStatusRequest},
-            service::ClusterService,
+            service::{ClusterService, ClusterServiceError},
         },
+        secret::service::SecretService,
         training_job::service::TrainingJobService,
         user::models::{ApiKeyId, NewApiKey},
     },
@@ -23,7 +24,7 @@ use crate::{
             CreateClusterHttpRequest, CreateClusterHttpResponse, GetClusterDetailsHttpResponse,
             GetClusterHttpResponse, HttpApiKey, HttpClusterNode, HttpClusterNodeHeartbeat,
             HttpHeartbeatResponse, HttpJobDetails, ListClusterJobsHttpResponse,
-            ListClusterNodesHttpResponse, ListClustersHttpResponse,
+            ListClusterNodesHttpResponse, ListClustersHttpResponse, SetNodeTaintsRequest,
         },
     },
 };
@@ -126,6 +127,7 @@ pub async fn cluster_node_heartbeat(
     Path(node_id): Path<NodeId>,
     State(cluster_service): State<Arc<dyn ClusterService>>,
     State(training_job_service): State<Arc<dyn TrainingJobService>>,
+    State(secret_service): State<Arc<dyn SecretService>>,
     TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
     Json(req): Json<HttpClusterNodeHeartbeat>,
 ) -> Result<Json<HttpHeartbeatResponse>, ApiError> {
@@ -141,18 +143,55 @@ pub async fn cluster_node_heartbeat(
             memory_info: req.memory_info,
             cpu_info: req.cpu_info,
             gpu_info: req.gpu_info,
-            job_info: req.job_info,
+            jobs: req.jobs,
+            address: req.address,
+            labels: req.labels,
         })
         .await?;
 
-    let assigned_job = if let Some(job_id) = node.assigned_job_id {
-        let job_details = training_job_service.get_training_job_by_id(&job_id).await?;
-        Some(HttpJobDetails::from(job_details))
-    } else {
-        None
-    };
+    let mut assigned_jobs = Vec::with_capacity(node.allocations.len());
+    for allocation in &node.allocations {
+        let job = training_job_service
+            .get_training_job_by_id(&allocation.job_id)
+            .await?;
+        let distributed = match cluster_service
+            .get_distributed_context(&job, &node.id)
+            .await
+        {
+            Ok(distributed) => distributed,
+            // The node gets the job once its rendezvous details are known.
+            Err(e @ ClusterServiceError::IncompleteDistributedContext { .. }) => {
+                tracing::warn!(node_id = %node.id, "Holding back job: {}", e);
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        };
+        // Secrets are only resolved for the nodes the job is allocated to. A
+        // job whose secrets can't be read fails on its own, and the node still
+        // gets its other jobs.
+        let secrets = match secret_service
+            .resolve_secrets(&job.secrets, job.created_at)
+            .await
+        {
+            Ok(secrets) => secrets,
+            Err(e) => {
+                tracing::error!(job_id = %job.id, "Failing job: {}", e);
+                training_job_service
+                    .fail(&job.id, format!("Failed to resolve secrets: {e}"))
+                    .await?;
+                continue;
+            }
+        };
+        let mut details = HttpJobDetails {
+            gpu_count: allocation.gpu_count,
+            distributed,
+            ..HttpJobDetails::from(job)
+        };
+        details.env.extend(secrets);
+        assigned_jobs.push(details);
+    }
 
-    Ok(Json(HttpHeartbeatResponse { assigned_job }))
+    Ok(Json(HttpHeartbeatResponse { assigned_jobs }))
 }
 
 #[axum::debug_handler(state = AppState)]
@@ -165,6 +204,49 @@ pub async fn get_node(
     Ok(Json(node.into()))
 }
 
+#[axum::debug_handler(state = AppState)]
+pub async fn cordon_node(
+    _claims: Claims,
+    State(cluster_service): State<Arc<dyn ClusterService>>,
+    Path(node_id): Path<NodeId>,
+) -> Result<Json<HttpClusterNode>, ApiError> {
+    let node = cluster_service.cordon_node(&node_id).await?;
+    Ok(Json(node.into()))
+}
+
+#[axum::debug_handler(state = AppState)]
+pub async fn drain_node(
+    _claims: Claims,
+    State(cluster_service): State<Arc<dyn ClusterService>>,
+    Path(node_id): Path<NodeId>,
+) -> Result<Json<HttpClusterNode>, ApiError> {
+    let node = cluster_service.drain_node(&node_id).await?;
+    Ok(Json(node.into()))
+}
+
+#[axum::debug_handler(state = AppState)]
+pub async fn uncordon_node(
+    _claims: Claims,
+    State(cluster_service): State<Arc<dyn ClusterService>>,
+    Path(node_id): Path<NodeId>,
+) -> Result<Json<HttpClusterNode>, ApiError> {
+    let node = cluster_service.uncordon_node(&node_id).await?;
+    Ok(Json(node.into()))
+}
+
+#[axum::debug_handler(state = AppState)]
+pub async fn set_node_taints(
+    _claims: Claims,
+    State(cluster_service): State<Arc<dyn ClusterService>>,
+    Path(node_id): Path<NodeId>,
+    Json(req): Json<SetNodeTaintsRequest>,
+) -> Result<Json<HttpClusterNode>, ApiError> {
+    let node = cluster_service
+        .set_node_taints(&node_id, req.taints)
+        .await?;
+    Ok(Json(node.into()))
+}
+
 #[axum::debug_handler(state = AppState)]
 pub async fn list_cluster_nodes(
     _claims: Claims,
@@ -1,5 +1,5 @@
 use axum::{
-    routing::{delete, get, post},
+    routing::{delete, get, post, put},
     Router,
 };
 
@@ -28,5 +28,9 @@ pub fn router() -> Router<AppState> {
         )
         .route("/clusters/{cluster_id}/jobs", get(list_cluster_jobs))
         .route("/nodes/{node_id}", get(get_node))
+        .route("/nodes/{node_id}/cordon", post(cordon_node))
+        .route("/nodes/{node_id}/drain", post(drain_node))
+        .route("/nodes/{node_id}/uncordon", post(uncordon_node))
+        .route("/nodes/{node_id}/taints", put(set_node_taints))
         .route("/node/{node_id}/status", post(cluster_node_heartbeat))
 }
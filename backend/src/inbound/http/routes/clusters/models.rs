@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -5,10 +7,10 @@ use crate::{
     domain::{
         cluster::models::{
             Cluster, ClusterCpuStats, ClusterDetails, ClusterGpuStats, ClusterId, ClusterJobStats,
-            ClusterMemoryStats, ClusterNode, ClusterSummary, Cpu, CreateClusterRequest, Gpu,
-            JobInfo, NodeId, NodeStatus,
+            ClusterMemoryStats, ClusterNode, ClusterSummary, Cpu, CreateClusterRequest,
+            DistributedContext, Gpu, JobInfo, NodeId, NodeSchedulability, NodeStatus, Taint,
         },
-        training_job::models::TrainingJob,
+        training_job::models::{JobAllocation, JobId, JobOutput, JobVolume, TrainingJob},
         user::models::{ApiKey, ApiKeyId},
     },
     inbound::http::routes::training_jobs::models::HttpTrainingJob,
@@ -120,7 +122,15 @@ pub struct HttpClusterNodeHeartbeat {
     pub memory_info: i32,
     pub cpu_info: Cpu,
     pub gpu_info: Option<Gpu>,
-    pub job_info: Option<JobInfo>,
+    /// Every job the agent is currently running or has finished but not yet
+    /// been unassigned from.
+    #[serde(default)]
+    pub jobs: Vec<JobInfo>,
+    /// The address other nodes can reach this node on for multi-node jobs.
+    pub address: Option<String>,
+    /// Labels describing the node, which jobs can select nodes by.
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
 }
 
 /// The body of a [Cluster] list response.
@@ -128,20 +138,59 @@ pub struct HttpClusterNodeHeartbeat {
 pub struct HttpJobDetails {
     pub id: String,
     pub docker_uri: String,
+    /// Replaces the image's entrypoint. An empty list clears it.
+    pub entrypoint: Option<Vec<String>>,
+    /// Replaces the image's command.
+    pub command: Option<Vec<String>>,
+    /// Appended to the command, or replacing the image's command if there
+    /// is none.
+    pub args: Vec<String>,
+    pub workdir: Option<String>,
+    pub user: Option<String>,
+    /// The number of GPUs on the node reserved for this job.
+    pub gpu_count: i32,
+    /// Rendezvous details for multi-node jobs.
+    pub distributed: Option<DistributedContext>,
+    /// How long the agent lets the job's container run, in seconds.
+    pub max_runtime_secs: Option<i64>,
+    /// Environment variables to set in the job's container, including the
+    /// values of the job's secrets.
+    pub env: BTreeMap<String, String>,
+    /// Directories in the job's container to upload as artifacts.
+    pub outputs: Vec<JobOutput>,
+    /// Storage on the node to mount into the job's container.
+    pub volumes: Vec<JobVolume>,
 }
 
 impl From<TrainingJob> for HttpJobDetails {
     fn from(job: TrainingJob) -> Self {
+        let mut env = job.env;
+        env.extend(job.parameters);
+        if let (Some(array_id), Some(array_index)) = (job.array_id, job.array_index) {
+            env.insert("LILAC_ARRAY_ID".to_string(), array_id.to_string());
+            env.insert("LILAC_ARRAY_INDEX".to_string(), array_index.to_string());
+        }
         Self {
             id: job.id.to_string(),
-            docker_uri: job.definition,
+            gpu_count: job.resource_requirements.gpu_count(),
+            docker_uri: job.definition.image,
+            entrypoint: job.definition.entrypoint,
+            command: job.definition.command,
+            args: job.definition.args,
+            workdir: job.definition.workdir,
+            user: job.definition.user,
+            distributed: None,
+            max_runtime_secs: job.max_runtime_secs,
+            env,
+            outputs: job.outputs,
+            volumes: job.volumes,
         }
     }
 }
 
 #[derive(Clone, Debug, Serialize)]
 pub struct HttpHeartbeatResponse {
-    pub assigned_job: Option<HttpJobDetails>,
+    pub assigned_jobs: Vec<HttpJobDetails>,
 }
 
 /// The body of a [ClusterNode] get request.
@@ -150,10 +199,16 @@ pub struct HttpClusterNode {
     pub id: NodeId,
     pub cluster_id: ClusterId,
     pub node_status: NodeStatus,
+    pub schedulability: NodeSchedulability,
     pub last_heartbeat: DateTime<Utc>,
     pub memory_mb: i32,
     pub cpu: Cpu,
     pub gpu: Option<Gpu>,
+    pub address: Option<String>,
+    pub allocations: Vec<JobAllocation>,
+    pub reported_job_ids: Vec<JobId>,
+    pub labels: BTreeMap<String, String>,
+    pub taints: Vec<Taint>,
 }
 
 impl From<ClusterNode> for HttpClusterNode {
@@ -162,14 +217,26 @@ impl From<ClusterNode> for HttpClusterNode {
             id: value.id,
             cluster_id: value.cluster_id,
             node_status: value.node_status,
+            schedulability: value.schedulability,
             last_heartbeat: value.heartbeat_timestamp,
             memory_mb: value.memory_mb,
             cpu: value.cpu,
             gpu: value.gpu,
+            address: value.address,
+            allocations: value.allocations,
+            reported_job_ids: value.reported_job_ids,
+            labels: value.labels,
+            taints: value.taints,
         }
     }
 }
 
+/// Replaces the taints of a node.
+#[derive(Clone, Debug, Deserialize)]
+pub struct SetNodeTaintsRequest {
+    pub taints: Vec<Taint>,
+}
+
 /// The body of a [Cluster] list response.
 #[derive(Clone, Debug, Serialize)]
 pub struct ListClusterNodesHttpResponse {
@@ -1,5 +1,9 @@
+pub mod artifacts;
 pub mod auth;
 pub mod clusters;
 pub mod queues;
+pub mod scheduler;
+pub mod schedules;
+pub mod secrets;
 pub mod training_jobs;
 pub mod users;
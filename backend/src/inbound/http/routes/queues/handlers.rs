@@ -25,6 +25,11 @@ pub async fn create_queue(
         name: request.name,
         priority: request.priority,
         cluster_targets: request.cluster_targets,
+        preemptible: request.preemptible,
+        scheduling_policy: request.scheduling_policy,
+        retry_policy: request.retry_policy,
+        default_max_runtime_secs: request.default_max_runtime_secs,
+        max_runtime_limit_secs: request.max_runtime_limit_secs,
     };
 
     let queue = queue_service.create_queue(new_queue).await?;
@@ -65,6 +70,11 @@ pub async fn update_queue(
         name: request.name,
         priority: request.priority,
         cluster_targets: request.cluster_targets,
+        preemptible: request.preemptible,
+        scheduling_policy: request.scheduling_policy,
+        retry_policy: request.retry_policy,
+        default_max_runtime_secs: request.default_max_runtime_secs,
+        max_runtime_limit_secs: request.max_runtime_limit_secs,
     };
 
     let queue = queue_service.update_queue(updated_queue).await?;
@@ -3,6 +3,8 @@ use serde::{Deserialize, Serialize};
 use crate::domain::{
     cluster::models::ClusterId,
     queue::models::{Queue, QueueId},
+    scheduler::models::SchedulingPolicyKind,
+    training_job::models::RetryPolicy,
 };
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -10,6 +12,16 @@ pub struct HttpCreateQueueRequest {
     pub name: String,
     pub priority: i32,
     pub cluster_targets: Vec<ClusterId>,
+    #[serde(default)]
+    pub preemptible: bool,
+    #[serde(default)]
+    pub scheduling_policy: SchedulingPolicyKind,
+    #[serde(default)]
+    pub retry_policy: Option<RetryPolicy>,
+    #[serde(default)]
+    pub default_max_runtime_secs: Option<i64>,
+    #[serde(default)]
+    pub max_runtime_limit_secs: Option<i64>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -17,6 +29,16 @@ pub struct HttpUpdateQueueRequest {
     pub name: String,
     pub priority: i32,
     pub cluster_targets: Vec<ClusterId>,
+    #[serde(default)]
+    pub preemptible: bool,
+    #[serde(default)]
+    pub scheduling_policy: SchedulingPolicyKind,
+    #[serde(default)]
+    pub retry_policy: Option<RetryPolicy>,
+    #[serde(default)]
+    pub default_max_runtime_secs: Option<i64>,
+    #[serde(default)]
+    pub max_runtime_limit_secs: Option<i64>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -25,6 +47,11 @@ pub struct HttpQueueResponse {
     pub name: String,
     pub priority: i32,
     pub cluster_targets: Vec<ClusterId>,
+    pub preemptible: bool,
+    pub scheduling_policy: SchedulingPolicyKind,
+    pub retry_policy: Option<RetryPolicy>,
+    pub default_max_runtime_secs: Option<i64>,
+    pub max_runtime_limit_secs: Option<i64>,
 }
 
 impl From<Queue> for HttpQueueResponse {
@@ -34,6 +61,11 @@ impl From<Queue> for HttpQueueResponse {
             name: queue.name,
             priority: queue.priority,
             cluster_targets: queue.cluster_targets,
+            preemptible: queue.preemptible,
+            scheduling_policy: queue.scheduling_policy,
+            retry_policy: queue.retry_policy,
+            default_max_runtime_secs: queue.default_max_runtime_secs,
+            max_runtime_limit_secs: queue.max_runtime_limit_secs,
         }
     }
 }
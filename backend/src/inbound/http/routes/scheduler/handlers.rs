@@ -0,0 +1,19 @@
+use std::sync::Arc;
+
+use axum::{extract::State, Json};
+
+use crate::{
+    domain::{auth::models::Claims, scheduler::leader::LeaderElection},
+    inbound::http::{errors::ApiError, AppState},
+};
+
+use super::models::HttpSchedulerLeader;
+
+#[axum::debug_handler(state = AppState)]
+pub async fn get_scheduler_leader(
+    _claims: Claims,
+    State(leader_election): State<Arc<dyn LeaderElection>>,
+) -> Result<Json<HttpSchedulerLeader>, ApiError> {
+    let leader = leader_election.current_leader().await?;
+    Ok(Json(leader.into()))
+}
@@ -0,0 +1,10 @@
+pub mod handlers;
+pub mod models;
+
+use axum::{routing::get, Router};
+
+use crate::inbound::http::AppState;
+
+pub fn router() -> Router<AppState> {
+    Router::new().route("/scheduler/leader", get(handlers::get_scheduler_leader))
+}
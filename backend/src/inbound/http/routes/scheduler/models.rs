@@ -0,0 +1,31 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::domain::scheduler::leader::SchedulerLeader;
+
+/// The body of a scheduler leader response.
+#[derive(Clone, Debug, Serialize)]
+pub struct HttpSchedulerLeader {
+    /// The replica that answered the request.
+    pub replica_id: String,
+    pub is_leader: bool,
+    /// The replica holding the scheduler lease, if any.
+    pub leader_id: Option<String>,
+    pub acquired_at: Option<DateTime<Utc>>,
+    pub renewed_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl From<SchedulerLeader> for HttpSchedulerLeader {
+    fn from(value: SchedulerLeader) -> Self {
+        let lease = value.lease;
+        Self {
+            replica_id: value.replica_id,
+            is_leader: value.is_leader,
+            leader_id: lease.as_ref().map(|lease| lease.holder_id.clone()),
+            acquired_at: lease.as_ref().map(|lease| lease.acquired_at),
+            renewed_at: lease.as_ref().map(|lease| lease.renewed_at),
+            expires_at: lease.as_ref().map(|lease| lease.expires_at),
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+
+use crate::{
+    domain::{
+        auth::models::Claims,
+        schedule::{
+            models::{CreateScheduleRequest, ScheduleId, UpdateScheduleRequest},
+            service::ScheduleService,
+        },
+    },
+    inbound::http::errors::ApiError,
+};
+
+use super::models::{
+    HttpCreateScheduleRequest, HttpScheduleResponse, HttpScheduleRunResponse,
+    HttpUpdateScheduleRequest,
+};
+
+pub async fn create_schedule(
+    claims: Claims,
+    State(schedule_service): State<Arc<dyn ScheduleService>>,
+    Json(request): Json<HttpCreateScheduleRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let new_schedule = CreateScheduleRequest {
+        name: request.name,
+        cron: request.cron,
+        job_template: request.job_template,
+        concurrency_policy: request.concurrency_policy,
+        paused: request.paused,
+        owner_id: claims.sub,
+    };
+
+    let schedule = schedule_service.create_schedule(new_schedule).await?;
+    Ok((
+        StatusCode::CREATED,
+        Json(HttpScheduleResponse::from(schedule)),
+    ))
+}
+
+pub async fn list_schedules(
+    _claims: Claims,
+    State(schedule_service): State<Arc<dyn ScheduleService>>,
+) -> Result<Json<Vec<HttpScheduleResponse>>, ApiError> {
+    let schedules = schedule_service.list_schedules().await?;
+    Ok(Json(schedules.into_iter().map(Into::into).collect()))
+}
+
+pub async fn get_schedule(
+    _claims: Claims,
+    State(schedule_service): State<Arc<dyn ScheduleService>>,
+    Path(schedule_id): Path<ScheduleId>,
+) -> Result<Json<HttpScheduleResponse>, ApiError> {
+    let schedule = schedule_service.get_schedule_by_id(&schedule_id).await?;
+    Ok(Json(schedule.into()))
+}
+
+pub async fn update_schedule(
+    claims: Claims,
+    State(schedule_service): State<Arc<dyn ScheduleService>>,
+    Path(schedule_id): Path<ScheduleId>,
+    Json(request): Json<HttpUpdateScheduleRequest>,
+) -> Result<Json<HttpScheduleResponse>, ApiError> {
+    let updated_schedule = UpdateScheduleRequest {
+        id: schedule_id,
+        name: request.name,
+        cron: request.cron,
+        job_template: request.job_template,
+        concurrency_policy: request.concurrency_policy,
+        paused: request.paused,
+        updated_by: claims.sub,
+    };
+
+    let schedule = schedule_service.update_schedule(updated_schedule).await?;
+    Ok(Json(schedule.into()))
+}
+
+pub async fn delete_schedule(
+    _claims: Claims,
+    State(schedule_service): State<Arc<dyn ScheduleService>>,
+    Path(schedule_id): Path<ScheduleId>,
+) -> Result<(), ApiError> {
+    schedule_service.delete_schedule(&schedule_id).await?;
+    Ok(())
+}
+
+pub async fn list_schedule_runs(
+    _claims: Claims,
+    State(schedule_service): State<Arc<dyn ScheduleService>>,
+    Path(schedule_id): Path<ScheduleId>,
+) -> Result<Json<Vec<HttpScheduleRunResponse>>, ApiError> {
+    let runs = schedule_service.list_schedule_runs(&schedule_id).await?;
+    Ok(Json(runs))
+}
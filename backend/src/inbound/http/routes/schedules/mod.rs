@@ -0,0 +1,24 @@
+pub mod handlers;
+pub mod models;
+
+use axum::{routing::get, Router};
+
+use crate::inbound::http::AppState;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/schedules",
+            get(handlers::list_schedules).post(handlers::create_schedule),
+        )
+        .route(
+            "/schedules/{schedule_id}",
+            get(handlers::get_schedule)
+                .put(handlers::update_schedule)
+                .delete(handlers::delete_schedule),
+        )
+        .route(
+            "/schedules/{schedule_id}/runs",
+            get(handlers::list_schedule_runs),
+        )
+}
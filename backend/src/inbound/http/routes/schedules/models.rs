@@ -0,0 +1,60 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::schedule::models::{
+    ConcurrencyPolicy, JobSchedule, JobTemplate, ScheduleId, ScheduleRun,
+};
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct HttpCreateScheduleRequest {
+    pub name: String,
+    /// A five field cron expression, evaluated in UTC.
+    pub cron: String,
+    pub job_template: JobTemplate,
+    #[serde(default)]
+    pub concurrency_policy: ConcurrencyPolicy,
+    #[serde(default)]
+    pub paused: bool,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct HttpUpdateScheduleRequest {
+    pub name: String,
+    pub cron: String,
+    pub job_template: JobTemplate,
+    #[serde(default)]
+    pub concurrency_policy: ConcurrencyPolicy,
+    #[serde(default)]
+    pub paused: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct HttpScheduleResponse {
+    pub id: ScheduleId,
+    pub name: String,
+    pub cron: String,
+    pub job_template: JobTemplate,
+    pub concurrency_policy: ConcurrencyPolicy,
+    pub paused: bool,
+    pub next_run_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<JobSchedule> for HttpScheduleResponse {
+    fn from(schedule: JobSchedule) -> Self {
+        Self {
+            id: schedule.id,
+            name: schedule.name,
+            cron: schedule.cron.into(),
+            job_template: schedule.job_template,
+            concurrency_policy: schedule.concurrency_policy,
+            paused: schedule.paused,
+            next_run_at: schedule.next_run_at,
+            created_at: schedule.created_at,
+            updated_at: schedule.updated_at,
+        }
+    }
+}
+
+pub type HttpScheduleRunResponse = ScheduleRun;
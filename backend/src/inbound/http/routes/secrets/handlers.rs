@@ -0,0 +1,49 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+
+use crate::{
+    domain::{
+        auth::models::Claims,
+        secret::{models::PutSecretRequest, service::SecretService},
+    },
+    inbound::http::errors::ApiError,
+};
+
+use super::models::{HttpPutSecretRequest, HttpSecretResponse};
+
+pub async fn put_secret(
+    claims: Claims,
+    State(secret_service): State<Arc<dyn SecretService>>,
+    Path(name): Path<String>,
+    Json(request): Json<HttpPutSecretRequest>,
+) -> Result<Json<HttpSecretResponse>, ApiError> {
+    let secret = secret_service
+        .put_secret(PutSecretRequest {
+            name,
+            owner_id: claims.sub,
+            value: request.value,
+        })
+        .await?;
+    Ok(Json(secret))
+}
+
+pub async fn list_secrets(
+    claims: Claims,
+    State(secret_service): State<Arc<dyn SecretService>>,
+) -> Result<Json<Vec<HttpSecretResponse>>, ApiError> {
+    let secrets = secret_service.list_secrets(&claims.sub).await?;
+    Ok(Json(secrets))
+}
+
+pub async fn delete_secret(
+    claims: Claims,
+    State(secret_service): State<Arc<dyn SecretService>>,
+    Path(name): Path<String>,
+) -> Result<(), ApiError> {
+    secret_service.delete_secret(&claims.sub, &name).await?;
+    Ok(())
+}
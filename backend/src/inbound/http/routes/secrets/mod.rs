@@ -0,0 +1,18 @@
+pub mod handlers;
+pub mod models;
+
+use axum::{
+    routing::{get, put},
+    Router,
+};
+
+use crate::inbound::http::AppState;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/secrets", get(handlers::list_secrets))
+        .route(
+            "/secrets/{name}",
+            put(handlers::put_secret).delete(handlers::delete_secret),
+        )
+}
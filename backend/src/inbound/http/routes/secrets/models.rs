@@ -0,0 +1,12 @@
+use secrecy::SecretString;
+use serde::Deserialize;
+
+use crate::domain::secret::models::Secret;
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct HttpPutSecretRequest {
+    pub value: SecretString,
+}
+
+/// A secret's name and timestamps. Values are never returned.
+pub type HttpSecretResponse = Secret;
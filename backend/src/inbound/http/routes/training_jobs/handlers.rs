@@ -1,14 +1,21 @@
 use std::sync::Arc;
 
 use super::models::{
-    CreateTrainingJobRequest, CreateTrainingJobResponse, PostLogsRequest,
-    UpdateTrainingJobStatusRequest,
+    CancelJobArrayResponse, CreateJobArrayRequest, CreatePipelineRequest, CreatePipelineResponse,
+    CreateTrainingJobRequest, CreateTrainingJobResponse, HttpJobArray, HttpSchedulingExplanation,
+    PipelineJob, PostLogsRequest, PostMetricsRequest, UpdateTrainingJobStatusRequest,
+};
+use crate::domain::training_job::models::{
+    ArrayId, GetTrainingJobsFilters, JobAttempt, JobEvent, JobLogs, JobMetrics, LogQuery,
+    MetricsQuery,
 };
-use crate::domain::training_job::models::GetTrainingJobsFilters;
 use crate::domain::training_job::service::TrainingJobService;
 use crate::inbound::http::routes::training_jobs::models::HttpTrainingJob;
 use crate::{
-    domain::{auth::models::Claims, training_job::models::JobId},
+    domain::{
+        auth::models::{Claims, UserOrApiKey},
+        training_job::models::JobId,
+    },
     inbound::http::{
         errors::ApiError, routes::training_jobs::models::ListTrainingJobsHttpResponse, AppState,
     },
@@ -17,26 +24,35 @@ use axum::extract::Path;
 use axum::{
     extract::{Query, State},
     http::StatusCode,
-    response::IntoResponse,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse,
+    },
     Json,
 };
 use axum_extra::{
     headers::{authorization::Bearer, Authorization},
     TypedHeader,
 };
+use futures::{stream, Stream};
 use secrecy::SecretString;
+use std::convert::Infallible;
+use tokio::sync::mpsc;
 
 pub async fn create_training_job(
     State(state): State<AppState>,
     TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
     Json(request): Json<CreateTrainingJobRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
-    state
+    let user = state
         .user_service
         .authenticate_by_api_key(&SecretString::from(auth.token().to_string()))
         .await?;
 
-    let training_job_with_targets = state.training_job_service.create(request).await?;
+    let training_job_with_targets = state
+        .training_job_service
+        .create(request, Some(user.id))
+        .await?;
 
     Ok((
         StatusCode::CREATED,
@@ -44,6 +60,74 @@ pub async fn create_training_job(
     ))
 }
 
+pub async fn create_pipeline(
+    State(state): State<AppState>,
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    Json(request): Json<CreatePipelineRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let user = state
+        .user_service
+        .authenticate_by_api_key(&SecretString::from(auth.token().to_string()))
+        .await?;
+
+    let keys: Vec<String> = request.jobs.iter().map(|job| job.key.clone()).collect();
+    let jobs = state
+        .training_job_service
+        .create_pipeline(request, Some(user.id))
+        .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CreatePipelineResponse {
+            jobs: keys
+                .into_iter()
+                .zip(jobs)
+                .map(|(key, job)| PipelineJob { key, job })
+                .collect(),
+        }),
+    ))
+}
+
+pub async fn create_job_array(
+    State(state): State<AppState>,
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    Json(request): Json<CreateJobArrayRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let user = state
+        .user_service
+        .authenticate_by_api_key(&SecretString::from(auth.token().to_string()))
+        .await?;
+
+    let (array, jobs) = state
+        .training_job_service
+        .create_array(request, Some(user.id))
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(HttpJobArray::new(array, jobs))))
+}
+
+/// Shows an array's jobs and overall progress.
+pub async fn get_job_array(
+    _user: UserOrApiKey,
+    State(state): State<AppState>,
+    Path(array_id): Path<ArrayId>,
+) -> Result<Json<HttpJobArray>, ApiError> {
+    let (array, jobs) = state.training_job_service.get_job_array(&array_id).await?;
+
+    Ok(Json(HttpJobArray::new(array, jobs)))
+}
+
+/// Cancels every unfinished job of an array.
+pub async fn cancel_job_array(
+    _user: UserOrApiKey,
+    State(state): State<AppState>,
+    Path(array_id): Path<ArrayId>,
+) -> Result<Json<CancelJobArrayResponse>, ApiError> {
+    let cancelled_job_ids = state.training_job_service.cancel_array(&array_id).await?;
+
+    Ok(Json(CancelJobArrayResponse { cancelled_job_ids }))
+}
+
 #[axum::debug_handler(state = AppState)]
 pub async fn get_training_job(
     _claims: Claims,
@@ -55,6 +139,61 @@ pub async fn get_training_job(
     Ok(Json(training_job.into()))
 }
 
+pub async fn list_training_job_attempts(
+    _claims: Claims,
+    State(state): State<AppState>,
+    Path(job_id): Path<JobId>,
+) -> Result<Json<Vec<JobAttempt>>, ApiError> {
+    let attempts = state.training_job_service.get_attempts(&job_id).await?;
+
+    Ok(Json(attempts))
+}
+
+pub async fn list_training_job_events(
+    _claims: Claims,
+    State(state): State<AppState>,
+    Path(job_id): Path<JobId>,
+) -> Result<Json<Vec<JobEvent>>, ApiError> {
+    let events = state.training_job_service.get_events(&job_id).await?;
+
+    Ok(Json(events))
+}
+
+/// Receives resource usage samples from the agents running the job,
+/// authenticated with their cluster's API key.
+pub async fn post_metrics(
+    State(state): State<AppState>,
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    Path(job_id): Path<JobId>,
+    Json(request): Json<PostMetricsRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let cluster = state
+        .cluster_service
+        .authenticate_by_api_key(&SecretString::from(auth.token().to_string()))
+        .await?;
+
+    state
+        .training_job_service
+        .post_metrics(&job_id, &cluster.id, request.samples)
+        .await?;
+
+    Ok((StatusCode::OK, Json(())))
+}
+
+pub async fn get_metrics(
+    _claims: Claims,
+    State(state): State<AppState>,
+    Path(job_id): Path<JobId>,
+    Query(query): Query<MetricsQuery>,
+) -> Result<Json<JobMetrics>, ApiError> {
+    let metrics = state
+        .training_job_service
+        .get_metrics(&job_id, query)
+        .await?;
+
+    Ok(Json(metrics))
+}
+
 #[axum::debug_handler]
 pub async fn list_training_jobs(
     _claims: Claims,
@@ -80,13 +219,92 @@ pub async fn update_training_job_status(
     (StatusCode::OK, Json(()))
 }
 
+/// Receives log output from the agent running the job, authenticated with
+/// its cluster's API key.
 pub async fn post_logs(
-    State(_state): State<AppState>,
-    Path(_job_id): Path<JobId>,
-    Json(_request): Json<PostLogsRequest>,
-) -> impl IntoResponse {
-    // TODO: Implement log ingestion
-    (StatusCode::OK, Json(()))
+    State(state): State<AppState>,
+    TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
+    Path(job_id): Path<JobId>,
+    Json(request): Json<PostLogsRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let cluster = state
+        .cluster_service
+        .authenticate_by_api_key(&SecretString::from(auth.token().to_string()))
+        .await?;
+
+    state
+        .training_job_service
+        .post_logs(&job_id, &cluster.id, request.logs)
+        .await?;
+
+    Ok((StatusCode::OK, Json(())))
+}
+
+/// Reads a job's logs.
+pub async fn get_logs(
+    _user: UserOrApiKey,
+    State(state): State<AppState>,
+    Path(job_id): Path<JobId>,
+    Query(query): Query<LogQuery>,
+) -> Result<Json<JobLogs>, ApiError> {
+    let logs = state.training_job_service.get_logs(&job_id, query).await?;
+
+    Ok(Json(logs))
+}
+
+/// Streams a job's logs as server-sent events: a `log` event for every
+/// chunk, first the ones the query asks for and then new ones as they arrive,
+/// followed by an `end` event once the job has finished.
+pub async fn stream_logs(
+    _user: UserOrApiKey,
+    State(state): State<AppState>,
+    Path(job_id): Path<JobId>,
+    Query(query): Query<LogQuery>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    // Read the backlog up front, so unknown jobs are rejected right away.
+    let service = state.training_job_service.clone();
+    let backlog = service.get_logs(&job_id, query).await?;
+
+    // The task stops as soon as the client disconnects and the channel closes,
+    // even while it is waiting for new output.
+    let (events, receiver) = mpsc::channel(16);
+    tokio::spawn(async move {
+        let mut logs = backlog;
+        loop {
+            for chunk in &logs.chunks {
+                let event = Event::default()
+                    .event("log")
+                    .id(chunk.offset.to_string())
+                    .json_data(chunk)
+                    .unwrap_or_default();
+                if events.send(event).await.is_err() {
+                    return;
+                }
+            }
+            let followed = tokio::select! {
+                _ = events.closed() => return,
+                followed = service.follow_logs(&job_id, logs.next_offset) => followed,
+            };
+            logs = match followed {
+                Ok(Some(logs)) => logs,
+                Ok(None) => {
+                    let _ = events.send(Event::default().event("end").data("")).await;
+                    return;
+                }
+                Err(e) => {
+                    tracing::error!("Error following logs of job {}: {}", job_id, e);
+                    let _ = events.send(Event::default().event("error").data("")).await;
+                    return;
+                }
+            };
+        }
+    });
+
+    let stream = stream::unfold(receiver, |mut receiver| async move {
+        let event = receiver.recv().await?;
+        Some((Ok(event), receiver))
+    });
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
 }
 
 pub async fn cancel_training_job(
@@ -97,3 +315,21 @@ pub async fn cancel_training_job(
     state.training_job_service.cancel(&job_id).await?;
     Ok((StatusCode::OK, Json(())))
 }
+
+/// Explains why a job is or isn't running.
+pub async fn get_training_job_scheduling(
+    _user: UserOrApiKey,
+    State(state): State<AppState>,
+    Path(job_id): Path<JobId>,
+) -> Result<Json<HttpSchedulingExplanation>, ApiError> {
+    let job = state
+        .training_job_service
+        .get_training_job_by_id(&job_id)
+        .await?;
+    let report = state
+        .training_job_service
+        .get_scheduling_report(&job_id)
+        .await?;
+
+    Ok(Json(HttpSchedulingExplanation::new(job, report)))
+}
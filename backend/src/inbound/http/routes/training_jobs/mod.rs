@@ -6,8 +6,10 @@ use axum::{
 use crate::inbound::http::AppState;
 
 use self::handlers::{
-    cancel_training_job, create_training_job, get_training_job, list_training_jobs, post_logs,
-    update_training_job_status,
+    cancel_job_array, cancel_training_job, create_job_array, create_pipeline, create_training_job,
+    get_job_array, get_logs, get_metrics, get_training_job, get_training_job_scheduling,
+    list_training_job_attempts, list_training_job_events, list_training_jobs, post_logs,
+    post_metrics, stream_logs, update_training_job_status,
 };
 
 pub mod handlers;
@@ -17,11 +19,38 @@ pub fn training_jobs_router() -> Router<AppState> {
     Router::new()
         .route("/training_jobs", post(create_training_job))
         .route("/training_jobs", get(list_training_jobs))
+        .route("/training_jobs/pipelines", post(create_pipeline))
+        .route("/training_jobs/arrays", post(create_job_array))
+        .route("/training_jobs/arrays/{array_id}", get(get_job_array))
+        .route(
+            "/training_jobs/arrays/{array_id}/cancel",
+            post(cancel_job_array),
+        )
         .route("/training_jobs/{job_id}", get(get_training_job))
         .route(
             "/training_jobs/{job_id}/status",
             patch(update_training_job_status),
         )
-        .route("/training_jobs/{job_id}/logs", post(post_logs))
+        .route(
+            "/training_jobs/{job_id}/logs",
+            post(post_logs).get(get_logs),
+        )
+        .route("/training_jobs/{job_id}/logs/stream", get(stream_logs))
         .route("/training_jobs/{job_id}/cancel", post(cancel_training_job))
+        .route(
+            "/training_jobs/{job_id}/attempts",
+            get(list_training_job_attempts),
+        )
+        .route(
+            "/training_jobs/{job_id}/events",
+            get(list_training_job_events),
+        )
+        .route(
+            "/training_jobs/{job_id}/metrics",
+            post(post_metrics).get(get_metrics),
+        )
+        .route(
+            "/training_jobs/{job_id}/scheduling",
+            get(get_training_job_scheduling),
+        )
 }
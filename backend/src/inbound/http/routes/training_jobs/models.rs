@@ -1,22 +1,156 @@
+use std::collections::BTreeMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::domain::{
     cluster::models::NodeId,
     queue::models::QueueId,
-    training_job::models::{JobId, ResourceRequirements, TrainingJob, TrainingJobStatus},
+    scheduler::models::{ClusterReport, SchedulingOutcome, SchedulingReport},
+    training_job::models::{
+        ArrayId, DependencyCondition, JobArray, JobArrayCounts, JobDefinition, JobDependency,
+        JobExit, JobId, JobOutput, JobVolume, MetricSample, ParameterSpace, ResourceRequirements,
+        RetryPolicy, Toleration, TrainingJob, TrainingJobStatus,
+    },
 };
 
 #[derive(Debug, Deserialize)]
 pub struct CreateTrainingJobRequest {
     pub name: String,
-    pub definition: String,
+    /// The image to run and how to start it. A plain string is taken as the
+    /// image.
+    pub definition: JobDefinition,
     pub queue_id: QueueId,
     pub resource_requirements: serde_json::Value,
+    /// The number of nodes to launch the job on. Defaults to a single node.
+    #[serde(default = "default_node_count")]
+    pub node_count: i32,
+    /// Existing jobs that have to finish before this job is scheduled.
+    #[serde(default)]
+    pub depends_on: Vec<JobDependency>,
+    /// Overrides the retry policy of the queue.
+    #[serde(default)]
+    pub retry_policy: Option<RetryPolicy>,
+    /// How long the job may run before it is stopped, in seconds. Defaults to
+    /// the queue's default maximum runtime.
+    #[serde(default)]
+    pub max_runtime_secs: Option<i64>,
+    /// Labels a node must have, with these values, to run the job.
+    #[serde(default)]
+    pub node_selector: BTreeMap<String, String>,
+    /// The node taints the job may be placed on despite.
+    #[serde(default)]
+    pub tolerations: Vec<Toleration>,
+    /// Environment variables to set in the job's container.
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// Secrets to pass to the job, keyed by the environment variable each is
+    /// set in.
+    #[serde(default)]
+    pub secrets: BTreeMap<String, String>,
+    /// Directories in the job's container to upload as artifacts.
+    #[serde(default)]
+    pub outputs: Vec<JobOutput>,
+    /// Storage on the node to mount into the job's container.
+    #[serde(default)]
+    pub volumes: Vec<JobVolume>,
+}
+
+fn default_node_count() -> i32 {
+    1
 }
 
 pub type CreateTrainingJobResponse = TrainingJob;
 
+/// A set of jobs submitted together, which may depend on each other.
+#[derive(Debug, Deserialize)]
+pub struct CreatePipelineRequest {
+    pub jobs: Vec<PipelineJobRequest>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PipelineJobRequest {
+    /// Identifies the job within the pipeline.
+    pub key: String,
+    /// Other jobs of the pipeline that have to finish before this one is
+    /// scheduled.
+    #[serde(default)]
+    pub needs: Vec<PipelineDependency>,
+    #[serde(flatten)]
+    pub job: CreateTrainingJobRequest,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PipelineDependency {
+    pub key: String,
+    #[serde(default)]
+    pub condition: DependencyCondition,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PipelineJob {
+    pub key: String,
+    #[serde(flatten)]
+    pub job: TrainingJob,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CreatePipelineResponse {
+    pub jobs: Vec<PipelineJob>,
+}
+
+/// One job template run once for every set of parameters.
+#[derive(Debug, Deserialize)]
+pub struct CreateJobArrayRequest {
+    /// The template of the array's jobs. Its name becomes the array's name,
+    /// and each job is named after the array and its index.
+    #[serde(flatten)]
+    pub job: CreateTrainingJobRequest,
+    pub parameters: ParameterSpace,
+    /// How many of the array's jobs may be starting or running at once.
+    #[serde(default)]
+    pub max_concurrent: Option<i32>,
+}
+
+/// An HTTP representation of a [JobArray] together with its jobs.
+#[derive(Debug, Clone, Serialize)]
+pub struct HttpJobArray {
+    pub array_id: ArrayId,
+    pub name: String,
+    pub size: i32,
+    pub max_concurrent: Option<i32>,
+    pub parameters: ParameterSpace,
+    /// The status of the array as a whole.
+    pub status: TrainingJobStatus,
+    pub counts: JobArrayCounts,
+    pub jobs: Vec<HttpTrainingJob>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl HttpJobArray {
+    pub fn new(array: JobArray, jobs: Vec<TrainingJob>) -> Self {
+        let counts = JobArrayCounts::from_jobs(&jobs);
+        Self {
+            array_id: array.id,
+            name: array.name,
+            size: array.size,
+            max_concurrent: array.max_concurrent,
+            parameters: array.parameters,
+            status: counts.status(),
+            counts,
+            jobs: jobs.into_iter().map(Into::into).collect(),
+            created_at: array.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CancelJobArrayResponse {
+    /// The jobs that were cancelled. Jobs that had already finished are left
+    /// as they were.
+    pub cancelled_job_ids: Vec<JobId>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct UpdateTrainingJobStatusRequest {
     pub status: TrainingJobStatus,
@@ -27,15 +161,39 @@ pub struct PostLogsRequest {
     pub logs: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct PostMetricsRequest {
+    pub samples: Vec<MetricSample>,
+}
+
 /// An HTTP representation of a [TrainingJob].
 #[derive(Debug, Clone, Serialize)]
 pub struct HttpTrainingJob {
     pub job_id: JobId,
     pub job_name: String,
+    pub definition: JobDefinition,
     pub job_status: TrainingJobStatus,
     pub node_id: Option<NodeId>,
     pub queue_id: Option<QueueId>,
     pub resource_requirements: ResourceRequirements,
+    pub node_count: i32,
+    pub preempted_at: Option<DateTime<Utc>>,
+    pub preemption_reason: Option<String>,
+    pub dependencies: Vec<JobDependency>,
+    pub retry_policy: Option<RetryPolicy>,
+    pub not_before: Option<DateTime<Utc>>,
+    pub max_runtime_secs: Option<i64>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub exit: Option<JobExit>,
+    pub array_id: Option<ArrayId>,
+    pub array_index: Option<i32>,
+    pub parameters: BTreeMap<String, String>,
+    pub node_selector: BTreeMap<String, String>,
+    pub tolerations: Vec<Toleration>,
+    pub env: BTreeMap<String, String>,
+    pub secrets: BTreeMap<String, String>,
+    pub outputs: Vec<JobOutput>,
+    pub volumes: Vec<JobVolume>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -45,10 +203,29 @@ impl From<TrainingJob> for HttpTrainingJob {
         Self {
             job_id: job.id,
             job_name: job.name,
+            definition: job.definition,
             job_status: job.status,
             node_id: job.node_id,
             queue_id: job.queue_id,
             resource_requirements: job.resource_requirements,
+            node_count: job.node_count,
+            preempted_at: job.preempted_at,
+            preemption_reason: job.preemption_reason,
+            dependencies: job.dependencies,
+            retry_policy: job.retry_policy,
+            not_before: job.not_before,
+            max_runtime_secs: job.max_runtime_secs,
+            started_at: job.started_at,
+            exit: job.exit,
+            array_id: job.array_id,
+            array_index: job.array_index,
+            parameters: job.parameters,
+            node_selector: job.node_selector,
+            tolerations: job.tolerations,
+            env: job.env,
+            secrets: job.secrets,
+            outputs: job.outputs,
+            volumes: job.volumes,
             created_at: job.created_at,
             updated_at: job.updated_at,
         }
@@ -67,3 +244,44 @@ impl From<Vec<TrainingJob>> for ListTrainingJobsHttpResponse {
         }
     }
 }
+
+/// Why a job is, or is not, running.
+#[derive(Debug, Clone, Serialize)]
+pub struct HttpSchedulingExplanation {
+    pub job_id: JobId,
+    pub job_status: TrainingJobStatus,
+    pub queue_id: Option<QueueId>,
+    /// The most recent scheduling attempt. `None` until the scheduler has
+    /// looked at the job.
+    pub last_attempt: Option<HttpSchedulingAttempt>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HttpSchedulingAttempt {
+    pub attempted_at: DateTime<Utc>,
+    pub outcome: SchedulingOutcome,
+    pub preempted_jobs: bool,
+    pub clusters: Vec<ClusterReport>,
+}
+
+impl From<SchedulingReport> for HttpSchedulingAttempt {
+    fn from(report: SchedulingReport) -> Self {
+        Self {
+            attempted_at: report.attempted_at,
+            outcome: report.outcome,
+            preempted_jobs: report.preempted_jobs,
+            clusters: report.clusters,
+        }
+    }
+}
+
+impl HttpSchedulingExplanation {
+    pub fn new(job: TrainingJob, report: Option<SchedulingReport>) -> Self {
+        Self {
+            job_id: job.id,
+            job_status: job.status,
+            queue_id: job.queue_id,
+            last_attempt: report.map(HttpSchedulingAttempt::from),
+        }
+    }
+}
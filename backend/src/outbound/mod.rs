@@ -1,3 +1,4 @@
 pub mod jwt;
+pub mod object_store;
 pub mod persistence;
 pub mod scheduler;
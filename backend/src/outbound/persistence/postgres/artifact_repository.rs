@@ -0,0 +1,93 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+use super::records::ArtifactRecord;
+use crate::domain::{
+    artifact::{
+        models::{Artifact, ArtifactFile},
+        ports::{ArtifactRepository, ArtifactRepositoryError},
+    },
+    training_job::models::JobId,
+};
+
+pub struct PostgresArtifactRepository {
+    pool: PgPool,
+}
+
+impl PostgresArtifactRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ArtifactRepository for PostgresArtifactRepository {
+    async fn record_artifacts(
+        &self,
+        job_id: &JobId,
+        files: &[ArtifactFile],
+    ) -> Result<(), ArtifactRepositoryError> {
+        let paths: Vec<String> = files.iter().map(|file| file.path.clone()).collect();
+        let sizes: Vec<i64> = files.iter().map(|file| file.size_bytes).collect();
+        sqlx::query!(
+            r#"
+            INSERT INTO training_job_artifacts (job_id, path, size_bytes)
+            SELECT $1, path, size_bytes
+            FROM UNNEST($2::TEXT[], $3::BIGINT[]) AS files (path, size_bytes)
+            ON CONFLICT (job_id, path) DO UPDATE
+            SET size_bytes = EXCLUDED.size_bytes, uploaded_at = NOW()
+            "#,
+            job_id.inner(),
+            &paths,
+            &sizes
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| ArtifactRepositoryError::Unknown(e.into()))?;
+        Ok(())
+    }
+
+    async fn list_artifacts(
+        &self,
+        job_id: &JobId,
+    ) -> Result<Vec<Artifact>, ArtifactRepositoryError> {
+        let records = sqlx::query_as!(
+            ArtifactRecord,
+            r#"
+            SELECT job_id, path, size_bytes, uploaded_at
+            FROM training_job_artifacts
+            WHERE job_id = $1
+            ORDER BY path
+            "#,
+            job_id.inner()
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| ArtifactRepositoryError::Unknown(e.into()))?;
+        Ok(records.into_iter().map(Into::into).collect())
+    }
+
+    async fn get_artifact(
+        &self,
+        job_id: &JobId,
+        path: &str,
+    ) -> Result<Artifact, ArtifactRepositoryError> {
+        sqlx::query_as!(
+            ArtifactRecord,
+            r#"
+            SELECT job_id, path, size_bytes, uploaded_at
+            FROM training_job_artifacts
+            WHERE job_id = $1 AND path = $2
+            "#,
+            job_id.inner(),
+            path
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map(Into::into)
+        .map_err(|err| match err {
+            sqlx::Error::RowNotFound => ArtifactRepositoryError::NotFound(path.to_string()),
+            _ => ArtifactRepositoryError::Unknown(err.into()),
+        })
+    }
+}
@@ -1,5 +1,7 @@
+use std::collections::HashMap;
+
 use async_trait::async_trait;
-use sqlx::PgPool;
+use sqlx::{PgExecutor, PgPool};
 
 use crate::{
     domain::{
@@ -7,17 +9,17 @@ use crate::{
             errors::ClusterApiKeyRepositoryError,
             models::{
                 Cluster, ClusterDetails, ClusterId, ClusterNode, ClusterSummary,
-                CreateClusterRequest, NodeId, UpdateNodeStatusRequest,
+                CreateClusterRequest, NodeId, NodeSchedulability, Taint, UpdateNodeStatusRequest,
             },
             ports::{ClusterApiKeyRepository, ClusterRepository, ClusterRepositoryError},
         },
-        training_job::models::{JobId, TrainingJob},
+        training_job::models::{JobAllocation, JobId, ResourceRequirements, TrainingJob},
         user::models::{ApiKey, ApiKeyId},
     },
     outbound::persistence::postgres::records::{
         ApiKeyRecord, ClusterDetailsRecord, ClusterNodeRecord, ClusterRecord, ClusterSummaryRecord,
-        CpuConfigurationRecord, GpuConfigurationRecord, NodeStatusRecord, TrainingJobRecord,
-        TrainingJobStatusRecord,
+        CpuConfigurationRecord, GpuConfigurationRecord, JobAllocationRecord,
+        NodeSchedulabilityRecord, NodeStatusRecord, TrainingJobRecord, TrainingJobStatusRecord,
     },
 };
 
@@ -30,6 +32,57 @@ impl PostgresClusterRepository {
     pub fn new(pool: PgPool) -> Self {
         Self { pool }
     }
+
+    /// Loads the allocations held by each of the nodes.
+    async fn with_allocations(
+        &self,
+        records: Vec<ClusterNodeRecord>,
+    ) -> Result<Vec<ClusterNode>, ClusterRepositoryError> {
+        load_allocations(&self.pool, records).await
+    }
+}
+
+/// Loads the allocations held by each of the nodes through `executor`, so
+/// nodes locked in a transaction are read together with their allocations.
+async fn load_allocations<'e>(
+    executor: impl PgExecutor<'e>,
+    records: Vec<ClusterNodeRecord>,
+) -> Result<Vec<ClusterNode>, ClusterRepositoryError> {
+    let node_ids: Vec<uuid::Uuid> = records.iter().map(|r| r.node_id).collect();
+    let allocations = sqlx::query_as!(
+        JobAllocationRecord,
+        r#"
+        SELECT job_id, node_id, rank, cpu_millicores, memory_mb, gpu_count
+        FROM training_job_nodes
+        WHERE node_id = ANY($1)
+        "#,
+        &node_ids
+    )
+    .fetch_all(executor)
+    .await
+    .map_err(|e| ClusterRepositoryError::Unknown(anyhow::anyhow!(e)))?;
+
+    let mut allocations_by_node: HashMap<uuid::Uuid, Vec<JobAllocation>> = HashMap::new();
+    for allocation in allocations {
+        allocations_by_node
+            .entry(allocation.node_id)
+            .or_default()
+            .push(allocation.into());
+    }
+
+    records
+        .into_iter()
+        .map(|record| {
+            let allocations = allocations_by_node
+                .remove(&record.node_id)
+                .unwrap_or_default();
+            Ok(ClusterNode {
+                allocations,
+                ..record.try_into()?
+            })
+        })
+        .collect::<Result<_, anyhow::Error>>()
+        .map_err(ClusterRepositoryError::Unknown)
 }
 
 #[async_trait]
@@ -82,18 +135,31 @@ impl ClusterRepository for PostgresClusterRepository {
         let record = sqlx::query_as!(
             ClusterDetailsRecord,
             r#"SELECT c.cluster_id, c.cluster_name, c.cluster_description, c.created_at, c.updated_at,
-                COUNT(DISTINCT n.node_id) AS "total_nodes!: i64",
-                COUNT(DISTINCT n.node_id) FILTER (WHERE n.node_status = 'busy') AS "busy_nodes!: i64",
-                COUNT(running_jobs.id) AS "total_running_jobs!: i64",
+                COUNT(n.node_id) AS "total_nodes!: i64",
+                COUNT(n.node_id) FILTER (WHERE n.node_status = 'busy') AS "busy_nodes!: i64",
+                (
+                    SELECT COUNT(DISTINCT j.id)
+                    FROM training_jobs j
+                    JOIN training_job_nodes a ON a.job_id = j.id
+                    JOIN cluster_nodes an ON an.node_id = a.node_id
+                    WHERE an.cluster_id = c.cluster_id AND j.status = 'running'
+                ) AS "total_running_jobs!: i64",
                 COALESCE(SUM((n.cpu).millicores), 0) AS "total_millicores!: i64",
-                COALESCE(SUM((n.cpu).millicores) FILTER (WHERE n.node_status = 'busy'), 0) AS "used_millicores!: i64",
+                COALESCE(SUM(used.cpu_millicores), 0)::BIGINT AS "used_millicores!: i64",
                 COALESCE(SUM(n.memory_mb), 0) AS "total_memory_mb!: i64",
-                COALESCE(SUM(n.memory_mb) FILTER (WHERE n.node_status = 'busy'), 0) AS "used_memory_mb!: i64",
+                COALESCE(SUM(used.memory_mb), 0)::BIGINT AS "used_memory_mb!: i64",
                 COALESCE(SUM((n.gpu).count), 0) AS "total_gpus!: i64",
-                COALESCE(SUM((n.gpu).count) FILTER (WHERE n.node_status = 'busy'), 0) AS "used_gpus!: i64"
+                COALESCE(SUM(used.gpu_count), 0)::BIGINT AS "used_gpus!: i64"
             FROM clusters c
             LEFT JOIN cluster_nodes n ON c.cluster_id = n.cluster_id
-            LEFT JOIN training_jobs running_jobs ON running_jobs.status = 'running' AND n.node_id = running_jobs.node_id
+            LEFT JOIN (
+                SELECT node_id,
+                    SUM(cpu_millicores) AS cpu_millicores,
+                    SUM(memory_mb) AS memory_mb,
+                    SUM(gpu_count) AS gpu_count
+                FROM training_job_nodes
+                GROUP BY node_id
+            ) used ON used.node_id = n.node_id
             WHERE c.cluster_id = $1
             GROUP BY c.cluster_id;
             "#,
@@ -138,7 +204,7 @@ impl ClusterRepository for PostgresClusterRepository {
         let records = sqlx::query_as!(
             TrainingJobRecord,
             r#"
-            SELECT id, name, definition, status AS "status: TrainingJobStatusRecord", node_id, queue_id, resource_requirements, created_at, updated_at
+            SELECT id, name, definition, status AS "status: TrainingJobStatusRecord", node_id, queue_id, resource_requirements, node_count, preempted_at, preemption_reason, dependencies, retry_policy, not_before, avoid_node_id, max_runtime_secs, started_at, exit, array_id, array_index, parameters, node_selector, tolerations, env, secrets, outputs, volumes, created_at, updated_at
             FROM training_jobs
             WHERE node_id = ANY(SELECT node_id FROM cluster_nodes WHERE cluster_id = $1)
             "#,
@@ -165,7 +231,7 @@ impl ClusterRepository for PostgresClusterRepository {
         let records = sqlx::query_as!(
             ClusterNodeRecord,
             r#"
-            SELECT node_id, cluster_id, node_status as "node_status: NodeStatusRecord", heartbeat_timestamp, memory_mb, cpu as "cpu: CpuConfigurationRecord", gpu as "gpu: GpuConfigurationRecord", created_at, updated_at, assigned_job_id, reported_job_id
+            SELECT node_id, cluster_id, node_status as "node_status: NodeStatusRecord", schedulability as "schedulability: NodeSchedulabilityRecord", heartbeat_timestamp, memory_mb, cpu as "cpu: CpuConfigurationRecord", gpu as "gpu: GpuConfigurationRecord", created_at, updated_at, reported_job_ids, address, labels, taints
             FROM cluster_nodes
             "#,
         )
@@ -173,8 +239,7 @@ impl ClusterRepository for PostgresClusterRepository {
         .await
         .map_err(|e: sqlx::Error| ClusterRepositoryError::Unknown(anyhow::anyhow!(e)))?;
 
-        let nodes: Vec<ClusterNode> = records.into_iter().map(|r| r.into()).collect();
-        Ok(nodes)
+        self.with_allocations(records).await
     }
 
     async fn list_cluster_nodes(
@@ -184,7 +249,7 @@ impl ClusterRepository for PostgresClusterRepository {
         let records = sqlx::query_as!(
             ClusterNodeRecord,
             r#"
-            SELECT node_id, cluster_id, node_status as "node_status: NodeStatusRecord", heartbeat_timestamp, memory_mb, cpu as "cpu: CpuConfigurationRecord", gpu as "gpu: GpuConfigurationRecord", created_at, updated_at, assigned_job_id, reported_job_id
+            SELECT node_id, cluster_id, node_status as "node_status: NodeStatusRecord", schedulability as "schedulability: NodeSchedulabilityRecord", heartbeat_timestamp, memory_mb, cpu as "cpu: CpuConfigurationRecord", gpu as "gpu: GpuConfigurationRecord", created_at, updated_at, reported_job_ids, address, labels, taints
             FROM cluster_nodes
             WHERE cluster_id = $1
             "#,
@@ -194,8 +259,7 @@ impl ClusterRepository for PostgresClusterRepository {
         .await
         .map_err(|e: sqlx::Error| ClusterRepositoryError::Unknown(anyhow::anyhow!(e)))?;
 
-        let clusters: Vec<ClusterNode> = records.into_iter().map(|r| r.into()).collect();
-        Ok(clusters)
+        self.with_allocations(records).await
     }
     async fn get_cluster_node_by_id(
         &self,
@@ -204,7 +268,7 @@ impl ClusterRepository for PostgresClusterRepository {
         let record = sqlx::query_as!(
             ClusterNodeRecord,
             r#"
-            SELECT node_id, cluster_id, node_status as "node_status: NodeStatusRecord", heartbeat_timestamp, memory_mb, cpu as "cpu: CpuConfigurationRecord", gpu as "gpu: GpuConfigurationRecord", created_at, updated_at, assigned_job_id, reported_job_id
+            SELECT node_id, cluster_id, node_status as "node_status: NodeStatusRecord", schedulability as "schedulability: NodeSchedulabilityRecord", heartbeat_timestamp, memory_mb, cpu as "cpu: CpuConfigurationRecord", gpu as "gpu: GpuConfigurationRecord", created_at, updated_at, reported_job_ids, address, labels, taints
             FROM cluster_nodes
             WHERE node_id = $1
             "#,
@@ -213,7 +277,9 @@ impl ClusterRepository for PostgresClusterRepository {
         .fetch_one(&self.pool)
         .await
         .map_err(|e: sqlx::Error| ClusterRepositoryError::Unknown(anyhow::anyhow!(e)))?;
-        Ok(record.into())
+        self.with_allocations(vec![record])
+            .await
+            .map(|mut nodes| nodes.remove(0))
     }
     async fn update_cluster_node_status(
         &self,
@@ -222,20 +288,33 @@ impl ClusterRepository for PostgresClusterRepository {
         let record = sqlx::query_as!(
             ClusterNodeRecord,
             r#"
-            INSERT INTO cluster_nodes (node_id, cluster_id, node_status, heartbeat_timestamp, memory_mb, cpu, gpu, reported_job_id)
-                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            INSERT INTO cluster_nodes (node_id, cluster_id, node_status, heartbeat_timestamp, memory_mb, cpu, gpu, reported_job_ids, address, labels, schedulability, taints)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, COALESCE(
+                    (SELECT schedulability FROM retained_node_settings WHERE node_id = $1),
+                    'schedulable'
+                ), COALESCE(
+                    (SELECT taints FROM retained_node_settings WHERE node_id = $1),
+                    '[]'
+                ))
                 ON CONFLICT (node_id) DO UPDATE SET
                     node_status = EXCLUDED.node_status,
                     heartbeat_timestamp = EXCLUDED.heartbeat_timestamp,
-                    reported_job_id = EXCLUDED.reported_job_id,
+                    memory_mb = EXCLUDED.memory_mb,
+                    cpu = EXCLUDED.cpu,
+                    gpu = EXCLUDED.gpu,
+                    reported_job_ids = EXCLUDED.reported_job_ids,
+                    address = EXCLUDED.address,
+                    labels = EXCLUDED.labels,
                     updated_at = NOW()
-                RETURNING node_id, cluster_id, node_status as "node_status: NodeStatusRecord", heartbeat_timestamp, memory_mb, cpu as "cpu: CpuConfigurationRecord", gpu as "gpu: GpuConfigurationRecord", created_at, updated_at, assigned_job_id, reported_job_id;
+                WHERE cluster_nodes.cluster_id = EXCLUDED.cluster_id
+                RETURNING node_id, cluster_id, node_status as "node_status: NodeStatusRecord", schedulability as "schedulability: NodeSchedulabilityRecord", heartbeat_timestamp, memory_mb, cpu as "cpu: CpuConfigurationRecord", gpu as "gpu: GpuConfigurationRecord", created_at, updated_at, reported_job_ids, address, labels, taints;
             "#,
             req.node_id.inner(),
             req.cluster_id.inner(),
-            match req.job_info {
-                Some(_) => NodeStatusRecord::Busy,
-                None => NodeStatusRecord::Available,
+            if req.jobs.is_empty() {
+                NodeStatusRecord::Available
+            } else {
+                NodeStatusRecord::Busy
             } as _,
             req.heartbeat_timestamp,
             req.memory_info,
@@ -243,58 +322,259 @@ impl ClusterRepository for PostgresClusterRepository {
             req.gpu_info
                 .clone()
                 .map(GpuConfigurationRecord::from) as _,
-            req.job_info
-                .as_ref()
-                .map(|info| info.current_job_id)
-                .map(|id| id.into_inner()),
+            &req.jobs
+                .iter()
+                .map(|info| info.job_id.into_inner())
+                .collect::<Vec<_>>(),
+            req.address,
+            &serde_json::to_value(&req.labels)
+                .map_err(|e| ClusterRepositoryError::Unknown(anyhow::anyhow!(e)))?,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e: sqlx::Error| ClusterRepositoryError::Unknown(anyhow::anyhow!(e)))?
+        .ok_or_else(|| ClusterRepositoryError::NotFound(req.node_id.to_string()))?;
+        self.with_allocations(vec![record])
+            .await
+            .map(|mut nodes| nodes.remove(0))
+    }
+    async fn set_node_schedulability(
+        &self,
+        node_id: &NodeId,
+        schedulability: NodeSchedulability,
+    ) -> Result<ClusterNode, ClusterRepositoryError> {
+        let record = sqlx::query_as!(
+            ClusterNodeRecord,
+            r#"
+            UPDATE cluster_nodes
+            SET schedulability = $1
+            WHERE node_id = $2
+            RETURNING node_id, cluster_id, node_status as "node_status: NodeStatusRecord", schedulability as "schedulability: NodeSchedulabilityRecord", heartbeat_timestamp, memory_mb, cpu as "cpu: CpuConfigurationRecord", gpu as "gpu: GpuConfigurationRecord", created_at, updated_at, reported_job_ids, address, labels, taints
+            "#,
+            NodeSchedulabilityRecord::from(schedulability) as _,
+            node_id.inner(),
         )
         .fetch_one(&self.pool)
         .await
-        .map_err(|e: sqlx::Error| ClusterRepositoryError::Unknown(anyhow::anyhow!(e)))?;
-        Ok(record.into())
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => ClusterRepositoryError::NotFound(node_id.to_string()),
+            _ => ClusterRepositoryError::Unknown(anyhow::anyhow!(e)),
+        })?;
+        self.with_allocations(vec![record])
+            .await
+            .map(|mut nodes| nodes.remove(0))
+    }
+    async fn set_node_taints(
+        &self,
+        node_id: &NodeId,
+        taints: &[Taint],
+    ) -> Result<ClusterNode, ClusterRepositoryError> {
+        let record = sqlx::query_as!(
+            ClusterNodeRecord,
+            r#"
+            UPDATE cluster_nodes
+            SET taints = $1
+            WHERE node_id = $2
+            RETURNING node_id, cluster_id, node_status as "node_status: NodeStatusRecord", schedulability as "schedulability: NodeSchedulabilityRecord", heartbeat_timestamp, memory_mb, cpu as "cpu: CpuConfigurationRecord", gpu as "gpu: GpuConfigurationRecord", created_at, updated_at, reported_job_ids, address, labels, taints
+            "#,
+            &serde_json::to_value(taints)
+                .map_err(|e| ClusterRepositoryError::Unknown(anyhow::anyhow!(e)))?,
+            node_id.inner(),
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => ClusterRepositoryError::NotFound(node_id.to_string()),
+            _ => ClusterRepositoryError::Unknown(anyhow::anyhow!(e)),
+        })?;
+        self.with_allocations(vec![record])
+            .await
+            .map(|mut nodes| nodes.remove(0))
     }
     async fn delete_cluster_node(&self, node_id: &NodeId) -> Result<(), ClusterRepositoryError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| ClusterRepositoryError::Unknown(anyhow::anyhow!(e)))?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO retained_node_settings (node_id, schedulability, taints)
+            SELECT node_id, schedulability, taints FROM cluster_nodes WHERE node_id = $1
+            ON CONFLICT (node_id) DO UPDATE SET
+                schedulability = EXCLUDED.schedulability,
+                taints = EXCLUDED.taints
+            "#,
+            node_id.inner()
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e: sqlx::Error| ClusterRepositoryError::Unknown(anyhow::anyhow!(e)))?;
+
         sqlx::query!(
             "DELETE FROM cluster_nodes WHERE node_id = $1",
             node_id.inner()
         )
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await
         .map_err(|e: sqlx::Error| ClusterRepositoryError::Unknown(anyhow::anyhow!(e)))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| ClusterRepositoryError::Unknown(anyhow::anyhow!(e)))?;
         Ok(())
     }
 
-    async fn clear_assigned_job_id(&self, node_id: &NodeId) -> Result<(), ClusterRepositoryError> {
+    async fn assign_job_to_nodes(
+        &self,
+        job_id: &JobId,
+        node_ids: &[NodeId],
+        requirements: &ResourceRequirements,
+    ) -> Result<bool, ClusterRepositoryError> {
+        let Some(primary_node_id) = node_ids.first() else {
+            return Ok(false);
+        };
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| ClusterRepositoryError::Unknown(anyhow::anyhow!(e)))?;
+
+        // Lock the nodes, always in the same order, so that heartbeats and
+        // other allocations wait until this one is done.
+        let ids: Vec<uuid::Uuid> = node_ids.iter().map(|id| *id.inner()).collect();
+        let records = sqlx::query_as!(
+            ClusterNodeRecord,
+            r#"
+            SELECT node_id, cluster_id, node_status as "node_status: NodeStatusRecord", schedulability as "schedulability: NodeSchedulabilityRecord", heartbeat_timestamp, memory_mb, cpu as "cpu: CpuConfigurationRecord", gpu as "gpu: GpuConfigurationRecord", created_at, updated_at, reported_job_ids, address, labels, taints
+            FROM cluster_nodes
+            WHERE node_id = ANY($1)
+            ORDER BY node_id
+            FOR UPDATE
+            "#,
+            &ids
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| ClusterRepositoryError::Unknown(anyhow::anyhow!(e)))?;
+
+        // Drop allocations left over from a previous run of the job.
         sqlx::query!(
-            "UPDATE cluster_nodes SET assigned_job_id = NULL WHERE node_id = $1",
-            node_id.inner()
+            "DELETE FROM training_job_nodes WHERE job_id = $1",
+            job_id.inner()
         )
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await
         .map_err(|e| ClusterRepositoryError::Unknown(anyhow::anyhow!(e)))?;
 
-        Ok(())
+        // The nodes may have changed since the scheduler looked at them.
+        let nodes = load_allocations(&mut *tx, records).await?;
+        if nodes.len() != node_ids.len()
+            || !nodes.iter().all(|node| node.has_room_for(requirements))
+        {
+            return Ok(false);
+        }
+
+        let marked = sqlx::query!(
+            "UPDATE training_jobs SET status = 'starting', node_id = $1, started_at = NULL WHERE id = $2 AND status = 'queued'",
+            primary_node_id.inner(),
+            job_id.inner()
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ClusterRepositoryError::Unknown(anyhow::anyhow!(e)))?;
+        if marked.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        for (rank, node_id) in node_ids.iter().enumerate() {
+            // Only allocate what the node has left, so that the node is never
+            // overcommitted even if it wasn't locked above.
+            let allocated = sqlx::query!(
+                r#"
+                INSERT INTO training_job_nodes (job_id, node_id, rank, cpu_millicores, memory_mb, gpu_count)
+                SELECT $1, n.node_id, $3, $4::INT, $5::INT, $6::INT
+                FROM cluster_nodes n
+                LEFT JOIN (
+                    SELECT node_id,
+                        SUM(cpu_millicores) AS cpu_millicores,
+                        SUM(memory_mb) AS memory_mb,
+                        SUM(gpu_count) AS gpu_count
+                    FROM training_job_nodes
+                    WHERE node_id = $2
+                    GROUP BY node_id
+                ) used ON used.node_id = n.node_id
+                WHERE n.node_id = $2
+                    AND (n.cpu).millicores - COALESCE(used.cpu_millicores, 0) >= $4
+                    AND n.memory_mb - COALESCE(used.memory_mb, 0) >= $5
+                    AND COALESCE((n.gpu).count, 0) - COALESCE(used.gpu_count, 0) >= $6
+                "#,
+                job_id.inner(),
+                node_id.inner(),
+                rank as i32,
+                requirements.cpu_millicores,
+                requirements.memory_mb,
+                requirements.gpu_count(),
+            )
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ClusterRepositoryError::Unknown(anyhow::anyhow!(e)))?;
+            if allocated.rows_affected() == 0 {
+                return Ok(false);
+            }
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO training_job_attempts (job_id, attempt_number, node_id)
+            SELECT $1, COALESCE(MAX(attempt_number), 0) + 1, $2
+            FROM training_job_attempts
+            WHERE job_id = $1
+            "#,
+            job_id.inner(),
+            primary_node_id.inner(),
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ClusterRepositoryError::Unknown(anyhow::anyhow!(e)))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| ClusterRepositoryError::Unknown(anyhow::anyhow!(e)))?;
+
+        Ok(true)
     }
 
-    async fn assign_job_to_node(
+    async fn list_job_allocations(
         &self,
-        node_id: &NodeId,
         job_id: &JobId,
-    ) -> Result<(), ClusterRepositoryError> {
-        let result = sqlx::query!(
-            "UPDATE cluster_nodes SET assigned_job_id = $1 WHERE node_id = $2",
-            job_id.inner(),
-            node_id.inner()
+    ) -> Result<Vec<JobAllocation>, ClusterRepositoryError> {
+        let records = sqlx::query_as!(
+            JobAllocationRecord,
+            r#"
+            SELECT job_id, node_id, rank, cpu_millicores, memory_mb, gpu_count
+            FROM training_job_nodes
+            WHERE job_id = $1
+            ORDER BY rank ASC
+            "#,
+            job_id.inner()
         )
-        .execute(&self.pool)
+        .fetch_all(&self.pool)
         .await
         .map_err(|e| ClusterRepositoryError::Unknown(anyhow::anyhow!(e)))?;
 
-        if result.rows_affected() == 0 {
-            return Err(ClusterRepositoryError::NotFound(
-                node_id.inner().to_string(),
-            ));
-        }
+        Ok(records.into_iter().map(JobAllocation::from).collect())
+    }
+
+    async fn release_job_nodes(&self, job_id: &JobId) -> Result<(), ClusterRepositoryError> {
+        sqlx::query!(
+            "DELETE FROM training_job_nodes WHERE job_id = $1",
+            job_id.inner()
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| ClusterRepositoryError::Unknown(anyhow::anyhow!(e)))?;
 
         Ok(())
     }
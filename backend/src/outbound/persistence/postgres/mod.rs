@@ -1,6 +1,11 @@
+pub mod artifact_repository;
 pub mod cluster_repository;
 pub mod queue_repository;
 pub mod records;
+pub mod schedule_repository;
+pub mod scheduler_lease_repository;
+pub mod scheduler_wakeups;
+pub mod secret_repository;
 pub mod session_repository;
 pub mod training_job_repository;
 pub mod user_repository;
@@ -7,6 +7,8 @@ use crate::domain::queue::{
     ports::{QueueRepository, QueueRepositoryError},
 };
 
+use super::records::SchedulingPolicyRecord;
+
 pub struct PostgresQueueRepository {
     pool: PgPool,
 }
@@ -22,11 +24,18 @@ pub struct QueueRecord {
     name: String,
     priority: i32,
     cluster_targets: Option<Vec<uuid::Uuid>>,
+    preemptible: bool,
+    scheduling_policy: SchedulingPolicyRecord,
+    retry_policy: Option<serde_json::Value>,
+    default_max_runtime_secs: Option<i64>,
+    max_runtime_limit_secs: Option<i64>,
 }
 
-impl From<QueueRecord> for Queue {
-    fn from(value: QueueRecord) -> Self {
-        Self {
+impl TryFrom<QueueRecord> for Queue {
+    type Error = anyhow::Error;
+
+    fn try_from(value: QueueRecord) -> Result<Self, Self::Error> {
+        Ok(Self {
             id: value.queue_id.into(),
             name: value.name,
             priority: value.priority,
@@ -36,7 +45,12 @@ impl From<QueueRecord> for Queue {
                 .into_iter()
                 .map(|v| v.into())
                 .collect(),
-        }
+            preemptible: value.preemptible,
+            scheduling_policy: value.scheduling_policy.into(),
+            retry_policy: value.retry_policy.map(serde_json::from_value).transpose()?,
+            default_max_runtime_secs: value.default_max_runtime_secs,
+            max_runtime_limit_secs: value.max_runtime_limit_secs,
+        })
     }
 }
 
@@ -50,7 +64,12 @@ impl QueueRepository for PostgresQueueRepository {
                 q.queue_id,
                 q.name,
                 q.priority,
-                ARRAY_AGG(qca.cluster_id ORDER BY qca.order) FILTER (WHERE qca.cluster_id IS NOT NULL) as "cluster_targets: Vec<Uuid>"
+                ARRAY_AGG(qca.cluster_id ORDER BY qca.order) FILTER (WHERE qca.cluster_id IS NOT NULL) as "cluster_targets: Vec<Uuid>",
+                q.preemptible,
+                q.scheduling_policy as "scheduling_policy: SchedulingPolicyRecord",
+                q.retry_policy,
+                q.default_max_runtime_secs,
+                q.max_runtime_limit_secs
             FROM
                 queues q
             LEFT JOIN
@@ -65,7 +84,11 @@ impl QueueRepository for PostgresQueueRepository {
         .await
         .map_err(|e| QueueRepositoryError::Unknown(e.into()))?;
 
-        let queues = records.into_iter().map(|record| record.into()).collect();
+        let queues = records
+            .into_iter()
+            .map(Queue::try_from)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(QueueRepositoryError::Unknown)?;
 
         Ok(queues)
     }
@@ -77,10 +100,20 @@ impl QueueRepository for PostgresQueueRepository {
             .map_err(|e| QueueRepositoryError::Unknown(e.into()))?;
 
         sqlx::query!(
-            "INSERT INTO queues (queue_id, name, priority) VALUES ($1, $2, $3)",
+            "INSERT INTO queues (queue_id, name, priority, preemptible, scheduling_policy, retry_policy, default_max_runtime_secs, max_runtime_limit_secs) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
             queue.id.inner(),
             queue.name,
-            queue.priority
+            queue.priority,
+            queue.preemptible,
+            SchedulingPolicyRecord::from(queue.scheduling_policy) as _,
+            queue
+                .retry_policy
+                .as_ref()
+                .map(serde_json::to_value)
+                .transpose()
+                .map_err(|e| QueueRepositoryError::Unknown(e.into()))?,
+            queue.default_max_runtime_secs,
+            queue.max_runtime_limit_secs
         )
         .execute(&mut *tx)
         .await
@@ -113,9 +146,19 @@ impl QueueRepository for PostgresQueueRepository {
             .map_err(|e| QueueRepositoryError::Unknown(e.into()))?;
 
         sqlx::query!(
-            "UPDATE queues SET name = $1, priority = $2 WHERE queue_id = $3",
+            "UPDATE queues SET name = $1, priority = $2, preemptible = $3, scheduling_policy = $4, retry_policy = $5, default_max_runtime_secs = $6, max_runtime_limit_secs = $7 WHERE queue_id = $8",
             queue.name,
             queue.priority,
+            queue.preemptible,
+            SchedulingPolicyRecord::from(queue.scheduling_policy) as _,
+            queue
+                .retry_policy
+                .as_ref()
+                .map(serde_json::to_value)
+                .transpose()
+                .map_err(|e| QueueRepositoryError::Unknown(e.into()))?,
+            queue.default_max_runtime_secs,
+            queue.max_runtime_limit_secs,
             queue.id.inner()
         )
         .execute(&mut *tx)
@@ -165,7 +208,12 @@ impl QueueRepository for PostgresQueueRepository {
                 q.queue_id,
                 q.name,
                 q.priority,
-                ARRAY_AGG(qca.cluster_id ORDER BY qca.order) FILTER (WHERE qca.cluster_id IS NOT NULL) as "cluster_targets: Vec<Uuid>"
+                ARRAY_AGG(qca.cluster_id ORDER BY qca.order) FILTER (WHERE qca.cluster_id IS NOT NULL) as "cluster_targets: Vec<Uuid>",
+                q.preemptible,
+                q.scheduling_policy as "scheduling_policy: SchedulingPolicyRecord",
+                q.retry_policy,
+                q.default_max_runtime_secs,
+                q.max_runtime_limit_secs
             FROM
                 queues q
             LEFT JOIN
@@ -184,6 +232,6 @@ impl QueueRepository for PostgresQueueRepository {
             _ => QueueRepositoryError::Unknown(err.into()),
         })?;
 
-        Ok(record.into())
+        record.try_into().map_err(QueueRepositoryError::Unknown)
     }
 }
@@ -1,10 +1,16 @@
 use crate::domain::{
+    artifact::models::Artifact,
     cluster::models::{
         Architecture, Cluster, ClusterCpuStats, ClusterDetails, ClusterGpuStats, ClusterJobStats,
         ClusterMemoryStats, ClusterNode, ClusterSummary, Cpu, CpuManufacturer, Gpu,
-        GpuManufacturer, GpuModel, NodeStatus,
+        GpuManufacturer, GpuModel, NodeSchedulability, NodeStatus,
+    },
+    schedule::models::{ConcurrencyPolicy, ScheduleRunOutcome},
+    scheduler::models::SchedulingPolicyKind,
+    training_job::models::{
+        AttemptOutcome, JobAllocation, JobArray, JobAttempt, JobEvent, LogChunk, MetricSample,
+        TrainingJob, TrainingJobStatus,
     },
-    training_job::models::{TrainingJob, TrainingJobStatus},
     user::models::ApiKey,
 };
 use chrono::{DateTime, Utc};
@@ -76,6 +82,34 @@ impl From<NodeStatus> for NodeStatusRecord {
     }
 }
 
+#[derive(sqlx::Type, Debug, Clone, Copy, PartialEq, Eq)]
+#[sqlx(type_name = "node_schedulability", rename_all = "snake_case")]
+pub enum NodeSchedulabilityRecord {
+    Schedulable,
+    Cordoned,
+    Draining,
+}
+
+impl From<NodeSchedulability> for NodeSchedulabilityRecord {
+    fn from(value: NodeSchedulability) -> Self {
+        match value {
+            NodeSchedulability::Schedulable => Self::Schedulable,
+            NodeSchedulability::Cordoned => Self::Cordoned,
+            NodeSchedulability::Draining => Self::Draining,
+        }
+    }
+}
+
+impl From<NodeSchedulabilityRecord> for NodeSchedulability {
+    fn from(value: NodeSchedulabilityRecord) -> Self {
+        match value {
+            NodeSchedulabilityRecord::Schedulable => Self::Schedulable,
+            NodeSchedulabilityRecord::Cordoned => Self::Cordoned,
+            NodeSchedulabilityRecord::Draining => Self::Draining,
+        }
+    }
+}
+
 #[derive(sqlx::Type, Debug, Clone, PartialEq, Eq)]
 #[sqlx(type_name = "cpu_manufacturer", rename_all = "PascalCase")]
 pub enum CpuManufacturerRecord {
@@ -281,25 +315,31 @@ pub struct ClusterNodeRecord {
     pub node_id: uuid::Uuid,
     pub cluster_id: uuid::Uuid,
     pub node_status: NodeStatusRecord,
+    pub schedulability: NodeSchedulabilityRecord,
     pub heartbeat_timestamp: DateTime<Utc>,
     pub memory_mb: i32,
     pub cpu: CpuConfigurationRecord,
     pub gpu: Option<GpuConfigurationRecord>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
-    pub assigned_job_id: Option<uuid::Uuid>,
-    pub reported_job_id: Option<uuid::Uuid>,
+    pub reported_job_ids: Vec<uuid::Uuid>,
+    pub address: Option<String>,
+    pub labels: serde_json::Value,
+    pub taints: serde_json::Value,
 }
 
-impl From<ClusterNodeRecord> for ClusterNode {
-    fn from(record: ClusterNodeRecord) -> Self {
-        Self {
+impl TryFrom<ClusterNodeRecord> for ClusterNode {
+    type Error = anyhow::Error;
+
+    fn try_from(record: ClusterNodeRecord) -> Result<Self, Self::Error> {
+        Ok(Self {
             id: record.node_id.into(),
             cluster_id: record.cluster_id.into(),
             node_status: match record.node_status {
                 NodeStatusRecord::Available => NodeStatus::Available,
                 NodeStatusRecord::Busy => NodeStatus::Busy,
             },
+            schedulability: record.schedulability.into(),
             heartbeat_timestamp: record.heartbeat_timestamp,
             memory_mb: record.memory_mb,
             cpu: Cpu {
@@ -315,9 +355,16 @@ impl From<ClusterNodeRecord> for ClusterNode {
             }),
             created_at: record.created_at,
             updated_at: record.updated_at,
-            assigned_job_id: record.assigned_job_id.map(Into::into),
-            reported_job_id: record.reported_job_id.map(Into::into),
-        }
+            allocations: Vec::new(),
+            reported_job_ids: record
+                .reported_job_ids
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            address: record.address,
+            labels: serde_json::from_value(record.labels)?,
+            taints: serde_json::from_value(record.taints)?,
+        })
     }
 }
 
@@ -395,6 +442,36 @@ impl From<ApiKeyRecord> for ApiKey {
     }
 }
 
+//  Queue Repository Records
+
+#[derive(sqlx::Type, Debug, Clone, Copy, PartialEq, Eq)]
+#[sqlx(type_name = "scheduling_policy", rename_all = "snake_case")]
+pub enum SchedulingPolicyRecord {
+    BestFit,
+    Spread,
+    GpuPacking,
+}
+
+impl From<SchedulingPolicyKind> for SchedulingPolicyRecord {
+    fn from(value: SchedulingPolicyKind) -> Self {
+        match value {
+            SchedulingPolicyKind::BestFit => Self::BestFit,
+            SchedulingPolicyKind::Spread => Self::Spread,
+            SchedulingPolicyKind::GpuPacking => Self::GpuPacking,
+        }
+    }
+}
+
+impl From<SchedulingPolicyRecord> for SchedulingPolicyKind {
+    fn from(value: SchedulingPolicyRecord) -> Self {
+        match value {
+            SchedulingPolicyRecord::BestFit => Self::BestFit,
+            SchedulingPolicyRecord::Spread => Self::Spread,
+            SchedulingPolicyRecord::GpuPacking => Self::GpuPacking,
+        }
+    }
+}
+
 //  Training Job Repository Records
 
 #[derive(sqlx::Type, Debug, Clone, PartialEq, Eq)]
@@ -406,6 +483,8 @@ pub enum TrainingJobStatusRecord {
     Succeeded,
     Failed,
     Cancelled,
+    #[sqlx(rename = "timed_out")]
+    TimedOut,
 }
 
 impl From<TrainingJobStatus> for TrainingJobStatusRecord {
@@ -417,6 +496,7 @@ impl From<TrainingJobStatus> for TrainingJobStatusRecord {
             TrainingJobStatus::Succeeded => Self::Succeeded,
             TrainingJobStatus::Failed => Self::Failed,
             TrainingJobStatus::Cancelled => Self::Cancelled,
+            TrainingJobStatus::TimedOut => Self::TimedOut,
         }
     }
 }
@@ -430,6 +510,7 @@ impl From<TrainingJobStatusRecord> for TrainingJobStatus {
             TrainingJobStatusRecord::Succeeded => Self::Succeeded,
             TrainingJobStatusRecord::Failed => Self::Failed,
             TrainingJobStatusRecord::Cancelled => Self::Cancelled,
+            TrainingJobStatusRecord::TimedOut => Self::TimedOut,
         }
     }
 }
@@ -438,11 +519,30 @@ impl From<TrainingJobStatusRecord> for TrainingJobStatus {
 pub struct TrainingJobRecord {
     pub id: Uuid,
     pub name: String,
-    pub definition: String,
+    pub definition: serde_json::Value,
     pub status: TrainingJobStatusRecord,
     pub node_id: Option<Uuid>,
     pub queue_id: Option<Uuid>,
     pub resource_requirements: serde_json::Value,
+    pub node_count: i32,
+    pub preempted_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub preemption_reason: Option<String>,
+    pub dependencies: serde_json::Value,
+    pub retry_policy: Option<serde_json::Value>,
+    pub not_before: Option<chrono::DateTime<chrono::Utc>>,
+    pub avoid_node_id: Option<Uuid>,
+    pub max_runtime_secs: Option<i64>,
+    pub started_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub exit: Option<serde_json::Value>,
+    pub array_id: Option<Uuid>,
+    pub array_index: Option<i32>,
+    pub parameters: serde_json::Value,
+    pub node_selector: serde_json::Value,
+    pub tolerations: serde_json::Value,
+    pub env: serde_json::Value,
+    pub secrets: serde_json::Value,
+    pub outputs: serde_json::Value,
+    pub volumes: serde_json::Value,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
@@ -452,16 +552,286 @@ impl TryFrom<TrainingJobRecord> for TrainingJob {
 
     fn try_from(value: TrainingJobRecord) -> Result<Self, Self::Error> {
         let resource_requirements = serde_json::from_value(value.resource_requirements)?;
+        let dependencies = serde_json::from_value(value.dependencies)?;
+        let retry_policy = value.retry_policy.map(serde_json::from_value).transpose()?;
+        let parameters = serde_json::from_value(value.parameters)?;
         Ok(Self {
             id: value.id.into(),
             name: value.name,
-            definition: value.definition,
+            definition: serde_json::from_value(value.definition)?,
             status: value.status.into(),
             node_id: value.node_id.map(|v| v.into()),
             queue_id: value.queue_id.map(Into::into),
             resource_requirements,
+            node_count: value.node_count,
+            preempted_at: value.preempted_at,
+            preemption_reason: value.preemption_reason,
+            dependencies,
+            retry_policy,
+            not_before: value.not_before,
+            avoid_node_id: value.avoid_node_id.map(Into::into),
+            max_runtime_secs: value.max_runtime_secs,
+            started_at: value.started_at,
+            exit: value.exit.map(serde_json::from_value).transpose()?,
+            array_id: value.array_id.map(Into::into),
+            array_index: value.array_index,
+            parameters,
+            node_selector: serde_json::from_value(value.node_selector)?,
+            tolerations: serde_json::from_value(value.tolerations)?,
+            env: serde_json::from_value(value.env)?,
+            secrets: serde_json::from_value(value.secrets)?,
+            outputs: serde_json::from_value(value.outputs)?,
+            volumes: serde_json::from_value(value.volumes)?,
             created_at: value.created_at,
             updated_at: value.updated_at,
         })
     }
 }
+
+#[derive(sqlx::FromRow)]
+pub struct JobArrayRecord {
+    pub array_id: Uuid,
+    pub name: String,
+    pub size: i32,
+    pub max_concurrent: Option<i32>,
+    pub parameters: serde_json::Value,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl TryFrom<JobArrayRecord> for JobArray {
+    type Error = anyhow::Error;
+
+    fn try_from(value: JobArrayRecord) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: value.array_id.into(),
+            name: value.name,
+            size: value.size,
+            max_concurrent: value.max_concurrent,
+            parameters: serde_json::from_value(value.parameters)?,
+            created_at: value.created_at,
+        })
+    }
+}
+
+#[derive(sqlx::Type, Debug, Clone, Copy, PartialEq, Eq)]
+#[sqlx(type_name = "attempt_outcome", rename_all = "snake_case")]
+pub enum AttemptOutcomeRecord {
+    Succeeded,
+    Failed,
+    NodeLost,
+    Preempted,
+    Cancelled,
+    TimedOut,
+}
+
+impl From<AttemptOutcome> for AttemptOutcomeRecord {
+    fn from(value: AttemptOutcome) -> Self {
+        match value {
+            AttemptOutcome::Succeeded => Self::Succeeded,
+            AttemptOutcome::Failed => Self::Failed,
+            AttemptOutcome::NodeLost => Self::NodeLost,
+            AttemptOutcome::Preempted => Self::Preempted,
+            AttemptOutcome::Cancelled => Self::Cancelled,
+            AttemptOutcome::TimedOut => Self::TimedOut,
+        }
+    }
+}
+
+impl From<AttemptOutcomeRecord> for AttemptOutcome {
+    fn from(value: AttemptOutcomeRecord) -> Self {
+        match value {
+            AttemptOutcomeRecord::Succeeded => Self::Succeeded,
+            AttemptOutcomeRecord::Failed => Self::Failed,
+            AttemptOutcomeRecord::NodeLost => Self::NodeLost,
+            AttemptOutcomeRecord::Preempted => Self::Preempted,
+            AttemptOutcomeRecord::Cancelled => Self::Cancelled,
+            AttemptOutcomeRecord::TimedOut => Self::TimedOut,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+pub struct JobAttemptRecord {
+    pub job_id: Uuid,
+    pub attempt_number: i32,
+    pub node_id: Option<Uuid>,
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    pub finished_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub outcome: Option<AttemptOutcomeRecord>,
+    pub exit_code: Option<i32>,
+}
+
+impl From<JobAttemptRecord> for JobAttempt {
+    fn from(value: JobAttemptRecord) -> Self {
+        Self {
+            job_id: value.job_id.into(),
+            attempt_number: value.attempt_number,
+            node_id: value.node_id.map(Into::into),
+            started_at: value.started_at,
+            finished_at: value.finished_at,
+            outcome: value.outcome.map(Into::into),
+            exit_code: value.exit_code,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+pub struct ArtifactRecord {
+    pub job_id: Uuid,
+    pub path: String,
+    pub size_bytes: i64,
+    pub uploaded_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<ArtifactRecord> for Artifact {
+    fn from(value: ArtifactRecord) -> Self {
+        Self {
+            job_id: value.job_id.into(),
+            path: value.path,
+            size_bytes: value.size_bytes,
+            uploaded_at: value.uploaded_at,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+pub struct JobEventRecord {
+    pub id: i64,
+    pub job_id: Uuid,
+    pub event: serde_json::Value,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl TryFrom<JobEventRecord> for JobEvent {
+    type Error = anyhow::Error;
+
+    fn try_from(value: JobEventRecord) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: value.id,
+            job_id: value.job_id.into(),
+            kind: serde_json::from_value(value.event)?,
+            created_at: value.created_at,
+        })
+    }
+}
+
+#[derive(sqlx::FromRow)]
+pub struct MetricSampleRecord {
+    pub node_id: Uuid,
+    pub sampled_at: chrono::DateTime<chrono::Utc>,
+    pub cpu_millicores: Option<i32>,
+    pub memory_mb: Option<i64>,
+    pub gpus: serde_json::Value,
+}
+
+impl TryFrom<MetricSampleRecord> for MetricSample {
+    type Error = anyhow::Error;
+
+    fn try_from(value: MetricSampleRecord) -> Result<Self, Self::Error> {
+        Ok(Self {
+            node_id: value.node_id.into(),
+            sampled_at: value.sampled_at,
+            cpu_millicores: value.cpu_millicores,
+            memory_mb: value.memory_mb,
+            gpus: serde_json::from_value(value.gpus)?,
+        })
+    }
+}
+
+#[derive(sqlx::FromRow)]
+pub struct LogChunkRecord {
+    pub id: i64,
+    pub content: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<LogChunkRecord> for LogChunk {
+    fn from(value: LogChunkRecord) -> Self {
+        Self {
+            offset: value.id,
+            content: value.content,
+            created_at: value.created_at,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+pub struct JobAllocationRecord {
+    pub job_id: Uuid,
+    pub node_id: Uuid,
+    pub rank: i32,
+    pub cpu_millicores: i32,
+    pub memory_mb: i32,
+    pub gpu_count: i32,
+}
+
+impl From<JobAllocationRecord> for JobAllocation {
+    fn from(record: JobAllocationRecord) -> Self {
+        Self {
+            job_id: record.job_id.into(),
+            node_id: record.node_id.into(),
+            rank: record.rank,
+            cpu_millicores: record.cpu_millicores,
+            memory_mb: record.memory_mb,
+            gpu_count: record.gpu_count,
+        }
+    }
+}
+
+//  Schedule Repository Records
+
+#[derive(sqlx::Type, Debug, Clone, Copy, PartialEq, Eq)]
+#[sqlx(type_name = "concurrency_policy", rename_all = "snake_case")]
+pub enum ConcurrencyPolicyRecord {
+    Allow,
+    Forbid,
+    Replace,
+}
+
+impl From<ConcurrencyPolicy> for ConcurrencyPolicyRecord {
+    fn from(value: ConcurrencyPolicy) -> Self {
+        match value {
+            ConcurrencyPolicy::Allow => Self::Allow,
+            ConcurrencyPolicy::Forbid => Self::Forbid,
+            ConcurrencyPolicy::Replace => Self::Replace,
+        }
+    }
+}
+
+impl From<ConcurrencyPolicyRecord> for ConcurrencyPolicy {
+    fn from(value: ConcurrencyPolicyRecord) -> Self {
+        match value {
+            ConcurrencyPolicyRecord::Allow => Self::Allow,
+            ConcurrencyPolicyRecord::Forbid => Self::Forbid,
+            ConcurrencyPolicyRecord::Replace => Self::Replace,
+        }
+    }
+}
+
+#[derive(sqlx::Type, Debug, Clone, Copy, PartialEq, Eq)]
+#[sqlx(type_name = "schedule_run_outcome", rename_all = "snake_case")]
+pub enum ScheduleRunOutcomeRecord {
+    Started,
+    Skipped,
+    Failed,
+}
+
+impl From<ScheduleRunOutcome> for ScheduleRunOutcomeRecord {
+    fn from(value: ScheduleRunOutcome) -> Self {
+        match value {
+            ScheduleRunOutcome::Started => Self::Started,
+            ScheduleRunOutcome::Skipped => Self::Skipped,
+            ScheduleRunOutcome::Failed => Self::Failed,
+        }
+    }
+}
+
+impl From<ScheduleRunOutcomeRecord> for ScheduleRunOutcome {
+    fn from(value: ScheduleRunOutcomeRecord) -> Self {
+        match value {
+            ScheduleRunOutcomeRecord::Started => Self::Started,
+            ScheduleRunOutcomeRecord::Skipped => Self::Skipped,
+            ScheduleRunOutcomeRecord::Failed => Self::Failed,
+        }
+    }
+}
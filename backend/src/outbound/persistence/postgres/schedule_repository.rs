@@ -0,0 +1,357 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use crate::domain::{
+    schedule::{
+        models::{JobSchedule, ScheduleId, ScheduleRun},
+        ports::{ScheduleRepository, ScheduleRepositoryError},
+    },
+    training_job::models::JobId,
+};
+
+use super::records::{ConcurrencyPolicyRecord, ScheduleRunOutcomeRecord};
+
+pub struct PostgresScheduleRepository {
+    pool: PgPool,
+}
+
+impl PostgresScheduleRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+pub struct ScheduleRecord {
+    schedule_id: uuid::Uuid,
+    name: String,
+    cron_expression: String,
+    job_template: serde_json::Value,
+    concurrency_policy: ConcurrencyPolicyRecord,
+    paused: bool,
+    next_run_at: Option<DateTime<Utc>>,
+    owner_id: Option<uuid::Uuid>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<ScheduleRecord> for JobSchedule {
+    type Error = anyhow::Error;
+
+    fn try_from(value: ScheduleRecord) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: value.schedule_id.into(),
+            name: value.name,
+            cron: value
+                .cron_expression
+                .parse()
+                .map_err(|e: String| anyhow::anyhow!(e))?,
+            job_template: serde_json::from_value(value.job_template)?,
+            concurrency_policy: value.concurrency_policy.into(),
+            paused: value.paused,
+            next_run_at: value.next_run_at,
+            owner_id: value.owner_id.map(Into::into),
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        })
+    }
+}
+
+pub struct ScheduleRunRecord {
+    schedule_id: uuid::Uuid,
+    scheduled_at: DateTime<Utc>,
+    outcome: ScheduleRunOutcomeRecord,
+    job_id: Option<uuid::Uuid>,
+    message: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+impl From<ScheduleRunRecord> for ScheduleRun {
+    fn from(value: ScheduleRunRecord) -> Self {
+        Self {
+            schedule_id: value.schedule_id.into(),
+            scheduled_at: value.scheduled_at,
+            outcome: value.outcome.into(),
+            job_id: value.job_id.map(Into::into),
+            message: value.message,
+            created_at: value.created_at,
+        }
+    }
+}
+
+fn to_schedules(records: Vec<ScheduleRecord>) -> Result<Vec<JobSchedule>, ScheduleRepositoryError> {
+    records
+        .into_iter()
+        .map(JobSchedule::try_from)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(ScheduleRepositoryError::Unknown)
+}
+
+#[async_trait]
+impl ScheduleRepository for PostgresScheduleRepository {
+    async fn create(&self, schedule: &JobSchedule) -> Result<(), ScheduleRepositoryError> {
+        sqlx::query!(
+            r#"
+            INSERT INTO job_schedules (schedule_id, name, cron_expression, job_template, concurrency_policy, paused, next_run_at, owner_id, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            "#,
+            schedule.id.inner(),
+            schedule.name,
+            schedule.cron.as_str(),
+            serde_json::to_value(&schedule.job_template)
+                .map_err(|e| ScheduleRepositoryError::Unknown(e.into()))?,
+            ConcurrencyPolicyRecord::from(schedule.concurrency_policy) as _,
+            schedule.paused,
+            schedule.next_run_at,
+            schedule.owner_id.map(|id| id.into_inner()),
+            schedule.created_at,
+            schedule.updated_at
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|err| match err {
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+                ScheduleRepositoryError::Duplicate {
+                    field: "name".to_string(),
+                    value: schedule.name.clone(),
+                }
+            }
+            _ => ScheduleRepositoryError::Unknown(err.into()),
+        })?;
+
+        Ok(())
+    }
+
+    async fn get_schedule_by_id(
+        &self,
+        id: &ScheduleId,
+    ) -> Result<JobSchedule, ScheduleRepositoryError> {
+        let record = sqlx::query_as!(
+            ScheduleRecord,
+            r#"
+            SELECT
+                schedule_id,
+                name,
+                cron_expression,
+                job_template,
+                concurrency_policy as "concurrency_policy: ConcurrencyPolicyRecord",
+                paused,
+                next_run_at,
+                owner_id,
+                created_at,
+                updated_at
+            FROM job_schedules
+            WHERE schedule_id = $1
+            "#,
+            id.inner()
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|err| match err {
+            sqlx::Error::RowNotFound => ScheduleRepositoryError::NotFound(id.to_string()),
+            _ => ScheduleRepositoryError::Unknown(err.into()),
+        })?;
+
+        record.try_into().map_err(ScheduleRepositoryError::Unknown)
+    }
+
+    async fn list_schedules(&self) -> Result<Vec<JobSchedule>, ScheduleRepositoryError> {
+        let records = sqlx::query_as!(
+            ScheduleRecord,
+            r#"
+            SELECT
+                schedule_id,
+                name,
+                cron_expression,
+                job_template,
+                concurrency_policy as "concurrency_policy: ConcurrencyPolicyRecord",
+                paused,
+                next_run_at,
+                owner_id,
+                created_at,
+                updated_at
+            FROM job_schedules
+            ORDER BY name
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| ScheduleRepositoryError::Unknown(e.into()))?;
+
+        to_schedules(records)
+    }
+
+    async fn update(&self, schedule: &JobSchedule) -> Result<(), ScheduleRepositoryError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE job_schedules
+            SET name = $1, cron_expression = $2, job_template = $3, concurrency_policy = $4, paused = $5, next_run_at = $6, updated_at = $7
+            WHERE schedule_id = $8
+            "#,
+            schedule.name,
+            schedule.cron.as_str(),
+            serde_json::to_value(&schedule.job_template)
+                .map_err(|e| ScheduleRepositoryError::Unknown(e.into()))?,
+            ConcurrencyPolicyRecord::from(schedule.concurrency_policy) as _,
+            schedule.paused,
+            schedule.next_run_at,
+            schedule.updated_at,
+            schedule.id.inner()
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|err| match err {
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+                ScheduleRepositoryError::Duplicate {
+                    field: "name".to_string(),
+                    value: schedule.name.clone(),
+                }
+            }
+            _ => ScheduleRepositoryError::Unknown(err.into()),
+        })?;
+
+        if result.rows_affected() == 0 {
+            return Err(ScheduleRepositoryError::NotFound(schedule.id.to_string()));
+        }
+
+        Ok(())
+    }
+
+    async fn delete(&self, id: &ScheduleId) -> Result<(), ScheduleRepositoryError> {
+        let result = sqlx::query!(
+            "DELETE FROM job_schedules WHERE schedule_id = $1",
+            id.inner()
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| ScheduleRepositoryError::Unknown(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(ScheduleRepositoryError::NotFound(id.to_string()));
+        }
+
+        Ok(())
+    }
+
+    async fn get_due_schedules(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<JobSchedule>, ScheduleRepositoryError> {
+        let records = sqlx::query_as!(
+            ScheduleRecord,
+            r#"
+            SELECT
+                schedule_id,
+                name,
+                cron_expression,
+                job_template,
+                concurrency_policy as "concurrency_policy: ConcurrencyPolicyRecord",
+                paused,
+                next_run_at,
+                owner_id,
+                created_at,
+                updated_at
+            FROM job_schedules
+            WHERE NOT paused AND next_run_at <= $1
+            ORDER BY next_run_at
+            "#,
+            now
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| ScheduleRepositoryError::Unknown(e.into()))?;
+
+        to_schedules(records)
+    }
+
+    async fn claim_run(
+        &self,
+        id: &ScheduleId,
+        due_at: DateTime<Utc>,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<bool, ScheduleRepositoryError> {
+        // Only one replica can move the run on from `due_at`, the others
+        // find nothing left to update.
+        let result = sqlx::query!(
+            r#"
+            UPDATE job_schedules
+            SET next_run_at = $1
+            WHERE schedule_id = $2 AND next_run_at = $3 AND NOT paused
+            "#,
+            next_run_at,
+            id.inner(),
+            due_at
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| ScheduleRepositoryError::Unknown(e.into()))?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn record_run(&self, run: &ScheduleRun) -> Result<(), ScheduleRepositoryError> {
+        sqlx::query!(
+            r#"
+            INSERT INTO job_schedule_runs (schedule_id, scheduled_at, outcome, job_id, message, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (schedule_id, scheduled_at) DO NOTHING
+            "#,
+            run.schedule_id.inner(),
+            run.scheduled_at,
+            ScheduleRunOutcomeRecord::from(run.outcome) as _,
+            run.job_id.as_ref().map(|id| *id.inner()),
+            run.message,
+            run.created_at
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| ScheduleRepositoryError::Unknown(e.into()))?;
+
+        Ok(())
+    }
+
+    async fn get_runs(&self, id: &ScheduleId) -> Result<Vec<ScheduleRun>, ScheduleRepositoryError> {
+        let records = sqlx::query_as!(
+            ScheduleRunRecord,
+            r#"
+            SELECT
+                schedule_id,
+                scheduled_at,
+                outcome as "outcome: ScheduleRunOutcomeRecord",
+                job_id,
+                message,
+                created_at
+            FROM job_schedule_runs
+            WHERE schedule_id = $1
+            ORDER BY scheduled_at DESC
+            "#,
+            id.inner()
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| ScheduleRepositoryError::Unknown(e.into()))?;
+
+        Ok(records.into_iter().map(Into::into).collect())
+    }
+
+    async fn get_active_jobs(
+        &self,
+        id: &ScheduleId,
+    ) -> Result<Vec<JobId>, ScheduleRepositoryError> {
+        let job_ids = sqlx::query_scalar!(
+            r#"
+            SELECT tj.id
+            FROM job_schedule_runs r
+            JOIN training_jobs tj ON tj.id = r.job_id
+            WHERE r.schedule_id = $1
+              AND tj.status IN ('queued', 'starting', 'running')
+            "#,
+            id.inner()
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| ScheduleRepositoryError::Unknown(e.into()))?;
+
+        Ok(job_ids.into_iter().map(Into::into).collect())
+    }
+}
@@ -0,0 +1,98 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use crate::domain::scheduler::{
+    models::SchedulerLease,
+    ports::{SchedulerLeaseRepository, SchedulerLeaseRepositoryError},
+};
+
+/// The name of the lease row the scheduler replicas compete for.
+const SCHEDULER_LEASE: &str = "scheduler";
+
+pub struct PostgresSchedulerLeaseRepository {
+    pool: PgPool,
+}
+
+impl PostgresSchedulerLeaseRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+pub struct SchedulerLeaseRecord {
+    holder_id: String,
+    acquired_at: DateTime<Utc>,
+    renewed_at: DateTime<Utc>,
+    expires_at: DateTime<Utc>,
+}
+
+impl From<SchedulerLeaseRecord> for SchedulerLease {
+    fn from(value: SchedulerLeaseRecord) -> Self {
+        Self {
+            holder_id: value.holder_id,
+            acquired_at: value.acquired_at,
+            renewed_at: value.renewed_at,
+            expires_at: value.expires_at,
+        }
+    }
+}
+
+#[async_trait]
+impl SchedulerLeaseRepository for PostgresSchedulerLeaseRepository {
+    async fn try_acquire(
+        &self,
+        holder_id: &str,
+        ttl: Duration,
+    ) -> Result<bool, SchedulerLeaseRepositoryError> {
+        let ttl_secs = i64::try_from(ttl.as_secs()).unwrap_or(i64::MAX);
+
+        // The conditional upsert is atomic, so of several replicas racing for
+        // an expired lease exactly one wins.
+        let row = sqlx::query!(
+            r#"
+            INSERT INTO scheduler_leases (name, holder_id, acquired_at, renewed_at, expires_at)
+            VALUES ($1, $2, NOW(), NOW(), NOW() + $3::BIGINT * INTERVAL '1 second')
+            ON CONFLICT (name) DO UPDATE SET
+                holder_id = EXCLUDED.holder_id,
+                acquired_at = CASE
+                    WHEN scheduler_leases.holder_id = EXCLUDED.holder_id
+                    THEN scheduler_leases.acquired_at
+                    ELSE NOW()
+                END,
+                renewed_at = NOW(),
+                expires_at = EXCLUDED.expires_at
+            WHERE scheduler_leases.holder_id = EXCLUDED.holder_id
+                OR scheduler_leases.expires_at < NOW()
+            RETURNING holder_id
+            "#,
+            SCHEDULER_LEASE,
+            holder_id,
+            ttl_secs,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| SchedulerLeaseRepositoryError::Unknown(e.into()))?;
+
+        Ok(row.is_some())
+    }
+
+    async fn get_lease(&self) -> Result<Option<SchedulerLease>, SchedulerLeaseRepositoryError> {
+        let record = sqlx::query_as!(
+            SchedulerLeaseRecord,
+            r#"
+            SELECT holder_id, acquired_at, renewed_at, expires_at
+            FROM scheduler_leases
+            WHERE name = $1
+            "#,
+            SCHEDULER_LEASE,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| SchedulerLeaseRepositoryError::Unknown(e.into()))?;
+
+        Ok(record.map(SchedulerLease::from))
+    }
+}
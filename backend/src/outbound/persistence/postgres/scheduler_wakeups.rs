@@ -0,0 +1,58 @@
+use std::time::Duration;
+
+use sqlx::{postgres::PgListener, PgPool};
+
+use crate::domain::scheduler::notifier::SchedulerNotifier;
+
+/// The channel scheduler notifications are sent between replicas on.
+const WAKEUP_CHANNEL: &str = "lilac_scheduler_wakeups";
+
+/// How long to wait before listening again after the connection failed.
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// Relays scheduler notifications between control plane replicas through
+/// Postgres `LISTEN`/`NOTIFY`, so that a job submitted to a follower wakes up
+/// the leader's scheduler right away.
+pub struct PostgresSchedulerWakeups {
+    pool: PgPool,
+    replica_id: String,
+}
+
+impl PostgresSchedulerWakeups {
+    pub fn new(pool: PgPool, replica_id: String) -> Self {
+        Self { pool, replica_id }
+    }
+
+    /// Forwards the notifications of this replica's `notifier` to the other
+    /// replicas and theirs to it, forever.
+    pub async fn run(&self, notifier: SchedulerNotifier) {
+        loop {
+            if let Err(e) = self.relay(&notifier).await {
+                tracing::error!("Scheduler wakeup relay failed: {}", e);
+            }
+            tokio::time::sleep(RECONNECT_DELAY).await;
+        }
+    }
+
+    async fn relay(&self, notifier: &SchedulerNotifier) -> Result<(), sqlx::Error> {
+        let mut listener = PgListener::connect_with(&self.pool).await?;
+        listener.listen(WAKEUP_CHANNEL).await?;
+        // Anything that happened while not listening is picked up by the
+        // periodic cycle.
+        loop {
+            tokio::select! {
+                _ = notifier.outgoing() => {
+                    sqlx::query!("SELECT pg_notify($1, $2)", WAKEUP_CHANNEL, self.replica_id)
+                        .execute(&self.pool)
+                        .await?;
+                }
+                notification = listener.recv() => {
+                    // Replicas hear their own notifications too.
+                    if notification?.payload() != self.replica_id {
+                        notifier.notify_local();
+                    }
+                }
+            }
+        }
+    }
+}
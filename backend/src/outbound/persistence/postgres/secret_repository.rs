@@ -0,0 +1,126 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use crate::domain::{
+    secret::{
+        models::{EncryptedSecret, Secret},
+        ports::{SecretRepository, SecretRepositoryError},
+    },
+    user::models::UserId,
+};
+
+struct EncryptedSecretRecord {
+    name: String,
+    owner_id: uuid::Uuid,
+    ciphertext: Vec<u8>,
+    nonce: Vec<u8>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<EncryptedSecretRecord> for EncryptedSecret {
+    fn from(value: EncryptedSecretRecord) -> Self {
+        Self {
+            name: value.name,
+            owner_id: value.owner_id.into(),
+            ciphertext: value.ciphertext,
+            nonce: value.nonce,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+pub struct PostgresSecretRepository {
+    pool: PgPool,
+}
+
+impl PostgresSecretRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl SecretRepository for PostgresSecretRepository {
+    async fn upsert(&self, secret: &EncryptedSecret) -> Result<Secret, SecretRepositoryError> {
+        // Nothing is returned when the name is taken by another user's secret.
+        sqlx::query_as!(
+            Secret,
+            r#"
+            INSERT INTO secrets (name, owner_id, ciphertext, nonce, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (name) DO UPDATE
+            SET ciphertext = EXCLUDED.ciphertext, nonce = EXCLUDED.nonce, updated_at = EXCLUDED.updated_at
+            WHERE secrets.owner_id = EXCLUDED.owner_id
+            RETURNING name, created_at, updated_at
+            "#,
+            secret.name,
+            secret.owner_id.inner(),
+            secret.ciphertext,
+            secret.nonce,
+            secret.created_at,
+            secret.updated_at
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| SecretRepositoryError::Unknown(e.into()))?
+        .ok_or_else(|| SecretRepositoryError::NameTaken(secret.name.clone()))
+    }
+
+    async fn get_secret_by_name(
+        &self,
+        name: &str,
+    ) -> Result<EncryptedSecret, SecretRepositoryError> {
+        let record = sqlx::query_as!(
+            EncryptedSecretRecord,
+            r#"
+            SELECT name, owner_id, ciphertext, nonce, created_at, updated_at
+            FROM secrets
+            WHERE name = $1
+            "#,
+            name
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|err| match err {
+            sqlx::Error::RowNotFound => SecretRepositoryError::NotFound(name.to_string()),
+            _ => SecretRepositoryError::Unknown(err.into()),
+        })?;
+
+        Ok(record.into())
+    }
+
+    async fn list_secrets(&self, owner_id: &UserId) -> Result<Vec<Secret>, SecretRepositoryError> {
+        sqlx::query_as!(
+            Secret,
+            r#"
+            SELECT name, created_at, updated_at
+            FROM secrets
+            WHERE owner_id = $1
+            ORDER BY name
+            "#,
+            owner_id.inner()
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| SecretRepositoryError::Unknown(e.into()))
+    }
+
+    async fn delete(&self, owner_id: &UserId, name: &str) -> Result<(), SecretRepositoryError> {
+        let result = sqlx::query!(
+            "DELETE FROM secrets WHERE name = $1 AND owner_id = $2",
+            name,
+            owner_id.inner()
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| SecretRepositoryError::Unknown(e.into()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(SecretRepositoryError::NotFound(name.to_string()));
+        }
+        Ok(())
+    }
+}
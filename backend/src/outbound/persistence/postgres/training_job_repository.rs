@@ -1,16 +1,66 @@
 use async_trait::async_trait;
-use sqlx::PgPool;
+use chrono::{DateTime, Utc};
+use sqlx::{PgExecutor, PgPool};
 
 use crate::domain::{
     cluster::models::NodeId,
     queue::models::QueueId,
+    scheduler::models::SchedulingReport,
     training_job::{
-        models::{GetTrainingJobsFilters, JobId, TrainingJob, TrainingJobStatus},
+        models::{
+            ArrayId, AttemptOutcome, GetTrainingJobsFilters, JobArray, JobAttempt, JobEvent,
+            JobEventKind, JobExit, JobId, LogChunk, MetricSample, MetricsQuery, TrainingJob,
+            TrainingJobStatus, MAX_METRIC_SAMPLES,
+        },
         ports::{TrainingJobRepository, TrainingJobRepositoryError},
     },
 };
 
-use super::records::{TrainingJobRecord, TrainingJobStatusRecord};
+use super::records::{
+    AttemptOutcomeRecord, JobArrayRecord, JobAttemptRecord, JobEventRecord, LogChunkRecord,
+    MetricSampleRecord, TrainingJobRecord, TrainingJobStatusRecord,
+};
+
+async fn insert_training_job(
+    executor: impl PgExecutor<'_>,
+    training_job: &TrainingJob,
+) -> Result<(), TrainingJobRepositoryError> {
+    sqlx::query!(
+        "INSERT INTO training_jobs (id, name, definition, status, queue_id, resource_requirements, node_count, dependencies, retry_policy, max_runtime_secs, array_id, array_index, parameters, node_selector, tolerations, env, secrets, outputs, volumes, created_at, updated_at)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21)",
+        training_job.id.inner(),
+        training_job.name,
+        &serde_json::to_value(&training_job.definition).map_err(|e| anyhow::anyhow!(e))?,
+        TrainingJobStatusRecord::from(training_job.status.clone()) as _,
+        training_job.queue_id.map(|q| q.into_inner()),
+        &serde_json::to_value(&training_job.resource_requirements).map_err(|e| anyhow::anyhow!(e))?,
+        training_job.node_count,
+        &serde_json::to_value(&training_job.dependencies).map_err(|e| anyhow::anyhow!(e))?,
+        training_job
+            .retry_policy
+            .as_ref()
+            .map(serde_json::to_value)
+            .transpose()
+            .map_err(|e| anyhow::anyhow!(e))?,
+        training_job.max_runtime_secs,
+        training_job.array_id.map(|id| id.into_inner()),
+        training_job.array_index,
+        &serde_json::to_value(&training_job.parameters).map_err(|e| anyhow::anyhow!(e))?,
+        &serde_json::to_value(&training_job.node_selector).map_err(|e| anyhow::anyhow!(e))?,
+        &serde_json::to_value(&training_job.tolerations).map_err(|e| anyhow::anyhow!(e))?,
+        &serde_json::to_value(&training_job.env).map_err(|e| anyhow::anyhow!(e))?,
+        &serde_json::to_value(&training_job.secrets).map_err(|e| anyhow::anyhow!(e))?,
+        &serde_json::to_value(&training_job.outputs).map_err(|e| anyhow::anyhow!(e))?,
+        &serde_json::to_value(&training_job.volumes).map_err(|e| anyhow::anyhow!(e))?,
+        training_job.created_at,
+        training_job.updated_at,
+    )
+    .execute(executor)
+    .await
+    .map_err(|e: sqlx::Error| TrainingJobRepositoryError::Unknown(anyhow::anyhow!(e)))?;
+
+    Ok(())
+}
 
 pub struct PostgresTrainingJobRepository {
     pool: PgPool,
@@ -25,25 +75,130 @@ impl PostgresTrainingJobRepository {
 #[async_trait]
 impl TrainingJobRepository for PostgresTrainingJobRepository {
     async fn create(&self, training_job: &TrainingJob) -> Result<(), TrainingJobRepositoryError> {
+        insert_training_job(&self.pool, training_job).await
+    }
+
+    async fn create_all(
+        &self,
+        training_jobs: &[TrainingJob],
+    ) -> Result<(), TrainingJobRepositoryError> {
+        let mut tx =
+            self.pool.begin().await.map_err(|e: sqlx::Error| {
+                TrainingJobRepositoryError::Unknown(anyhow::anyhow!(e))
+            })?;
+
+        for training_job in training_jobs {
+            insert_training_job(&mut *tx, training_job).await?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e: sqlx::Error| TrainingJobRepositoryError::Unknown(anyhow::anyhow!(e)))?;
+
+        Ok(())
+    }
+
+    async fn create_array(
+        &self,
+        array: &JobArray,
+        training_jobs: &[TrainingJob],
+    ) -> Result<(), TrainingJobRepositoryError> {
+        let mut tx =
+            self.pool.begin().await.map_err(|e: sqlx::Error| {
+                TrainingJobRepositoryError::Unknown(anyhow::anyhow!(e))
+            })?;
+
         sqlx::query!(
-            "INSERT INTO training_jobs (id, name, definition, status, queue_id, resource_requirements, created_at, updated_at)
-             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
-            training_job.id.inner(),
-            training_job.name,
-            training_job.definition,
-            TrainingJobStatusRecord::from(training_job.status.clone()) as _,
-            training_job.queue_id.map(|q| q.into_inner()),
-            &serde_json::to_value(&training_job.resource_requirements).map_err(|e| anyhow::anyhow!(e))?,
-            training_job.created_at,
-            training_job.updated_at,
+            "INSERT INTO job_arrays (array_id, name, size, max_concurrent, parameters, created_at)
+             VALUES ($1, $2, $3, $4, $5, $6)",
+            array.id.inner(),
+            array.name,
+            array.size,
+            array.max_concurrent,
+            &serde_json::to_value(&array.parameters).map_err(|e| anyhow::anyhow!(e))?,
+            array.created_at,
         )
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await
         .map_err(|e: sqlx::Error| TrainingJobRepositoryError::Unknown(anyhow::anyhow!(e)))?;
 
+        for training_job in training_jobs {
+            insert_training_job(&mut *tx, training_job).await?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e: sqlx::Error| TrainingJobRepositoryError::Unknown(anyhow::anyhow!(e)))?;
+
         Ok(())
     }
 
+    async fn get_job_array(
+        &self,
+        array_id: &ArrayId,
+    ) -> Result<JobArray, TrainingJobRepositoryError> {
+        let record = sqlx::query_as!(
+            JobArrayRecord,
+            "SELECT array_id, name, size, max_concurrent, parameters, created_at FROM job_arrays WHERE array_id = $1",
+            array_id.inner()
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => TrainingJobRepositoryError::NotFound(array_id.to_string()),
+            _ => TrainingJobRepositoryError::Unknown(anyhow::anyhow!(e)),
+        })?;
+
+        Ok(record.try_into()?)
+    }
+
+    async fn get_array_jobs(
+        &self,
+        array_id: &ArrayId,
+    ) -> Result<Vec<TrainingJob>, TrainingJobRepositoryError> {
+        let rows = sqlx::query_as!(
+            TrainingJobRecord,
+            r#"
+            SELECT
+                id, name, definition, status AS "status: TrainingJobStatusRecord", node_id, queue_id,
+                resource_requirements, node_count, preempted_at, preemption_reason, dependencies, retry_policy, not_before, avoid_node_id, max_runtime_secs, started_at, exit, array_id, array_index, parameters, node_selector, tolerations, env, secrets, outputs, volumes, created_at, updated_at
+            FROM training_jobs
+            WHERE array_id = $1
+            ORDER BY array_index
+            "#,
+            array_id.inner(),
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e: sqlx::Error| TrainingJobRepositoryError::Unknown(anyhow::anyhow!(e)))?;
+
+        let jobs = rows
+            .into_iter()
+            .map(|row| row.try_into())
+            .collect::<Result<Vec<_>, anyhow::Error>>()?;
+
+        Ok(jobs)
+    }
+
+    async fn count_active_array_jobs(
+        &self,
+        array_id: &ArrayId,
+    ) -> Result<i64, TrainingJobRepositoryError> {
+        let count = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) AS "count!"
+            FROM training_jobs
+            WHERE array_id = $1 AND status IN ('starting', 'running')
+            "#,
+            array_id.inner(),
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e: sqlx::Error| TrainingJobRepositoryError::Unknown(anyhow::anyhow!(e)))?;
+
+        Ok(count)
+    }
+
     async fn get_training_jobs(
         &self,
         filters: GetTrainingJobsFilters,
@@ -51,7 +206,8 @@ impl TrainingJobRepository for PostgresTrainingJobRepository {
         let mut query = sqlx::QueryBuilder::new(
             r#"
             SELECT id, name, definition, status,
-                node_id, queue_id, resource_requirements, created_at, updated_at
+                node_id, queue_id, resource_requirements, node_count, preempted_at,
+                preemption_reason, dependencies, retry_policy, not_before, avoid_node_id, max_runtime_secs, started_at, exit, array_id, array_index, parameters, node_selector, tolerations, env, secrets, outputs, volumes, created_at, updated_at
                 FROM training_jobs WHERE 1 = 1"#,
         );
 
@@ -90,7 +246,11 @@ impl TrainingJobRepository for PostgresTrainingJobRepository {
         status: TrainingJobStatus,
     ) -> Result<(), TrainingJobRepositoryError> {
         sqlx::query!(
-            "UPDATE training_jobs SET status = $1 WHERE id = $2",
+            // The runtime of a job counts from when it is first reported running.
+            "UPDATE training_jobs
+             SET status = $1,
+                 started_at = CASE WHEN $1 = 'running'::training_job_status THEN COALESCE(started_at, NOW()) ELSE started_at END
+             WHERE id = $2",
             TrainingJobStatusRecord::from(status) as _,
             job_id.inner()
         )
@@ -101,23 +261,6 @@ impl TrainingJobRepository for PostgresTrainingJobRepository {
         Ok(())
     }
 
-    async fn mark_as_starting(
-        &self,
-        job_id: &JobId,
-        node_id: &NodeId,
-    ) -> Result<(), TrainingJobRepositoryError> {
-        sqlx::query!(
-            "UPDATE training_jobs SET status = 'starting', node_id = $1 WHERE id = $2",
-            node_id.inner(),
-            job_id.inner()
-        )
-        .execute(&self.pool)
-        .await
-        .map_err(|e: sqlx::Error| TrainingJobRepositoryError::Unknown(anyhow::anyhow!(e)))?;
-
-        Ok(())
-    }
-
     async fn get_queued_jobs_for_queue(
         &self,
         queue_id: &QueueId,
@@ -127,9 +270,10 @@ impl TrainingJobRepository for PostgresTrainingJobRepository {
             r#"
             SELECT
                 id, name, definition, status AS "status: TrainingJobStatusRecord", node_id, queue_id,
-                resource_requirements, created_at, updated_at
+                resource_requirements, node_count, preempted_at, preemption_reason, dependencies, retry_policy, not_before, avoid_node_id, max_runtime_secs, started_at, exit, array_id, array_index, parameters, node_selector, tolerations, env, secrets, outputs, volumes, created_at, updated_at
             FROM training_jobs
             WHERE status = 'queued' AND queue_id = $1
+                AND (not_before IS NULL OR not_before <= NOW())
             ORDER BY created_at ASC
             "#,
             queue_id.inner(),
@@ -145,14 +289,201 @@ impl TrainingJobRepository for PostgresTrainingJobRepository {
 
         Ok(jobs)
     }
-    async fn post_logs(
+    async fn append_logs(
+        &self,
+        id: &JobId,
+        content: &str,
+        max_bytes: i64,
+    ) -> Result<(), TrainingJobRepositoryError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| TrainingJobRepositoryError::Unknown(anyhow::anyhow!(e)))?;
+
+        // Bumping the job's counter also locks it, so appends to the same job
+        // get their offsets in order.
+        let appended_bytes = sqlx::query_scalar!(
+            r#"
+            INSERT INTO training_job_log_sizes (job_id, appended_bytes)
+            VALUES ($1, octet_length($2))
+            ON CONFLICT (job_id) DO UPDATE
+            SET appended_bytes = training_job_log_sizes.appended_bytes + EXCLUDED.appended_bytes
+            RETURNING appended_bytes
+            "#,
+            id.inner(),
+            content
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e: sqlx::Error| match e {
+            sqlx::Error::Database(db_err) if db_err.is_foreign_key_violation() => {
+                TrainingJobRepositoryError::NotFound(id.to_string())
+            }
+            _ => TrainingJobRepositoryError::Unknown(anyhow::anyhow!(e)),
+        })?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO training_job_logs (job_id, content, start_offset)
+            VALUES ($1, $2, $3::BIGINT - octet_length($2))
+            "#,
+            id.inner(),
+            content,
+            appended_bytes
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| TrainingJobRepositoryError::Unknown(anyhow::anyhow!(e)))?;
+
+        // Keep the newest chunks that fit in the limit together, which are the
+        // ones starting within the last `max_bytes` appended.
+        sqlx::query!(
+            "DELETE FROM training_job_logs WHERE job_id = $1 AND start_offset < $2",
+            id.inner(),
+            appended_bytes - max_bytes
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| TrainingJobRepositoryError::Unknown(anyhow::anyhow!(e)))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| TrainingJobRepositoryError::Unknown(anyhow::anyhow!(e)))?;
+
+        Ok(())
+    }
+
+    async fn get_logs(
+        &self,
+        id: &JobId,
+        after: Option<i64>,
+        tail_lines: Option<i64>,
+    ) -> Result<Vec<LogChunk>, TrainingJobRepositoryError> {
+        // Counts the lines of each chunk and of all chunks after it, so the
+        // tail can be cut off at the first chunk that completes it.
+        let records = sqlx::query_as!(
+            LogChunkRecord,
+            r#"
+            SELECT id AS "id!", content AS "content!", created_at AS "created_at!"
+            FROM (
+                SELECT id, content, created_at,
+                    SUM(cardinality(string_to_array(rtrim(content, E'\n'), E'\n')))
+                        OVER (ORDER BY id DESC) AS lines_from_end,
+                    cardinality(string_to_array(rtrim(content, E'\n'), E'\n')) AS lines
+                FROM training_job_logs
+                WHERE job_id = $1 AND ($2::BIGINT IS NULL OR id > $2)
+            ) counted
+            WHERE $3::BIGINT IS NULL OR lines_from_end - lines < $3
+            ORDER BY id ASC
+            "#,
+            id.inner(),
+            after,
+            tail_lines
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e: sqlx::Error| TrainingJobRepositoryError::Unknown(anyhow::anyhow!(e)))?;
+
+        Ok(records.into_iter().map(LogChunk::from).collect())
+    }
+
+    async fn delete_logs_before(
         &self,
-        _id: &JobId,
-        _logs: String,
+        cutoff: DateTime<Utc>,
+    ) -> Result<u64, TrainingJobRepositoryError> {
+        let result = sqlx::query!(
+            "DELETE FROM training_job_logs WHERE created_at < $1",
+            cutoff
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e: sqlx::Error| TrainingJobRepositoryError::Unknown(anyhow::anyhow!(e)))?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn record_metrics(
+        &self,
+        job_id: &JobId,
+        samples: &[MetricSample],
     ) -> Result<(), TrainingJobRepositoryError> {
-        // TODO: Implement log ingestion. This could involve writing to a file,
-        // a separate logging service, or another table.
-        todo!("TODO: Implement log posting");
+        let node_ids: Vec<uuid::Uuid> = samples
+            .iter()
+            .map(|sample| sample.node_id.into_inner())
+            .collect();
+        let sampled_at: Vec<DateTime<Utc>> =
+            samples.iter().map(|sample| sample.sampled_at).collect();
+        let cpu_millicores: Vec<Option<i32>> =
+            samples.iter().map(|sample| sample.cpu_millicores).collect();
+        let memory_mb: Vec<Option<i64>> = samples.iter().map(|sample| sample.memory_mb).collect();
+        let gpus = samples
+            .iter()
+            .map(|sample| serde_json::to_value(&sample.gpus))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| anyhow::anyhow!(e))?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO training_job_metrics
+                (job_id, node_id, sampled_at, cpu_millicores, memory_mb, gpus)
+            SELECT $1, * FROM UNNEST($2::uuid[], $3::timestamptz[], $4::int4[], $5::int8[], $6::jsonb[])
+            "#,
+            job_id.inner(),
+            &node_ids,
+            &sampled_at,
+            &cpu_millicores as &[Option<i32>],
+            &memory_mb as &[Option<i64>],
+            &gpus,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e: sqlx::Error| TrainingJobRepositoryError::Unknown(anyhow::anyhow!(e)))?;
+
+        Ok(())
+    }
+
+    async fn get_metrics(
+        &self,
+        job_id: &JobId,
+        query: &MetricsQuery,
+    ) -> Result<Vec<MetricSample>, TrainingJobRepositoryError> {
+        let records = sqlx::query_as!(
+            MetricSampleRecord,
+            r#"
+            SELECT node_id, sampled_at, cpu_millicores, memory_mb, gpus
+            FROM training_job_metrics
+            WHERE job_id = $1 AND ($2::timestamptz IS NULL OR sampled_at > $2)
+            ORDER BY sampled_at ASC, id ASC
+            LIMIT $3
+            "#,
+            job_id.inner(),
+            query.since,
+            MAX_METRIC_SAMPLES,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e: sqlx::Error| TrainingJobRepositoryError::Unknown(anyhow::anyhow!(e)))?;
+
+        Ok(records
+            .into_iter()
+            .map(|record| record.try_into())
+            .collect::<Result<Vec<_>, anyhow::Error>>()?)
+    }
+
+    async fn delete_metrics_before(
+        &self,
+        cutoff: DateTime<Utc>,
+    ) -> Result<u64, TrainingJobRepositoryError> {
+        let result = sqlx::query!(
+            "DELETE FROM training_job_metrics WHERE sampled_at < $1",
+            cutoff
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e: sqlx::Error| TrainingJobRepositoryError::Unknown(anyhow::anyhow!(e)))?;
+
+        Ok(result.rows_affected())
     }
 
     async fn get_training_job_by_id(
@@ -163,7 +494,7 @@ impl TrainingJobRepository for PostgresTrainingJobRepository {
             TrainingJobRecord,
             r#"
             SELECT id, name, definition, status AS "status: TrainingJobStatusRecord", node_id, queue_id,
-                   resource_requirements, created_at, updated_at
+                   resource_requirements, node_count, preempted_at, preemption_reason, dependencies, retry_policy, not_before, avoid_node_id, max_runtime_secs, started_at, exit, array_id, array_index, parameters, node_selector, tolerations, env, secrets, outputs, volumes, created_at, updated_at
             FROM training_jobs
             WHERE id = $1
             "#,
@@ -191,6 +522,88 @@ impl TrainingJobRepository for PostgresTrainingJobRepository {
         Ok(())
     }
 
+    async fn preempt_job(
+        &self,
+        job_id: &JobId,
+        reason: &str,
+    ) -> Result<bool, TrainingJobRepositoryError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| TrainingJobRepositoryError::Unknown(anyhow::anyhow!(e)))?;
+
+        let requeued = sqlx::query!(
+            r#"
+            UPDATE training_jobs
+            SET status = 'queued', node_id = NULL, preempted_at = NOW(), preemption_reason = $2
+            WHERE id = $1 AND status IN ('starting', 'running')
+            "#,
+            job_id.inner(),
+            reason
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e: sqlx::Error| TrainingJobRepositoryError::Unknown(anyhow::anyhow!(e)))?;
+        if requeued.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        sqlx::query!(
+            "DELETE FROM training_job_nodes WHERE job_id = $1",
+            job_id.inner()
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e: sqlx::Error| TrainingJobRepositoryError::Unknown(anyhow::anyhow!(e)))?;
+
+        sqlx::query!(
+            r#"
+            UPDATE training_job_attempts
+            SET finished_at = NOW(), outcome = $2
+            WHERE job_id = $1 AND finished_at IS NULL
+            "#,
+            job_id.inner(),
+            AttemptOutcomeRecord::from(AttemptOutcome::Preempted) as _,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e: sqlx::Error| TrainingJobRepositoryError::Unknown(anyhow::anyhow!(e)))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| TrainingJobRepositoryError::Unknown(anyhow::anyhow!(e)))?;
+
+        Ok(true)
+    }
+
+    async fn get_dependent_jobs(
+        &self,
+        job_id: &JobId,
+    ) -> Result<Vec<TrainingJob>, TrainingJobRepositoryError> {
+        let rows = sqlx::query_as!(
+            TrainingJobRecord,
+            r#"
+            SELECT
+                id, name, definition, status AS "status: TrainingJobStatusRecord", node_id, queue_id,
+                resource_requirements, node_count, preempted_at, preemption_reason, dependencies, retry_policy, not_before, avoid_node_id, max_runtime_secs, started_at, exit, array_id, array_index, parameters, node_selector, tolerations, env, secrets, outputs, volumes, created_at, updated_at
+            FROM training_jobs
+            WHERE dependencies @> jsonb_build_array(jsonb_build_object('job_id', $1::uuid))
+            "#,
+            job_id.inner(),
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e: sqlx::Error| TrainingJobRepositoryError::Unknown(anyhow::anyhow!(e)))?;
+
+        let jobs = rows
+            .into_iter()
+            .map(|row| row.try_into())
+            .collect::<Result<Vec<_>, anyhow::Error>>()?;
+
+        Ok(jobs)
+    }
+
     async fn get_jobs_by_status(
         &self,
         status: TrainingJobStatus,
@@ -200,7 +613,7 @@ impl TrainingJobRepository for PostgresTrainingJobRepository {
             r#"
             SELECT
                 id, name, definition, status AS "status: TrainingJobStatusRecord", node_id, queue_id,
-                resource_requirements, created_at, updated_at
+                resource_requirements, node_count, preempted_at, preemption_reason, dependencies, retry_policy, not_before, avoid_node_id, max_runtime_secs, started_at, exit, array_id, array_index, parameters, node_selector, tolerations, env, secrets, outputs, volumes, created_at, updated_at
             FROM training_jobs
             WHERE status = $1
             "#,
@@ -217,4 +630,243 @@ impl TrainingJobRepository for PostgresTrainingJobRepository {
 
         Ok(jobs)
     }
+
+    async fn record_scheduling_report(
+        &self,
+        report: &SchedulingReport,
+    ) -> Result<(), TrainingJobRepositoryError> {
+        sqlx::query!(
+            r#"
+            INSERT INTO training_job_scheduling_reports (job_id, attempted_at, report)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (job_id) DO UPDATE SET
+                attempted_at = EXCLUDED.attempted_at,
+                report = EXCLUDED.report
+            "#,
+            report.job_id.inner(),
+            report.attempted_at,
+            serde_json::to_value(report).map_err(|e| anyhow::anyhow!(e))?,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e: sqlx::Error| TrainingJobRepositoryError::Unknown(anyhow::anyhow!(e)))?;
+
+        Ok(())
+    }
+
+    async fn get_scheduling_report(
+        &self,
+        job_id: &JobId,
+    ) -> Result<Option<SchedulingReport>, TrainingJobRepositoryError> {
+        let report = sqlx::query_scalar!(
+            "SELECT report FROM training_job_scheduling_reports WHERE job_id = $1",
+            job_id.inner()
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e: sqlx::Error| TrainingJobRepositoryError::Unknown(anyhow::anyhow!(e)))?;
+
+        report
+            .map(serde_json::from_value)
+            .transpose()
+            .map_err(|e| TrainingJobRepositoryError::Unknown(anyhow::anyhow!(e)))
+    }
+
+    async fn finish_attempt(
+        &self,
+        job_id: &JobId,
+        outcome: AttemptOutcome,
+        exit_code: Option<i32>,
+    ) -> Result<Option<JobAttempt>, TrainingJobRepositoryError> {
+        let record = sqlx::query_as!(
+            JobAttemptRecord,
+            r#"
+            UPDATE training_job_attempts
+            SET finished_at = NOW(), outcome = $2, exit_code = $3
+            WHERE job_id = $1 AND finished_at IS NULL
+            RETURNING job_id, attempt_number, node_id, started_at, finished_at,
+                outcome AS "outcome: AttemptOutcomeRecord", exit_code
+            "#,
+            job_id.inner(),
+            AttemptOutcomeRecord::from(outcome) as _,
+            exit_code,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e: sqlx::Error| TrainingJobRepositoryError::Unknown(anyhow::anyhow!(e)))?;
+
+        Ok(record.map(Into::into))
+    }
+
+    async fn set_exit(
+        &self,
+        job_id: &JobId,
+        exit: &JobExit,
+    ) -> Result<(), TrainingJobRepositoryError> {
+        sqlx::query!(
+            "UPDATE training_jobs SET exit = $2 WHERE id = $1",
+            job_id.inner(),
+            &serde_json::to_value(exit).map_err(|e| anyhow::anyhow!(e))?,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e: sqlx::Error| TrainingJobRepositoryError::Unknown(anyhow::anyhow!(e)))?;
+
+        Ok(())
+    }
+
+    async fn get_attempts(
+        &self,
+        job_id: &JobId,
+    ) -> Result<Vec<JobAttempt>, TrainingJobRepositoryError> {
+        let records = sqlx::query_as!(
+            JobAttemptRecord,
+            r#"
+            SELECT job_id, attempt_number, node_id, started_at, finished_at,
+                outcome AS "outcome: AttemptOutcomeRecord", exit_code
+            FROM training_job_attempts
+            WHERE job_id = $1
+            ORDER BY attempt_number ASC
+            "#,
+            job_id.inner(),
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e: sqlx::Error| TrainingJobRepositoryError::Unknown(anyhow::anyhow!(e)))?;
+
+        Ok(records.into_iter().map(Into::into).collect())
+    }
+
+    async fn get_failed_attempts_to_evaluate(
+        &self,
+    ) -> Result<Vec<JobAttempt>, TrainingJobRepositoryError> {
+        let records = sqlx::query_as!(
+            JobAttemptRecord,
+            r#"
+            SELECT a.job_id, a.attempt_number, a.node_id, a.started_at, a.finished_at,
+                a.outcome AS "outcome: AttemptOutcomeRecord", a.exit_code
+            FROM training_job_attempts a
+            JOIN training_jobs j ON j.id = a.job_id
+            WHERE a.outcome = 'failed' AND NOT a.retry_evaluated AND j.status = 'failed'
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e: sqlx::Error| TrainingJobRepositoryError::Unknown(anyhow::anyhow!(e)))?;
+
+        Ok(records.into_iter().map(Into::into).collect())
+    }
+
+    async fn mark_retry_evaluated(
+        &self,
+        attempt: &JobAttempt,
+    ) -> Result<(), TrainingJobRepositoryError> {
+        sqlx::query!(
+            "UPDATE training_job_attempts SET retry_evaluated = TRUE WHERE job_id = $1 AND attempt_number = $2",
+            attempt.job_id.inner(),
+            attempt.attempt_number,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e: sqlx::Error| TrainingJobRepositoryError::Unknown(anyhow::anyhow!(e)))?;
+
+        Ok(())
+    }
+
+    async fn requeue_for_retry(
+        &self,
+        job_id: &JobId,
+        not_before: DateTime<Utc>,
+        avoid_node_id: Option<NodeId>,
+    ) -> Result<(), TrainingJobRepositoryError> {
+        sqlx::query!(
+            "UPDATE training_jobs SET status = 'queued', node_id = NULL, not_before = $2, avoid_node_id = $3 WHERE id = $1",
+            job_id.inner(),
+            not_before,
+            avoid_node_id.map(|node_id| node_id.into_inner()),
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e: sqlx::Error| TrainingJobRepositoryError::Unknown(anyhow::anyhow!(e)))?;
+
+        Ok(())
+    }
+
+    async fn record_event(
+        &self,
+        job_ids: &[JobId],
+        event: &JobEventKind,
+    ) -> Result<(), TrainingJobRepositoryError> {
+        if job_ids.is_empty() {
+            return Ok(());
+        }
+        let job_ids: Vec<uuid::Uuid> = job_ids.iter().map(|job_id| job_id.into_inner()).collect();
+        sqlx::query!(
+            r#"
+            INSERT INTO training_job_events (job_id, event)
+            SELECT job_id, $2 FROM UNNEST($1::uuid[]) AS job_id
+            "#,
+            &job_ids,
+            &serde_json::to_value(event).map_err(|e| anyhow::anyhow!(e))?,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e: sqlx::Error| TrainingJobRepositoryError::Unknown(anyhow::anyhow!(e)))?;
+        Ok(())
+    }
+
+    async fn get_events(
+        &self,
+        job_id: &JobId,
+    ) -> Result<Vec<JobEvent>, TrainingJobRepositoryError> {
+        let records = sqlx::query_as!(
+            JobEventRecord,
+            r#"
+            SELECT id, job_id, event, created_at
+            FROM training_job_events
+            WHERE job_id = $1
+            ORDER BY id ASC
+            "#,
+            job_id.inner(),
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e: sqlx::Error| TrainingJobRepositoryError::Unknown(anyhow::anyhow!(e)))?;
+
+        let events = records
+            .into_iter()
+            .map(|record| record.try_into())
+            .collect::<Result<Vec<_>, anyhow::Error>>()?;
+
+        Ok(events)
+    }
+
+    async fn get_jobs_timed_out_before(
+        &self,
+        deadline: DateTime<Utc>,
+    ) -> Result<Vec<TrainingJob>, TrainingJobRepositoryError> {
+        let rows = sqlx::query_as!(
+            TrainingJobRecord,
+            r#"
+            SELECT
+                id, name, definition, status AS "status: TrainingJobStatusRecord", node_id, queue_id,
+                resource_requirements, node_count, preempted_at, preemption_reason, dependencies, retry_policy, not_before, avoid_node_id, max_runtime_secs, started_at, exit, array_id, array_index, parameters, node_selector, tolerations, env, secrets, outputs, volumes, created_at, updated_at
+            FROM training_jobs
+            WHERE status = 'running'
+              AND max_runtime_secs IS NOT NULL
+              AND started_at + make_interval(secs => max_runtime_secs) < $1
+            "#,
+            deadline,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e: sqlx::Error| TrainingJobRepositoryError::Unknown(anyhow::anyhow!(e)))?;
+
+        let jobs = rows
+            .into_iter()
+            .map(|row| row.try_into())
+            .collect::<Result<Vec<_>, anyhow::Error>>()?;
+
+        Ok(jobs)
+    }
 }
@@ -1,13 +1,17 @@
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 
 use tracing::debug;
 
 use crate::domain::{
     cluster::{
-        models::{ClusterId, NodeId, NodeStatus},
+        models::{ClusterId, ClusterNode, NodeId, NodeSchedulability},
         ports::ClusterRepository,
     },
-    training_job::models::{JobId, ResourceRequirements},
+    scheduler::{
+        models::{ClusterOutcome, ClusterReport, NodeRejection, RejectionReason},
+        ports::SchedulingPolicy,
+    },
+    training_job::models::{ResourceRequirements, Toleration, TrainingJob},
 };
 use thiserror::Error;
 
@@ -21,6 +25,110 @@ pub enum AgentSchedulerError {
     Unknown(#[from] anyhow::Error),
 }
 
+/// Returns the reasons the node can never run its share of a job, regardless
+/// of what the node is currently running. An empty list means the node fits.
+pub fn node_rejections(
+    node: &ClusterNode,
+    requirements: &ResourceRequirements,
+    node_count: usize,
+) -> Vec<RejectionReason> {
+    let mut reasons = Vec::new();
+
+    // Multi-node jobs need every node to be reachable by its peers.
+    if node_count > 1 && node.address.is_none() {
+        reasons.push(RejectionReason::NoAdvertisedAddress);
+    }
+    if node.cpu.millicores < requirements.cpu_millicores {
+        reasons.push(RejectionReason::InsufficientCpu {
+            required: requirements.cpu_millicores,
+            available: node.cpu.millicores,
+        });
+    }
+    if node.memory_mb < requirements.memory_mb {
+        reasons.push(RejectionReason::InsufficientMemory {
+            required: requirements.memory_mb,
+            available: node.memory_mb,
+        });
+    }
+    if let Some(req_gpu) = &requirements.gpus {
+        match &node.gpu {
+            None => reasons.push(RejectionReason::NoGpus),
+            Some(node_gpu) => reasons.extend(req_gpu.rejections(node_gpu)),
+        }
+    }
+
+    reasons
+}
+
+/// Returns the reasons the node's labels and taints keep a job off it. An
+/// empty list means the job may be placed on the node.
+pub fn placement_rejections(
+    node: &ClusterNode,
+    node_selector: &BTreeMap<String, String>,
+    tolerations: &[Toleration],
+) -> Vec<RejectionReason> {
+    let mut reasons = Vec::new();
+
+    for (key, required) in node_selector {
+        let actual = node.labels.get(key);
+        if actual != Some(required) {
+            reasons.push(RejectionReason::MissingLabel {
+                key: key.clone(),
+                required: required.clone(),
+                actual: actual.cloned(),
+            });
+        }
+    }
+    for taint in &node.taints {
+        if !tolerations
+            .iter()
+            .any(|toleration| toleration.tolerates(taint))
+        {
+            reasons.push(RejectionReason::UntoleratedTaint {
+                key: taint.key.clone(),
+                value: taint.value.clone(),
+            });
+        }
+    }
+
+    reasons
+}
+
+/// Returns whether the node could run its share of the job, regardless of
+/// what the node is currently running.
+pub fn node_fits(node: &ClusterNode, job: &TrainingJob) -> bool {
+    let node_count = usize::try_from(job.node_count).unwrap_or(0).max(1);
+    node_rejections(node, &job.resource_requirements, node_count).is_empty()
+        && placement_rejections(node, &job.node_selector, &job.tolerations).is_empty()
+}
+
+/// Returns why a node that fits a job cannot take it on right now, next to the
+/// jobs it is already running. An empty list means the node has room.
+pub fn capacity_rejections(
+    node: &ClusterNode,
+    requirements: &ResourceRequirements,
+) -> Vec<RejectionReason> {
+    match node.schedulability {
+        NodeSchedulability::Schedulable => {}
+        NodeSchedulability::Cordoned => return vec![RejectionReason::Cordoned],
+        NodeSchedulability::Draining => return vec![RejectionReason::Draining],
+    }
+    if node.is_stopping_jobs() {
+        return vec![RejectionReason::StoppingJobs];
+    }
+
+    let free = node.free_capacity();
+    if free.can_hold(requirements) {
+        Vec::new()
+    } else {
+        vec![RejectionReason::NodeBusy {
+            free_cpu_millicores: free.cpu_millicores,
+            free_memory_mb: free.memory_mb,
+            free_gpus: free.gpu_count,
+        }]
+    }
+}
+
 #[derive(Clone)]
 pub struct AgentSchedulerAdapter {
     cluster_repo: Arc<dyn ClusterRepository>,
@@ -31,48 +139,105 @@ impl AgentSchedulerAdapter {
         Self { cluster_repo }
     }
 
+    /// Finds as many suitable nodes on the cluster as the job needs,
+    /// preferring the ones ranked highest by `policy`, and assigns the job to
+    /// all of them at once, marking the job as starting. The node the job's
+    /// last attempt failed on is only used when there is no other choice.
+    ///
+    /// Allocated nodes are reported in rank order, so the first one is the
+    /// job's coordinator. Every node that was passed over is reported together
+    /// with the reasons why.
     pub async fn find_and_allocate_job(
         &self,
-        job_id: &JobId,
+        job: &TrainingJob,
         cluster_id: &ClusterId,
-        requirements: &ResourceRequirements,
-    ) -> Result<Option<NodeId>, AgentSchedulerError> {
-        let mut nodes = self.cluster_repo.list_cluster_nodes(cluster_id).await?;
-
-        // Filter nodes that are available and meet the resource requirements.
-        nodes.retain(|node| {
-            let status_ok =
-                node.node_status == NodeStatus::Available && node.assigned_job_id.is_none();
-            if !status_ok {
-                return false;
+        policy: &dyn SchedulingPolicy,
+    ) -> Result<ClusterReport, AgentSchedulerError> {
+        let job_id = &job.id;
+        let requirements = &job.resource_requirements;
+        let avoid_node_id = job.avoid_node_id;
+        let node_count = usize::try_from(job.node_count).unwrap_or(0).max(1);
+        let nodes = self.cluster_repo.list_cluster_nodes(cluster_id).await?;
+        let report = |outcome, rejected_nodes| ClusterReport {
+            cluster_id: *cluster_id,
+            outcome,
+            rejected_nodes,
+        };
+
+        if nodes.is_empty() {
+            return Ok(report(ClusterOutcome::NoNodes, Vec::new()));
+        }
+
+        // Filter nodes that meet the resource requirements.
+        let mut rejected_nodes = Vec::new();
+        let mut fitting = Vec::new();
+        for node in nodes {
+            let mut reasons = node_rejections(&node, requirements, node_count);
+            reasons.extend(placement_rejections(
+                &node,
+                &job.node_selector,
+                &job.tolerations,
+            ));
+            if reasons.is_empty() {
+                fitting.push(node);
+            } else {
+                rejected_nodes.push(NodeRejection {
+                    node_id: node.id,
+                    reasons,
+                });
             }
+        }
+        if fitting.len() < node_count {
+            return Ok(report(ClusterOutcome::Unsatisfiable, rejected_nodes));
+        }
 
-            let cpu_ok = node.cpu.millicores >= requirements.cpu_millicores;
-            let mem_ok = node.memory_mb >= requirements.memory_mb;
-            let gpu_ok = match &requirements.gpus {
-                None => true,
-                Some(req_gpu) => match &node.gpu {
-                    Some(node_gpu) => node_gpu.count >= req_gpu.count,
-                    None => false,
-                },
-            };
-
-            cpu_ok && mem_ok && gpu_ok
-        });
+        // Of those, keep the ones that have enough capacity left right now.
+        let mut candidates = Vec::new();
+        for node in fitting {
+            let reasons = capacity_rejections(&node, requirements);
+            if reasons.is_empty() {
+                candidates.push(node);
+            } else {
+                rejected_nodes.push(NodeRejection {
+                    node_id: node.id,
+                    reasons,
+                });
+            }
+        }
+        if candidates.len() < node_count {
+            return Ok(report(ClusterOutcome::NoCapacity, rejected_nodes));
+        }
 
-        // Sort the suitable nodes by memory in ascending order (best fit).
-        nodes.sort_by_key(|node| node.memory_mb);
+        policy.rank(&mut candidates, requirements);
+        // The sort is stable, so the policy's order holds among the other nodes.
+        candidates.sort_by_key(|node| Some(node.id) == avoid_node_id);
 
-        let suitable_node = nodes.into_iter().next();
+        let node_ids: Vec<NodeId> = candidates
+            .into_iter()
+            .take(node_count)
+            .map(|node| node.id)
+            .collect();
 
-        if let Some(node) = suitable_node {
-            debug!("Found suitable node {} for job {}", node.id, job_id);
-            self.cluster_repo
-                .assign_job_to_node(&node.id, job_id)
-                .await?;
-            Ok(Some(node.id))
-        } else {
-            Ok(None)
+        debug!(
+            "Found {} suitable node(s) for job {}",
+            node_ids.len(),
+            job_id
+        );
+        let assigned = self
+            .cluster_repo
+            .assign_job_to_nodes(job_id, &node_ids, requirements)
+            .await?;
+        if !assigned {
+            debug!(
+                "Nodes for job {} changed while allocating, retrying next cycle",
+                job_id
+            );
+            return Ok(report(ClusterOutcome::Conflict, rejected_nodes));
         }
+
+        Ok(report(
+            ClusterOutcome::Allocated { node_ids },
+            rejected_nodes,
+        ))
     }
 }
@@ -3,6 +3,9 @@ export default {
   "auth": "Authentication",
   "clusters": "Clusters",
   "queues": "Queues",
+  "schedules": "Schedules",
+  "scheduler": "Scheduler",
+  "secrets": "Secrets",
   "training-jobs": "Training Jobs",
   "users": "Users"
 }
\ No newline at end of file